@@ -16,22 +16,27 @@ use crate::{
 use super::AstExpr;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct Argument {
     pub name: Identifier,
     pub default: Option<AstExpr>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct ArgumentDeclaration {
     pub args: Vec<Argument>,
     pub rest: Option<Identifier>,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
+    pub span: Span,
 }
 
 impl ArgumentDeclaration {
-    pub fn empty() -> Self {
+    pub fn empty(span: Span) -> Self {
         Self {
             args: Vec::new(),
             rest: None,
+            span,
         }
     }
 
@@ -130,11 +135,14 @@ impl ArgumentDeclaration {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "camelCase"))]
 pub struct ArgumentInvocation {
     pub(crate) positional: Vec<AstExpr>,
     pub(crate) named: BTreeMap<Identifier, AstExpr>,
     pub(crate) rest: Option<AstExpr>,
     pub(crate) keyword_rest: Option<AstExpr>,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub(crate) span: Span,
 }
 
@@ -152,8 +160,8 @@ impl ArgumentInvocation {
 
 // todo: hack for builtin `call`
 #[derive(Debug, Clone)]
-pub(crate) enum MaybeEvaledArguments {
-    Invocation(ArgumentInvocation),
+pub(crate) enum MaybeEvaledArguments<'a> {
+    Invocation(&'a ArgumentInvocation),
     Evaled(ArgumentResult),
 }
 
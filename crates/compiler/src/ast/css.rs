@@ -1,6 +1,6 @@
 use codemap::Span;
 
-use crate::selector::ExtendedSelector;
+use crate::{selector::ExtendedSelector, Options};
 
 use super::{MediaRule, Style, UnknownAtRule};
 
@@ -51,18 +51,25 @@ impl CssStmt {
         }
     }
 
-    pub fn is_invisible(&self) -> bool {
+    /// Whether nothing would be written for this statement, such as for style
+    /// rules with only placeholder selectors, or for at-rules and style rules
+    /// whose children are all invisible
+    ///
+    /// Loud comments are invisible in compressed mode, unless they are preserved
+    /// with `/*!`.
+    pub fn is_invisible(&self, options: &Options) -> bool {
+        let all_invisible = |body: &[CssStmt]| body.iter().all(|stmt| stmt.is_invisible(options));
+
         match self {
             CssStmt::RuleSet { selector, body, .. } => {
-                selector.is_invisible() || body.iter().all(CssStmt::is_invisible)
+                selector.is_invisible() || all_invisible(body)
             }
             CssStmt::Style(style) => style.value.node.is_blank(),
-            CssStmt::Media(media_rule, ..) => media_rule.body.iter().all(CssStmt::is_invisible),
-            CssStmt::UnknownAtRule(..) | CssStmt::Import(..) | CssStmt::Comment(..) => false,
-            CssStmt::Supports(supports_rule, ..) => {
-                supports_rule.body.iter().all(CssStmt::is_invisible)
-            }
-            CssStmt::KeyframesRuleSet(kf) => kf.body.iter().all(CssStmt::is_invisible),
+            CssStmt::Media(media_rule, ..) => all_invisible(&media_rule.body),
+            CssStmt::UnknownAtRule(..) | CssStmt::Import(..) => false,
+            CssStmt::Comment(comment, _) => options.is_compressed() && !comment.starts_with("/*!"),
+            CssStmt::Supports(supports_rule, ..) => all_invisible(&supports_rule.body),
+            CssStmt::KeyframesRuleSet(kf) => all_invisible(&kf.body),
         }
     }
 
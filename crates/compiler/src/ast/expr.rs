@@ -13,43 +13,69 @@ use super::{ArgumentInvocation, AstSupportsCondition, Interpolation, Interpolati
 
 /// Represented by the `if` function
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct Ternary(pub ArgumentInvocation);
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct ListExpr {
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::spanned_list")
+    )]
     pub elems: Vec<Spanned<AstExpr>>,
     pub separator: ListSeparator,
     pub brackets: Brackets,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct FunctionCallExpr {
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::optional_spanned")
+    )]
     pub namespace: Option<Spanned<Identifier>>,
     pub name: Identifier,
     pub arguments: Arc<ArgumentInvocation>,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct InterpolatedFunction {
     pub name: Interpolation,
     pub arguments: ArgumentInvocation,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone, Default)]
-pub struct AstSassMap(pub Vec<(Spanned<AstExpr>, AstExpr)>);
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+pub struct AstSassMap(
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::map_pairs")
+    )]
+    pub Vec<(Spanned<AstExpr>, AstExpr)>,
+);
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "camelCase"))]
 pub struct BinaryOpExpr {
     pub lhs: AstExpr,
     pub op: BinaryOp,
     pub rhs: AstExpr,
     pub allows_slash: bool,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(tag = "type", content = "value"))]
 pub enum AstExpr {
     BinaryOp(Arc<BinaryOpExpr>),
     True,
@@ -57,6 +83,8 @@ pub enum AstExpr {
     Calculation {
         name: CalculationName,
         args: Vec<Self>,
+        #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
+        span: Span,
     },
     Color(Arc<Color>),
     FunctionCall(FunctionCallExpr),
@@ -71,11 +99,26 @@ pub enum AstExpr {
     },
     Paren(Arc<Self>),
     ParentSelector,
-    String(StringExpr, Span),
+    String(
+        StringExpr,
+        #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))] Span,
+    ),
     Supports(Arc<AstSupportsCondition>),
-    UnaryOp(UnaryOp, Arc<Self>, Span),
+    UnaryOp(
+        UnaryOp,
+        Arc<Self>,
+        #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))] Span,
+    ),
     Variable {
+        #[cfg_attr(
+            feature = "ast-json",
+            serde(serialize_with = "super::serialize::spanned")
+        )]
         name: Spanned<Identifier>,
+        #[cfg_attr(
+            feature = "ast-json",
+            serde(serialize_with = "super::serialize::optional_spanned")
+        )]
         namespace: Option<Spanned<Identifier>>,
     },
 }
@@ -83,6 +126,7 @@ pub enum AstExpr {
 // todo: make quotes bool
 // todo: track span inside
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct StringExpr(pub Interpolation, pub QuoteKind);
 
 impl StringExpr {
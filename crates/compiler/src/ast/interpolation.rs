@@ -1,8 +1,12 @@
-use codemap::Spanned;
+use std::sync::Arc;
+
+use codemap::{File, Span, Spanned};
 
 use super::AstExpr;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(transparent))]
 pub struct Interpolation {
     pub contents: Vec<InterpolationPart>,
 }
@@ -41,6 +45,17 @@ impl Interpolation {
         }
     }
 
+    pub fn add_str(&mut self, s: &str) {
+        if s.is_empty() {
+            return;
+        }
+
+        match self.contents.last_mut() {
+            Some(InterpolationPart::String(existing)) => existing.push_str(s),
+            _ => self.contents.push(InterpolationPart::String(s.to_owned())),
+        }
+    }
+
     pub fn add_char(&mut self, c: char) {
         match self.contents.last_mut() {
             Some(InterpolationPart::String(existing)) => existing.push(c),
@@ -81,7 +96,126 @@ impl Interpolation {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(untagged))]
 pub enum InterpolationPart {
     String(String),
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::spanned")
+    )]
     Expr(Spanned<AstExpr>),
 }
+
+/// Maps byte offsets in the evaluated text of an [`Interpolation`] back to
+/// spans in the source it was parsed from
+///
+/// The evaluated text of an interpolation is frequently re-parsed (e.g. as a
+/// selector). Without this mapping, errors found while re-parsing would point
+/// into a synthetic string rather than the user's stylesheet.
+#[derive(Debug, Clone)]
+pub(crate) struct InterpolationMap {
+    file: Arc<File>,
+
+    /// The span of the entire interpolation in the source
+    span: Span,
+
+    /// For each expression in the interpolation, the range of bytes its value
+    /// occupies in the evaluated text, the span of the expression itself, and the
+    /// span including the surrounding `#{` and `}`
+    expressions: Vec<(usize, usize, Span, Span)>,
+
+    /// The number of bytes removed from the start of the evaluated text, e.g.
+    /// by trimming whitespace
+    offset: usize,
+}
+
+impl InterpolationMap {
+    pub fn new(file: Arc<File>, span: Span) -> Self {
+        Self {
+            file,
+            span,
+            expressions: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn add_expr(&mut self, start: usize, end: usize, span: Span) {
+        let outer = self.expand_to_delimiters(span);
+        self.expressions.push((start, end, span, outer));
+    }
+
+    pub fn trim_start(&mut self, len: usize) {
+        self.offset += len;
+    }
+
+    /// The span in the original source corresponding to `len` bytes of
+    /// evaluated text beginning at `start`
+    pub fn span_for(&self, start: usize, len: usize) -> Span {
+        let start = start + self.offset;
+        let begin = self.map_offset(start);
+
+        if len <= 1 {
+            return begin;
+        }
+
+        begin.merge(self.map_offset(start + len - 1))
+    }
+
+    fn map_offset(&self, offset: usize) -> Span {
+        if let Some((_, _, span, _)) = self
+            .expressions
+            .iter()
+            .find(|(start, end, ..)| *start <= offset && offset < *end)
+        {
+            return *span;
+        }
+
+        // plain text is assumed to appear verbatim in the source, so we locate it
+        // relative to the closest expression that precedes it. if no such expression
+        // exists, we instead work backwards from the first expression following it
+        let pos = match self
+            .expressions
+            .iter()
+            .rev()
+            .find(|(_, end, ..)| *end <= offset)
+        {
+            Some((_, end, _, outer)) => (outer.high() - self.span.low()) + (offset - end) as u64,
+            None => match self.expressions.first() {
+                Some((start, _, _, outer)) => {
+                    (outer.low() - self.span.low()).saturating_sub((start - offset) as u64)
+                }
+                None => offset as u64,
+            },
+        };
+
+        if self.span.len() == 0 {
+            return self.span;
+        }
+
+        let pos = pos.min(self.span.len() - 1);
+
+        self.span.subspan(pos, pos + 1)
+    }
+
+    /// Expands the span of an interpolated expression to include the `#{` and `}`
+    /// surrounding it, if they are present within the interpolation
+    fn expand_to_delimiters(&self, span: Span) -> Span {
+        if !self.span.contains(span) {
+            return span;
+        }
+
+        let source = self.file.source_slice(self.span);
+        let low = (span.low() - self.span.low()) as usize;
+        let high = (span.high() - self.span.low()) as usize;
+
+        let start = source[..low].rfind("#{").unwrap_or(low);
+        let end = source[high..].find('}').map_or(high, |idx| high + idx + 1);
+
+        self.span.subspan(start as u64, end as u64)
+    }
+}
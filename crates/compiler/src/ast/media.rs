@@ -1,8 +1,11 @@
 use std::fmt::{self, Write};
 
-use codemap::Span;
-
-use crate::{ast::CssStmt, error::SassResult, lexer::Lexer, parse::MediaQueryParser};
+use crate::{
+    ast::{CssStmt, InterpolationMap},
+    error::SassResult,
+    lexer::Lexer,
+    parse::MediaQueryParser,
+};
 
 #[derive(Debug, Clone)]
 pub(crate) struct MediaRule {
@@ -24,7 +27,7 @@ impl MediaQuery {
             || self
                 .media_type
                 .as_ref()
-                .map_or(false, |v| v.to_ascii_lowercase() == "all")
+                .is_some_and(|v| v.to_ascii_lowercase() == "all")
     }
 
     pub fn condition(
@@ -53,8 +56,8 @@ impl MediaQuery {
         }
     }
 
-    pub fn parse_list(list: &str, span: Span) -> SassResult<Vec<Self>> {
-        let toks = Lexer::new_from_string(list, span);
+    pub(crate) fn parse_list(list: &str, map: InterpolationMap) -> SassResult<Vec<Self>> {
+        let toks = Lexer::new_from_interpolation(list, map);
 
         MediaQueryParser::new(toks).parse()
     }
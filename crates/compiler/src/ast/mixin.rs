@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 use crate::{
     ast::ArgumentResult,
@@ -12,7 +12,7 @@ pub(crate) use crate::ast::AstMixin as UserDefinedMixin;
 
 #[derive(Clone)]
 pub(crate) enum Mixin {
-    UserDefined(UserDefinedMixin, Environment),
+    UserDefined(Arc<UserDefinedMixin>, Environment),
     Builtin(BuiltinMixin),
 }
 
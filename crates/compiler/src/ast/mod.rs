@@ -9,6 +9,8 @@ pub(crate) use style::*;
 pub(crate) use unknown::*;
 
 pub use args::ArgumentResult;
+#[cfg(feature = "ast-json")]
+pub(crate) use serialize::to_json;
 
 mod args;
 mod css;
@@ -16,6 +18,8 @@ mod expr;
 mod interpolation;
 mod media;
 mod mixin;
+#[cfg(feature = "ast-json")]
+mod serialize;
 mod stmt;
 mod style;
 mod unknown;
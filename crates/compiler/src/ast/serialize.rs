@@ -0,0 +1,203 @@
+//! Serialization of the parsed stylesheet with `serde`, for tools written in
+//! other languages
+//!
+//! Most of the syntax tree derives `Serialize` directly. This module provides
+//! the pieces which can't: types from other crates, such as spans, and interned
+//! or computed values, which are written as the text they represent.
+
+use std::{cell::RefCell, collections::HashSet, path::Path, sync::Arc};
+
+use codemap::{File, LineCol, Span, Spanned};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+use crate::{
+    ast::StyleSheet,
+    color::{Color, ColorFormat},
+    common::{BinaryOp, Identifier},
+    unit::Unit,
+    utils::look_up_span_in_file,
+    value::{CalculationName, Number},
+};
+
+thread_local! {
+    /// The file spans are looked up in while serializing
+    static FILE: RefCell<Option<Arc<File>>> = const { RefCell::new(None) };
+}
+
+/// Serialize `stylesheet`, which was parsed from `file`, as JSON
+pub(crate) fn to_json(stylesheet: &StyleSheet, file: &Arc<File>) -> String {
+    let previous = FILE.with(|current| current.replace(Some(Arc::clone(file))));
+    let json = serde_json::to_string(stylesheet);
+    FILE.with(|current| current.replace(previous));
+
+    json.expect("the syntax tree only contains serializable values")
+}
+
+/// A span, as its file and the (1-indexed) lines and columns of its start and
+/// end, in the same format as spans in errors, and with the same definition of
+/// a line break
+///
+/// Spans outside of the file being serialized are written as `null`.
+pub(crate) fn span<S: Serializer>(span: &Span, serializer: S) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Location {
+        line: usize,
+        column: usize,
+    }
+
+    let file = FILE.with(|file| file.borrow().clone());
+    let file = match file {
+        Some(file) if file.span.contains(*span) => file,
+        _ => return serializer.serialize_none(),
+    };
+
+    let loc = look_up_span_in_file(&file, *span);
+    let location = |location: LineCol| Location {
+        line: location.line + 1,
+        column: location.column + 1,
+    };
+
+    let mut state = serializer.serialize_struct("Span", 3)?;
+    state.serialize_field("file", file.name())?;
+    state.serialize_field("start", &location(loc.begin))?;
+    state.serialize_field("end", &location(loc.end))?;
+    state.end()
+}
+
+/// A node, as `{"node": ..., "span": ...}`
+pub(crate) fn spanned<T: Serialize, S: Serializer>(
+    spanned: &Spanned<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    struct SpanOf<'a>(&'a Span);
+
+    impl Serialize for SpanOf<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            span(self.0, serializer)
+        }
+    }
+
+    let mut state = serializer.serialize_struct("Spanned", 2)?;
+    state.serialize_field("node", &spanned.node)?;
+    state.serialize_field("span", &SpanOf(&spanned.span))?;
+    state.end()
+}
+
+pub(crate) fn optional_spanned<T: Serialize, S: Serializer>(
+    value: &Option<Spanned<T>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => spanned(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub(crate) fn spanned_list<T: Serialize, S: Serializer>(
+    values: &[Spanned<T>],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    struct Item<'a, T>(&'a Spanned<T>);
+
+    impl<T: Serialize> Serialize for Item<'_, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            spanned(self.0, serializer)
+        }
+    }
+
+    serializer.collect_seq(values.iter().map(Item))
+}
+
+/// The pairs of a map literal, as `[[key, value], ...]`
+pub(crate) fn map_pairs<K: Serialize, V: Serialize, S: Serializer>(
+    pairs: &[(Spanned<K>, V)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    struct Pair<'a, K, V>(&'a (Spanned<K>, V));
+
+    impl<K: Serialize, V: Serialize> Serialize for Pair<'_, K, V> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            struct Key<'a, K>(&'a Spanned<K>);
+
+            impl<K: Serialize> Serialize for Key<'_, K> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    spanned(self.0, serializer)
+                }
+            }
+
+            (Key(&self.0 .0), &self.0 .1).serialize(serializer)
+        }
+    }
+
+    serializer.collect_seq(pairs.iter().map(Pair))
+}
+
+/// A set of names, such as those shown by a `@forward`, in sorted order
+pub(crate) fn sorted_names<S: Serializer>(
+    names: &Option<HashSet<Identifier>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match names {
+        Some(names) => {
+            let mut names: Vec<String> = names.iter().map(ToString::to_string).collect();
+            names.sort_unstable();
+            serializer.collect_seq(names)
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
+pub(crate) fn path<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&path.to_string_lossy())
+}
+
+impl Serialize for Identifier {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Serialize for Number {
+    /// Infinite and `NaN` numbers are written as `null`
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.0)
+    }
+}
+
+impl Serialize for Unit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Serialize for BinaryOp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Serialize for CalculationName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Serialize for Color {
+    /// The channels of the color, along with its text if it was written as a
+    /// name or a hex code
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Color", 5)?;
+        state.serialize_field(
+            "text",
+            &match &self.format {
+                ColorFormat::Literal(text) => Some(text),
+                _ => None,
+            },
+        )?;
+        state.serialize_field("red", &self.red())?;
+        state.serialize_field("green", &self.green())?;
+        state.serialize_field("blue", &self.blue())?;
+        state.serialize_field("alpha", &self.alpha())?;
+        state.end()
+    }
+}
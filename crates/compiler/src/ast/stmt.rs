@@ -1,6 +1,7 @@
 use std::{
     cell::RefCell,
     collections::{BTreeMap, HashSet},
+    mem,
     path::PathBuf,
     sync::Arc,
 };
@@ -17,66 +18,101 @@ use crate::{
 
 #[derive(Debug, Clone)]
 #[allow(unused)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstSilentComment {
     pub text: String,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstPlainCssImport {
     pub url: Interpolation,
     pub modifiers: Option<Interpolation>,
     #[allow(unused)]
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstSassImport {
     pub url: String,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "camelCase"))]
 pub struct AstIf {
     pub if_clauses: Vec<AstIfClause>,
     pub else_clause: Option<Vec<AstStmt>>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstIfClause {
     pub condition: AstExpr,
     pub body: Vec<AstStmt>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "camelCase"))]
 pub struct AstFor {
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::spanned")
+    )]
     pub variable: Spanned<Identifier>,
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::spanned")
+    )]
     pub from: Spanned<AstExpr>,
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::spanned")
+    )]
     pub to: Spanned<AstExpr>,
     pub is_exclusive: bool,
     pub body: Vec<AstStmt>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstReturn {
     pub val: AstExpr,
     #[allow(unused)]
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "camelCase"))]
 pub struct AstRuleSet {
     pub selector: Interpolation,
     pub body: Vec<AstStmt>,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub selector_span: Span,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstStyle {
     pub name: Interpolation,
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::optional_spanned")
+    )]
     pub value: Option<Spanned<AstExpr>>,
     pub body: Vec<AstStmt>,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
@@ -87,6 +123,7 @@ impl AstStyle {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstEach {
     pub variables: Vec<Identifier>,
     pub list: AstExpr,
@@ -94,53 +131,76 @@ pub struct AstEach {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "camelCase"))]
 pub struct AstMedia {
     pub query: Interpolation,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub query_span: Span,
     pub body: Vec<AstStmt>,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 pub type CssMediaQuery = MediaQuery;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstWhile {
     pub condition: AstExpr,
     pub body: Vec<AstStmt>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "camelCase"))]
 pub struct AstVariableDecl {
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::optional_spanned")
+    )]
     pub namespace: Option<Spanned<Identifier>>,
     pub name: Identifier,
     pub value: AstExpr,
     pub is_guarded: bool,
     pub is_global: bool,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstFunctionDecl {
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::spanned")
+    )]
     pub name: Spanned<Identifier>,
     pub arguments: ArgumentDeclaration,
     pub body: Vec<AstStmt>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstDebugRule {
     pub value: AstExpr,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstWarn {
     pub value: AstExpr,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstErrorRule {
     pub value: AstExpr,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
@@ -153,12 +213,16 @@ impl PartialEq for AstFunctionDecl {
 impl Eq for AstFunctionDecl {}
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstLoudComment {
     pub text: Interpolation,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "camelCase"))]
 pub struct AstMixin {
     pub name: Identifier,
     pub args: ArgumentDeclaration,
@@ -168,45 +232,70 @@ pub struct AstMixin {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstContentRule {
     pub args: ArgumentInvocation,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstContentBlock {
     pub args: ArgumentDeclaration,
     pub body: Vec<AstStmt>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstInclude {
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::optional_spanned")
+    )]
     pub namespace: Option<Spanned<Identifier>>,
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::spanned")
+    )]
     pub name: Spanned<Identifier>,
     pub args: ArgumentInvocation,
-    pub content: Option<AstContentBlock>,
+    pub content: Option<Arc<AstContentBlock>>,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstUnknownAtRule {
     pub name: Interpolation,
     pub value: Option<Interpolation>,
     pub body: Option<Vec<AstStmt>>,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "camelCase"))]
 pub struct AstExtendRule {
     pub value: Interpolation,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
+    pub value_span: Span,
     pub is_optional: bool,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstAtRootRule {
     pub body: Vec<AstStmt>,
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::optional_spanned")
+    )]
     pub query: Option<Spanned<Interpolation>>,
     #[allow(unused)]
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
@@ -266,11 +355,14 @@ impl Default for AtRootQuery {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstImportRule {
     pub imports: Vec<AstImport>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(tag = "type", content = "value"))]
 pub enum AstImport {
     Plain(AstPlainCssImport),
     Sass(AstSassImport),
@@ -283,16 +375,28 @@ impl AstImport {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstUseRule {
     pub url: PathBuf,
     pub namespace: Option<String>,
     pub configuration: Vec<ConfiguredVariable>,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "camelCase"))]
 pub struct ConfiguredVariable {
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::spanned")
+    )]
     pub name: Spanned<Identifier>,
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::spanned")
+    )]
     pub expr: Spanned<AstExpr>,
     pub is_guarded: bool,
 }
@@ -425,14 +529,33 @@ impl ConfiguredValue {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "camelCase"))]
 pub struct AstForwardRule {
     pub url: PathBuf,
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::sorted_names")
+    )]
     pub shown_mixins_and_functions: Option<HashSet<Identifier>>,
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::sorted_names")
+    )]
     pub shown_variables: Option<HashSet<Identifier>>,
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::sorted_names")
+    )]
     pub hidden_mixins_and_functions: Option<HashSet<Identifier>>,
+    #[cfg_attr(
+        feature = "ast-json",
+        serde(serialize_with = "super::serialize::sorted_names")
+    )]
     pub hidden_variables: Option<HashSet<Identifier>>,
     pub prefix: Option<String>,
     pub configuration: Vec<ConfiguredVariable>,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
@@ -497,6 +620,8 @@ impl AstForwardRule {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(tag = "type", content = "value"))]
 pub enum AstSupportsCondition {
     Anything {
         contents: Interpolation,
@@ -519,13 +644,17 @@ pub enum AstSupportsCondition {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
 pub struct AstSupportsRule {
     pub condition: AstSupportsCondition,
     pub body: Vec<AstStmt>,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::span"))]
     pub span: Span,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(tag = "type", content = "value"))]
 pub enum AstStmt {
     If(AstIf),
     For(AstFor),
@@ -539,8 +668,8 @@ pub enum AstStmt {
     VariableDecl(AstVariableDecl),
     LoudComment(AstLoudComment),
     SilentComment(AstSilentComment),
-    FunctionDecl(AstFunctionDecl),
-    Mixin(AstMixin),
+    FunctionDecl(Arc<AstFunctionDecl>),
+    Mixin(Arc<AstMixin>),
     ContentRule(AstContentRule),
     Warn(AstWarn),
     UnknownAtRule(AstUnknownAtRule),
@@ -554,17 +683,109 @@ pub enum AstStmt {
     Supports(AstSupportsRule),
 }
 
+impl AstStmt {
+    /// The blocks of statements nested directly within this one
+    pub(crate) fn child_blocks(&self) -> Vec<&[AstStmt]> {
+        match self {
+            AstStmt::If(if_stmt) => if_stmt
+                .if_clauses
+                .iter()
+                .map(|clause| clause.body.as_slice())
+                .chain(if_stmt.else_clause.as_deref())
+                .collect(),
+            AstStmt::For(AstFor { body, .. })
+            | AstStmt::RuleSet(AstRuleSet { body, .. })
+            | AstStmt::Style(AstStyle { body, .. })
+            | AstStmt::Each(AstEach { body, .. })
+            | AstStmt::Media(AstMedia { body, .. })
+            | AstStmt::While(AstWhile { body, .. })
+            | AstStmt::AtRootRule(AstAtRootRule { body, .. })
+            | AstStmt::Supports(AstSupportsRule { body, .. })
+            | AstStmt::UnknownAtRule(AstUnknownAtRule {
+                body: Some(body), ..
+            }) => vec![body],
+            AstStmt::FunctionDecl(decl) => vec![&decl.body],
+            AstStmt::Mixin(mixin) => vec![&mixin.body],
+            AstStmt::Include(AstInclude {
+                content: Some(content),
+                ..
+            }) => vec![&content.body],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Move the statements nested directly within this one into `stmts`
+    fn take_children(&mut self, stmts: &mut Vec<AstStmt>) {
+        match self {
+            AstStmt::If(if_stmt) => {
+                for clause in &mut if_stmt.if_clauses {
+                    stmts.append(&mut clause.body);
+                }
+                if let Some(else_clause) = &mut if_stmt.else_clause {
+                    stmts.append(else_clause);
+                }
+            }
+            AstStmt::For(AstFor { body, .. })
+            | AstStmt::RuleSet(AstRuleSet { body, .. })
+            | AstStmt::Style(AstStyle { body, .. })
+            | AstStmt::Each(AstEach { body, .. })
+            | AstStmt::Media(AstMedia { body, .. })
+            | AstStmt::While(AstWhile { body, .. })
+            | AstStmt::AtRootRule(AstAtRootRule { body, .. })
+            | AstStmt::Supports(AstSupportsRule { body, .. })
+            | AstStmt::UnknownAtRule(AstUnknownAtRule {
+                body: Some(body), ..
+            }) => stmts.append(body),
+            AstStmt::FunctionDecl(decl) => {
+                if let Some(decl) = Arc::get_mut(decl) {
+                    stmts.append(&mut decl.body);
+                }
+            }
+            AstStmt::Mixin(mixin) => {
+                if let Some(mixin) = Arc::get_mut(mixin) {
+                    stmts.append(&mut mixin.body);
+                }
+            }
+            AstStmt::Include(AstInclude {
+                content: Some(content),
+                ..
+            }) => {
+                if let Some(content) = Arc::get_mut(content) {
+                    stmts.append(&mut content.body);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(tag = "type", rename_all = "camelCase"))]
 pub struct StyleSheet {
     pub body: Vec<AstStmt>,
+    #[cfg_attr(feature = "ast-json", serde(serialize_with = "super::serialize::path"))]
     pub url: PathBuf,
     pub is_plain_css: bool,
     /// Array of indices into `body`
+    #[cfg_attr(feature = "ast-json", serde(skip))]
     pub uses: Vec<usize>,
     /// Array of indices into `body`
+    #[cfg_attr(feature = "ast-json", serde(skip))]
     pub forwards: Vec<usize>,
 }
 
+impl Drop for StyleSheet {
+    /// Take apart the body one statement at a time, as dropping it directly
+    /// would recurse once for every level of nesting
+    fn drop(&mut self) {
+        let mut stmts = mem::take(&mut self.body);
+        while let Some(mut stmt) = stmts.pop() {
+            stmt.take_children(&mut stmts);
+        }
+    }
+}
+
 impl StyleSheet {
     pub fn new(is_plain_css: bool, url: PathBuf) -> Self {
         Self {
@@ -1,9 +1,33 @@
-use crate::ast::CssStmt;
+use std::borrow::Cow;
+
+use crate::{ast::CssStmt, common::known_name};
+
+/// At-rules common enough that their names are borrowed from here, rather than
+/// allocated for each rule
+static KNOWN_NAMES: phf::Set<&'static str> = phf::phf_set! {
+    "charset",
+    "container",
+    "counter-style",
+    "document",
+    "font-face",
+    "font-feature-values",
+    "font-palette-values",
+    "keyframes",
+    "-moz-keyframes",
+    "-webkit-keyframes",
+    "layer",
+    "namespace",
+    "page",
+    "property",
+    "scope",
+    "starting-style",
+    "viewport",
+};
 
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub(crate) struct UnknownAtRule {
-    pub name: String,
+    pub name: Cow<'static, str>,
     // pub super_selector: Selector,
     pub params: String,
     pub body: Vec<CssStmt>,
@@ -12,3 +36,9 @@ pub(crate) struct UnknownAtRule {
     /// braces. A body may not necessarily have contents
     pub has_body: bool,
 }
+
+impl UnknownAtRule {
+    pub fn name_from(name: String) -> Cow<'static, str> {
+        known_name(name, &KNOWN_NAMES)
+    }
+}
@@ -0,0 +1,176 @@
+//! Helpers for compiling Sass from a Cargo build script
+//!
+//! Each function prints a `cargo:rerun-if-changed` line for every file the
+//! compilation loaded, including files found through load paths, so that the
+//! build script is rerun exactly when the output could change.
+//!
+//! ```no_run
+//! // build.rs
+//! # use grass_compiler as grass;
+//! fn main() {
+//!     grass::build_helper::compile_dir_to_out_dir("styles", &grass::Options::default())
+//!         .unwrap_or_else(|e| panic!("{}", e));
+//! }
+//! ```
+//!
+//! The compiled CSS can then be included in the crate:
+//!
+//! ```ignore
+//! static CSS: &str = include_str!(concat!(env!("OUT_DIR"), "/main.css"));
+//! ```
+
+use std::{
+    collections::BTreeSet,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{Compiler, Options, Result};
+
+/// Compile `entrypoint` to a file of the same name with a `.css` extension in
+/// the directory Cargo sets as `OUT_DIR`, returning the path of that file
+pub fn compile_file_to_out_dir<P: AsRef<Path>>(
+    entrypoint: P,
+    options: &Options,
+) -> Result<PathBuf> {
+    let entrypoint = entrypoint.as_ref();
+    let out_dir = out_dir()?;
+
+    let output = out_dir.join(css_file_name(entrypoint));
+
+    compile_file(&Compiler::new(), entrypoint, &output, options)?;
+
+    Ok(output)
+}
+
+/// Compile every Sass file in `dir` to the directory Cargo sets as `OUT_DIR`,
+/// returning the paths of the files written
+///
+/// See [`compile_dir`] for details.
+pub fn compile_dir_to_out_dir<P: AsRef<Path>>(dir: P, options: &Options) -> Result<Vec<PathBuf>> {
+    compile_dir(dir, out_dir()?, options)
+}
+
+/// Compile every Sass file in `dir` to `out_dir`, returning the paths of the
+/// files written
+///
+/// `dir` is searched recursively for files with a `.scss` or `.sass`
+/// extension, skipping partials, whose names begin with `_`. A file's output
+/// has the same path relative to `out_dir` as the file has relative to `dir`,
+/// but with a `.css` extension. Directories are read from disk rather than
+/// through [`Options::fs`].
+///
+/// `dir` itself is printed as a `cargo:rerun-if-changed` path, so that adding a
+/// new file to it reruns the build script.
+pub fn compile_dir<P: AsRef<Path>, Q: AsRef<Path>>(
+    dir: P,
+    out_dir: Q,
+    options: &Options,
+) -> Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    let out_dir = out_dir.as_ref();
+
+    rerun_if_changed(dir);
+
+    let mut entrypoints = Vec::new();
+    find_entrypoints(dir, &mut entrypoints)?;
+
+    let compiler = Compiler::new();
+    let mut outputs = Vec::new();
+
+    for entrypoint in entrypoints {
+        let relative = entrypoint.strip_prefix(dir).unwrap_or(&entrypoint);
+        let output = out_dir.join(relative).with_extension("css");
+
+        compile_file(&compiler, &entrypoint, &output, options)?;
+
+        outputs.push(output);
+    }
+
+    Ok(outputs)
+}
+
+fn compile_file(
+    compiler: &Compiler,
+    entrypoint: &Path,
+    output: &Path,
+    options: &Options,
+) -> Result<()> {
+    let result = compiler.compile(entrypoint, options);
+
+    // files are watched even if compilation fails, so that fixing the error
+    // reruns the build script
+    let dependencies = compiler
+        .dependencies(entrypoint, options)
+        .unwrap_or_default()
+        .into_iter()
+        .chain(std::iter::once(entrypoint.to_path_buf()))
+        .collect::<BTreeSet<PathBuf>>();
+
+    for dependency in &dependencies {
+        rerun_if_changed(dependency);
+    }
+
+    let css = result?;
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(output, css)?;
+
+    Ok(())
+}
+
+/// Add every Sass file in `dir` to `entrypoints`, searching recursively and
+/// skipping partials, in the order [`compile_dir`] compiles them
+pub fn find_entrypoints(dir: &Path, entrypoints: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+
+    // sort so that the order of compilation, and so of any warnings, is stable
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            find_entrypoints(&path, entrypoints)?;
+            continue;
+        }
+
+        let is_partial = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(true, |name| name.starts_with('_'));
+
+        let is_sass = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("scss" | "sass")
+        );
+
+        if is_sass && !is_partial {
+            entrypoints.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn css_file_name(entrypoint: &Path) -> PathBuf {
+    Path::new(entrypoint.file_name().unwrap_or_default()).with_extension("css")
+}
+
+fn out_dir() -> Result<PathBuf> {
+    match env::var_os("OUT_DIR") {
+        Some(out_dir) => Ok(PathBuf::from(out_dir)),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "OUT_DIR is not set, so grass::build_helper must be run from a build script",
+        )
+        .into()),
+    }
+}
+
+fn rerun_if_changed(path: &Path) {
+    println!("cargo:rerun-if-changed={}", path.display());
+}
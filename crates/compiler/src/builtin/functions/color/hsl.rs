@@ -160,6 +160,12 @@ pub(crate) fn lightness(mut args: ArgumentResult, visitor: &mut Visitor) -> Sass
 
 pub(crate) fn adjust_hue(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
     args.max_args(2)?;
+    visitor.emit_deprecation_warning(
+        Deprecation::ColorFunctions,
+        "adjust-hue() is deprecated.\n\nRecommendation: color.adjust($color, $hue: $degrees)",
+        args.span(),
+    )?;
+
     let color = args
         .get_err(0, "color")?
         .assert_color_with_name("color", args.span())?;
@@ -170,6 +176,12 @@ pub(crate) fn adjust_hue(mut args: ArgumentResult, visitor: &mut Visitor) -> Sas
 
 fn lighten(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
     args.max_args(2)?;
+    visitor.emit_deprecation_warning(
+        Deprecation::ColorFunctions,
+        "lighten() is deprecated.\n\nRecommendation: color.adjust($color, $lightness: $amount)",
+        args.span(),
+    )?;
+
     let color = args
         .get_err(0, "color")?
         .assert_color_with_name("color", args.span())?;
@@ -187,6 +199,12 @@ fn lighten(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value>
 
 fn darken(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
     args.max_args(2)?;
+    visitor.emit_deprecation_warning(
+        Deprecation::ColorFunctions,
+        "darken() is deprecated.\n\nRecommendation: color.adjust($color, $lightness: -$amount)",
+        args.span(),
+    )?;
+
     let color = args
         .get_err(0, "color")?
         .assert_color_with_name("color", args.span())?;
@@ -218,6 +236,12 @@ fn saturate(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value
         ));
     }
 
+    visitor.emit_deprecation_warning(
+        Deprecation::ColorFunctions,
+        "saturate() is deprecated.\n\nRecommendation: color.adjust($color, $saturation: $amount)",
+        args.span(),
+    )?;
+
     let mut amount = args
         .get_err(1, "amount")?
         .assert_number_with_name("amount", args.span())?;
@@ -235,6 +259,12 @@ fn saturate(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value
 
 fn desaturate(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
     args.max_args(2)?;
+    visitor.emit_deprecation_warning(
+        Deprecation::ColorFunctions,
+        "desaturate() is deprecated.\n\nRecommendation: color.adjust($color, $saturation: -$amount)",
+        args.span(),
+    )?;
+
     let color = args
         .get_err(0, "color")?
         .assert_color_with_name("color", args.span())?;
@@ -329,17 +359,17 @@ pub(crate) fn invert(mut args: ArgumentResult, visitor: &mut Visitor) -> SassRes
 }
 
 pub(crate) fn declare(f: &mut GlobalFunctionMap) {
-    f.insert("hsl", Builtin::new(hsl));
-    f.insert("hsla", Builtin::new(hsla));
-    f.insert("hue", Builtin::new(hue));
-    f.insert("saturation", Builtin::new(saturation));
-    f.insert("adjust-hue", Builtin::new(adjust_hue));
-    f.insert("lightness", Builtin::new(lightness));
-    f.insert("lighten", Builtin::new(lighten));
-    f.insert("darken", Builtin::new(darken));
-    f.insert("saturate", Builtin::new(saturate));
-    f.insert("desaturate", Builtin::new(desaturate));
-    f.insert("grayscale", Builtin::new(grayscale));
-    f.insert("complement", Builtin::new(complement));
-    f.insert("invert", Builtin::new(invert));
+    f.insert("hsl", Builtin::new_pure(hsl));
+    f.insert("hsla", Builtin::new_pure(hsla));
+    f.insert("hue", Builtin::new_pure(hue));
+    f.insert("saturation", Builtin::new_pure(saturation));
+    f.insert("adjust-hue", Builtin::new_pure(adjust_hue));
+    f.insert("lightness", Builtin::new_pure(lightness));
+    f.insert("lighten", Builtin::new_pure(lighten));
+    f.insert("darken", Builtin::new_pure(darken));
+    f.insert("saturate", Builtin::new_pure(saturate));
+    f.insert("desaturate", Builtin::new_pure(desaturate));
+    f.insert("grayscale", Builtin::new_pure(grayscale));
+    f.insert("complement", Builtin::new_pure(complement));
+    f.insert("invert", Builtin::new_pure(invert));
 }
@@ -4,7 +4,7 @@ use crate::builtin::builtin_imports::*;
 fn is_ms_filter(s: &str) -> bool {
     let mut bytes = s.bytes();
 
-    if !bytes.next().map_or(false, |c| c.is_ascii_alphabetic()) {
+    if !bytes.next().is_some_and(|c| c.is_ascii_alphabetic()) {
         return false;
     }
 
@@ -86,6 +86,12 @@ pub(crate) fn opacity(mut args: ArgumentResult, visitor: &mut Visitor) -> SassRe
 
 fn opacify(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
     args.max_args(2)?;
+    visitor.emit_deprecation_warning(
+        Deprecation::ColorFunctions,
+        "opacify() and fade-in() are deprecated.\n\nRecommendation: color.adjust($color, $alpha: $amount)",
+        args.span(),
+    )?;
+
     let color = args
         .get_err(0, "color")?
         .assert_color_with_name("color", args.span())?;
@@ -100,6 +106,12 @@ fn opacify(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value>
 
 fn transparentize(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
     args.max_args(2)?;
+    visitor.emit_deprecation_warning(
+        Deprecation::ColorFunctions,
+        "transparentize() and fade-out() are deprecated.\n\nRecommendation: color.adjust($color, $alpha: -$amount)",
+        args.span(),
+    )?;
+
     let color = args
         .get_err(0, "color")?
         .assert_color_with_name("color", args.span())?;
@@ -114,10 +126,10 @@ fn transparentize(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult
 }
 
 pub(crate) fn declare(f: &mut GlobalFunctionMap) {
-    f.insert("alpha", Builtin::new(alpha));
-    f.insert("opacity", Builtin::new(opacity));
-    f.insert("opacify", Builtin::new(opacify));
-    f.insert("fade-in", Builtin::new(opacify));
-    f.insert("transparentize", Builtin::new(transparentize));
-    f.insert("fade-out", Builtin::new(transparentize));
+    f.insert("alpha", Builtin::new_pure(alpha));
+    f.insert("opacity", Builtin::new_pure(opacity));
+    f.insert("opacify", Builtin::new_pure(opacify));
+    f.insert("fade-in", Builtin::new_pure(opacify));
+    f.insert("transparentize", Builtin::new_pure(transparentize));
+    f.insert("fade-out", Builtin::new_pure(transparentize));
 }
@@ -250,8 +250,8 @@ pub(crate) fn ie_hex_str(mut args: ArgumentResult, visitor: &mut Visitor) -> Sas
 }
 
 pub(crate) fn declare(f: &mut GlobalFunctionMap) {
-    f.insert("change-color", Builtin::new(change_color));
-    f.insert("adjust-color", Builtin::new(adjust_color));
-    f.insert("scale-color", Builtin::new(scale_color));
-    f.insert("ie-hex-str", Builtin::new(ie_hex_str));
+    f.insert("change-color", Builtin::new_pure(change_color));
+    f.insert("adjust-color", Builtin::new_pure(adjust_color));
+    f.insert("scale-color", Builtin::new_pure(scale_color));
+    f.insert("ie-hex-str", Builtin::new_pure(ie_hex_str));
 }
@@ -410,10 +410,10 @@ pub(crate) fn mix(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult
 }
 
 pub(crate) fn declare(f: &mut GlobalFunctionMap) {
-    f.insert("rgb", Builtin::new(rgb));
-    f.insert("rgba", Builtin::new(rgba));
-    f.insert("red", Builtin::new(red));
-    f.insert("green", Builtin::new(green));
-    f.insert("blue", Builtin::new(blue));
-    f.insert("mix", Builtin::new(mix));
+    f.insert("rgb", Builtin::new_pure(rgb));
+    f.insert("rgba", Builtin::new_pure(rgba));
+    f.insert("red", Builtin::new_pure(red));
+    f.insert("green", Builtin::new_pure(green));
+    f.insert("blue", Builtin::new_pure(blue));
+    f.insert("mix", Builtin::new_pure(mix));
 }
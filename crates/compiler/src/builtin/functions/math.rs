@@ -222,14 +222,14 @@ pub(crate) fn divide(mut args: ArgumentResult, visitor: &mut Visitor) -> SassRes
 }
 
 pub(crate) fn declare(f: &mut GlobalFunctionMap) {
-    f.insert("percentage", Builtin::new(percentage));
-    f.insert("round", Builtin::new(round));
-    f.insert("ceil", Builtin::new(ceil));
-    f.insert("floor", Builtin::new(floor));
-    f.insert("abs", Builtin::new(abs));
-    f.insert("min", Builtin::new(min));
-    f.insert("max", Builtin::new(max));
-    f.insert("comparable", Builtin::new(comparable));
+    f.insert("percentage", Builtin::new_pure(percentage));
+    f.insert("round", Builtin::new_pure(round));
+    f.insert("ceil", Builtin::new_pure(ceil));
+    f.insert("floor", Builtin::new_pure(floor));
+    f.insert("abs", Builtin::new_pure(abs));
+    f.insert("min", Builtin::new_pure(min));
+    f.insert("max", Builtin::new_pure(max));
+    f.insert("comparable", Builtin::new_pure(comparable));
     #[cfg(feature = "random")]
     f.insert("random", Builtin::new(random));
 }
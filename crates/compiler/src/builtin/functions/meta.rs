@@ -2,7 +2,7 @@ use crate::builtin::builtin_imports::*;
 
 // todo: this should be a constant of some sort. we shouldn't be allocating this
 // every time
-pub(crate) fn if_arguments() -> ArgumentDeclaration {
+pub(crate) fn if_arguments(span: Span) -> ArgumentDeclaration {
     ArgumentDeclaration {
         args: vec![
             Argument {
@@ -19,6 +19,7 @@ pub(crate) fn if_arguments() -> ArgumentDeclaration {
             },
         ],
         rest: None,
+        span,
     }
 }
 
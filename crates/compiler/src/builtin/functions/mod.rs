@@ -11,12 +11,18 @@ use once_cell::sync::Lazy;
 
 use crate::{ast::ArgumentResult, error::SassResult, evaluate::Visitor, value::Value};
 
+#[cfg(feature = "color-fns")]
 pub mod color;
+#[cfg(feature = "list-fns")]
 pub mod list;
+#[cfg(feature = "map-fns")]
 pub mod map;
+#[cfg(feature = "math-fns")]
 pub mod math;
 pub mod meta;
+#[cfg(feature = "selector-fns")]
 pub mod selector;
+#[cfg(feature = "string-fns")]
 pub mod string;
 
 // todo: maybe Identifier instead of str?
@@ -54,6 +60,9 @@ static FUNCTION_COUNT: AtomicUsize = AtomicUsize::new(0);
 pub struct Builtin(
     pub(crate) fn(ArgumentResult, &mut Visitor) -> SassResult<Value>,
     usize,
+    /// Whether this function always returns the same value for the same
+    /// arguments, allowing its results to be memoized
+    bool,
 );
 
 impl fmt::Debug for Builtin {
@@ -61,6 +70,7 @@ impl fmt::Debug for Builtin {
         f.debug_struct("Builtin")
             .field("id", &self.1)
             .field("fn_ptr", &(self.0 as usize))
+            .field("pure", &self.2)
             .finish()
     }
 }
@@ -68,7 +78,22 @@ impl fmt::Debug for Builtin {
 impl Builtin {
     pub fn new(body: fn(ArgumentResult, &mut Visitor) -> SassResult<Value>) -> Builtin {
         let count = FUNCTION_COUNT.fetch_add(1, Ordering::Relaxed);
-        Self(body, count)
+        Self(body, count, false)
+    }
+
+    /// A builtin with no side effects other than warnings, whose result
+    /// depends only on its arguments
+    pub(crate) fn new_pure(body: fn(ArgumentResult, &mut Visitor) -> SassResult<Value>) -> Builtin {
+        let count = FUNCTION_COUNT.fetch_add(1, Ordering::Relaxed);
+        Self(body, count, true)
+    }
+
+    pub(crate) const fn id(&self) -> usize {
+        self.1
+    }
+
+    pub(crate) const fn is_pure(&self) -> bool {
+        self.2
     }
 }
 
@@ -82,12 +107,18 @@ impl Eq for Builtin {}
 
 pub(crate) static GLOBAL_FUNCTIONS: Lazy<GlobalFunctionMap> = Lazy::new(|| {
     let mut m = HashMap::new();
+    #[cfg(feature = "color-fns")]
     color::declare(&mut m);
+    #[cfg(feature = "list-fns")]
     list::declare(&mut m);
+    #[cfg(feature = "map-fns")]
     map::declare(&mut m);
+    #[cfg(feature = "math-fns")]
     math::declare(&mut m);
     meta::declare(&mut m);
+    #[cfg(feature = "selector-fns")]
     selector::declare(&mut m);
+    #[cfg(feature = "string-fns")]
     string::declare(&mut m);
     m
 });
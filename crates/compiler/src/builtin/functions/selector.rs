@@ -27,22 +27,12 @@ pub(crate) fn simple_selectors(
     visitor: &mut Visitor,
 ) -> SassResult<Value> {
     args.max_args(1)?;
-    // todo: Value::to_compound_selector
-    let selector =
-        args.get_err(0, "selector")?
-            .to_selector(visitor, "selector", false, args.span())?;
-
-    if selector.0.components.len() != 1 {
-        return Err(("$selector: expected selector.", args.span()).into());
-    }
-
-    let compound = if let Some(ComplexSelectorComponent::Compound(compound)) =
-        selector.0.components[0].components.first().cloned()
-    {
-        compound
-    } else {
-        todo!()
-    };
+    let compound = args.get_err(0, "selector")?.to_compound_selector(
+        visitor,
+        "selector",
+        false,
+        args.span(),
+    )?;
 
     Ok(Value::List(
         compound
@@ -174,13 +164,13 @@ pub(crate) fn selector_replace(
     args.max_args(3)?;
     let selector =
         args.get_err(0, "selector")?
-            .to_selector(visitor, "selector", true, args.span())?;
+            .to_selector(visitor, "selector", false, args.span())?;
     let target =
         args.get_err(1, "original")?
-            .to_selector(visitor, "original", true, args.span())?;
+            .to_selector(visitor, "original", false, args.span())?;
     let source =
         args.get_err(2, "replacement")?
-            .to_selector(visitor, "replacement", true, args.span())?;
+            .to_selector(visitor, "replacement", false, args.span())?;
     Ok(ExtensionStore::replace(selector.0, source.0, target.0, args.span())?.to_sass_list())
 }
 
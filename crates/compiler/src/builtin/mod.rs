@@ -1,10 +1,19 @@
 mod functions;
 pub(crate) mod modules;
 
-pub(crate) use functions::{
-    color, list, map, math, meta, selector, string, DISALLOWED_PLAIN_CSS_FUNCTION_NAMES,
-    GLOBAL_FUNCTIONS,
-};
+#[cfg(feature = "color-fns")]
+pub(crate) use functions::color;
+#[cfg(feature = "list-fns")]
+pub(crate) use functions::list;
+#[cfg(feature = "map-fns")]
+pub(crate) use functions::map;
+#[cfg(feature = "math-fns")]
+pub(crate) use functions::math;
+#[cfg(feature = "selector-fns")]
+pub(crate) use functions::selector;
+#[cfg(feature = "string-fns")]
+pub(crate) use functions::string;
+pub(crate) use functions::{meta, DISALLOWED_PLAIN_CSS_FUNCTION_NAMES, GLOBAL_FUNCTIONS};
 
 pub use functions::Builtin;
 
@@ -25,7 +34,7 @@ mod builtin_imports {
         evaluate::Visitor,
         unit::Unit,
         value::{CalculationArg, Number, SassFunction, SassMap, SassNumber, Value},
-        Options,
+        Deprecation, Options,
     };
 
     pub(crate) use std::{
@@ -10,22 +10,22 @@ use crate::builtin::{
 };
 
 pub(crate) fn declare(f: &mut Module) {
-    f.insert_builtin("adjust", adjust_color);
-    f.insert_builtin("alpha", alpha);
-    f.insert_builtin("blue", blue);
-    f.insert_builtin("change", change_color);
-    f.insert_builtin("complement", complement);
-    f.insert_builtin("grayscale", grayscale);
-    f.insert_builtin("green", green);
-    f.insert_builtin("hue", hue);
-    f.insert_builtin("ie-hex-str", ie_hex_str);
-    f.insert_builtin("invert", invert);
-    f.insert_builtin("lightness", lightness);
-    f.insert_builtin("mix", mix);
-    f.insert_builtin("red", red);
-    f.insert_builtin("saturation", saturation);
-    f.insert_builtin("scale", scale_color);
-    f.insert_builtin("blackness", blackness);
-    f.insert_builtin("whiteness", whiteness);
-    f.insert_builtin("hwb", hwb);
+    f.insert_pure_builtin("adjust", adjust_color);
+    f.insert_pure_builtin("alpha", alpha);
+    f.insert_pure_builtin("blue", blue);
+    f.insert_pure_builtin("change", change_color);
+    f.insert_pure_builtin("complement", complement);
+    f.insert_pure_builtin("grayscale", grayscale);
+    f.insert_pure_builtin("green", green);
+    f.insert_pure_builtin("hue", hue);
+    f.insert_pure_builtin("ie-hex-str", ie_hex_str);
+    f.insert_pure_builtin("invert", invert);
+    f.insert_pure_builtin("lightness", lightness);
+    f.insert_pure_builtin("mix", mix);
+    f.insert_pure_builtin("red", red);
+    f.insert_pure_builtin("saturation", saturation);
+    f.insert_pure_builtin("scale", scale_color);
+    f.insert_pure_builtin("blackness", blackness);
+    f.insert_pure_builtin("whiteness", whiteness);
+    f.insert_pure_builtin("hwb", hwb);
 }
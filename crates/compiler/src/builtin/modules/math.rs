@@ -445,29 +445,29 @@ fn atan2(mut args: ArgumentResult, _: &mut Visitor) -> SassResult<Value> {
 }
 
 pub(crate) fn declare(f: &mut Module) {
-    f.insert_builtin("ceil", ceil);
-    f.insert_builtin("floor", floor);
-    f.insert_builtin("max", max);
-    f.insert_builtin("min", min);
-    f.insert_builtin("round", round);
-    f.insert_builtin("abs", abs);
-    f.insert_builtin("compatible", comparable);
-    f.insert_builtin("is-unitless", unitless);
-    f.insert_builtin("unit", unit);
-    f.insert_builtin("percentage", percentage);
-    f.insert_builtin("clamp", clamp);
-    f.insert_builtin("sqrt", sqrt);
-    f.insert_builtin("cos", cos);
-    f.insert_builtin("sin", sin);
-    f.insert_builtin("tan", tan);
-    f.insert_builtin("acos", acos);
-    f.insert_builtin("asin", asin);
-    f.insert_builtin("atan", atan);
-    f.insert_builtin("log", log);
-    f.insert_builtin("pow", pow);
-    f.insert_builtin("hypot", hypot);
-    f.insert_builtin("div", divide);
-    f.insert_builtin("atan2", atan2);
+    f.insert_pure_builtin("ceil", ceil);
+    f.insert_pure_builtin("floor", floor);
+    f.insert_pure_builtin("max", max);
+    f.insert_pure_builtin("min", min);
+    f.insert_pure_builtin("round", round);
+    f.insert_pure_builtin("abs", abs);
+    f.insert_pure_builtin("compatible", comparable);
+    f.insert_pure_builtin("is-unitless", unitless);
+    f.insert_pure_builtin("unit", unit);
+    f.insert_pure_builtin("percentage", percentage);
+    f.insert_pure_builtin("clamp", clamp);
+    f.insert_pure_builtin("sqrt", sqrt);
+    f.insert_pure_builtin("cos", cos);
+    f.insert_pure_builtin("sin", sin);
+    f.insert_pure_builtin("tan", tan);
+    f.insert_pure_builtin("acos", acos);
+    f.insert_pure_builtin("asin", asin);
+    f.insert_pure_builtin("atan", atan);
+    f.insert_pure_builtin("log", log);
+    f.insert_pure_builtin("pow", pow);
+    f.insert_pure_builtin("hypot", hypot);
+    f.insert_pure_builtin("div", divide);
+    f.insert_pure_builtin("atan2", atan2);
     #[cfg(feature = "random")]
     f.insert_builtin("random", random);
 
@@ -61,7 +61,7 @@ fn load_css(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<()> {
 
     let style_sheet = visitor.load_style_sheet(url.as_ref(), false, args.span())?;
 
-    visitor.visit_stylesheet(style_sheet)?;
+    visitor.visit_stylesheet(&style_sheet)?;
 
     // todo: support the $with argument to load-css
     // visitor.load_module(
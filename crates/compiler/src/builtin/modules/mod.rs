@@ -15,19 +15,26 @@ use crate::{
     evaluate::{Environment, Visitor},
     selector::ExtensionStore,
     utils::{
-        BaseMapView, LimitedMapView, MapView, MergedMapView, PrefixedMapView, PublicMemberMapView,
+        closest_match, BaseMapView, LimitedMapView, MapView, MergedMapView, PrefixedMapView,
+        PublicMemberMapView,
     },
     value::{SassFunction, SassMap, Value},
 };
 
 use super::builtin_imports::QuoteKind;
 
+#[cfg(feature = "color-fns")]
 mod color;
+#[cfg(feature = "list-fns")]
 mod list;
+#[cfg(feature = "map-fns")]
 mod map;
+#[cfg(feature = "math-fns")]
 mod math;
 mod meta;
+#[cfg(feature = "selector-fns")]
 mod selector;
+#[cfg(feature = "string-fns")]
 mod string;
 
 /// A [Module] that only exposes members that aren't shadowed by a given
@@ -179,11 +186,11 @@ impl ForwardedModule {
             && rule
                 .hidden_mixins_and_functions
                 .as_ref()
-                .map_or(false, HashSet::is_empty)
+                .is_some_and(HashSet::is_empty)
             && rule
                 .hidden_variables
                 .as_ref()
-                .map_or(false, HashSet::is_empty)
+                .is_some_and(HashSet::is_empty)
         {
             module
         } else {
@@ -260,14 +267,19 @@ impl Modules {
     pub fn get(&self, name: Identifier, span: Span) -> SassResult<Arc<RefCell<Module>>> {
         match self.0.get(&name) {
             Some(v) => Ok(Arc::clone(v)),
-            None => Err((
-                format!(
-                    "There is no module with the namespace \"{}\".",
-                    name.as_str()
-                ),
-                span,
-            )
-                .into()),
+            None => Err((self.no_module_message(name), span).into()),
+        }
+    }
+
+    fn no_module_message(&self, name: Identifier) -> String {
+        let message = format!(
+            "There is no module with the namespace \"{}\".",
+            name.as_str()
+        );
+
+        match closest_match(name.as_str(), self.0.keys().map(Identifier::as_str)) {
+            Some(suggestion) => format!("{}\n\nDid you mean \"{}\"?", message, suggestion),
+            None => message,
         }
     }
 
@@ -276,17 +288,11 @@ impl Modules {
         name: Identifier,
         span: Span,
     ) -> SassResult<&mut Arc<RefCell<Module>>> {
-        match self.0.get_mut(&name) {
-            Some(v) => Ok(v),
-            None => Err((
-                format!(
-                    "There is no module with the namespace \"{}\".",
-                    name.as_str()
-                ),
-                span,
-            )
-                .into()),
+        if !self.0.contains_key(&name) {
+            return Err((self.no_module_message(name), span).into());
         }
+
+        Ok(self.0.get_mut(&name).unwrap())
     }
 }
 
@@ -376,15 +382,6 @@ impl Module {
         }
     }
 
-    pub fn get_var(&self, name: Spanned<Identifier>) -> SassResult<Value> {
-        let scope = self.scope();
-
-        match scope.variables.get(name.node) {
-            Some(v) => Ok(v),
-            None => Err(("Undefined variable.", name.span).into()),
-        }
-    }
-
     pub fn get_var_no_err(&self, name: Identifier) -> Option<Value> {
         let scope = self.scope();
 
@@ -415,15 +412,6 @@ impl Module {
         Ok(())
     }
 
-    pub fn get_mixin(&self, name: Spanned<Identifier>) -> SassResult<Mixin> {
-        let scope = self.scope();
-
-        match scope.mixins.get(name.node) {
-            Some(v) => Ok(v),
-            None => Err(("Undefined mixin.", name.span).into()),
-        }
-    }
-
     pub fn insert_builtin_mixin(&mut self, name: &'static str, mixin: BuiltinMixin) {
         let scope = self.scope();
 
@@ -467,6 +455,20 @@ impl Module {
         name: &'static str,
         function: fn(ArgumentResult, &mut Visitor) -> SassResult<Value>,
     ) {
+        self.insert_function(name, Builtin::new(function));
+    }
+
+    /// Like [`Module::insert_builtin`], but marks the function as pure so that
+    /// its results may be memoized
+    pub(crate) fn insert_pure_builtin(
+        &mut self,
+        name: &'static str,
+        function: fn(ArgumentResult, &mut Visitor) -> SassResult<Value>,
+    ) {
+        self.insert_function(name, Builtin::new_pure(function));
+    }
+
+    fn insert_function(&mut self, name: &'static str, function: Builtin) {
         let ident = name.into();
 
         let scope = match self {
@@ -476,7 +478,7 @@ impl Module {
 
         scope
             .functions
-            .insert(ident, SassFunction::Builtin(Builtin::new(function), ident));
+            .insert(ident, SassFunction::Builtin(function, ident));
     }
 
     pub fn functions(&self, span: Span) -> SassMap {
@@ -514,44 +516,41 @@ impl Module {
     }
 }
 
-pub(crate) fn declare_module_color() -> Module {
-    let mut module = Module::new_builtin();
-    color::declare(&mut module);
-    module
-}
-
-pub(crate) fn declare_module_list() -> Module {
-    let mut module = Module::new_builtin();
-    list::declare(&mut module);
-    module
-}
-
-pub(crate) fn declare_module_map() -> Module {
-    let mut module = Module::new_builtin();
-    map::declare(&mut module);
-    module
-}
-
-pub(crate) fn declare_module_math() -> Module {
-    let mut module = Module::new_builtin();
-    math::declare(&mut module);
-    module
-}
-
-pub(crate) fn declare_module_meta() -> Module {
-    let mut module = Module::new_builtin();
-    meta::declare(&mut module);
-    module
-}
-
-pub(crate) fn declare_module_selector() -> Module {
-    let mut module = Module::new_builtin();
-    selector::declare(&mut module);
-    module
-}
+/// The builtin module at `url`, if there is one
+///
+/// If the module exists but was disabled at build time, the name of the cargo
+/// feature which enables it is returned as an error.
+pub(crate) fn declare_builtin_module(url: &str) -> Result<Option<Module>, &'static str> {
+    let declare: fn(&mut Module) = match url {
+        #[cfg(feature = "color-fns")]
+        "sass:color" => color::declare,
+        #[cfg(not(feature = "color-fns"))]
+        "sass:color" => return Err("color-fns"),
+        #[cfg(feature = "list-fns")]
+        "sass:list" => list::declare,
+        #[cfg(not(feature = "list-fns"))]
+        "sass:list" => return Err("list-fns"),
+        #[cfg(feature = "map-fns")]
+        "sass:map" => map::declare,
+        #[cfg(not(feature = "map-fns"))]
+        "sass:map" => return Err("map-fns"),
+        #[cfg(feature = "math-fns")]
+        "sass:math" => math::declare,
+        #[cfg(not(feature = "math-fns"))]
+        "sass:math" => return Err("math-fns"),
+        "sass:meta" => meta::declare,
+        #[cfg(feature = "selector-fns")]
+        "sass:selector" => selector::declare,
+        #[cfg(not(feature = "selector-fns"))]
+        "sass:selector" => return Err("selector-fns"),
+        #[cfg(feature = "string-fns")]
+        "sass:string" => string::declare,
+        #[cfg(not(feature = "string-fns"))]
+        "sass:string" => return Err("string-fns"),
+        _ => return Ok(None),
+    };
 
-pub(crate) fn declare_module_string() -> Module {
     let mut module = Module::new_builtin();
-    string::declare(&mut module);
-    module
+    declare(&mut module);
+    Ok(Some(module))
 }
@@ -0,0 +1,1137 @@
+//! A compact binary encoding of parsed stylesheets, used to persist them to
+//! disk
+//!
+//! Spans are encoded relative to the start of the file the stylesheet was
+//! parsed from, so that a decoded stylesheet can be attached to a new
+//! [`CodeMap`](codemap::CodeMap). Encoding fails if a span lies outside of that
+//! file, and decoding fails on any malformed input, in which case the
+//! stylesheet is simply parsed again.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    hash::Hash,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use codemap::{Span, Spanned};
+
+use crate::{
+    ast::*,
+    color::{Color, ColorFormat},
+    common::{BinaryOp, Brackets, Identifier, ListSeparator, QuoteKind, UnaryOp},
+    unit::{ComplexUnit, Unit},
+    utils::ensure_sufficient_stack,
+    value::{CalculationName, Number},
+};
+
+pub(super) struct Encoder {
+    buffer: Vec<u8>,
+    file_span: Span,
+}
+
+impl Encoder {
+    pub fn new(file_span: Span) -> Self {
+        Self {
+            buffer: Vec::new(),
+            file_span,
+        }
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    pub fn write_u64(&mut self, mut n: u64) {
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+
+            if n == 0 {
+                self.buffer.push(byte);
+                return;
+            }
+
+            self.buffer.push(byte | 0x80);
+        }
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_u64(bytes.len() as u64);
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    fn write_tag(&mut self, tag: u8) {
+        self.buffer.push(tag);
+    }
+}
+
+pub(super) struct Decoder<'a> {
+    bytes: &'a [u8],
+    file_span: Span,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8], file_span: Span) -> Self {
+        Self { bytes, file_span }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn read_u64(&mut self) -> Option<u64> {
+        let mut n: u64 = 0;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_tag()?;
+            n |= u64::from(byte & 0x7f).checked_shl(shift)?;
+
+            if byte & 0x80 == 0 {
+                return Some(n);
+            }
+        }
+
+        None
+    }
+
+    pub fn read_bytes(&mut self) -> Option<&'a [u8]> {
+        let len = usize::try_from(self.read_u64()?).ok()?;
+
+        if len > self.bytes.len() {
+            return None;
+        }
+
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Some(bytes)
+    }
+
+    fn read_tag(&mut self) -> Option<u8> {
+        let (&tag, rest) = self.bytes.split_first()?;
+        self.bytes = rest;
+        Some(tag)
+    }
+
+    fn read_len(&mut self) -> Option<usize> {
+        let len = usize::try_from(self.read_u64()?).ok()?;
+
+        // every element takes up at least one byte, so this guards against
+        // allocating huge vectors when reading malformed input
+        if len > self.bytes.len() {
+            return None;
+        }
+
+        Some(len)
+    }
+}
+
+pub(super) trait Encode {
+    /// Returns `None` if the value cannot be encoded
+    fn encode(&self, encoder: &mut Encoder) -> Option<()>;
+}
+
+pub(super) trait Decode: Sized {
+    /// Returns `None` if the input is malformed
+    fn decode(decoder: &mut Decoder) -> Option<Self>;
+}
+
+impl Encode for bool {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        encoder.write_tag(u8::from(*self));
+        Some(())
+    }
+}
+
+impl Decode for bool {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        match decoder.read_tag()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Encode for usize {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        encoder.write_u64(*self as u64);
+        Some(())
+    }
+}
+
+impl Decode for usize {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        usize::try_from(decoder.read_u64()?).ok()
+    }
+}
+
+impl Encode for Number {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        encoder.buffer.extend_from_slice(&self.0.to_le_bytes());
+        Some(())
+    }
+}
+
+impl Decode for Number {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        if decoder.bytes.len() < 8 {
+            return None;
+        }
+
+        let (bytes, rest) = decoder.bytes.split_at(8);
+        decoder.bytes = rest;
+
+        Some(Number(f64::from_le_bytes(bytes.try_into().ok()?)))
+    }
+}
+
+impl Encode for str {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        encoder.write_bytes(self.as_bytes());
+        Some(())
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        self.as_str().encode(encoder)
+    }
+}
+
+impl Decode for String {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        String::from_utf8(decoder.read_bytes()?.to_vec()).ok()
+    }
+}
+
+impl Encode for PathBuf {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        self.to_str()?.encode(encoder)
+    }
+}
+
+impl Decode for PathBuf {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        String::decode(decoder).map(PathBuf::from)
+    }
+}
+
+impl Encode for Identifier {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        self.as_str().encode(encoder)
+    }
+}
+
+impl Decode for Identifier {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        String::decode(decoder).map(Identifier::from)
+    }
+}
+
+impl Encode for Span {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        let file_span = encoder.file_span;
+
+        if self.low() < file_span.low() || self.high() > file_span.high() {
+            return None;
+        }
+
+        encoder.write_u64(self.low() - file_span.low());
+        encoder.write_u64(self.len());
+
+        Some(())
+    }
+}
+
+impl Decode for Span {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        let begin = decoder.read_u64()?;
+        let end = begin.checked_add(decoder.read_u64()?)?;
+
+        if end > decoder.file_span.len() {
+            return None;
+        }
+
+        Some(decoder.file_span.subspan(begin, end))
+    }
+}
+
+impl<T: Encode> Encode for Spanned<T> {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        self.node.encode(encoder)?;
+        self.span.encode(encoder)
+    }
+}
+
+impl<T: Decode> Decode for Spanned<T> {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        Some(Spanned {
+            node: T::decode(decoder)?,
+            span: Span::decode(decoder)?,
+        })
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        match self {
+            Some(value) => {
+                encoder.write_tag(1);
+                value.encode(encoder)
+            }
+            None => {
+                encoder.write_tag(0);
+                Some(())
+            }
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        match decoder.read_tag()? {
+            0 => Some(None),
+            1 => Some(Some(T::decode(decoder)?)),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        encoder.write_u64(self.len() as u64);
+        self.iter().try_for_each(|elem| elem.encode(encoder))
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        (0..decoder.read_len()?)
+            .map(|_| T::decode(decoder))
+            .collect()
+    }
+}
+
+impl<T: Encode + Eq + Hash> Encode for HashSet<T> {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        encoder.write_u64(self.len() as u64);
+        self.iter().try_for_each(|elem| elem.encode(encoder))
+    }
+}
+
+impl<T: Decode + Eq + Hash> Decode for HashSet<T> {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        (0..decoder.read_len()?)
+            .map(|_| T::decode(decoder))
+            .collect()
+    }
+}
+
+impl<K: Encode + Ord, V: Encode> Encode for BTreeMap<K, V> {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        encoder.write_u64(self.len() as u64);
+        self.iter().try_for_each(|(key, value)| {
+            key.encode(encoder)?;
+            value.encode(encoder)
+        })
+    }
+}
+
+impl<K: Decode + Ord, V: Decode> Decode for BTreeMap<K, V> {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        (0..decoder.read_len()?)
+            .map(|_| <(K, V)>::decode(decoder))
+            .collect()
+    }
+}
+
+impl<A: Encode, B: Encode> Encode for (A, B) {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        self.0.encode(encoder)?;
+        self.1.encode(encoder)
+    }
+}
+
+impl<A: Decode, B: Decode> Decode for (A, B) {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        Some((A::decode(decoder)?, B::decode(decoder)?))
+    }
+}
+
+impl<T: Encode> Encode for Box<T> {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        (**self).encode(encoder)
+    }
+}
+
+impl<T: Decode> Decode for Box<T> {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        T::decode(decoder).map(Box::new)
+    }
+}
+
+impl<T: Encode> Encode for Arc<T> {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        (**self).encode(encoder)
+    }
+}
+
+impl<T: Decode> Decode for Arc<T> {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        T::decode(decoder).map(Arc::new)
+    }
+}
+
+/// Implement [`Encode`] and [`Decode`] for a struct by encoding each of the
+/// given fields in order
+macro_rules! struct_codec {
+    ($ty:ident { $($field:ident),* $(,)? }) => {
+        impl Encode for $ty {
+            fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+                $(self.$field.encode(encoder)?;)*
+                Some(())
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(decoder: &mut Decoder) -> Option<Self> {
+                Some(Self {
+                    $($field: Decode::decode(decoder)?,)*
+                })
+            }
+        }
+    };
+}
+
+/// Implement [`Encode`] and [`Decode`] for an enum whose variants are either
+/// all fieldless or all contain a single value, encoding each variant as its
+/// index
+macro_rules! enum_codec {
+    ($ty:ident { $($variant:ident),* $(,)? }) => {
+        impl Encode for $ty {
+            fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+                let mut tag = 0_u8;
+                $(
+                    if matches!(self, Self::$variant) {
+                        encoder.write_tag(tag);
+                        return Some(());
+                    }
+                    tag += 1;
+                )*
+                let _ = tag;
+                unreachable!()
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(decoder: &mut Decoder) -> Option<Self> {
+                let tag = decoder.read_tag()?;
+                let mut idx = 0_u8;
+                $(
+                    if tag == idx {
+                        return Some(Self::$variant);
+                    }
+                    idx += 1;
+                )*
+                let _ = idx;
+                None
+            }
+        }
+    };
+    ($ty:ident { $($variant:ident(..)),* $(,)? }) => {
+        impl Encode for $ty {
+            fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+                let mut tag = 0_u8;
+                // stylesheets and expressions may nest arbitrarily deeply
+                ensure_sufficient_stack(|| {
+                    $(
+                        if let Self::$variant(value) = self {
+                            encoder.write_tag(tag);
+                            return value.encode(encoder);
+                        }
+                        tag += 1;
+                    )*
+                    let _ = tag;
+                    unreachable!()
+                })
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(decoder: &mut Decoder) -> Option<Self> {
+                let tag = decoder.read_tag()?;
+                let mut idx = 0_u8;
+                ensure_sufficient_stack(|| {
+                    $(
+                        if tag == idx {
+                            return Some(Self::$variant(Decode::decode(decoder)?));
+                        }
+                        idx += 1;
+                    )*
+                    let _ = idx;
+                    None
+                })
+            }
+        }
+    };
+}
+
+enum_codec!(QuoteKind { Quoted, None });
+enum_codec!(Brackets { None, Bracketed });
+enum_codec!(ListSeparator {
+    Space,
+    Comma,
+    Slash,
+    Undecided
+});
+enum_codec!(UnaryOp {
+    Plus,
+    Neg,
+    Div,
+    Not
+});
+enum_codec!(BinaryOp {
+    SingleEq,
+    Equal,
+    NotEqual,
+    GreaterThanEqual,
+    LessThanEqual,
+    GreaterThan,
+    LessThan,
+    Plus,
+    Minus,
+    Mul,
+    Div,
+    Rem,
+    And,
+    Or,
+});
+enum_codec!(CalculationName {
+    Calc,
+    Min,
+    Max,
+    Clamp
+});
+
+impl Encode for Unit {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        match self {
+            Unit::None => encoder.write_tag(0),
+            Unit::Complex(complex) => {
+                encoder.write_tag(1);
+                complex.numer.encode(encoder)?;
+                complex.denom.encode(encoder)?;
+            }
+            unit => {
+                encoder.write_tag(2);
+                unit.to_string().encode(encoder)?;
+            }
+        }
+
+        Some(())
+    }
+}
+
+impl Decode for Unit {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        match decoder.read_tag()? {
+            0 => Some(Unit::None),
+            1 => Some(Unit::Complex(Arc::new(ComplexUnit {
+                numer: Decode::decode(decoder)?,
+                denom: Decode::decode(decoder)?,
+            }))),
+            2 => Some(Unit::from(String::decode(decoder)?)),
+            _ => None,
+        }
+    }
+}
+
+// Colors in a parsed stylesheet are always written as literal RGB colors, so
+// only those components need to be encoded
+impl Encode for Color {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        self.red().encode(encoder)?;
+        self.green().encode(encoder)?;
+        self.blue().encode(encoder)?;
+        self.alpha().encode(encoder)?;
+
+        match &self.format {
+            ColorFormat::Rgb => encoder.write_tag(0),
+            ColorFormat::Hsl => encoder.write_tag(1),
+            ColorFormat::Literal(text) => {
+                encoder.write_tag(2);
+                text.encode(encoder)?;
+            }
+            ColorFormat::Infer => encoder.write_tag(3),
+        }
+
+        Some(())
+    }
+}
+
+impl Decode for Color {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        let red = Number::decode(decoder)?;
+        let green = Number::decode(decoder)?;
+        let blue = Number::decode(decoder)?;
+        let alpha = Number::decode(decoder)?;
+
+        let format = match decoder.read_tag()? {
+            0 => ColorFormat::Rgb,
+            1 => ColorFormat::Hsl,
+            2 => ColorFormat::Literal(String::decode(decoder)?),
+            3 => ColorFormat::Infer,
+            _ => return None,
+        };
+
+        Some(Color::new_rgba(red, green, blue, alpha, format))
+    }
+}
+
+struct_codec!(Interpolation { contents });
+enum_codec!(InterpolationPart {
+    String(..),
+    Expr(..)
+});
+
+struct_codec!(Argument { name, default });
+struct_codec!(ArgumentDeclaration { args, rest, span });
+struct_codec!(ArgumentInvocation {
+    positional,
+    named,
+    rest,
+    keyword_rest,
+    span
+});
+
+struct_codec!(ListExpr {
+    elems,
+    separator,
+    brackets
+});
+struct_codec!(FunctionCallExpr {
+    namespace,
+    name,
+    arguments,
+    span
+});
+struct_codec!(InterpolatedFunction {
+    name,
+    arguments,
+    span
+});
+struct_codec!(BinaryOpExpr {
+    lhs,
+    op,
+    rhs,
+    allows_slash,
+    span
+});
+
+impl Encode for Ternary {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        self.0.encode(encoder)
+    }
+}
+
+impl Decode for Ternary {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        Decode::decode(decoder).map(Ternary)
+    }
+}
+
+impl Encode for AstSassMap {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        self.0.encode(encoder)
+    }
+}
+
+impl Decode for AstSassMap {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        Decode::decode(decoder).map(AstSassMap)
+    }
+}
+
+impl Encode for StringExpr {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        self.0.encode(encoder)?;
+        self.1.encode(encoder)
+    }
+}
+
+impl Decode for StringExpr {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        Some(StringExpr(
+            Decode::decode(decoder)?,
+            Decode::decode(decoder)?,
+        ))
+    }
+}
+
+impl Encode for AstExpr {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        match self {
+            AstExpr::BinaryOp(binop) => {
+                encoder.write_tag(0);
+                binop.encode(encoder)
+            }
+            AstExpr::True => {
+                encoder.write_tag(1);
+                Some(())
+            }
+            AstExpr::False => {
+                encoder.write_tag(2);
+                Some(())
+            }
+            AstExpr::Calculation { name, args, span } => {
+                encoder.write_tag(3);
+                name.encode(encoder)?;
+                args.encode(encoder)?;
+                span.encode(encoder)
+            }
+            AstExpr::Color(color) => {
+                encoder.write_tag(4);
+                color.encode(encoder)
+            }
+            AstExpr::FunctionCall(call) => {
+                encoder.write_tag(5);
+                call.encode(encoder)
+            }
+            AstExpr::If(ternary) => {
+                encoder.write_tag(6);
+                ternary.encode(encoder)
+            }
+            AstExpr::InterpolatedFunction(func) => {
+                encoder.write_tag(7);
+                func.encode(encoder)
+            }
+            AstExpr::List(list) => {
+                encoder.write_tag(8);
+                list.encode(encoder)
+            }
+            AstExpr::Map(map) => {
+                encoder.write_tag(9);
+                map.encode(encoder)
+            }
+            AstExpr::Null => {
+                encoder.write_tag(10);
+                Some(())
+            }
+            AstExpr::Number { n, unit } => {
+                encoder.write_tag(11);
+                n.encode(encoder)?;
+                unit.encode(encoder)
+            }
+            AstExpr::Paren(expr) => {
+                encoder.write_tag(12);
+                expr.encode(encoder)
+            }
+            AstExpr::ParentSelector => {
+                encoder.write_tag(13);
+                Some(())
+            }
+            AstExpr::String(string, span) => {
+                encoder.write_tag(14);
+                string.encode(encoder)?;
+                span.encode(encoder)
+            }
+            AstExpr::Supports(condition) => {
+                encoder.write_tag(15);
+                condition.encode(encoder)
+            }
+            AstExpr::UnaryOp(op, expr, span) => {
+                encoder.write_tag(16);
+                op.encode(encoder)?;
+                expr.encode(encoder)?;
+                span.encode(encoder)
+            }
+            AstExpr::Variable { name, namespace } => {
+                encoder.write_tag(17);
+                name.encode(encoder)?;
+                namespace.encode(encoder)
+            }
+        }
+    }
+}
+
+impl Decode for AstExpr {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        Some(match decoder.read_tag()? {
+            0 => AstExpr::BinaryOp(Decode::decode(decoder)?),
+            1 => AstExpr::True,
+            2 => AstExpr::False,
+            3 => AstExpr::Calculation {
+                name: Decode::decode(decoder)?,
+                args: Decode::decode(decoder)?,
+                span: Decode::decode(decoder)?,
+            },
+            4 => AstExpr::Color(Decode::decode(decoder)?),
+            5 => AstExpr::FunctionCall(Decode::decode(decoder)?),
+            6 => AstExpr::If(Decode::decode(decoder)?),
+            7 => AstExpr::InterpolatedFunction(Decode::decode(decoder)?),
+            8 => AstExpr::List(Decode::decode(decoder)?),
+            9 => AstExpr::Map(Decode::decode(decoder)?),
+            10 => AstExpr::Null,
+            11 => AstExpr::Number {
+                n: Decode::decode(decoder)?,
+                unit: Decode::decode(decoder)?,
+            },
+            12 => AstExpr::Paren(Decode::decode(decoder)?),
+            13 => AstExpr::ParentSelector,
+            14 => AstExpr::String(Decode::decode(decoder)?, Decode::decode(decoder)?),
+            15 => AstExpr::Supports(Decode::decode(decoder)?),
+            16 => AstExpr::UnaryOp(
+                Decode::decode(decoder)?,
+                Decode::decode(decoder)?,
+                Decode::decode(decoder)?,
+            ),
+            17 => AstExpr::Variable {
+                name: Decode::decode(decoder)?,
+                namespace: Decode::decode(decoder)?,
+            },
+            _ => return None,
+        })
+    }
+}
+
+impl Encode for AstSupportsCondition {
+    fn encode(&self, encoder: &mut Encoder) -> Option<()> {
+        match self {
+            AstSupportsCondition::Anything { contents } => {
+                encoder.write_tag(0);
+                contents.encode(encoder)
+            }
+            AstSupportsCondition::Declaration { name, value } => {
+                encoder.write_tag(1);
+                name.encode(encoder)?;
+                value.encode(encoder)
+            }
+            AstSupportsCondition::Function { name, args } => {
+                encoder.write_tag(2);
+                name.encode(encoder)?;
+                args.encode(encoder)
+            }
+            AstSupportsCondition::Interpolation(expr) => {
+                encoder.write_tag(3);
+                expr.encode(encoder)
+            }
+            AstSupportsCondition::Negation(condition) => {
+                encoder.write_tag(4);
+                condition.encode(encoder)
+            }
+            AstSupportsCondition::Operation {
+                left,
+                operator,
+                right,
+            } => {
+                encoder.write_tag(5);
+                left.encode(encoder)?;
+                operator.encode(encoder)?;
+                right.encode(encoder)
+            }
+        }
+    }
+}
+
+impl Decode for AstSupportsCondition {
+    fn decode(decoder: &mut Decoder) -> Option<Self> {
+        Some(match decoder.read_tag()? {
+            0 => AstSupportsCondition::Anything {
+                contents: Decode::decode(decoder)?,
+            },
+            1 => AstSupportsCondition::Declaration {
+                name: Decode::decode(decoder)?,
+                value: Decode::decode(decoder)?,
+            },
+            2 => AstSupportsCondition::Function {
+                name: Decode::decode(decoder)?,
+                args: Decode::decode(decoder)?,
+            },
+            3 => AstSupportsCondition::Interpolation(Decode::decode(decoder)?),
+            4 => AstSupportsCondition::Negation(Decode::decode(decoder)?),
+            5 => AstSupportsCondition::Operation {
+                left: Decode::decode(decoder)?,
+                operator: Decode::decode(decoder)?,
+                right: Decode::decode(decoder)?,
+            },
+            _ => return None,
+        })
+    }
+}
+
+struct_codec!(AstSilentComment { text, span });
+struct_codec!(AstPlainCssImport {
+    url,
+    modifiers,
+    span
+});
+struct_codec!(AstSassImport { url, span });
+struct_codec!(AstIf {
+    if_clauses,
+    else_clause
+});
+struct_codec!(AstIfClause { condition, body });
+struct_codec!(AstFor {
+    variable,
+    from,
+    to,
+    is_exclusive,
+    body
+});
+struct_codec!(AstReturn { val, span });
+struct_codec!(AstRuleSet {
+    selector,
+    body,
+    selector_span,
+    span
+});
+struct_codec!(AstStyle {
+    name,
+    value,
+    body,
+    span
+});
+struct_codec!(AstEach {
+    variables,
+    list,
+    body
+});
+struct_codec!(AstMedia {
+    query,
+    query_span,
+    body,
+    span
+});
+struct_codec!(AstWhile { condition, body });
+struct_codec!(AstVariableDecl {
+    namespace,
+    name,
+    value,
+    is_guarded,
+    is_global,
+    span
+});
+struct_codec!(AstFunctionDecl {
+    name,
+    arguments,
+    body
+});
+struct_codec!(AstDebugRule { value, span });
+struct_codec!(AstWarn { value, span });
+struct_codec!(AstErrorRule { value, span });
+struct_codec!(AstLoudComment { text, span });
+struct_codec!(AstMixin {
+    name,
+    args,
+    body,
+    has_content
+});
+struct_codec!(AstContentRule { args });
+struct_codec!(AstContentBlock { args, body });
+struct_codec!(AstInclude {
+    namespace,
+    name,
+    args,
+    content,
+    span
+});
+struct_codec!(AstUnknownAtRule {
+    name,
+    value,
+    body,
+    span
+});
+struct_codec!(AstExtendRule {
+    value,
+    value_span,
+    is_optional,
+    span
+});
+struct_codec!(AstAtRootRule { body, query, span });
+struct_codec!(AstImportRule { imports });
+enum_codec!(AstImport {
+    Plain(..),
+    Sass(..)
+});
+struct_codec!(AstUseRule {
+    url,
+    namespace,
+    configuration,
+    span
+});
+struct_codec!(ConfiguredVariable {
+    name,
+    expr,
+    is_guarded
+});
+struct_codec!(AstForwardRule {
+    url,
+    shown_mixins_and_functions,
+    shown_variables,
+    hidden_mixins_and_functions,
+    hidden_variables,
+    prefix,
+    configuration,
+    span
+});
+struct_codec!(AstSupportsRule {
+    condition,
+    body,
+    span
+});
+enum_codec!(AstStmt {
+    If(..),
+    For(..),
+    Return(..),
+    RuleSet(..),
+    Style(..),
+    Each(..),
+    Media(..),
+    Include(..),
+    While(..),
+    VariableDecl(..),
+    LoudComment(..),
+    SilentComment(..),
+    FunctionDecl(..),
+    Mixin(..),
+    ContentRule(..),
+    Warn(..),
+    UnknownAtRule(..),
+    ErrorRule(..),
+    Extend(..),
+    AtRootRule(..),
+    Debug(..),
+    ImportRule(..),
+    Use(..),
+    Forward(..),
+    Supports(..),
+});
+struct_codec!(StyleSheet {
+    body,
+    url,
+    is_plain_css,
+    uses,
+    forwards
+});
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use codemap::CodeMap;
+
+    use super::{Decode, Decoder, Encode, Encoder};
+    use crate::{
+        ast::StyleSheet,
+        lexer::Lexer,
+        parse::{ScssParser, StylesheetParser},
+        Options,
+    };
+
+    /// Uses every kind of statement and expression
+    const INPUT: &str = r#"@use "sass:math" as m;
+@use "a" with ($a: 1, $b: 2);
+@forward "b" as b-* show c, $d;
+@forward "c" hide e with ($f: g !default);
+@import "d", "e.css", url(f) supports(display: grid) screen and (min-width: 1px);
+
+/* loud #{comment} */
+// silent comment
+$a: 1 !default;
+$b: 2 !global;
+m.$c: 3;
+
+@function f($a, $b: 2, $c...) {
+  @if $a == 1 and not $b or -$a {
+    @return true;
+  } @else if $a != null {
+    @return false;
+  } @else {
+    @return null;
+  }
+}
+
+@mixin g($a, $args...) {
+  @content($a);
+  @content;
+}
+
+a, .b > c + d ~ &:hover::after[e="f"] {
+  color: #fff (1px + 2em) * 3 / 4 % 5;
+  font: {
+    family: "foo #{1 + 1}" bar;
+  }
+  --custom: { a: b };
+  width: calc(1px + min(2px, 3%)) clamp(1px, 2px, 3px);
+  height: if(true, 1, 2) m.div(1, 2) of#{1}(a) [1, 2] (a: b, c: d) ();
+  margin: &, +2, -$a;
+
+  @include g(1, 2, $c: 3, $rest...) using ($d) {
+    e: $d;
+  }
+  @include g(1);
+
+  @for $i from 1 through 3 {
+    @each $k, $v in (a: b) {
+      @while $i < 2 {
+        @debug $i;
+        @warn "warning";
+        @error "error";
+      }
+    }
+  }
+
+  @extend %placeholder !optional;
+
+  @at-root (without: media) {
+    g {
+      h: i;
+    }
+  }
+
+  @media screen and (#{min-width}: 1px) {
+    j: k;
+  }
+
+  @supports ((a: b) and (not (c: d))) or selector(e) or f(g) or (#{h}: i) {
+    h: i;
+  }
+
+  @font-face {
+    font-family: j;
+  }
+  @foo bar;
+}
+"#;
+
+    #[test]
+    fn decoded_stylesheet_is_unchanged() {
+        let options = Options::default();
+        let path = Path::new("input.scss");
+
+        let mut map = CodeMap::new();
+        let file = map.add_file("input.scss".to_owned(), INPUT.to_owned());
+
+        let stylesheet = ScssParser::new(
+            Lexer::new_from_file(&file),
+            &mut map,
+            &options,
+            file.span.subspan(0, 0),
+            path,
+        )
+        .__parse()
+        .unwrap();
+
+        let mut encoder = Encoder::new(file.span);
+        stylesheet.encode(&mut encoder).unwrap();
+        let bytes = encoder.finish();
+
+        let mut decoder = Decoder::new(&bytes, file.span);
+        let decoded = StyleSheet::decode(&mut decoder).unwrap();
+
+        assert!(decoder.is_empty());
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", stylesheet));
+    }
+}
@@ -0,0 +1,484 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs, mem,
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use codemap::{CodeMap, File, Pos};
+
+use crate::ast::StyleSheet;
+
+use self::codec::{Decode, Decoder, Encode, Encoder};
+
+mod codec;
+
+/// Written at the start of every file in the cache directory. The version of
+/// `grass` is included, since the encoding of stylesheets may change between
+/// releases
+const HEADER: &str = concat!("grass parse cache ", env!("CARGO_PKG_VERSION"));
+
+/// The version of the encoding of stylesheets, written after [`HEADER`]
+///
+/// This must be incremented whenever the layout of the AST or its encoding
+/// changes, so that files written by earlier builds of the same release are
+/// not decoded as if they used the new layout. A test fails if `codec.rs`
+/// changes without it being incremented.
+const FORMAT_VERSION: u64 = 2;
+
+/// The 64-bit FNV-1a hash of `bytes`
+///
+/// Hashes are stored on disk and used to name files in the cache directory, so
+/// they must not change between builds, unlike those of [`DefaultHasher`].
+///
+/// [`DefaultHasher`]: std::collections::hash_map::DefaultHasher
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// A generation that no [`ParseCache`] has used yet, so that snapshots of
+/// different caches are never mistaken for one another
+fn next_generation() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+
+    NEXT.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A cache of parsed stylesheets, shared between compilations
+///
+/// When the same cache is passed to several compilations using
+/// [`Options::parse_cache`](crate::Options::parse_cache), files loaded using
+/// `@import`, `@use`, and `@forward` are only lexed and parsed once. This is
+/// useful when compiling many entrypoints that share the same dependencies, or
+/// when recompiling the same stylesheet repeatedly.
+///
+/// Entries are keyed by canonical path, and are only reused if the contents of
+/// the file have not changed since they were parsed.
+///
+/// The cache also keeps the source of each cached stylesheet, which its
+/// locations refer to. Each compilation adds its own files to the same map of
+/// sources, rather than to a copy of it. Once the files that are no longer
+/// used take up more memory than those which are, the cache is emptied.
+///
+/// A cache created using [`ParseCache::with_directory`] additionally persists
+/// parsed stylesheets to disk, so that they may be reused by later processes.
+#[derive(Debug, Default)]
+pub struct ParseCache {
+    sources: RefCell<Sources>,
+    stylesheets: RefCell<HashMap<PathBuf, CachedStyleSheet>>,
+    directory: Option<PathBuf>,
+}
+
+/// The files that the spans of cached stylesheets refer to
+#[derive(Debug)]
+struct Sources {
+    /// `None` while lent to a compilation
+    map: Option<CodeMap>,
+    /// The start of the first file in `map`
+    start: Pos,
+    /// Replaced each time `map` is replaced, with a generation unique among
+    /// all caches
+    generation: u64,
+}
+
+impl Default for Sources {
+    fn default() -> Self {
+        let mut map = CodeMap::new();
+        let start = end_of(&mut map);
+
+        Self {
+            map: Some(map),
+            start,
+            generation: next_generation(),
+        }
+    }
+}
+
+/// The position after the last file in `map`
+///
+/// `CodeMap` does not expose this, but an empty file added to it starts there.
+fn end_of(map: &mut CodeMap) -> Pos {
+    map.add_file(String::new(), String::new()).span.low()
+}
+
+#[derive(Debug)]
+struct CachedStyleSheet {
+    hash: u64,
+    file: Arc<File>,
+    stylesheet: Arc<StyleSheet>,
+}
+
+/// The state of a [`ParseCache`] when a compilation began
+///
+/// The spans of cached stylesheets are only valid in the map of sources that
+/// they were parsed in, so compilations may only use stylesheets from the
+/// same generation of the cache as their own map.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Snapshot {
+    generation: u64,
+}
+
+impl Snapshot {
+    /// Whether spans which were valid in `earlier` still are
+    pub fn includes(self, earlier: Self) -> bool {
+        self.generation == earlier.generation
+    }
+}
+
+/// The map of sources of a [`ParseCache`], lent to a compilation, which it is
+/// returned to when dropped
+#[derive(Debug)]
+pub(crate) struct LentMap<'a> {
+    map: CodeMap,
+    /// The cache the map belongs to and its generation, or `None` if the map
+    /// is the compilation's own
+    owner: Option<(&'a ParseCache, u64)>,
+}
+
+impl From<CodeMap> for LentMap<'_> {
+    fn from(map: CodeMap) -> Self {
+        Self { map, owner: None }
+    }
+}
+
+impl Deref for LentMap<'_> {
+    type Target = CodeMap;
+
+    fn deref(&self) -> &CodeMap {
+        &self.map
+    }
+}
+
+impl DerefMut for LentMap<'_> {
+    fn deref_mut(&mut self) -> &mut CodeMap {
+        &mut self.map
+    }
+}
+
+impl Drop for LentMap<'_> {
+    fn drop(&mut self) {
+        if let Some((cache, generation)) = self.owner {
+            let mut sources = cache.sources.borrow_mut();
+
+            // the cache may have been cleared in the meantime
+            if sources.generation == generation {
+                sources.map = Some(mem::take(&mut self.map));
+            }
+        }
+    }
+}
+
+impl ParseCache {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a cache which also stores parsed stylesheets in `directory`
+    ///
+    /// Stylesheets not found in memory are loaded from this directory if the
+    /// contents of their file have not changed since they were stored, and are
+    /// otherwise parsed and written to it. The directory is created if it does
+    /// not exist. Failing to read or write the directory is not an error; the
+    /// stylesheet is just parsed as if it were not cached.
+    ///
+    /// Cached files are only valid for the version of `grass`, and the version
+    /// of its encoding of stylesheets, that wrote them.
+    #[must_use]
+    pub fn with_directory<P: Into<PathBuf>>(directory: P) -> Self {
+        Self {
+            directory: Some(directory.into()),
+            ..Self::default()
+        }
+    }
+
+    /// The number of stylesheets in the cache
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stylesheets.borrow().len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stylesheets.borrow().is_empty()
+    }
+
+    /// Remove every stylesheet from the cache, along with their sources
+    pub fn clear(&self) {
+        self.stylesheets.borrow_mut().clear();
+
+        *self.sources.borrow_mut() = Sources::default();
+    }
+
+    pub(crate) fn hash_contents(contents: &[u8]) -> u64 {
+        fnv1a(contents)
+    }
+
+    /// The map containing every file in the cache, for a compilation to add
+    /// its own files to, along with the snapshot to pass to
+    /// [`ParseCache::get`] during that compilation
+    ///
+    /// The map is returned to the cache when the compilation is finished. If it
+    /// is already lent to another compilation, a new map is returned instead,
+    /// which no cached stylesheet may be used with.
+    pub(crate) fn lend(&self) -> (LentMap<'_>, Snapshot) {
+        self.compact();
+
+        let mut sources = self.sources.borrow_mut();
+
+        match sources.map.take() {
+            Some(map) => (
+                LentMap {
+                    map,
+                    owner: Some((self, sources.generation)),
+                },
+                Snapshot {
+                    generation: sources.generation,
+                },
+            ),
+            None => (
+                LentMap::from(CodeMap::new()),
+                Snapshot {
+                    generation: next_generation(),
+                },
+            ),
+        }
+    }
+
+    /// The stylesheet parsed from `path`, if its contents still hash to `hash`
+    /// and its file is part of `snapshot`
+    pub(crate) fn get(
+        &self,
+        path: &Path,
+        hash: u64,
+        snapshot: Snapshot,
+    ) -> Option<Arc<StyleSheet>> {
+        if snapshot.generation != self.sources.borrow().generation {
+            return None;
+        }
+
+        match self.stylesheets.borrow().get(path) {
+            Some(cached) if cached.hash == hash => Some(Arc::clone(&cached.stylesheet)),
+            _ => None,
+        }
+    }
+
+    /// Cache the stylesheet parsed from `path` by the compilation `snapshot`
+    /// was taken for, whose spans refer to `file`
+    ///
+    /// The stylesheet is only cached if `file` is in the cache's own map of
+    /// sources, so that the stylesheet remains valid once the compilation that
+    /// parsed it has finished.
+    pub(crate) fn insert(
+        &self,
+        path: PathBuf,
+        hash: u64,
+        file: &Arc<File>,
+        stylesheet: Arc<StyleSheet>,
+        snapshot: Snapshot,
+    ) {
+        if snapshot.generation != self.sources.borrow().generation {
+            return;
+        }
+
+        self.stylesheets.borrow_mut().insert(
+            path,
+            CachedStyleSheet {
+                hash,
+                file: Arc::clone(file),
+                stylesheet,
+            },
+        );
+    }
+
+    /// Empty the cache if the files in its map of sources which are no longer
+    /// used take up more space than those which are
+    ///
+    /// Moving the files still in use to a new map would mean moving the spans
+    /// of their stylesheets too, which is about as costly as parsing them
+    /// again, so they are parsed again when next used instead.
+    fn compact(&self) {
+        let mut sources = self.sources.borrow_mut();
+        let start = sources.start;
+
+        let map = match sources.map.as_mut() {
+            Some(map) => map,
+            None => return,
+        };
+
+        let len = end_of(map) - start;
+        let used_len: u64 = self
+            .stylesheets
+            .borrow()
+            .values()
+            .map(|cached| cached.file.span.len())
+            .sum();
+
+        if len - used_len > used_len {
+            self.stylesheets.borrow_mut().clear();
+            *sources = Sources::default();
+        }
+    }
+
+    /// The path in the cache directory that the stylesheet parsed from `path`
+    /// is stored at
+    fn stored_path(&self, path: &Path) -> Option<PathBuf> {
+        let hash = fnv1a(path.to_str()?.as_bytes());
+
+        Some(
+            self.directory
+                .as_ref()?
+                .join(format!("{:016x}.sasscache", hash)),
+        )
+    }
+
+    /// Load the stylesheet parsed from `path` from the cache directory, if it
+    /// was stored when the contents of `file` hashed to `hash`
+    pub(crate) fn load(&self, path: &Path, hash: u64, file: &File) -> Option<StyleSheet> {
+        let bytes = fs::read(self.stored_path(path)?).ok()?;
+        let mut decoder = Decoder::new(&bytes, file.span);
+
+        if decoder.read_bytes()? != HEADER.as_bytes()
+            || decoder.read_u64()? != FORMAT_VERSION
+            || decoder.read_u64()? != hash
+            || decoder.read_bytes()? != path.to_str()?.as_bytes()
+        {
+            return None;
+        }
+
+        let stylesheet = StyleSheet::decode(&mut decoder)?;
+
+        if !decoder.is_empty() {
+            return None;
+        }
+
+        Some(stylesheet)
+    }
+
+    /// Write the stylesheet parsed from `path` to the cache directory
+    pub(crate) fn store(&self, path: &Path, hash: u64, file: &File, stylesheet: &StyleSheet) {
+        let stored_path = match self.stored_path(path) {
+            Some(stored_path) => stored_path,
+            None => return,
+        };
+
+        let mut encoder = Encoder::new(file.span);
+        encoder.write_bytes(HEADER.as_bytes());
+        encoder.write_u64(FORMAT_VERSION);
+        encoder.write_u64(hash);
+
+        let encoded = path
+            .to_str()
+            .and_then(|path| path.encode(&mut encoder))
+            .and_then(|()| stylesheet.encode(&mut encoder));
+
+        if encoded.is_none() {
+            return;
+        }
+
+        // write to a temporary file first, so that other processes never
+        // observe a partially written file
+        let temp_path = stored_path.with_extension(format!("tmp{}", std::process::id()));
+
+        let written = self
+            .directory
+            .as_ref()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&temp_path, encoder.finish()))
+            .and_then(|()| fs::rename(&temp_path, &stored_path));
+
+        if written.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{path::PathBuf, sync::Arc};
+
+    use super::{fnv1a, ParseCache, FORMAT_VERSION};
+    use crate::ast::StyleSheet;
+
+    /// The fingerprint of `codec.rs` for each version of the encoding since
+    /// they were first recorded
+    ///
+    /// Every node of the AST is encoded and decoded field by field, so any
+    /// change to the AST that affects its encoding also changes `codec.rs`.
+    const FORMAT_FINGERPRINTS: [(u64, u64); 1] = [(2, 0x73d7_1fc0_9a9e_a27a)];
+
+    /// The hash of `codec.rs`, without its tests, comments, or whitespace
+    fn codec_fingerprint() -> u64 {
+        let source = include_str!("codec.rs");
+        let codec = source.split("#[cfg(test)]").next().unwrap();
+
+        let code: String = codec
+            .lines()
+            .flat_map(|line| line.split("//").next().unwrap().chars())
+            .filter(|c| !c.is_whitespace())
+            .collect();
+
+        fnv1a(code.as_bytes())
+    }
+
+    #[test]
+    fn format_version_changes_with_codec() {
+        let fingerprint = codec_fingerprint();
+
+        assert_eq!(
+            FORMAT_FINGERPRINTS.last(),
+            Some(&(FORMAT_VERSION, fingerprint)),
+            "the encoding of stylesheets has changed: increment FORMAT_VERSION, and add \
+             ({}, {:#018x}) to FORMAT_FINGERPRINTS",
+            FORMAT_VERSION + 1,
+            fingerprint,
+        );
+        assert!(FORMAT_FINGERPRINTS
+            .windows(2)
+            .all(|pair| pair[0].0 < pair[1].0 && pair[0].1 != pair[1].1));
+    }
+
+    #[test]
+    fn compilations_share_files() {
+        let cache = ParseCache::new();
+        let (mut map, snapshot) = cache.lend();
+        let file = map.add_file("a.scss".to_owned(), "a { color: red; }".to_owned());
+        let stylesheet = StyleSheet::new(false, PathBuf::from("a.scss"));
+        cache.insert(
+            PathBuf::from("a.scss"),
+            0,
+            &file,
+            Arc::new(stylesheet),
+            snapshot,
+        );
+        drop(map);
+
+        let (map, snapshot) = cache.lend();
+        assert!(Arc::ptr_eq(map.find_file(file.span.low()), &file));
+        assert!(cache.get(&PathBuf::from("a.scss"), 0, snapshot).is_some());
+
+        // while the map is lent, other compilations can't use the cache
+        let (_, nested_snapshot) = cache.lend();
+        assert!(cache
+            .get(&PathBuf::from("a.scss"), 0, nested_snapshot)
+            .is_none());
+        drop(map);
+    }
+
+    #[test]
+    fn fnv1a_is_stable() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+}
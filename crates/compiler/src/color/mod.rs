@@ -29,7 +29,7 @@ pub struct Color {
     pub(crate) format: ColorFormat,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub(crate) enum ColorFormat {
     Rgb,
     Hsl,
@@ -54,6 +54,39 @@ impl PartialEq for Color {
 
 impl Eq for Color {}
 
+/// The exact representation of a [`Color`], including its format and any
+/// retained HSLA values
+///
+/// Unlike [`Color`]'s `PartialEq` implementation, two colors only have the
+/// same key if they are indistinguishable in every context.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ColorKey {
+    rgba: [u64; 4],
+    hsla: Option<[u64; 3]>,
+    format: ColorFormat,
+}
+
+impl Color {
+    pub(crate) fn key(&self) -> ColorKey {
+        ColorKey {
+            rgba: [
+                self.rgba.red.0.to_bits(),
+                self.rgba.green.0.to_bits(),
+                self.rgba.blue.0.to_bits(),
+                self.alpha.0.to_bits(),
+            ],
+            hsla: self.hsla.as_ref().map(|hsla| {
+                [
+                    hsla.hue.0.to_bits(),
+                    hsla.saturation.0.to_bits(),
+                    hsla.luminance.0.to_bits(),
+                ]
+            }),
+            format: self.format.clone(),
+        }
+    }
+}
+
 impl Color {
     pub(crate) const fn new_rgba(
         red: Number,
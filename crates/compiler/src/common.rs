@@ -1,8 +1,13 @@
-use std::fmt::{self, Display, Write};
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Write},
+};
 
 use crate::interner::InternedString;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "lowercase"))]
 pub enum UnaryOp {
     Plus,
     Neg,
@@ -65,6 +70,8 @@ impl Display for BinaryOp {
 
 /// Strings can either have quotes or not
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "lowercase"))]
 pub enum QuoteKind {
     Quoted,
     None,
@@ -82,12 +89,16 @@ impl Display for QuoteKind {
 
 /// Lists can either be bracketed or not
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "lowercase"))]
 pub enum Brackets {
     None,
     Bracketed,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "ast-json", derive(serde::Serialize))]
+#[cfg_attr(feature = "ast-json", serde(rename_all = "lowercase"))]
 pub enum ListSeparator {
     Space,
     Comma,
@@ -193,3 +204,16 @@ pub(crate) fn unvendor(name: &str) -> &str {
 
     name
 }
+
+/// `name`, borrowed from `known` if it is one of those names
+///
+/// This avoids copying names common enough to be listed in `known` each time
+/// they are cloned. Unlike interning, names that are not known are kept as
+/// they are, rather than added to a table that is never freed, because names
+/// written in stylesheets are arbitrary.
+pub(crate) fn known_name(name: String, known: &phf::Set<&'static str>) -> Cow<'static, str> {
+    match known.get_key(name.as_str()) {
+        Some(known) => Cow::Borrowed(known),
+        None => Cow::Owned(name),
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use crate::{evaluate::ModuleCache, from_string_with_file_name, Options, ParseCache, Result};
+
+/// A compilation session which remembers the files each entrypoint depended on
+/// the last time it was compiled
+///
+/// This allows tools such as file watchers to skip recompiling an entrypoint
+/// when none of the files it loads have changed, using
+/// [`Compiler::recompile_if_changed`], and to find out which files to watch,
+/// using [`Compiler::dependencies`]. Files are considered changed if their
+/// contents differ from when they were loaded.
+///
+/// Only the files that were actually loaded are tracked, so creating a new
+/// file which would change how an existing `@use` or `@import` is resolved
+/// does not cause the entrypoint to be recompiled.
+///
+/// When a [`ParseCache`] is passed to [`Options::parse_cache`], unchanged
+/// dependencies are not parsed again, and the session also reuses the modules
+/// loaded with `@use` and `@forward` that were evaluated by earlier
+/// compilations, provided none of the files they loaded have changed. Only
+/// modules which just define members are reused: those loaded without a `with`
+/// clause, which emit no CSS or messages, and which don't call `random()`,
+/// `unique-id()`, or custom functions. Assignments made to their variables by
+/// earlier compilations are undone. Entrypoints are always evaluated again, as
+/// are modules which are not reused.
+///
+/// The same options should be passed each time an entrypoint is compiled, as
+/// cached results are reused regardless of the options used to produce them.
+///
+/// A session refers to the interned names of the modules it has evaluated, so
+/// it may not be sent to another thread.
+///
+/// ```
+/// # use grass_compiler as grass;
+/// fn main() -> Result<(), Box<grass::Error>> {
+///     let compiler = grass::Compiler::new();
+///     let options = grass::Options::default();
+///
+///     println!("{}", compiler.compile("input.scss", &options)?);
+///
+///     // later, when a file may have changed
+///     if let Some(css) = compiler.recompile_if_changed("input.scss", &options)? {
+///         println!("{}", css);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Compiler {
+    compilations: RefCell<HashMap<PathBuf, Compilation>>,
+    modules: ModuleCache,
+}
+
+#[derive(Debug)]
+struct Compilation {
+    /// Every file loaded by the compilation, including the entrypoint, along
+    /// with the hash of its contents
+    dependencies: BTreeMap<PathBuf, u64>,
+    succeeded: bool,
+}
+
+impl Compiler {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compile CSS from a path, recording the files it depends on
+    ///
+    /// This behaves the same as [`from_path`](crate::from_path).
+    pub fn compile<P: AsRef<Path>>(&self, entrypoint: P, options: &Options) -> Result<String> {
+        let entrypoint = entrypoint.as_ref();
+        let mut dependencies = BTreeMap::new();
+
+        let result = self.compile_with_dependencies(entrypoint, options, &mut dependencies);
+
+        self.compilations.borrow_mut().insert(
+            Self::key(entrypoint, options),
+            Compilation {
+                dependencies,
+                succeeded: result.is_ok(),
+            },
+        );
+
+        result
+    }
+
+    /// Compile CSS from a path if it has not been compiled by this session,
+    /// or if it or any of the files it depended on have changed since it was
+    /// last compiled
+    ///
+    /// Returns `None` if nothing has changed, in which case the output of the
+    /// last compilation is still up to date. Entrypoints which failed to
+    /// compile are always recompiled.
+    pub fn recompile_if_changed<P: AsRef<Path>>(
+        &self,
+        entrypoint: P,
+        options: &Options,
+    ) -> Result<Option<String>> {
+        let entrypoint = entrypoint.as_ref();
+
+        let is_up_to_date = match self
+            .compilations
+            .borrow()
+            .get(&Self::key(entrypoint, options))
+        {
+            Some(compilation) => compilation.succeeded && !compilation.has_changed(options),
+            None => false,
+        };
+
+        if is_up_to_date {
+            return Ok(None);
+        }
+
+        self.compile(entrypoint, options).map(Some)
+    }
+
+    /// Whether any of the files `entrypoint` depended on the last time it was
+    /// compiled have changed since, which is also the case if it has not been
+    /// compiled by this session
+    ///
+    /// Unlike [`Compiler::recompile_if_changed`], this doesn't consider
+    /// entrypoints which failed to compile to be out of date.
+    #[cfg(feature = "watch")]
+    pub(crate) fn has_changed(&self, entrypoint: &Path, options: &Options) -> bool {
+        self.compilations
+            .borrow()
+            .get(&Self::key(entrypoint, options))
+            .map_or(true, |compilation| compilation.has_changed(options))
+    }
+
+    /// The files `entrypoint` depended on the last time it was compiled,
+    /// including `entrypoint` itself, or `None` if it has not been compiled by
+    /// this session
+    ///
+    /// If the compilation failed, only the files loaded before the error was
+    /// encountered are included.
+    pub fn dependencies<P: AsRef<Path>>(
+        &self,
+        entrypoint: P,
+        options: &Options,
+    ) -> Option<Vec<PathBuf>> {
+        self.compilations
+            .borrow()
+            .get(&Self::key(entrypoint.as_ref(), options))
+            .map(|compilation| compilation.dependencies.keys().cloned().collect())
+    }
+
+    fn key(entrypoint: &Path, options: &Options) -> PathBuf {
+        options
+            .fs
+            .canonicalize(entrypoint)
+            .unwrap_or_else(|_| entrypoint.to_path_buf())
+    }
+
+    fn compile_with_dependencies(
+        &self,
+        entrypoint: &Path,
+        options: &Options,
+        dependencies: &mut BTreeMap<PathBuf, u64>,
+    ) -> Result<String> {
+        let contents = options.fs.read(entrypoint)?;
+        dependencies.insert(
+            entrypoint.to_path_buf(),
+            ParseCache::hash_contents(&contents),
+        );
+
+        from_string_with_file_name(
+            String::from_utf8(contents)?,
+            entrypoint,
+            options,
+            false,
+            Some(dependencies),
+            Some(&self.modules),
+        )
+        .map_err(|mut errors| Box::new(errors.swap_remove(0)))
+    }
+}
+
+impl Compilation {
+    /// Whether the contents of any of the files loaded by the compilation
+    /// differ from when they were loaded
+    fn has_changed(&self, options: &Options) -> bool {
+        self.dependencies
+            .iter()
+            .any(|(path, &hash)| match options.fs.read(path) {
+                Ok(contents) => ParseCache::hash_contents(&contents) != hash,
+                Err(..) => true,
+            })
+    }
+}
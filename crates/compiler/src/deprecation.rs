@@ -0,0 +1,127 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// A deprecated feature of the Sass language
+///
+/// Each deprecation has a stable ID matching the one used by dart-sass, so
+/// that deprecations may be silenced individually using
+/// [`Options::silence_deprecation`](crate::Options::silence_deprecation).
+///
+/// Future deprecations cover features that will be deprecated in the future,
+/// but for which warnings are not yet emitted by default. They may be opted
+/// into using [`Options::future_deprecation`](crate::Options::future_deprecation).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Deprecation {
+    /// Passing a string directly to `meta.call()`
+    CallString,
+    /// `@elseif`
+    Elseif,
+    /// `@-moz-document`
+    MozDocument,
+    /// Declaring new variables with `!global`
+    NewGlobal,
+    /// Using color module functions in place of plain CSS functions
+    ColorModuleCompat,
+    /// `/` operator for division
+    SlashDiv,
+    /// Leading, trailing, and repeated combinators
+    BogusCombinators,
+    /// Ambiguous `+` and `-` operators
+    StrictUnary,
+    /// Passing invalid units to built-in functions
+    FunctionUnits,
+    /// Using `!default` or `!global` multiple times for one variable
+    DuplicateVarFlags,
+    /// Passing `null` as alpha to color functions
+    NullAlpha,
+    /// Passing percentages to `abs()`
+    AbsPercent,
+    /// Declarations that appear after nested rules
+    MixedDecls,
+    /// `meta.feature-exists()`
+    FeatureExists,
+    /// `@import`
+    Import,
+    /// Global built-in functions that are available in modules
+    GlobalBuiltin,
+    /// Legacy color functions, such as `darken()` and `transparentize()`
+    ColorFunctions,
+}
+
+impl Deprecation {
+    /// Every known deprecation
+    pub const ALL: &'static [Deprecation] = &[
+        Deprecation::CallString,
+        Deprecation::Elseif,
+        Deprecation::MozDocument,
+        Deprecation::NewGlobal,
+        Deprecation::ColorModuleCompat,
+        Deprecation::SlashDiv,
+        Deprecation::BogusCombinators,
+        Deprecation::StrictUnary,
+        Deprecation::FunctionUnits,
+        Deprecation::DuplicateVarFlags,
+        Deprecation::NullAlpha,
+        Deprecation::AbsPercent,
+        Deprecation::MixedDecls,
+        Deprecation::FeatureExists,
+        Deprecation::Import,
+        Deprecation::GlobalBuiltin,
+        Deprecation::ColorFunctions,
+    ];
+
+    /// The stable ID of this deprecation, for example `slash-div`
+    #[must_use]
+    pub const fn id(self) -> &'static str {
+        match self {
+            Deprecation::CallString => "call-string",
+            Deprecation::Elseif => "elseif",
+            Deprecation::MozDocument => "moz-document",
+            Deprecation::NewGlobal => "new-global",
+            Deprecation::ColorModuleCompat => "color-module-compat",
+            Deprecation::SlashDiv => "slash-div",
+            Deprecation::BogusCombinators => "bogus-combinators",
+            Deprecation::StrictUnary => "strict-unary",
+            Deprecation::FunctionUnits => "function-units",
+            Deprecation::DuplicateVarFlags => "duplicate-var-flags",
+            Deprecation::NullAlpha => "null-alpha",
+            Deprecation::AbsPercent => "abs-percent",
+            Deprecation::MixedDecls => "mixed-decls",
+            Deprecation::FeatureExists => "feature-exists",
+            Deprecation::Import => "import",
+            Deprecation::GlobalBuiltin => "global-builtin",
+            Deprecation::ColorFunctions => "color-functions",
+        }
+    }
+
+    /// Whether this is a future deprecation, whose warnings are only emitted
+    /// when opted into
+    #[must_use]
+    pub const fn is_future(self) -> bool {
+        matches!(
+            self,
+            Deprecation::Import | Deprecation::GlobalBuiltin | Deprecation::ColorFunctions
+        )
+    }
+}
+
+impl Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl FromStr for Deprecation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Deprecation::ALL
+            .iter()
+            .copied()
+            .find(|deprecation| deprecation.id() == s)
+            .ok_or_else(|| format!("Invalid deprecation \"{}\".", s))
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    borrow::Cow,
     error::Error,
     fmt::{self, Display},
     io,
@@ -6,7 +7,14 @@ use std::{
     sync::Arc,
 };
 
-use codemap::{Span, SpanLoc};
+use codemap::{CodeMap, Span, SpanLoc};
+
+#[cfg(feature = "json")]
+use crate::json;
+use crate::{
+    utils::{look_up_span, source_line},
+    StackFrame,
+};
 
 pub type SassResult<T> = Result<T, Box<SassError>>;
 
@@ -28,9 +36,23 @@ pub type SassResult<T> = Result<T, Box<SassError>>;
 #[derive(Debug, Clone)]
 pub struct SassError {
     kind: SassErrorKind,
+    trace: Vec<StackFrame>,
+    primary_label: Option<String>,
+    raw_labels: Vec<(String, Span)>,
+    secondary_labels: Vec<Label>,
 }
 
 impl SassError {
+    const fn new(kind: SassErrorKind) -> Self {
+        Self {
+            kind,
+            trace: Vec::new(),
+            primary_label: None,
+            raw_labels: Vec::new(),
+            secondary_labels: Vec::new(),
+        }
+    }
+
     #[must_use]
     pub fn kind(self) -> PublicSassErrorKind {
         match self.kind {
@@ -49,22 +71,196 @@ impl SassError {
         }
     }
 
-    pub(crate) fn raw(self) -> (String, Span) {
+    /// Serialize this error as a single-line JSON object, suitable for
+    /// consumption by editors and other tooling
+    ///
+    /// The object has the form
+    ///
+    /// ```json
+    /// {
+    ///   "severity": "error",
+    ///   "message": "Expected expression.",
+    ///   "span": {
+    ///     "file": "input.scss",
+    ///     "start": { "line": 1, "column": 11 },
+    ///     "end": { "line": 1, "column": 12 }
+    ///   },
+    ///   "trace": []
+    /// }
+    /// ```
+    ///
+    /// Lines and columns are 1-indexed. `span` is `null` for errors that do
+    /// not originate from a stylesheet, such as failing to read the entry-point
+    /// file. `trace` has the same format as in [`Warning::to_json`](crate::Warning::to_json).
+    #[cfg(feature = "json")]
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let (message, span) = match &self.kind {
+            SassErrorKind::ParseError { message, loc, .. } => {
+                (Cow::Borrowed(message.as_str()), Some(json::Span::from(loc)))
+            }
+            SassErrorKind::IoError(error) => (Cow::Owned(error.to_string()), None),
+            SassErrorKind::FromUtf8Error(message) => (Cow::Borrowed(message.as_str()), None),
+            SassErrorKind::Raw(..) => unreachable!("raw errors should not be accessible by users"),
+        };
+
+        json::to_string(&json::Error {
+            severity: "error",
+            message: &message,
+            span,
+            trace: json::trace(&self.trace),
+        })
+    }
+
+    /// The stack of mixin includes, function calls, and imports that were
+    /// being evaluated when the error occurred, innermost first
+    ///
+    /// This is empty if the error did not occur inside any mixin, function, or
+    /// imported file.
+    #[must_use]
+    pub fn trace(&self) -> &[StackFrame] {
+        &self.trace
+    }
+
+    /// The message of this error, without the `Error: ` prefix or any location
+    /// information
+    ///
+    /// See [`PublicSassErrorKind::ParseError`] for caveats about relying on the
+    /// contents of error messages.
+    #[must_use]
+    pub fn message(&self) -> Cow<'_, str> {
+        match &self.kind {
+            SassErrorKind::ParseError { message, .. } | SassErrorKind::FromUtf8Error(message) => {
+                Cow::Borrowed(message)
+            }
+            SassErrorKind::IoError(error) => Cow::Owned(error.to_string()),
+            SassErrorKind::Raw(..) => unreachable!("raw errors should not be accessible by users"),
+        }
+    }
+
+    /// The primary location of this error
+    ///
+    /// This is `None` for errors that do not originate from a stylesheet, such
+    /// as failing to read the entry-point file.
+    #[must_use]
+    pub fn span(&self) -> Option<&SpanLoc> {
+        match &self.kind {
+            SassErrorKind::ParseError { loc, .. } => Some(loc),
+            SassErrorKind::IoError(..) | SassErrorKind::FromUtf8Error(..) => None,
+            SassErrorKind::Raw(..) => unreachable!("raw errors should not be accessible by users"),
+        }
+    }
+
+    /// A short description of the primary location of this error, if it has
+    /// one, for example `invocation` for errors in the arguments passed to a
+    /// function or mixin
+    #[must_use]
+    pub fn primary_label(&self) -> Option<&str> {
+        self.primary_label.as_deref()
+    }
+
+    /// Additional locations related to this error, for example the
+    /// `declaration` of a function or mixin that was passed the wrong
+    /// arguments
+    #[must_use]
+    pub fn secondary_labels(&self) -> &[Label] {
+        &self.secondary_labels
+    }
+
+    /// Label the primary location of a raw error
+    pub(crate) fn with_primary_label(mut self: Box<Self>, label: &str) -> Box<Self> {
+        self.primary_label = Some(label.to_owned());
+        self
+    }
+
+    /// Add a labelled location to a raw error, in addition to its primary
+    /// location
+    pub(crate) fn with_secondary_label(mut self: Box<Self>, label: &str, span: Span) -> Box<Self> {
+        self.raw_labels.push((label.to_owned(), span));
+        self
+    }
+
+    /// Prefix the message of a raw error with the name of the argument that
+    /// caused it, as in `$selector: expected selector.`
+    pub(crate) fn with_argument_name(mut self: Box<Self>, name: &str) -> Box<Self> {
+        if let SassErrorKind::Raw(message, ..) = &mut self.kind {
+            *message = format!("${}: {}", name, message);
+        }
+        self
+    }
+
+    /// Resolve a raw error against `map`, so that it can be shown to users
+    pub(crate) fn into_parse_error(self, map: &CodeMap, unicode: bool) -> Self {
         match self.kind {
-            SassErrorKind::Raw(string, span) => (string, span),
+            SassErrorKind::Raw(message, span) => SassError {
+                kind: SassErrorKind::ParseError {
+                    message,
+                    loc: look_up_span(map, span),
+                    unicode,
+                },
+                trace: self.trace,
+                primary_label: self.primary_label,
+                raw_labels: Vec::new(),
+                secondary_labels: self
+                    .raw_labels
+                    .into_iter()
+                    .map(|(message, span)| Label {
+                        message,
+                        location: look_up_span(map, span),
+                    })
+                    .collect(),
+            },
             e => unreachable!("unable to get raw of {:?}", e),
         }
     }
 
-    pub(crate) const fn from_loc(message: String, loc: SpanLoc, unicode: bool) -> Self {
-        SassError {
-            kind: SassErrorKind::ParseError {
-                message,
-                loc,
-                unicode,
-            },
+    /// The message of a raw error
+    #[cfg(feature = "plugins")]
+    pub(crate) fn raw_message(&self) -> Option<&str> {
+        match &self.kind {
+            SassErrorKind::Raw(message, _) => Some(message),
+            _ => None,
         }
     }
+
+    /// The span of a raw error
+    pub(crate) fn raw_span(&self) -> Option<Span> {
+        match self.kind {
+            SassErrorKind::Raw(_, span) => Some(span),
+            _ => None,
+        }
+    }
+
+    /// Attach `trace` to this error, unless a trace has already been attached
+    /// by an inner stack frame
+    pub(crate) fn set_trace_if_empty(&mut self, trace: impl FnOnce() -> Vec<StackFrame>) {
+        if self.trace.is_empty() {
+            self.trace = trace();
+        }
+    }
+}
+
+/// A labelled location related to a [`SassError`], in addition to its primary
+/// location
+#[derive(Debug, Clone)]
+pub struct Label {
+    message: String,
+    location: SpanLoc,
+}
+
+impl Label {
+    /// A short description of how this location relates to the error, for
+    /// example `declaration`
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The location this label points to
+    #[must_use]
+    pub const fn location(&self) -> &SpanLoc {
+        &self.location
+    }
 }
 
 #[non_exhaustive]
@@ -134,40 +330,121 @@ impl Display for SassError {
 
         let first_bar = if unicode { '╷' } else { ',' };
         let second_bar = if unicode { '│' } else { '|' };
-        let third_bar = if unicode { '│' } else { '|' };
         let fourth_bar = if unicode { '╵' } else { '\'' };
 
         let line = loc.begin.line + 1;
         let col = loc.begin.column + 1;
         writeln!(f, "Error: {}", message)?;
-        let padding = vec![' '; format!("{}", line).len() + 1]
-            .iter()
-            .collect::<String>();
+
+        // a span that ends at the very start of a line doesn't highlight any of it
+        let (end_line, end_column) = if loc.end.line > loc.begin.line && loc.end.column == 0 {
+            let end_line = loc.end.line - 1;
+            (end_line, source_line(&loc.file, end_line).chars().count())
+        } else {
+            (loc.end.line, loc.end.column)
+        };
+
+        let width = format!("{}", end_line + 1).len();
+        let padding = " ".repeat(width + 1);
         writeln!(f, "{}{}", padding, first_bar)?;
-        writeln!(
-            f,
-            "{} {} {}",
-            line,
-            second_bar,
-            loc.file.source_line(loc.begin.line)
-        )?;
-        writeln!(
-            f,
-            "{}{} {}{}",
-            padding,
-            third_bar,
-            vec![' '; loc.begin.column].iter().collect::<String>(),
-            vec!['^'; loc.end.column.max(loc.begin.column) - loc.begin.column.min(loc.end.column)]
-                .iter()
-                .collect::<String>()
-        )?;
-        writeln!(f, "{}{}", padding, fourth_bar)?;
 
-        if unicode {
-            writeln!(f, "./{}:{}:{}", loc.file.name(), line, col)?;
+        if end_line == loc.begin.line {
+            writeln!(
+                f,
+                "{:<width$} {} {}",
+                line,
+                second_bar,
+                source_line(&loc.file, loc.begin.line),
+                width = width
+            )?;
+            writeln!(
+                f,
+                "{}{} {}{}",
+                padding,
+                second_bar,
+                " ".repeat(loc.begin.column),
+                "^".repeat(end_column.saturating_sub(loc.begin.column).max(1))
+            )?;
         } else {
-            writeln!(f, "  {} {}:{}  root stylesheet", loc.file.name(), line, col)?;
+            let top_corner = if unicode { '┌' } else { ',' };
+            let bottom_corner = if unicode { '└' } else { '\'' };
+            let horizontal = if unicode { "─" } else { "-" };
+
+            writeln!(
+                f,
+                "{:<width$} {}   {}",
+                line,
+                second_bar,
+                source_line(&loc.file, loc.begin.line),
+                width = width
+            )?;
+            writeln!(
+                f,
+                "{}{} {}{}^",
+                padding,
+                second_bar,
+                top_corner,
+                horizontal.repeat(loc.begin.column + 1)
+            )?;
+
+            for line_idx in (loc.begin.line + 1)..=end_line {
+                writeln!(
+                    f,
+                    "{:<width$} {} {} {}",
+                    line_idx + 1,
+                    second_bar,
+                    second_bar,
+                    source_line(&loc.file, line_idx),
+                    width = width
+                )?;
+            }
+
+            if end_column == source_line(&loc.file, end_line).chars().count() {
+                writeln!(
+                    f,
+                    "{}{} {}{}",
+                    padding,
+                    second_bar,
+                    bottom_corner,
+                    horizontal.repeat(3)
+                )?;
+            } else {
+                writeln!(
+                    f,
+                    "{}{} {}{}^",
+                    padding,
+                    second_bar,
+                    bottom_corner,
+                    horizontal.repeat(end_column.max(1))
+                )?;
+            }
         }
+
+        writeln!(f, "{}{}", padding, fourth_bar)?;
+
+        if self.trace.is_empty() {
+            if unicode {
+                writeln!(f, "./{}:{}:{}", loc.file.name(), line, col)?;
+            } else {
+                writeln!(f, "  {} {}:{}  root stylesheet", loc.file.name(), line, col)?;
+            }
+        }
+
+        for frame in &self.trace {
+            if unicode {
+                writeln!(f, "{}", frame)?;
+            } else {
+                writeln!(
+                    f,
+                    "  {} {}:{}  {}",
+                    frame.location.file.name(),
+                    frame.location.begin.line + 1,
+                    frame.location.begin.column + 1,
+                    frame.member
+                )?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -175,39 +452,34 @@ impl Display for SassError {
 impl From<io::Error> for Box<SassError> {
     #[inline]
     fn from(error: io::Error) -> Box<SassError> {
-        Box::new(SassError {
-            kind: SassErrorKind::IoError(Arc::new(error)),
-        })
+        Box::new(SassError::new(SassErrorKind::IoError(Arc::new(error))))
     }
 }
 
 impl From<FromUtf8Error> for Box<SassError> {
     #[inline]
     fn from(error: FromUtf8Error) -> Box<SassError> {
-        Box::new(SassError {
-            kind: SassErrorKind::FromUtf8Error(format!(
-                "Invalid UTF-8 character \"\\x{:X?}\"",
-                error.as_bytes()[0]
-            )),
-        })
+        Box::new(SassError::new(SassErrorKind::FromUtf8Error(format!(
+            "Invalid UTF-8 character \"\\x{:X?}\"",
+            error.as_bytes()[0]
+        ))))
     }
 }
 
 impl From<(&str, Span)> for Box<SassError> {
     #[inline]
     fn from(error: (&str, Span)) -> Box<SassError> {
-        Box::new(SassError {
-            kind: SassErrorKind::Raw(error.0.to_owned(), error.1),
-        })
+        Box::new(SassError::new(SassErrorKind::Raw(
+            error.0.to_owned(),
+            error.1,
+        )))
     }
 }
 
 impl From<(String, Span)> for Box<SassError> {
     #[inline]
     fn from(error: (String, Span)) -> Box<SassError> {
-        Box::new(SassError {
-            kind: SassErrorKind::Raw(error.0, error.1),
-        })
+        Box::new(SassError::new(SassErrorKind::Raw(error.0, error.1)))
     }
 }
 
@@ -32,6 +32,12 @@ impl CssTree {
         tree
     }
 
+    /// The number of statements added to the tree, including those since
+    /// removed
+    pub fn stmt_count(&self) -> usize {
+        self.stmts.len()
+    }
+
     pub fn get(&self, idx: CssTreeIdx) -> Ref<Option<CssStmt>> {
         self.stmts[idx.0].borrow()
     }
@@ -128,8 +134,36 @@ impl CssTree {
 
         let parent_children = self.parent_to_child.get(parent_idx).unwrap();
 
-        // todo: we shouldn't take into account children that are invisible
-        parent_children.last() != Some(&child)
+        parent_children
+            .iter()
+            .skip_while(|&&sibling| sibling != child)
+            .skip(1)
+            .any(|&sibling| !self.is_invisible(sibling))
+    }
+
+    /// Whether nothing would be written for the statement at `idx`
+    ///
+    /// Statements in the tree don't contain their children until `finish` is
+    /// called, so we can't use `CssStmt::is_invisible` here. Comments are always
+    /// considered visible, as they are in dart-sass.
+    fn is_invisible(&self, idx: CssTreeIdx) -> bool {
+        let children_are_invisible = || {
+            self.parent_to_child.get(&idx).map_or(true, |children| {
+                children.iter().all(|&child| self.is_invisible(child))
+            })
+        };
+
+        match self.get(idx).as_ref() {
+            Some(CssStmt::RuleSet { selector, .. }) => {
+                selector.is_invisible() || children_are_invisible()
+            }
+            Some(CssStmt::Media(..) | CssStmt::Supports(..) | CssStmt::KeyframesRuleSet(..)) => {
+                children_are_invisible()
+            }
+            Some(CssStmt::Style(style)) => style.value.node.is_blank(),
+            Some(CssStmt::UnknownAtRule(..) | CssStmt::Comment(..) | CssStmt::Import(..)) => false,
+            None => true,
+        }
     }
 
     pub fn add_stmt(&mut self, child: CssStmt, parent: Option<CssTreeIdx>) -> CssTreeIdx {
@@ -4,8 +4,9 @@ use crate::{
     ast::{AstForwardRule, Configuration, ConfiguredValue, Mixin},
     builtin::modules::{ForwardedModule, Module, ModuleScope, Modules, ShadowedModule},
     common::Identifier,
-    error::SassResult,
+    error::{SassError, SassResult},
     selector::ExtensionStore,
+    utils::closest_match,
     value::{SassFunction, Value},
 };
 use std::{
@@ -261,17 +262,28 @@ impl Environment {
         if let Some(namespace) = namespace {
             let modules = (*self.modules).borrow();
             let module = modules.get(namespace.node, namespace.span)?;
-            return (*module).borrow().get_mixin(name);
+            let module = (*module).borrow();
+            return module.get_mixin_no_err(name.node).ok_or_else(|| {
+                undefined_member(
+                    "Undefined mixin.",
+                    &format!("{}.", namespace.node),
+                    name,
+                    module.scope().mixins.keys(),
+                )
+            });
         }
 
         match self.scopes.get_mixin(name) {
             Ok(v) => Ok(v),
-            Err(e) => {
+            Err(..) => {
                 if let Some(v) = self.get_mixin_from_global_modules(name.node) {
                     return Ok(v);
                 }
 
-                Err(e)
+                let mut candidates = self.scopes.mixin_names();
+                candidates.append(&mut self.module_member_names(|scope| scope.mixins.keys()));
+
+                Err(undefined_member("Undefined mixin.", "", name, candidates))
             }
         }
     }
@@ -323,17 +335,33 @@ impl Environment {
         if let Some(namespace) = namespace {
             let modules = (*self.modules).borrow();
             let module = modules.get(namespace.node, namespace.span)?;
-            return (*module).borrow().get_var(name);
+            let module = (*module).borrow();
+            return module.get_var_no_err(name.node).ok_or_else(|| {
+                undefined_member(
+                    "Undefined variable.",
+                    &format!("{}.$", namespace.node),
+                    name,
+                    module.scope().variables.keys(),
+                )
+            });
         }
 
         match self.scopes.get_var(name) {
             Ok(v) => Ok(v),
-            Err(e) => {
+            Err(..) => {
                 if let Some(v) = self.get_variable_from_global_modules(name.node) {
-                    Ok(v)
-                } else {
-                    Err(e)
+                    return Ok(v);
                 }
+
+                let mut candidates = self.scopes.variable_names();
+                candidates.append(&mut self.module_member_names(|scope| scope.variables.keys()));
+
+                Err(undefined_member(
+                    "Undefined variable.",
+                    "$",
+                    name,
+                    candidates,
+                ))
             }
         }
     }
@@ -411,6 +439,42 @@ impl Environment {
         self.scopes.global_functions()
     }
 
+    /// The names of the members of every module whose members are available
+    /// without a namespace
+    fn module_member_names(
+        &self,
+        members: impl Fn(&ModuleScope) -> Vec<Identifier>,
+    ) -> Vec<Identifier> {
+        let mut names = Vec::new();
+
+        let mut add_members = |module: &Mutable<Module>| {
+            names.append(&mut members(&(**module).borrow().scope()));
+        };
+
+        if let Some(nested_forwarded_modules) = &self.nested_forwarded_modules {
+            for modules in nested_forwarded_modules.borrow().iter() {
+                modules.borrow().iter().for_each(&mut add_members);
+            }
+        }
+
+        self.imported_modules
+            .borrow()
+            .iter()
+            .for_each(&mut add_members);
+        self.global_modules.iter().for_each(&mut add_members);
+
+        names
+    }
+
+    /// The names of the functions in the module with the given namespace, for
+    /// use in suggestions when a function is undefined
+    pub fn module_function_names(&self, namespace: Identifier) -> Vec<Identifier> {
+        match (*self.modules).borrow().0.get(&namespace) {
+            Some(module) => (**module).borrow().scope().functions.keys(),
+            None => Vec::new(),
+        }
+    }
+
     fn get_variable_from_global_modules(&self, name: Identifier) -> Option<Value> {
         self.from_one_module(name, "variable", |module| {
             (**module).borrow().get_var_no_err(name)
@@ -518,3 +582,30 @@ impl Environment {
         value
     }
 }
+
+/// An error for a reference to the undefined member `name`, suggesting the
+/// name in `candidates` it is most likely a misspelling of
+///
+/// `prefix` is prepended to the suggested name, for example `$` for variables.
+pub(crate) fn undefined_member(
+    message: &str,
+    prefix: &str,
+    name: Spanned<Identifier>,
+    candidates: Vec<Identifier>,
+) -> Box<SassError> {
+    let mut candidates = candidates
+        .iter()
+        .map(Identifier::as_str)
+        .collect::<Vec<_>>();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    match closest_match(name.node.as_str(), candidates) {
+        Some(suggestion) => (
+            format!("{}\n\nDid you mean {}{}?", message, prefix, suggestion),
+            name.span,
+        )
+            .into(),
+        None => (message, name.span).into(),
+    }
+}
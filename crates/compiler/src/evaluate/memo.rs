@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::ArgumentResult,
+    builtin::Builtin,
+    color::ColorKey,
+    common::{Identifier, ListSeparator, QuoteKind},
+    unit::Unit,
+    value::Value,
+};
+
+/// The maximum number of results retained. Once the table is full, further
+/// calls are evaluated as normal
+const MAX_ENTRIES: usize = 4096;
+
+/// An argument that is only equal to another argument if the two are
+/// indistinguishable, rather than by Sass equality
+///
+/// For example, `1px` and `0.0625pc` are equal in Sass, but a function may
+/// return different results for each.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum LiteralArg {
+    True,
+    False,
+    Null,
+    Number(u64, Unit),
+    Color(ColorKey),
+    String(String, QuoteKind),
+}
+
+impl LiteralArg {
+    fn new(value: &Value) -> Option<Self> {
+        Some(match value {
+            Value::True => Self::True,
+            Value::False => Self::False,
+            Value::Null => Self::Null,
+            Value::Dimension(number) if number.as_slash.is_none() => {
+                Self::Number(number.num.0.to_bits(), number.unit.clone())
+            }
+            Value::Color(color) => Self::Color(color.key()),
+            Value::String(text, quotes) => Self::String(text.clone(), *quotes),
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) struct CallKey {
+    function: usize,
+    positional: Vec<LiteralArg>,
+    named: Vec<(Identifier, LiteralArg)>,
+    separator: ListSeparator,
+}
+
+impl CallKey {
+    /// The key for calling `function` with `args`, or `None` if the call
+    /// cannot be memoized
+    pub(crate) fn new(function: &Builtin, args: &ArgumentResult) -> Option<Self> {
+        if !function.is_pure() {
+            return None;
+        }
+
+        Some(Self {
+            function: function.id(),
+            positional: args
+                .positional
+                .iter()
+                .map(LiteralArg::new)
+                .collect::<Option<_>>()?,
+            named: args
+                .named
+                .iter()
+                .map(|(name, value)| Some((*name, LiteralArg::new(value)?)))
+                .collect::<Option<_>>()?,
+            separator: args.separator,
+        })
+    }
+}
+
+/// The results of calls to pure builtin functions with literal arguments,
+/// such as `darken($brand, 10%)`, so that repeated calls need not be
+/// recomputed
+#[derive(Debug, Default)]
+pub(crate) struct BuiltinMemo {
+    results: HashMap<CallKey, Value>,
+}
+
+impl BuiltinMemo {
+    pub(crate) fn get(&self, key: &CallKey) -> Option<&Value> {
+        self.results.get(key)
+    }
+
+    pub(crate) fn insert(&mut self, key: CallKey, value: &Value) {
+        if self.results.len() < MAX_ENTRIES {
+            self.results.insert(key, value.clone());
+        }
+    }
+}
@@ -1,9 +1,12 @@
 pub(crate) use bin_op::{cmp, div};
 pub(crate) use env::Environment;
+pub(crate) use module_cache::ModuleCache;
 pub use visitor::Visitor;
 
 mod bin_op;
 mod css_tree;
 mod env;
+mod memo;
+mod module_cache;
 mod scope;
 mod visitor;
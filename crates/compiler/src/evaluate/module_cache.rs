@@ -0,0 +1,108 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
+
+use crate::{builtin::modules::Module, cache::Snapshot, common::Identifier, value::Value};
+
+/// Modules evaluated by earlier compilations of a [`Compiler`](crate::Compiler),
+/// which later compilations reuse if none of the files they loaded have
+/// changed
+///
+/// Only modules whose evaluation had no effect other than defining their
+/// members are cached: those loaded without configuration, which emitted no
+/// CSS or messages, and which called no functions that may return different
+/// results each time. Their spans refer to files in a
+/// [`ParseCache`](crate::ParseCache), so they are only reused while it still
+/// contains those files at the same locations.
+#[derive(Debug, Default)]
+pub(crate) struct ModuleCache {
+    modules: RefCell<BTreeMap<PathBuf, Rc<CachedModule>>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct CachedModule {
+    /// The state of the parse cache when the module was evaluated
+    pub snapshot: Snapshot,
+    pub module: Arc<RefCell<Module>>,
+    /// The module's variables, which may be reassigned by the stylesheets that
+    /// use it
+    pub variables: Arc<RefCell<BTreeMap<Identifier, Value>>>,
+    /// The values of `variables` once the module had been evaluated, which are
+    /// restored each time it is reused
+    pub initial_variables: BTreeMap<Identifier, Value>,
+    pub recording: ModuleRecording,
+}
+
+impl CachedModule {
+    /// Undo any assignments made to the module's variables since it was
+    /// evaluated
+    pub fn reset(&self) {
+        *self.variables.borrow_mut() = self.initial_variables.clone();
+    }
+}
+
+/// What the evaluation of a module depended on
+#[derive(Debug, Clone)]
+pub(crate) struct ModuleRecording {
+    /// Every file loaded, including the module's own, along with the hash of
+    /// its contents
+    pub files: BTreeMap<PathBuf, u64>,
+    /// Those of `files` whose warnings are silenced by
+    /// [`Options::quiet_deps`](crate::Options::quiet_deps)
+    pub dependencies: BTreeSet<PathBuf>,
+    /// Every module used, by URL, each of which was cacheable too
+    pub modules: BTreeMap<PathBuf, Rc<CachedModule>>,
+    pub cacheable: bool,
+}
+
+impl Default for ModuleRecording {
+    fn default() -> Self {
+        Self {
+            files: BTreeMap::new(),
+            dependencies: BTreeSet::new(),
+            modules: BTreeMap::new(),
+            cacheable: true,
+        }
+    }
+}
+
+impl ModuleRecording {
+    /// Record that `module`, found at `url`, was used
+    pub fn add_module(&mut self, url: &Path, module: &Rc<CachedModule>) {
+        self.files.extend(
+            module
+                .recording
+                .files
+                .iter()
+                .map(|(path, &hash)| (path.clone(), hash)),
+        );
+        self.dependencies
+            .extend(module.recording.dependencies.iter().cloned());
+        self.modules.extend(
+            module
+                .recording
+                .modules
+                .iter()
+                .map(|(url, module)| (url.clone(), Rc::clone(module))),
+        );
+        self.modules.insert(url.to_path_buf(), Rc::clone(module));
+    }
+}
+
+impl ModuleCache {
+    pub fn get(&self, url: &Path) -> Option<Rc<CachedModule>> {
+        self.modules.borrow().get(url).cloned()
+    }
+
+    pub fn insert(&self, url: PathBuf, module: Rc<CachedModule>) {
+        self.modules.borrow_mut().insert(url, module);
+    }
+
+    pub fn remove(&self, url: &Path) {
+        self.modules.borrow_mut().remove(url);
+    }
+}
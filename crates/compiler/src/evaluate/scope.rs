@@ -14,6 +14,13 @@ use crate::{
     value::{SassFunction, Value},
 };
 
+/// The stack of lexical scopes visible from the current point of evaluation
+///
+/// Each frame is reference counted, so [`Scopes::new_closure`] only copies the
+/// pointers to its parent frames rather than the variables they contain.
+/// Lookups walk the frames from innermost to outermost, and writes only touch
+/// the frame the variable was found in (or the innermost frame for new
+/// variables), so closures observe assignments made to their parent scopes.
 #[allow(clippy::type_complexity)]
 #[derive(Debug, Default, Clone)]
 pub(crate) struct Scopes {
@@ -168,6 +175,15 @@ impl Scopes {
         false
     }
 
+    /// The names of the variables in every scope
+    pub fn variable_names(&self) -> Vec<Identifier> {
+        (*self.variables)
+            .borrow()
+            .iter()
+            .flat_map(|scope| (**scope).borrow().keys().copied().collect::<Vec<_>>())
+            .collect()
+    }
+
     pub fn global_var_exists(&self, name: Identifier) -> bool {
         self.global_variables().borrow().contains_key(&name)
     }
@@ -194,6 +210,15 @@ impl Scopes {
         Err(("Undefined mixin.", name.span).into())
     }
 
+    /// The names of the mixins in every scope
+    pub fn mixin_names(&self) -> Vec<Identifier> {
+        (*self.mixins)
+            .borrow()
+            .iter()
+            .flat_map(|scope| (**scope).borrow().keys().copied().collect::<Vec<_>>())
+            .collect()
+    }
+
     pub fn mixin_exists(&self, name: Identifier) -> bool {
         debug_assert_eq!(self.len(), (*self.variables).borrow().len());
         for scope in (*self.mixins).borrow().iter() {
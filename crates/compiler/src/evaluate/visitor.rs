@@ -1,27 +1,27 @@
 use std::{
     cell::{Cell, RefCell},
-    collections::{BTreeMap, BTreeSet, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     ffi::OsStr,
     fmt,
     iter::FromIterator,
     mem,
     path::{Path, PathBuf},
+    ptr,
+    rc::Rc,
     sync::Arc,
 };
 
-use codemap::{CodeMap, Span, Spanned};
+use codemap::{CodeMap, File, Span, Spanned};
 use indexmap::IndexSet;
 
 use crate::{
     ast::*,
     builtin::{
         meta::if_arguments,
-        modules::{
-            declare_module_color, declare_module_list, declare_module_map, declare_module_math,
-            declare_module_meta, declare_module_selector, declare_module_string, Module,
-        },
+        modules::{declare_builtin_module, Module},
         GLOBAL_FUNCTIONS,
     },
+    cache::Snapshot,
     common::{unvendor, BinaryOp, Identifier, ListSeparator, QuoteKind, UnaryOp},
     error::{SassError, SassResult},
     interner::InternedString,
@@ -31,23 +31,30 @@ use crate::{
         StylesheetParser,
     },
     selector::{
-        ComplexSelectorComponent, ExtendRule, ExtendedSelector, ExtensionStore, SelectorList,
-        SelectorParser,
+        ComplexSelectorComponent, CompoundSelector, ExtendRule, ExtendedSelector, ExtensionStore,
+        SelectorList, SelectorParser,
     },
-    utils::{to_sentence, trim_ascii},
+    unit::Unit,
+    utils::{ensure_sufficient_stack, look_up_span, to_sentence, trim_ascii},
     value::{
         ArgList, CalculationArg, CalculationName, Number, SassCalculation, SassFunction, SassMap,
         SassNumber, UserDefinedFunction, Value,
     },
-    ContextFlags, InputSyntax, Options,
+    ContextFlags, Deprecation, InputSyntax, Lint, Options, ParseCache, StackFrame, Warning,
 };
 
 use super::{
     bin_op::{add, cmp, div, mul, rem, single_eq, sub},
     css_tree::{CssTree, CssTreeIdx},
-    env::Environment,
+    env::{undefined_member, Environment},
+    memo::{BuiltinMemo, CallKey},
+    module_cache::{CachedModule, ModuleCache, ModuleRecording},
 };
 
+/// The number of warnings emitted for a single deprecation before further
+/// warnings are omitted, unless running in verbose mode
+const MAX_REPETITIVE_DEPRECATIONS: usize = 5;
+
 trait UserDefinedCallable {
     fn name(&self) -> Identifier;
     fn arguments(&self) -> &ArgumentDeclaration;
@@ -73,7 +80,7 @@ impl UserDefinedCallable for Arc<AstFunctionDecl> {
     }
 }
 
-impl UserDefinedCallable for AstMixin {
+impl UserDefinedCallable for Arc<AstMixin> {
     fn name(&self) -> Identifier {
         self.name
     }
@@ -95,7 +102,7 @@ impl UserDefinedCallable for Arc<CallableContentBlock> {
 
 #[derive(Debug, Clone)]
 pub(crate) struct CallableContentBlock {
-    content: AstContentBlock,
+    content: Arc<AstContentBlock>,
     env: Environment,
 }
 
@@ -108,6 +115,25 @@ pub struct Visitor<'a> {
     pub(crate) style_rule_ignoring_at_root: Option<ExtendedSelector>,
     // avoid emitting duplicate warnings for the same span
     pub(crate) warnings_emitted: HashSet<Span>,
+    /// The number of warnings emitted for each deprecation, so that repetitive
+    /// warnings can be omitted unless running in verbose mode
+    deprecation_counts: HashMap<Deprecation, usize>,
+    /// The number of warnings emitted so far, before any are silenced or
+    /// omitted. Calls that emit warnings are not memoized, so that the
+    /// warnings are emitted every time
+    warning_count: usize,
+    builtin_memo: BuiltinMemo,
+    /// The name of the member currently being evaluated, used in stack traces
+    member: String,
+    /// The name the innermost builtin function currently being called was
+    /// called by, which allows several names to share one implementation
+    pub(crate) current_builtin: Option<Identifier>,
+    /// The call sites of the members currently being evaluated, each paired
+    /// with the name of the member that contains it
+    stack: Vec<(String, Span)>,
+    /// The unvendored, lowercase name of the innermost unknown at-rule being
+    /// evaluated, if any
+    unknown_at_rule_name: Option<String>,
     pub(crate) media_queries: Option<Vec<MediaQuery>>,
     pub(crate) media_query_sources: Option<IndexSet<MediaQuery>>,
     pub(crate) extender: ExtensionStore,
@@ -116,6 +142,11 @@ pub struct Visitor<'a> {
     /// resolved relative to this path
     pub current_import_path: PathBuf,
     pub(crate) is_plain_css: bool,
+    /// Whether the entrypoint is plain CSS, and is being evaluated by
+    /// [`Visitor::visit_plain_css_stylesheet`]. Plain CSS can neither load
+    /// other stylesheets nor use `@extend`, so in this case we skip tracking
+    /// selectors for extension entirely
+    is_plain_css_compilation: bool,
     pub(crate) modules: BTreeMap<PathBuf, Arc<RefCell<Module>>>,
     pub(crate) active_modules: BTreeSet<PathBuf>,
     css_tree: CssTree,
@@ -124,13 +155,31 @@ pub struct Visitor<'a> {
     import_nodes: Vec<CssStmt>,
     pub options: &'a Options<'a>,
     pub(crate) map: &'a mut CodeMap,
+    /// The state of [`Options::parse_cache`] when `map` was copied from it,
+    /// if a cache is in use
+    pub(crate) parse_cache_snapshot: Option<Snapshot>,
+    /// Modules evaluated by earlier compilations in the same
+    /// [`Compiler`](crate::Compiler), which are only used along with a parse
+    /// cache
+    pub(crate) module_cache: Option<&'a ModuleCache>,
+    /// What each of the modules currently being evaluated for `module_cache`
+    /// has depended on so far, innermost last
+    module_recordings: Vec<ModuleRecording>,
     // todo: remove
     empty_span: Span,
-    import_cache: BTreeMap<PathBuf, StyleSheet>,
+    import_cache: BTreeMap<PathBuf, Arc<StyleSheet>>,
     /// As a simple heuristic, we don't cache the results of an import unless it
     /// has been seen in the past. In the majority of cases, files are imported
     /// at most once.
     files_seen: BTreeSet<PathBuf>,
+    /// Every file loaded by this visitor, along with the hash of its contents
+    loaded_files: BTreeMap<PathBuf, u64>,
+    /// The location each file was first imported from using `@import`, used
+    /// to warn about duplicate imports
+    first_import_sites: BTreeMap<PathBuf, Span>,
+    /// The files which were loaded from a load path, or relative to such a
+    /// file, whose warnings are silenced by [`Options::quiet_deps`]
+    dependencies: HashSet<PathBuf>,
 }
 
 impl<'a> Visitor<'a> {
@@ -152,6 +201,13 @@ impl<'a> Visitor<'a> {
             style_rule_ignoring_at_root: None,
             flags,
             warnings_emitted: HashSet::new(),
+            deprecation_counts: HashMap::new(),
+            warning_count: 0,
+            builtin_memo: BuiltinMemo::default(),
+            member: "root stylesheet".to_owned(),
+            current_builtin: None,
+            stack: Vec::new(),
+            unknown_at_rule_name: None,
             media_queries: None,
             media_query_sources: None,
             env: Environment::new(),
@@ -161,29 +217,36 @@ impl<'a> Visitor<'a> {
             current_import_path,
             configuration: Arc::new(RefCell::new(Configuration::empty())),
             is_plain_css: false,
+            is_plain_css_compilation: false,
             import_nodes: Vec::new(),
             modules: BTreeMap::new(),
             active_modules: BTreeSet::new(),
             options,
             empty_span,
             map,
+            parse_cache_snapshot: None,
+            module_cache: None,
+            module_recordings: Vec::new(),
             import_cache: BTreeMap::new(),
             files_seen: BTreeSet::new(),
+            loaded_files: BTreeMap::new(),
+            first_import_sites: BTreeMap::new(),
+            dependencies: HashSet::new(),
         }
     }
 
-    pub(crate) fn visit_stylesheet(&mut self, mut style_sheet: StyleSheet) -> SassResult<()> {
+    pub(crate) fn visit_stylesheet(&mut self, style_sheet: &StyleSheet) -> SassResult<()> {
         self.active_modules.insert(style_sheet.url.clone());
         let was_in_plain_css = self.is_plain_css;
         self.is_plain_css = style_sheet.is_plain_css;
-        mem::swap(&mut self.current_import_path, &mut style_sheet.url);
+        let old_import_path = mem::replace(&mut self.current_import_path, style_sheet.url.clone());
 
-        for stmt in style_sheet.body {
+        for stmt in &style_sheet.body {
             let result = self.visit_stmt(stmt)?;
             debug_assert!(result.is_none());
         }
 
-        mem::swap(&mut self.current_import_path, &mut style_sheet.url);
+        self.current_import_path = old_import_path;
         self.is_plain_css = was_in_plain_css;
 
         self.active_modules.remove(&style_sheet.url);
@@ -191,7 +254,60 @@ impl<'a> Visitor<'a> {
         Ok(())
     }
 
+    /// Evaluate `style_sheet`, an entrypoint written in plain CSS
+    ///
+    /// The parser only accepts plain CSS statements, which contain no
+    /// variables, control flow or loads, so none of the module state used by
+    /// [`Visitor::visit_stylesheet`] is set up, and style rules are emitted
+    /// without being added to the extension store.
+    pub(crate) fn visit_plain_css_stylesheet(
+        &mut self,
+        style_sheet: &StyleSheet,
+    ) -> SassResult<()> {
+        debug_assert!(style_sheet.is_plain_css);
+
+        self.is_plain_css_compilation = true;
+        self.is_plain_css = true;
+        self.current_import_path = style_sheet.url.clone();
+
+        for stmt in &style_sheet.body {
+            let result = match stmt {
+                AstStmt::RuleSet(ruleset) => self.visit_ruleset(ruleset)?,
+                AstStmt::Style(style) => self.visit_style(style)?,
+                AstStmt::LoudComment(comment) => self.visit_loud_comment(comment)?,
+                AstStmt::Media(media_rule) => self.visit_media_rule(media_rule)?,
+                AstStmt::Supports(supports_rule) => {
+                    self.visit_supports_rule(supports_rule)?;
+                    None
+                }
+                AstStmt::UnknownAtRule(unknown_at_rule) => {
+                    self.visit_unknown_at_rule(unknown_at_rule)?
+                }
+                AstStmt::ImportRule(import_rule) => self.visit_import_rule(import_rule)?,
+                _ => unreachable!("the plain CSS parser only produces plain CSS statements"),
+            };
+            debug_assert!(result.is_none());
+        }
+
+        Ok(())
+    }
+
+    /// Every file loaded so far, along with the hash of its contents
+    pub(crate) fn take_loaded_files(&mut self) -> BTreeMap<PathBuf, u64> {
+        mem::take(&mut self.loaded_files)
+    }
+
     pub(crate) fn finish(mut self) -> Vec<CssStmt> {
+        let omitted: usize = self
+            .deprecation_counts
+            .values()
+            .map(|&count| count.saturating_sub(MAX_REPETITIVE_DEPRECATIONS))
+            .sum();
+
+        if omitted > 0 {
+            self.options.logger.omitted_deprecations(omitted);
+        }
+
         let mut finished_tree = self.css_tree.finish();
         if self.import_nodes.is_empty() {
             finished_tree
@@ -201,14 +317,18 @@ impl<'a> Visitor<'a> {
         }
     }
 
-    fn visit_return_rule(&mut self, ret: AstReturn) -> SassResult<Option<Value>> {
-        let val = self.visit_expr(ret.val)?;
+    fn visit_return_rule(&mut self, ret: &AstReturn) -> SassResult<Option<Value>> {
+        let val = self.visit_expr(&ret.val)?;
 
         Ok(Some(self.without_slash(val)))
     }
 
     // todo: we really don't have to return Option<Value> from all of these children
-    pub(crate) fn visit_stmt(&mut self, stmt: AstStmt) -> SassResult<Option<Value>> {
+    pub(crate) fn visit_stmt(&mut self, stmt: &AstStmt) -> SassResult<Option<Value>> {
+        ensure_sufficient_stack(|| self.visit_stmt_inner(stmt))
+    }
+
+    fn visit_stmt_inner(&mut self, stmt: &AstStmt) -> SassResult<Option<Value>> {
         match stmt {
             AstStmt::RuleSet(ruleset) => self.visit_ruleset(ruleset),
             AstStmt::Style(style) => self.visit_style(style),
@@ -219,7 +339,7 @@ impl<'a> Visitor<'a> {
             AstStmt::Each(each_stmt) => self.visit_each_stmt(each_stmt),
             AstStmt::Media(media_rule) => self.visit_media_rule(media_rule),
             AstStmt::Include(include_stmt) => self.visit_include_stmt(include_stmt),
-            AstStmt::While(while_stmt) => self.visit_while_stmt(&while_stmt),
+            AstStmt::While(while_stmt) => self.visit_while_stmt(while_stmt),
             AstStmt::VariableDecl(decl) => self.visit_variable_decl(decl),
             AstStmt::LoudComment(comment) => self.visit_loud_comment(comment),
             AstStmt::ImportRule(import_rule) => self.visit_import_rule(import_rule),
@@ -256,14 +376,13 @@ impl<'a> Visitor<'a> {
         }
     }
 
-    fn visit_forward_rule(&mut self, forward_rule: AstForwardRule) -> SassResult<()> {
+    fn visit_forward_rule(&mut self, forward_rule: &AstForwardRule) -> SassResult<()> {
         let old_config = Arc::clone(&self.configuration);
-        let adjusted_config =
-            Configuration::through_forward(Arc::clone(&old_config), &forward_rule);
+        let adjusted_config = Configuration::through_forward(Arc::clone(&old_config), forward_rule);
 
         if !forward_rule.configuration.is_empty() {
             let new_configuration =
-                self.add_forward_configuration(Arc::clone(&adjusted_config), &forward_rule)?;
+                self.add_forward_configuration(Arc::clone(&adjusted_config), forward_rule)?;
 
             self.load_module(
                 forward_rule.url.as_path(),
@@ -358,7 +477,7 @@ impl<'a> Visitor<'a> {
             }
 
             // todo: superfluous clone?
-            let value = self.visit_expr(variable.expr.node.clone())?;
+            let value = self.visit_expr(&variable.expr.node)?;
             let value = self.without_slash(value);
 
             new_values.insert(
@@ -402,10 +521,10 @@ impl<'a> Visitor<'a> {
 
     fn parenthesize_supports_condition(
         &mut self,
-        condition: AstSupportsCondition,
+        condition: &AstSupportsCondition,
         operator: Option<&str>,
     ) -> SassResult<String> {
-        match &condition {
+        match condition {
             AstSupportsCondition::Negation(..) => {
                 Ok(format!("({})", self.visit_supports_condition(condition)?))
             }
@@ -419,7 +538,7 @@ impl<'a> Visitor<'a> {
         }
     }
 
-    fn visit_supports_condition(&mut self, condition: AstSupportsCondition) -> SassResult<String> {
+    fn visit_supports_condition(&mut self, condition: &AstSupportsCondition) -> SassResult<String> {
         match condition {
             AstSupportsCondition::Operation {
                 left,
@@ -427,13 +546,13 @@ impl<'a> Visitor<'a> {
                 right,
             } => Ok(format!(
                 "{} {} {}",
-                self.parenthesize_supports_condition(*left, operator.as_deref())?,
+                self.parenthesize_supports_condition(left, operator.as_deref())?,
                 operator.as_ref().unwrap(),
-                self.parenthesize_supports_condition(*right, operator.as_deref())?
+                self.parenthesize_supports_condition(right, operator.as_deref())?
             )),
             AstSupportsCondition::Negation(condition) => Ok(format!(
                 "not {}",
-                self.parenthesize_supports_condition(*condition, None)?
+                self.parenthesize_supports_condition(condition, None)?
             )),
             AstSupportsCondition::Interpolation(expr) => {
                 self.evaluate_to_css(expr, QuoteKind::None, self.empty_span)
@@ -442,7 +561,7 @@ impl<'a> Visitor<'a> {
                 let old_in_supports_decl = self.flags.in_supports_declaration();
                 self.flags.set(ContextFlags::IN_SUPPORTS_DECLARATION, true);
 
-                let is_custom_property = match &name {
+                let is_custom_property = match name {
                     AstExpr::String(StringExpr(text, QuoteKind::None), ..) => {
                         text.initial_plain().starts_with("--")
                     }
@@ -473,7 +592,7 @@ impl<'a> Visitor<'a> {
         }
     }
 
-    fn visit_supports_rule(&mut self, supports_rule: AstSupportsRule) -> SassResult<()> {
+    fn visit_supports_rule(&mut self, supports_rule: &AstSupportsRule) -> SassResult<()> {
         if self.declaration_name.is_some() {
             return Err((
                 "Supports rules may not be used within nested declarations.",
@@ -482,7 +601,7 @@ impl<'a> Visitor<'a> {
                 .into());
         }
 
-        let condition = self.visit_supports_condition(supports_rule.condition)?;
+        let condition = self.visit_supports_condition(&supports_rule.condition)?;
 
         let css_supports_rule = CssStmt::Supports(
             SupportsRule {
@@ -492,7 +611,7 @@ impl<'a> Visitor<'a> {
             false,
         );
 
-        let children = supports_rule.body;
+        let children = &supports_rule.body;
 
         self.with_parent(
             css_supports_rule,
@@ -541,7 +660,7 @@ impl<'a> Visitor<'a> {
 
     fn execute(
         &mut self,
-        stylesheet: StyleSheet,
+        stylesheet: &StyleSheet,
         configuration: Option<Arc<RefCell<Configuration>>>,
         // todo: different errors based on this
         _names_in_errors: bool,
@@ -549,7 +668,7 @@ impl<'a> Visitor<'a> {
         let url = stylesheet.url.clone();
 
         // todo: use canonical url for modules
-        if let Some(already_loaded) = self.modules.get(&stylesheet.url) {
+        if let Some(already_loaded) = self.modules.get(&stylesheet.url).cloned() {
             let current_configuration =
                 configuration.unwrap_or_else(|| Arc::clone(&self.configuration));
 
@@ -577,13 +696,41 @@ impl<'a> Visitor<'a> {
                 //   }
             }
 
-            return Ok(Arc::clone(already_loaded));
+            self.record_module(&url, &already_loaded);
+
+            return Ok(already_loaded);
+        }
+
+        let is_cacheable = self.module_cache.is_some()
+            && self.parse_cache_snapshot.is_some()
+            && configuration
+                .as_ref()
+                .unwrap_or(&self.configuration)
+                .borrow()
+                .is_empty();
+
+        if is_cacheable {
+            if let Some(module) = self.reuse_module(&url) {
+                self.record_module(&url, &module);
+                return Ok(module);
+            }
+
+            self.module_recordings.push(ModuleRecording::default());
+            self.record_file(&url);
+
+            if !self.is_from_parse_cache(stylesheet) {
+                self.mark_uncacheable();
+            }
         }
 
+        let css_len = self.css_tree.stmt_count();
+        let imports_len = self.import_nodes.len();
+
         let env = Environment::new();
+        let variables = env.global_vars();
         let mut extension_store = ExtensionStore::new(self.empty_span);
 
-        self.with_environment::<SassResult<()>, _>(env.new_closure(), |visitor| {
+        let result = self.with_environment::<SassResult<()>, _>(env.new_closure(), |visitor| {
             let old_parent = visitor.parent;
             mem::swap(&mut visitor.extender, &mut extension_store);
             let old_style_rule = visitor.style_rule_ignoring_at_root.take();
@@ -631,32 +778,183 @@ impl<'a> Visitor<'a> {
             }
 
             Ok(())
-        })?;
+        });
+
+        let recording = if is_cacheable {
+            self.module_recordings.pop()
+        } else {
+            None
+        };
+
+        result?;
 
         let module = env.to_module(extension_store);
 
-        self.modules.insert(url, Arc::clone(&module));
+        self.modules.insert(url.clone(), Arc::clone(&module));
+
+        if let (Some(cache), Some(snapshot), Some(recording)) =
+            (self.module_cache, self.parse_cache_snapshot, recording)
+        {
+            // modules which emit CSS are never reused, as their CSS would be
+            // missing from later compilations
+            if recording.cacheable
+                && self.css_tree.stmt_count() == css_len
+                && self.import_nodes.len() == imports_len
+            {
+                let initial_variables = variables.borrow().clone();
+
+                cache.insert(
+                    url.clone(),
+                    Rc::new(CachedModule {
+                        snapshot,
+                        module: Arc::clone(&module),
+                        variables,
+                        initial_variables,
+                        recording,
+                    }),
+                );
+            } else {
+                cache.remove(&url);
+            }
+        }
+
+        self.record_module(&url, &module);
 
         Ok(module)
     }
 
+    /// The module at `url` evaluated by an earlier compilation, if none of the
+    /// files it loaded have changed and the modules it used can be shared with
+    /// this compilation
+    fn reuse_module(&mut self, url: &Path) -> Option<Arc<RefCell<Module>>> {
+        let cached = self.module_cache?.get(url)?;
+
+        if !self.parse_cache_snapshot?.includes(cached.snapshot) {
+            return None;
+        }
+
+        for (path, &hash) in &cached.recording.files {
+            if self.file_hash(path)? != hash {
+                return None;
+            }
+        }
+
+        let mut used = Vec::new();
+
+        for (used_url, used_module) in &cached.recording.modules {
+            match self.modules.get(used_url) {
+                Some(loaded) if Arc::ptr_eq(loaded, &used_module.module) => {}
+                Some(..) => return None,
+                None => used.push((used_url, used_module)),
+            }
+        }
+
+        for (used_url, used_module) in used {
+            used_module.reset();
+            self.modules
+                .insert(used_url.clone(), Arc::clone(&used_module.module));
+        }
+
+        cached.reset();
+        self.modules
+            .insert(url.to_path_buf(), Arc::clone(&cached.module));
+        self.dependencies
+            .extend(cached.recording.dependencies.iter().cloned());
+
+        Some(Arc::clone(&cached.module))
+    }
+
+    /// The hash of the contents of `path`, which is read if it has not been
+    /// loaded yet
+    fn file_hash(&mut self, path: &Path) -> Option<u64> {
+        if let Some(&hash) = self.loaded_files.get(path) {
+            return Some(hash);
+        }
+
+        let hash = ParseCache::hash_contents(&self.options.fs.read(path).ok()?);
+        self.loaded_files.insert(path.to_path_buf(), hash);
+
+        Some(hash)
+    }
+
+    /// Whether `stylesheet` is the one in the parse cache, whose spans are
+    /// valid in later compilations, rather than one only this compilation uses
+    fn is_from_parse_cache(&self, stylesheet: &StyleSheet) -> bool {
+        match (
+            self.options.parse_cache,
+            self.parse_cache_snapshot,
+            self.loaded_files.get(&stylesheet.url),
+        ) {
+            (Some(cache), Some(snapshot), Some(&hash)) => cache
+                .get(&stylesheet.url, hash, snapshot)
+                .is_some_and(|cached| ptr::eq(&*cached, stylesheet)),
+            _ => false,
+        }
+    }
+
+    /// Record that the module being evaluated for the module cache, if any,
+    /// loaded the file at `path`
+    fn record_file(&mut self, path: &Path) {
+        if let Some(recording) = self.module_recordings.last_mut() {
+            if let Some(&hash) = self.loaded_files.get(path) {
+                recording.files.insert(path.to_path_buf(), hash);
+            }
+
+            if self.dependencies.contains(path) {
+                recording.dependencies.insert(path.to_path_buf());
+            }
+        }
+    }
+
+    /// Record that the module being evaluated for the module cache, if any,
+    /// used `module`, which it may only be cached along with
+    fn record_module(&mut self, url: &Path, module: &Arc<RefCell<Module>>) {
+        if self.module_recordings.is_empty() {
+            return;
+        }
+
+        let cached = self
+            .module_cache
+            .and_then(|cache| cache.get(url))
+            .filter(|cached| Arc::ptr_eq(&cached.module, module));
+
+        let recording = self.module_recordings.last_mut().unwrap();
+        match cached {
+            Some(cached) => recording.add_module(url, &cached),
+            None => recording.cacheable = false,
+        }
+    }
+
+    /// Prevent the module being evaluated for the module cache, if any, from
+    /// being cached, since reusing it would skip an effect of evaluating it,
+    /// such as emitting a warning
+    fn mark_uncacheable(&mut self) {
+        if let Some(recording) = self.module_recordings.last_mut() {
+            recording.cacheable = false;
+        }
+    }
+
     pub(crate) fn load_module(
         &mut self,
         url: &Path,
         configuration: Option<Arc<RefCell<Configuration>>>,
         names_in_errors: bool,
         span: Span,
-        callback: impl Fn(&mut Self, Arc<RefCell<Module>>, StyleSheet) -> SassResult<()>,
+        callback: impl Fn(&mut Self, Arc<RefCell<Module>>, Arc<StyleSheet>) -> SassResult<()>,
     ) -> SassResult<()> {
-        let builtin = match url.to_string_lossy().as_ref() {
-            "sass:color" => Some(declare_module_color()),
-            "sass:list" => Some(declare_module_list()),
-            "sass:map" => Some(declare_module_map()),
-            "sass:math" => Some(declare_module_math()),
-            "sass:meta" => Some(declare_module_meta()),
-            "sass:selector" => Some(declare_module_selector()),
-            "sass:string" => Some(declare_module_string()),
-            _ => None,
+        let builtin = match declare_builtin_module(&url.to_string_lossy()) {
+            Ok(builtin) => builtin,
+            Err(feature) => {
+                return Err((
+                    format!(
+                        "Built-in module {} is unavailable, as grass was built without the `{}` feature.",
+                        url.to_string_lossy(),
+                        feature
+                    ),
+                    span,
+                )
+                    .into())
+            }
         };
 
         if let Some(builtin) = builtin {
@@ -683,7 +981,7 @@ impl<'a> Visitor<'a> {
             callback(
                 self,
                 Arc::new(RefCell::new(builtin)),
-                StyleSheet::new(false, url.to_path_buf()),
+                Arc::new(StyleSheet::new(false, url.to_path_buf())),
             )?;
             return Ok(());
         }
@@ -703,7 +1001,7 @@ impl<'a> Visitor<'a> {
 
         self.active_modules.insert(canonical_url.clone());
 
-        let module = self.execute(stylesheet.clone(), configuration, names_in_errors)?;
+        let module = self.execute(&stylesheet, configuration, names_in_errors)?;
 
         self.active_modules.remove(&canonical_url);
 
@@ -712,14 +1010,14 @@ impl<'a> Visitor<'a> {
         Ok(())
     }
 
-    fn visit_use_rule(&mut self, use_rule: AstUseRule) -> SassResult<()> {
+    fn visit_use_rule(&mut self, use_rule: &AstUseRule) -> SassResult<()> {
         let configuration = if use_rule.configuration.is_empty() {
             Arc::new(RefCell::new(Configuration::empty()))
         } else {
             let mut values = BTreeMap::new();
 
-            for var in use_rule.configuration {
-                let value = self.visit_expr(var.expr.node)?;
+            for var in &use_rule.configuration {
+                let value = self.visit_expr(&var.expr.node)?;
                 let value = self.without_slash(value);
                 values.insert(
                     var.name.node,
@@ -779,11 +1077,11 @@ impl<'a> Visitor<'a> {
         Err((msg, span).into())
     }
 
-    fn visit_import_rule(&mut self, import_rule: AstImportRule) -> SassResult<Option<Value>> {
-        for import in import_rule.imports {
+    fn visit_import_rule(&mut self, import_rule: &AstImportRule) -> SassResult<Option<Value>> {
+        for import in &import_rule.imports {
             match import {
                 AstImport::Sass(dynamic_import) => {
-                    self.visit_dynamic_import_rule(&dynamic_import)?;
+                    self.visit_dynamic_import_rule(dynamic_import)?;
                 }
                 AstImport::Plain(static_import) => self.visit_static_import_rule(static_import)?,
             }
@@ -797,17 +1095,81 @@ impl<'a> Visitor<'a> {
     ///
     /// <https://sass-lang.com/documentation/at-rules/import#finding-the-file>
     /// <https://sass-lang.com/documentation/at-rules/import#load-paths>
-    #[allow(clippy::cognitive_complexity, clippy::redundant_clone)]
     pub fn find_import(&self, path: &Path) -> Option<PathBuf> {
-        let path_buf = if path.is_absolute() {
+        self.find_import_with_origin(path)
+            .ok()
+            .flatten()
+            .map(|(path, _)| path)
+    }
+
+    /// Like [`Visitor::find_import`], but also returns whether the import was
+    /// found in one of the load paths, rather than relative to the current file,
+    /// and errors if `path` is a `pkg:` URL which can't be resolved unambiguously
+    fn find_import_with_origin(&self, path: &Path) -> Result<Option<(PathBuf, bool)>, String> {
+        Self::resolve_import(self.options, &self.current_import_path, path)
+    }
+
+    /// Resolve `path`, as loaded by the file `importer`, in the same way as
+    /// [`Visitor::find_import_with_origin`]
+    pub(crate) fn resolve_import(
+        options: &Options,
+        importer: &Path,
+        path: &Path,
+    ) -> Result<Option<(PathBuf, bool)>, String> {
+        #[cfg(feature = "pkg-importer")]
+        if options.node_package_importer {
+            let package_url = path
+                .to_str()
+                .and_then(|url| url.strip_prefix("pkg:").or_else(|| url.strip_prefix('~')));
+
+            if let Some(url) = package_url {
+                return Ok(
+                    crate::node_package::resolve(options, importer, url)?.map(|path| (path, true))
+                );
+            }
+        }
+
+        Ok(Self::resolve_relative_import(options, importer, path))
+    }
+
+    /// Resolve `path` relative to `importer`, or failing that, in one of the
+    /// load paths
+    fn resolve_relative_import(
+        options: &Options,
+        importer: &Path,
+        path: &Path,
+    ) -> Option<(PathBuf, bool)> {
+        let path_buf = if path.is_absolute() || is_url(path) {
             path.into()
         } else {
-            self.current_import_path
+            importer
                 .parent()
                 .unwrap_or_else(|| Path::new(""))
                 .join(path)
         };
 
+        if let Some(path) = Self::resolve_import_path(options, &path_buf) {
+            return Some((path, false));
+        }
+
+        if has_sass_extension(&path_buf) || is_url(path) {
+            // todo: consider load paths
+            return None;
+        }
+
+        for load_path in &options.load_paths {
+            if let Some(path) = Self::resolve_import_path(options, &load_path.join(path)) {
+                return Some((path, true));
+            }
+        }
+
+        None
+    }
+
+    /// Find the file `path` refers to, trying import-only files, partials,
+    /// each of the Sass extensions, and index files
+    #[allow(clippy::cognitive_complexity, clippy::redundant_clone)]
+    pub(crate) fn resolve_import_path(options: &Options, path_buf: &Path) -> Option<PathBuf> {
         macro_rules! try_path {
             ($path:expr) => {
                 let path = $path;
@@ -816,24 +1178,20 @@ impl<'a> Visitor<'a> {
 
                 let partial = dirname.join(format!("_{}", basename.to_str().unwrap()));
 
-                if self.options.fs.is_file(&path) {
+                if options.fs.is_file(&path) {
                     return Some(path.to_path_buf());
                 }
 
-                if self.options.fs.is_file(&partial) {
+                if options.fs.is_file(&partial) {
                     return Some(partial);
                 }
             };
         }
 
-        if path_buf.extension() == Some(OsStr::new("scss"))
-            || path_buf.extension() == Some(OsStr::new("sass"))
-            || path_buf.extension() == Some(OsStr::new("css"))
-        {
+        if has_sass_extension(path_buf) {
             let extension = path_buf.extension().unwrap();
             try_path!(path_buf.with_extension(format!(".import{}", extension.to_str().unwrap())));
             try_path!(path_buf);
-            // todo: consider load paths
             return None;
         }
 
@@ -849,22 +1207,12 @@ impl<'a> Visitor<'a> {
             };
         }
 
-        try_path_with_extensions!(path_buf.clone());
+        try_path_with_extensions!(path_buf);
 
-        if self.options.fs.is_dir(&path_buf) {
+        if options.fs.is_dir(path_buf) {
             try_path_with_extensions!(path_buf.join("index"));
         }
 
-        for load_path in &self.options.load_paths {
-            let path_buf = load_path.join(path);
-
-            try_path_with_extensions!(&path_buf);
-
-            if self.options.fs.is_dir(&path_buf) {
-                try_path_with_extensions!(path_buf.join("index"));
-            }
-        }
-
         None
     }
 
@@ -892,29 +1240,65 @@ impl<'a> Visitor<'a> {
         url: &str,
         _for_import: bool,
         span: Span,
-    ) -> SassResult<StyleSheet> {
-        if let Some(name) = self.find_import(url.as_ref()) {
-            let name = self.options.fs.canonicalize(&name).unwrap_or(name);
-            if let Some(style_sheet) = self.import_cache.get(&name) {
-                return Ok(style_sheet.clone());
-            }
-
-            let file = self.map.add_file(
-                name.to_string_lossy().into(),
-                String::from_utf8(self.options.fs.read(&name)?)?,
-            );
+    ) -> SassResult<Arc<StyleSheet>> {
+        let found = self
+            .find_import_with_origin(url.as_ref())
+            .map_err(|message| (message, span))?;
 
-            let old_is_use_allowed = self.flags.is_use_allowed();
-            self.flags.set(ContextFlags::IS_USE_ALLOWED, true);
+        if let Some((name, from_load_path)) = found {
+            let name = self.options.fs.canonicalize(&name).unwrap_or(name);
 
-            let style_sheet =
-                self.parse_file(Lexer::new_from_file(&file), &name, file.span.subspan(0, 0))?;
+            // files loaded relative to a dependency are dependencies too
+            if from_load_path || self.dependencies.contains(&self.current_import_path) {
+                self.dependencies.insert(name.clone());
+            }
 
-            self.flags
-                .set(ContextFlags::IS_USE_ALLOWED, old_is_use_allowed);
+            if let Some(style_sheet) = self.import_cache.get(&name) {
+                return Ok(Arc::clone(style_sheet));
+            }
+
+            // reported at the load, as a failed read isn't a parse error
+            let contents = self
+                .options
+                .fs
+                .read(&name)
+                .map_err(|e| (e.to_string(), span))?;
+            let hash = ParseCache::hash_contents(&contents);
+            self.loaded_files.insert(name.clone(), hash);
+
+            let style_sheet = match (self.options.parse_cache, self.parse_cache_snapshot) {
+                (Some(cache), Some(snapshot)) => match cache.get(&name, hash, snapshot) {
+                    Some(style_sheet) => style_sheet,
+                    None => {
+                        let file = self.add_imported_file(&name, contents)?;
+
+                        let style_sheet = Arc::new(match cache.load(&name, hash, &file) {
+                            Some(style_sheet) => style_sheet,
+                            None => {
+                                let style_sheet = self.parse_imported_file(&name, &file)?;
+                                cache.store(&name, hash, &file, &style_sheet);
+                                style_sheet
+                            }
+                        });
+
+                        cache.insert(
+                            name.clone(),
+                            hash,
+                            &file,
+                            Arc::clone(&style_sheet),
+                            snapshot,
+                        );
+                        style_sheet
+                    }
+                },
+                _ => {
+                    let file = self.add_imported_file(&name, contents)?;
+                    Arc::new(self.parse_imported_file(&name, &file)?)
+                }
+            };
 
             if self.files_seen.contains(&name) {
-                self.import_cache.insert(name, style_sheet.clone());
+                self.import_cache.insert(name, Arc::clone(&style_sheet));
             } else {
                 self.files_seen.insert(name);
             }
@@ -925,18 +1309,53 @@ impl<'a> Visitor<'a> {
         Err(("Can't find stylesheet to import.", span).into())
     }
 
+    fn add_imported_file(&mut self, name: &Path, contents: Vec<u8>) -> SassResult<Arc<File>> {
+        Ok(self
+            .map
+            .add_file(name.to_string_lossy().into(), String::from_utf8(contents)?))
+    }
+
+    fn parse_imported_file(&mut self, name: &Path, file: &Arc<File>) -> SassResult<StyleSheet> {
+        let old_is_use_allowed = self.flags.is_use_allowed();
+        self.flags.set(ContextFlags::IS_USE_ALLOWED, true);
+
+        let style_sheet =
+            self.parse_file(Lexer::new_from_file(file), name, file.span.subspan(0, 0))?;
+
+        self.flags
+            .set(ContextFlags::IS_USE_ALLOWED, old_is_use_allowed);
+
+        Ok(style_sheet)
+    }
+
     pub(crate) fn load_style_sheet(
         &mut self,
         url: &str,
         // default=false
         for_import: bool,
         span: Span,
-    ) -> SassResult<StyleSheet> {
+    ) -> SassResult<Arc<StyleSheet>> {
         // todo: import cache
-        self.import_like_node(url, for_import, span)
+        let stylesheet = self.import_like_node(url, for_import, span)?;
+
+        if !self.module_recordings.is_empty() {
+            self.record_file(&stylesheet.url);
+
+            if !self.is_from_parse_cache(&stylesheet) {
+                self.mark_uncacheable();
+            }
+        }
+
+        Ok(stylesheet)
     }
 
     fn visit_dynamic_import_rule(&mut self, dynamic_import: &AstSassImport) -> SassResult<()> {
+        self.emit_deprecation_warning(
+            Deprecation::Import,
+            "Sass @import rules are deprecated and will be removed in Dart Sass 3.0.0.\n\nMore info and automated migrator: https://sass-lang.com/d/import",
+            dynamic_import.span,
+        )?;
+
         let stylesheet = self.load_style_sheet(&dynamic_import.url, true, dynamic_import.span)?;
 
         let url = stylesheet.url.clone();
@@ -945,8 +1364,44 @@ impl<'a> Visitor<'a> {
             return Err(("This file is already being loaded.", dynamic_import.span).into());
         }
 
+        if self.options.warn_duplicate_imports {
+            self.warn_if_duplicate_import(&url, dynamic_import.span);
+        }
+
         self.active_modules.insert(url.clone());
 
+        self.with_stack_frame("@import".to_owned(), dynamic_import.span, |visitor| {
+            visitor.visit_imported_stylesheet(&stylesheet)
+        })?;
+
+        self.active_modules.remove(&url);
+
+        Ok(())
+    }
+
+    fn warn_if_duplicate_import(&mut self, url: &Path, span: Span) {
+        let first_import = match self.first_import_sites.get(url) {
+            Some(&first_import) => look_up_span(self.map, first_import),
+            None => {
+                // a reused module wouldn't record its imports
+                self.mark_uncacheable();
+                self.first_import_sites.insert(url.to_path_buf(), span);
+                return;
+            }
+        };
+
+        self.emit_warning(
+            &format!(
+                "This file was already imported at {}:{}:{}, so its CSS will be emitted again.",
+                first_import.file.name(),
+                first_import.begin.line + 1,
+                first_import.begin.column + 1
+            ),
+            span,
+        );
+    }
+
+    fn visit_imported_stylesheet(&mut self, stylesheet: &StyleSheet) -> SassResult<()> {
         // If the imported stylesheet doesn't use any modules, we can inject its
         // CSS directly into the current stylesheet. If it does use modules, we
         // need to put its CSS into an intermediate [ModifiableCssStylesheet] so
@@ -991,6 +1446,8 @@ impl<'a> Visitor<'a> {
         // Create a dummy module with empty CSS and no extensions to make forwarded
         // members available in the current import context and to combine all the
         // CSS from modules used by [stylesheet].
+        // the dummy module refers to this compilation's entrypoint
+        self.mark_uncacheable();
         let module = env.to_dummy_module(self.empty_span);
         self.env.import_forwards(module);
 
@@ -1012,16 +1469,15 @@ impl<'a> Visitor<'a> {
             //     }
         }
 
-        self.active_modules.remove(&url);
-
         Ok(())
     }
 
-    fn visit_static_import_rule(&mut self, static_import: AstPlainCssImport) -> SassResult<()> {
-        let import = self.interpolation_to_value(static_import.url, false, false)?;
+    fn visit_static_import_rule(&mut self, static_import: &AstPlainCssImport) -> SassResult<()> {
+        let import = self.interpolation_to_value(&static_import.url, false, false)?;
 
         let modifiers = static_import
             .modifiers
+            .as_ref()
             .map(|modifiers| self.interpolation_to_value(modifiers, false, false))
             .transpose()?;
 
@@ -1036,35 +1492,33 @@ impl<'a> Visitor<'a> {
         Ok(())
     }
 
-    fn visit_debug_rule(&mut self, debug_rule: AstDebugRule) -> SassResult<Option<Value>> {
+    fn visit_debug_rule(&mut self, debug_rule: &AstDebugRule) -> SassResult<Option<Value>> {
+        self.mark_uncacheable();
+
         if self.options.quiet {
             return Ok(None);
         }
 
-        let message = self.visit_expr(debug_rule.value)?;
+        let message = self.visit_expr(&debug_rule.value)?;
 
-        let loc = self.map.look_up_span(debug_rule.span);
-        eprintln!(
-            "{}:{} DEBUG: {}",
-            loc.file.name(),
-            loc.begin.line + 1,
-            message.inspect(debug_rule.span)?
-        );
+        let loc = look_up_span(self.map, debug_rule.span);
+        self.options
+            .logger
+            .debug(loc, &message.inspect(debug_rule.span)?);
 
         Ok(None)
     }
 
-    fn visit_content_rule(&mut self, content_rule: AstContentRule) -> SassResult<Option<Value>> {
+    fn visit_content_rule(&mut self, content_rule: &AstContentRule) -> SassResult<Option<Value>> {
         let span = content_rule.args.span;
-        if let Some(content) = &self.env.content {
-            #[allow(mutable_borrow_reservation_conflict)]
+        if let Some(content) = self.env.content.as_ref().map(Arc::clone) {
             self.run_user_defined_callable(
-                MaybeEvaledArguments::Invocation(content_rule.args),
-                Arc::clone(content),
-                &content.env.clone(),
+                MaybeEvaledArguments::Invocation(&content_rule.args),
+                Arc::clone(&content),
+                &content.env,
                 span,
                 |content, visitor| {
-                    for stmt in content.content.body.clone() {
+                    for stmt in &content.content.body {
                         let result = visitor.visit_stmt(stmt)?;
                         debug_assert!(result.is_none());
                     }
@@ -1118,14 +1572,13 @@ impl<'a> Visitor<'a> {
         nodes[innermost_contiguous.unwrap()]
     }
 
-    fn visit_at_root_rule(&mut self, mut at_root_rule: AstAtRootRule) -> SassResult<Option<Value>> {
-        let query = match at_root_rule.query.clone() {
+    fn visit_at_root_rule(&mut self, at_root_rule: &AstAtRootRule) -> SassResult<Option<Value>> {
+        let query = match &at_root_rule.query {
             Some(query) => {
-                let resolved = self.perform_interpolation(query.node, true)?;
-
-                let span = query.span;
+                let (resolved, map) =
+                    self.interpolation_to_value_with_map(&query.node, false, query.span)?;
 
-                let query_toks = Lexer::new_from_string(&resolved, span);
+                let query_toks = Lexer::new_from_interpolation(&resolved, map);
 
                 AtRootQueryParser::new(query_toks).parse()?
             }
@@ -1157,7 +1610,7 @@ impl<'a> Visitor<'a> {
         // have created.
         if Some(root) == self.parent {
             self.with_scope::<SassResult<()>, _>(false, true, |visitor| {
-                for stmt in at_root_rule.body {
+                for stmt in &at_root_rule.body {
                     let result = visitor.visit_stmt(stmt)?;
                     debug_assert!(result.is_none());
                 }
@@ -1199,7 +1652,7 @@ impl<'a> Visitor<'a> {
             inner_copy.map(|p| self.css_tree.add_stmt(p, None))
         };
 
-        let body = mem::take(&mut at_root_rule.body);
+        let body = &at_root_rule.body;
 
         self.with_scope_for_at_root::<SassResult<()>, _>(inner_copy, &query, |visitor| {
             for stmt in body {
@@ -1265,12 +1718,12 @@ impl<'a> Visitor<'a> {
         res
     }
 
-    fn visit_function_decl(&mut self, fn_decl: AstFunctionDecl) {
+    fn visit_function_decl(&mut self, fn_decl: &Arc<AstFunctionDecl>) {
         let name = fn_decl.name.node;
         // todo: independency
 
         let func = SassFunction::UserDefined(UserDefinedFunction {
-            function: Arc::new(fn_decl),
+            function: Arc::clone(fn_decl),
             name,
             env: self.env.new_closure(),
         });
@@ -1285,12 +1738,45 @@ impl<'a> Visitor<'a> {
         allows_placeholder: bool,
         span: Span,
     ) -> SassResult<SelectorList> {
-        let sel_toks = Lexer::new_from_string(selector_text, span);
+        let sel_toks = Lexer::new_unmapped(selector_text, span);
 
         SelectorParser::new(sel_toks, allows_parent, allows_placeholder, span).parse()
     }
 
-    fn visit_extend_rule(&mut self, extend_rule: AstExtendRule) -> SassResult<Option<Value>> {
+    pub(crate) fn parse_compound_selector_from_string(
+        &mut self,
+        selector_text: &str,
+        allows_parent: bool,
+        span: Span,
+    ) -> SassResult<CompoundSelector> {
+        let sel_toks = Lexer::new_unmapped(selector_text, span);
+
+        SelectorParser::new(sel_toks, allows_parent, true, span).parse_compound()
+    }
+
+    /// Evaluate `text`, the Sass source of an expression from outside of any
+    /// stylesheet, such as a value returned by a plugin function
+    #[cfg(feature = "plugins")]
+    pub(crate) fn evaluate_source(&mut self, text: &str, span: Span) -> SassResult<Value> {
+        let source = format!("$value: {};", text);
+        let stylesheet = ScssParser::new(
+            Lexer::new_from_string(source, span),
+            self.map,
+            self.options,
+            span,
+            Path::new(""),
+        )
+        .__parse()?;
+
+        // reject text which closes the expression and continues with other
+        // statements
+        match stylesheet.body.as_slice() {
+            [AstStmt::VariableDecl(decl)] => self.visit_expr(&decl.value),
+            _ => Err(("Expected a single expression.", span).into()),
+        }
+    }
+
+    fn visit_extend_rule(&mut self, extend_rule: &AstExtendRule) -> SassResult<Option<Value>> {
         if !self.style_rule_exists() || self.declaration_name.is_some() {
             return Err((
                 "@extend may only be used within style rules.",
@@ -1301,9 +1787,19 @@ impl<'a> Visitor<'a> {
 
         let super_selector = self.style_rule_ignoring_at_root.clone().unwrap();
 
-        let target_text = self.interpolation_to_value(extend_rule.value, false, true)?;
+        let (target_text, target_map) = self.interpolation_to_value_with_map(
+            &extend_rule.value,
+            false,
+            extend_rule.value_span,
+        )?;
 
-        let list = self.parse_selector_from_string(&target_text, false, true, extend_rule.span)?;
+        let list = SelectorParser::new(
+            Lexer::new_from_interpolation(&target_text, target_map),
+            false,
+            true,
+            extend_rule.value_span,
+        )
+        .parse()?;
 
         for complex in list.components {
             if complex.components.len() != 1 || !complex.components.first().unwrap().is_compound() {
@@ -1333,15 +1829,15 @@ impl<'a> Visitor<'a> {
                 },
                 &self.media_queries,
                 extend_rule.span,
-            );
+            )?;
         }
 
         Ok(None)
     }
 
-    fn visit_error_rule(&mut self, error_rule: AstErrorRule) -> SassResult<Box<SassError>> {
+    fn visit_error_rule(&mut self, error_rule: &AstErrorRule) -> SassResult<Box<SassError>> {
         let value = self
-            .visit_expr(error_rule.value)?
+            .visit_expr(&error_rule.value)?
             .inspect(error_rule.span)?;
 
         Ok((value, error_rule.span).into())
@@ -1368,15 +1864,15 @@ impl<'a> Visitor<'a> {
 
     fn visit_media_queries(
         &mut self,
-        queries: Interpolation,
+        queries: &Interpolation,
         span: Span,
     ) -> SassResult<Vec<CssMediaQuery>> {
-        let resolved = self.perform_interpolation(queries, true)?;
+        let (resolved, map) = self.interpolation_to_value_with_map(queries, false, span)?;
 
-        CssMediaQuery::parse_list(&resolved, span)
+        CssMediaQuery::parse_list(&resolved, map)
     }
 
-    fn visit_media_rule(&mut self, media_rule: AstMedia) -> SassResult<Option<Value>> {
+    fn visit_media_rule(&mut self, media_rule: &AstMedia) -> SassResult<Option<Value>> {
         if self.declaration_name.is_some() {
             return Err((
                 "Media rules may not be used within nested declarations.",
@@ -1385,7 +1881,7 @@ impl<'a> Visitor<'a> {
                 .into());
         }
 
-        let queries1 = self.visit_media_queries(media_rule.query, media_rule.query_span)?;
+        let queries1 = self.visit_media_queries(&media_rule.query, media_rule.query_span)?;
         // todo: superfluous clone?
         let queries2 = self.media_queries.clone();
         let merged_queries = queries2
@@ -1404,7 +1900,7 @@ impl<'a> Visitor<'a> {
             None => IndexSet::new(),
         };
 
-        let children = media_rule.body;
+        let children = &media_rule.body;
 
         let query = merged_queries.clone().unwrap_or_else(|| queries1.clone());
 
@@ -1480,7 +1976,7 @@ impl<'a> Visitor<'a> {
 
     fn visit_unknown_at_rule(
         &mut self,
-        unknown_at_rule: AstUnknownAtRule,
+        unknown_at_rule: &AstUnknownAtRule,
     ) -> SassResult<Option<Value>> {
         if self.declaration_name.is_some() {
             return Err((
@@ -1490,10 +1986,12 @@ impl<'a> Visitor<'a> {
                 .into());
         }
 
-        let name = self.interpolation_to_value(unknown_at_rule.name, false, false)?;
+        let name = self.interpolation_to_value(&unknown_at_rule.name, false, false)?;
+        let name = UnknownAtRule::name_from(name);
 
         let value = unknown_at_rule
             .value
+            .as_ref()
             .map(|v| self.interpolation_to_value(v, true, true))
             .transpose()?;
 
@@ -1522,7 +2020,11 @@ impl<'a> Visitor<'a> {
             self.flags.set(ContextFlags::IN_UNKNOWN_AT_RULE, true);
         }
 
-        let children = unknown_at_rule.body.unwrap();
+        let children = unknown_at_rule.body.as_ref().unwrap();
+
+        let old_unknown_at_rule_name = self
+            .unknown_at_rule_name
+            .replace(unvendor(&name).to_ascii_lowercase());
 
         let stmt = CssStmt::UnknownAtRule(
             UnknownAtRule {
@@ -1579,28 +2081,171 @@ impl<'a> Visitor<'a> {
         self.flags.set(ContextFlags::IN_KEYFRAMES, was_in_keyframes);
         self.flags
             .set(ContextFlags::IN_UNKNOWN_AT_RULE, was_in_unknown_at_rule);
+        self.unknown_at_rule_name = old_unknown_at_rule_name;
 
         Ok(None)
     }
 
     pub(crate) fn emit_warning(&mut self, message: &str, span: Span) {
+        self.warning_count += 1;
+        self.mark_uncacheable();
+
         if self.options.quiet {
             return;
         }
-        let loc = self.map.look_up_span(span);
-        eprintln!(
-            "Warning: {}\n    ./{}:{}:{}",
-            message,
-            loc.file.name(),
-            loc.begin.line + 1,
-            loc.begin.column + 1
-        );
+
+        let warning = Warning {
+            message: message.to_owned(),
+            location: look_up_span(self.map, span),
+            trace: self.stack_trace(span),
+            deprecation: None,
+            lint: None,
+        };
+
+        self.options.logger.warn(&warning);
+    }
+
+    /// Whether warnings about `span` are silenced because it is within a
+    /// dependency, and [`Options::quiet_deps`] is set
+    fn is_in_quiet_dependency(&self, span: Span) -> bool {
+        self.options.quiet_deps
+            && !self.dependencies.is_empty()
+            && self
+                .dependencies
+                .contains(Path::new(self.map.find_file(span.low()).name()))
+    }
+
+    /// Emits a warning for `deprecation`, unless it has been silenced or is a
+    /// future deprecation that has not been opted into
+    ///
+    /// Returns an error instead if the deprecation has been made fatal using
+    /// [`Options::fatal_deprecation`].
+    pub(crate) fn emit_deprecation_warning(
+        &mut self,
+        deprecation: Deprecation,
+        message: &str,
+        span: Span,
+    ) -> SassResult<()> {
+        self.warning_count += 1;
+        self.mark_uncacheable();
+
+        if self.options.is_deprecation_fatal(deprecation) {
+            return Err((
+                format!(
+                    "{}\n\nThis is only an error because you've set the {} deprecation to be fatal.\nRemove this setting if you need to keep using this feature.",
+                    message,
+                    deprecation.id()
+                ),
+                span,
+            )
+                .into());
+        }
+
+        if self.options.quiet
+            || !self.options.is_deprecation_enabled(deprecation)
+            || self.is_in_quiet_dependency(span)
+        {
+            return Ok(());
+        }
+
+        if !self.options.verbose {
+            let count = self.deprecation_counts.entry(deprecation).or_insert(0);
+            *count += 1;
+
+            if *count > MAX_REPETITIVE_DEPRECATIONS {
+                return Ok(());
+            }
+        }
+
+        let warning = Warning {
+            message: message.to_owned(),
+            location: look_up_span(self.map, span),
+            trace: self.stack_trace(span),
+            deprecation: Some(deprecation),
+            lint: None,
+        };
+
+        self.options.logger.warn(&warning);
+
+        Ok(())
+    }
+
+    /// Emits a warning for `lint`, if it has been enabled
+    fn emit_lint_warning(&mut self, lint: Lint, message: &str, span: Span) {
+        self.warning_count += 1;
+
+        if self.options.lints.contains(&lint) {
+            self.mark_uncacheable();
+        }
+
+        if self.options.quiet
+            || !self.options.lints.contains(&lint)
+            || self.is_in_quiet_dependency(span)
+        {
+            return;
+        }
+
+        let warning = Warning {
+            message: message.to_owned(),
+            location: look_up_span(self.map, span),
+            trace: self.stack_trace(span),
+            deprecation: None,
+            lint: Some(lint),
+        };
+
+        self.options.logger.warn(&warning);
+    }
+
+    /// The current stack trace, innermost frame first, with `span` as the
+    /// location of the innermost frame
+    fn stack_trace(&self, span: Span) -> Vec<StackFrame> {
+        let mut trace = Vec::with_capacity(self.stack.len() + 1);
+
+        trace.push(StackFrame {
+            location: look_up_span(self.map, span),
+            member: self.member.clone(),
+        });
+
+        for (member, span) in self.stack.iter().rev() {
+            trace.push(StackFrame {
+                location: look_up_span(self.map, *span),
+                member: member.clone(),
+            });
+        }
+
+        trace
     }
 
-    fn visit_warn_rule(&mut self, warn_rule: AstWarn) -> SassResult<()> {
+    /// Runs `callback` with a new stack frame for `member`, called from `span`
+    fn with_stack_frame<T>(
+        &mut self,
+        member: String,
+        span: Span,
+        callback: impl FnOnce(&mut Self) -> SassResult<T>,
+    ) -> SassResult<T> {
+        let old_member = mem::replace(&mut self.member, member);
+        self.stack.push((old_member, span));
+
+        let result = callback(self).map_err(|mut err| {
+            if let Some(span) = err.raw_span() {
+                err.set_trace_if_empty(|| self.stack_trace(span));
+            }
+            err
+        });
+
+        let (old_member, _) = self.stack.pop().unwrap();
+        self.member = old_member;
+
+        result
+    }
+
+    fn visit_warn_rule(&mut self, warn_rule: &AstWarn) -> SassResult<()> {
         if self.warnings_emitted.insert(warn_rule.span) {
-            let value = self.visit_expr(warn_rule.value)?;
-            let message = value.to_css_string(warn_rule.span, self.options.is_compressed())?;
+            let value = self.visit_expr(&warn_rule.value)?;
+            let message = match value {
+                Value::String(text, _) => text,
+                value => value.to_css_string(warn_rule.span, self.options.is_compressed())?,
+            };
             self.emit_warning(&message, warn_rule.span);
         }
 
@@ -1735,7 +2380,7 @@ impl<'a> Visitor<'a> {
         v
     }
 
-    fn visit_include_stmt(&mut self, include_stmt: AstInclude) -> SassResult<Option<Value>> {
+    fn visit_include_stmt(&mut self, include_stmt: &AstInclude) -> SassResult<Option<Value>> {
         let mixin = self
             .env
             .get_mixin(include_stmt.name, include_stmt.namespace)?;
@@ -1746,7 +2391,7 @@ impl<'a> Visitor<'a> {
                     return Err(("Mixin doesn't accept a content block.", include_stmt.span).into());
                 }
 
-                let args = self.eval_args(include_stmt.args, include_stmt.name.span)?;
+                let args = self.eval_args(&include_stmt.args, include_stmt.name.span)?;
                 mixin(args, self)?;
 
                 Ok(None)
@@ -1761,9 +2406,9 @@ impl<'a> Visitor<'a> {
                 let old_in_mixin = self.flags.in_mixin();
                 self.flags.set(ContextFlags::IN_MIXIN, true);
 
-                let callable_content = content.map(|c| {
+                let callable_content = content.as_ref().map(|c| {
                     Arc::new(CallableContentBlock {
-                        content: c,
+                        content: Arc::clone(c),
                         env: self.env.new_closure(),
                     })
                 });
@@ -1775,7 +2420,7 @@ impl<'a> Visitor<'a> {
                     include_stmt.name.span,
                     |mixin, visitor| {
                         visitor.with_content(callable_content, |visitor| {
-                            for stmt in mixin.body {
+                            for stmt in &mixin.body {
                                 let result = visitor.visit_stmt(stmt)?;
                                 debug_assert!(result.is_none());
                             }
@@ -1791,15 +2436,15 @@ impl<'a> Visitor<'a> {
         }
     }
 
-    fn visit_mixin_decl(&mut self, mixin: AstMixin) {
+    fn visit_mixin_decl(&mut self, mixin: &Arc<AstMixin>) {
         self.env.insert_mixin(
             mixin.name,
-            Mixin::UserDefined(mixin, self.env.new_closure()),
+            Mixin::UserDefined(Arc::clone(mixin), self.env.new_closure()),
         );
     }
 
-    fn visit_each_stmt(&mut self, each_stmt: AstEach) -> SassResult<Option<Value>> {
-        let list = self.visit_expr(each_stmt.list)?.as_list();
+    fn visit_each_stmt(&mut self, each_stmt: &AstEach) -> SassResult<Option<Value>> {
+        let list = self.visit_expr(&each_stmt.list)?.as_list();
 
         // todo: not setting semi_global: true maybe means we can't assign to global scope when declared as global
         self.env.scopes_mut().enter_new_scope();
@@ -1823,7 +2468,7 @@ impl<'a> Visitor<'a> {
                 }
             }
 
-            for stmt in each_stmt.body.clone() {
+            for stmt in &each_stmt.body {
                 let val = self.visit_stmt(stmt)?;
                 if val.is_some() {
                     result = val;
@@ -1837,13 +2482,13 @@ impl<'a> Visitor<'a> {
         Ok(result)
     }
 
-    fn visit_for_stmt(&mut self, for_stmt: AstFor) -> SassResult<Option<Value>> {
+    fn visit_for_stmt(&mut self, for_stmt: &AstFor) -> SassResult<Option<Value>> {
         let from_span = for_stmt.from.span;
         let to_span = for_stmt.to.span;
         let from_number = self
-            .visit_expr(for_stmt.from.node)?
+            .visit_expr(&for_stmt.from.node)?
             .assert_number(from_span)?;
-        let to_number = self.visit_expr(for_stmt.to.node)?.assert_number(to_span)?;
+        let to_number = self.visit_expr(&for_stmt.to.node)?.assert_number(to_span)?;
 
         if !to_number.unit().comparable(from_number.unit()) {
             // todo: better error message here
@@ -1855,36 +2500,32 @@ impl<'a> Visitor<'a> {
         }
 
         let from = from_number.num.assert_int(from_span)?;
-        let mut to = to_number
+        let to = to_number
             .num
             .convert(to_number.unit(), from_number.unit())
             .assert_int(to_span)?;
 
         let direction = if from > to { -1 } else { 1 };
 
-        if to == i64::MAX || to == i64::MIN {
-            return Err((
-                "@for loop upper bound exceeds valid integer representation (i64::MAX)",
-                to_span,
-            )
-                .into());
-        }
-
-        if !for_stmt.is_exclusive {
-            to += direction;
-        }
-
-        if from == to {
+        if for_stmt.is_exclusive && from == to {
             return Ok(None);
         }
 
+        // the final value of `$i`, which always lies between `from` and `to` so
+        // that stepping towards it can't overflow
+        let last = if for_stmt.is_exclusive {
+            to - direction
+        } else {
+            to
+        };
+
         // todo: self.with_scopes
         self.env.scopes_mut().enter_new_scope();
 
         let mut result = None;
 
         let mut i = from;
-        'outer: while i != to {
+        'outer: loop {
             self.env.scopes_mut().insert_var_last(
                 for_stmt.variable.node,
                 Value::Dimension(SassNumber {
@@ -1894,7 +2535,7 @@ impl<'a> Visitor<'a> {
                 }),
             );
 
-            for stmt in for_stmt.body.clone() {
+            for stmt in &for_stmt.body {
                 let val = self.visit_stmt(stmt)?;
                 if val.is_some() {
                     result = val;
@@ -1902,6 +2543,10 @@ impl<'a> Visitor<'a> {
                 }
             }
 
+            if i == last {
+                break;
+            }
+
             i += direction;
         }
 
@@ -1914,11 +2559,8 @@ impl<'a> Visitor<'a> {
         self.with_scope(true, true, |visitor| {
             let mut result = None;
 
-            'outer: while visitor
-                .visit_expr(while_stmt.condition.clone())?
-                .is_truthy()
-            {
-                for stmt in while_stmt.body.clone() {
+            'outer: while visitor.visit_expr(&while_stmt.condition)?.is_truthy() {
+                for stmt in &while_stmt.body {
                     let val = visitor.visit_stmt(stmt)?;
                     if val.is_some() {
                         result = val;
@@ -1931,11 +2573,11 @@ impl<'a> Visitor<'a> {
         })
     }
 
-    fn visit_if_stmt(&mut self, if_stmt: AstIf) -> SassResult<Option<Value>> {
-        let mut clause: Option<Vec<AstStmt>> = if_stmt.else_clause;
-        for clause_to_check in if_stmt.if_clauses {
-            if self.visit_expr(clause_to_check.condition)?.is_truthy() {
-                clause = Some(clause_to_check.body);
+    fn visit_if_stmt(&mut self, if_stmt: &AstIf) -> SassResult<Option<Value>> {
+        let mut clause: Option<&Vec<AstStmt>> = if_stmt.else_clause.as_ref();
+        for clause_to_check in &if_stmt.if_clauses {
+            if self.visit_expr(&clause_to_check.condition)?.is_truthy() {
+                clause = Some(&clause_to_check.body);
                 break;
             }
         }
@@ -1960,7 +2602,7 @@ impl<'a> Visitor<'a> {
         Ok(result)
     }
 
-    fn visit_loud_comment(&mut self, comment: AstLoudComment) -> SassResult<Option<Value>> {
+    fn visit_loud_comment(&mut self, comment: &AstLoudComment) -> SassResult<Option<Value>> {
         if self.flags.in_function() {
             return Ok(None);
         }
@@ -1971,7 +2613,7 @@ impl<'a> Visitor<'a> {
         // }
 
         let comment = CssStmt::Comment(
-            self.perform_interpolation(comment.text, false)?,
+            self.perform_interpolation(&comment.text, false)?,
             comment.span,
         );
         self.css_tree.add_stmt(comment, self.parent);
@@ -1979,7 +2621,7 @@ impl<'a> Visitor<'a> {
         Ok(None)
     }
 
-    fn visit_variable_decl(&mut self, decl: AstVariableDecl) -> SassResult<Option<Value>> {
+    fn visit_variable_decl(&mut self, decl: &AstVariableDecl) -> SassResult<Option<Value>> {
         let name = Spanned {
             node: decl.name,
             span: decl.span,
@@ -2015,7 +2657,7 @@ impl<'a> Visitor<'a> {
             }
         }
 
-        let value = self.visit_expr(decl.value)?;
+        let value = self.visit_expr(&decl.value)?;
         let value = self.without_slash(value);
 
         self.env.insert_var(
@@ -2031,7 +2673,7 @@ impl<'a> Visitor<'a> {
 
     fn interpolation_to_value(
         &mut self,
-        interpolation: Interpolation,
+        interpolation: &Interpolation,
         // default=false
         trim: bool,
         // default=false
@@ -2046,32 +2688,68 @@ impl<'a> Visitor<'a> {
         })
     }
 
+    /// Evaluates `interpolation` to text that will be re-parsed, alongside a
+    /// mapping from that text back to `span`, the location of the interpolation
+    /// in the source
+    fn interpolation_to_value_with_map(
+        &mut self,
+        interpolation: &Interpolation,
+        // default=false
+        trim: bool,
+        span: Span,
+    ) -> SassResult<(String, InterpolationMap)> {
+        let file = Arc::clone(self.map.find_file(span.low()));
+        let mut map = InterpolationMap::new(file, span);
+        let mut result = String::new();
+
+        for part in &interpolation.contents {
+            match part {
+                InterpolationPart::String(s) => result.push_str(s),
+                InterpolationPart::Expr(e) => {
+                    let span = e.span;
+                    let value = self.visit_expr(&e.node)?;
+                    let start = result.len();
+                    result.push_str(&self.serialize(value, QuoteKind::None, span)?);
+                    map.add_expr(start, result.len(), span);
+                }
+            }
+        }
+
+        if trim {
+            map.trim_start(
+                result.len()
+                    - result
+                        .trim_start_matches(|c: char| c.is_ascii_whitespace())
+                        .len(),
+            );
+            result = trim_ascii(&result, true).to_owned();
+        }
+
+        Ok((result, map))
+    }
+
     fn perform_interpolation(
         &mut self,
-        mut interpolation: Interpolation,
+        interpolation: &Interpolation,
         // todo check to emit warning if this is true
         _warn_for_color: bool,
     ) -> SassResult<String> {
-        let result = match interpolation.contents.len() {
-            0 => String::new(),
-            1 => match interpolation.contents.pop() {
-                Some(InterpolationPart::String(s)) => s,
-                Some(InterpolationPart::Expr(e)) => {
-                    let span = e.span;
-                    let result = self.visit_expr(e.node)?;
-                    // todo: span for specific expr
-                    self.serialize(result, QuoteKind::None, span)?
-                }
-                None => unreachable!(),
-            },
-            _ => interpolation
-                .contents
-                .into_iter()
+        let result = match interpolation.contents.as_slice() {
+            [] => String::new(),
+            [InterpolationPart::String(s)] => s.clone(),
+            [InterpolationPart::Expr(e)] => {
+                let span = e.span;
+                let result = self.visit_expr(&e.node)?;
+                // todo: span for specific expr
+                self.serialize(result, QuoteKind::None, span)?
+            }
+            contents => contents
+                .iter()
                 .map(|part| match part {
-                    InterpolationPart::String(s) => Ok(s),
+                    InterpolationPart::String(s) => Ok(s.clone()),
                     InterpolationPart::Expr(e) => {
                         let span = e.span;
-                        let result = self.visit_expr(e.node)?;
+                        let result = self.visit_expr(&e.node)?;
                         // todo: span for specific expr
                         self.serialize(result, QuoteKind::None, span)
                     }
@@ -2084,7 +2762,7 @@ impl<'a> Visitor<'a> {
 
     fn evaluate_to_css(
         &mut self,
-        expr: AstExpr,
+        expr: &AstExpr,
         quote: QuoteKind,
         span: Span,
     ) -> SassResult<String> {
@@ -2121,19 +2799,19 @@ impl<'a> Visitor<'a> {
 
     fn eval_args(
         &mut self,
-        arguments: ArgumentInvocation,
+        arguments: &ArgumentInvocation,
         span: Span,
     ) -> SassResult<ArgumentResult> {
         let mut positional = Vec::with_capacity(arguments.positional.len());
 
-        for expr in arguments.positional {
+        for expr in &arguments.positional {
             let val = self.visit_expr(expr)?;
             positional.push(self.without_slash(val));
         }
 
         let mut named = BTreeMap::new();
 
-        for (key, expr) in arguments.named {
+        for (&key, expr) in &arguments.named {
             let val = self.visit_expr(expr)?;
             named.insert(key, self.without_slash(val));
         }
@@ -2148,7 +2826,7 @@ impl<'a> Visitor<'a> {
             });
         }
 
-        let rest = self.visit_expr(arguments.rest.unwrap())?;
+        let rest = self.visit_expr(arguments.rest.as_ref().unwrap())?;
 
         let mut separator = ListSeparator::Undecided;
 
@@ -2191,7 +2869,7 @@ impl<'a> Visitor<'a> {
             });
         }
 
-        match self.visit_expr(arguments.keyword_rest.unwrap())? {
+        match self.visit_expr(arguments.keyword_rest.as_ref().unwrap())? {
             Value::Map(keyword_rest) => {
                 self.add_rest_map(&mut named, keyword_rest)?;
 
@@ -2260,110 +2938,112 @@ impl<'a> Visitor<'a> {
         }
 
         self.with_environment(env.new_closure(), |visitor| {
-            visitor.with_scope(false, true, move |visitor| {
-                func.arguments().verify(
-                    evaluated.positional.len(),
-                    &evaluated.named,
-                    evaluated.span,
-                )?;
+            visitor.with_stack_frame(name, span, |visitor| {
+                visitor.with_scope(false, true, move |visitor| {
+                    func.arguments()
+                        .verify(evaluated.positional.len(), &evaluated.named, evaluated.span)
+                        .map_err(|e| {
+                            e.with_primary_label("invocation")
+                                .with_secondary_label("declaration", func.arguments().span)
+                        })?;
+
+                    let declared_arguments = &func.arguments().args;
+                    let min_len = evaluated.positional.len().min(declared_arguments.len());
+
+                    let positional_len = evaluated.positional.len();
+
+                    #[allow(clippy::needless_range_loop)]
+                    for i in (0..min_len).rev() {
+                        visitor.env.scopes_mut().insert_var_last(
+                            declared_arguments[i].name,
+                            evaluated.positional.remove(i),
+                        );
+                    }
 
-                let declared_arguments = &func.arguments().args;
-                let min_len = evaluated.positional.len().min(declared_arguments.len());
+                    // todo: better name for var
+                    let additional_declared_args = if declared_arguments.len() > positional_len {
+                        &declared_arguments[positional_len..declared_arguments.len()]
+                    } else {
+                        &[]
+                    };
 
-                let positional_len = evaluated.positional.len();
+                    for argument in additional_declared_args {
+                        let name = argument.name;
+                        let value = evaluated.named.remove(&argument.name).map_or_else(
+                            || {
+                                let v = visitor.visit_expr(argument.default.as_ref().unwrap())?;
+                                Ok(visitor.without_slash(v))
+                            },
+                            SassResult::Ok,
+                        )?;
+                        visitor.env.scopes_mut().insert_var_last(name, value);
+                    }
 
-                #[allow(clippy::needless_range_loop)]
-                for i in (0..min_len).rev() {
-                    visitor.env.scopes_mut().insert_var_last(
-                        declared_arguments[i].name,
-                        evaluated.positional.remove(i),
-                    );
-                }
+                    let were_keywords_accessed = Arc::new(Cell::new(false));
 
-                // todo: better name for var
-                let additional_declared_args = if declared_arguments.len() > positional_len {
-                    &declared_arguments[positional_len..declared_arguments.len()]
-                } else {
-                    &[]
-                };
+                    let num_named_args = evaluated.named.len();
 
-                for argument in additional_declared_args {
-                    let name = argument.name;
-                    let value = evaluated.named.remove(&argument.name).map_or_else(
-                        || {
-                            // todo: superfluous clone
-                            let v = visitor.visit_expr(argument.default.clone().unwrap())?;
-                            Ok(visitor.without_slash(v))
-                        },
-                        SassResult::Ok,
-                    )?;
-                    visitor.env.scopes_mut().insert_var_last(name, value);
-                }
+                    let has_arg_list = if let Some(rest_arg) = func.arguments().rest {
+                        let rest = if !evaluated.positional.is_empty() {
+                            evaluated.positional
+                        } else {
+                            Vec::new()
+                        };
 
-                let were_keywords_accessed = Arc::new(Cell::new(false));
+                        let arg_list = Value::ArgList(ArgList::new(
+                            rest,
+                            Arc::clone(&were_keywords_accessed),
+                            // todo: superfluous clone
+                            evaluated.named.clone(),
+                            if evaluated.separator == ListSeparator::Undecided {
+                                ListSeparator::Comma
+                            } else {
+                                ListSeparator::Space
+                            },
+                        ));
 
-                let num_named_args = evaluated.named.len();
+                        visitor.env.scopes_mut().insert_var_last(rest_arg, arg_list);
 
-                let has_arg_list = if let Some(rest_arg) = func.arguments().rest {
-                    let rest = if !evaluated.positional.is_empty() {
-                        evaluated.positional
+                        true
                     } else {
-                        Vec::new()
+                        false
                     };
 
-                    let arg_list = Value::ArgList(ArgList::new(
-                        rest,
-                        Arc::clone(&were_keywords_accessed),
-                        // todo: superfluous clone
-                        evaluated.named.clone(),
-                        if evaluated.separator == ListSeparator::Undecided {
-                            ListSeparator::Comma
-                        } else {
-                            ListSeparator::Space
-                        },
-                    ));
-
-                    visitor.env.scopes_mut().insert_var_last(rest_arg, arg_list);
-
-                    true
-                } else {
-                    false
-                };
-
-                let val = run(func, visitor)?;
+                    let val = run(func, visitor)?;
 
-                if !has_arg_list || num_named_args == 0 {
-                    return Ok(val);
-                }
+                    if !has_arg_list || num_named_args == 0 {
+                        return Ok(val);
+                    }
 
-                if (*were_keywords_accessed).get() {
-                    return Ok(val);
-                }
+                    if (*were_keywords_accessed).get() {
+                        return Ok(val);
+                    }
 
-                let argument_word = if num_named_args == 1 {
-                    "argument"
-                } else {
-                    "arguments"
-                };
+                    let argument_word = if num_named_args == 1 {
+                        "argument"
+                    } else {
+                        "arguments"
+                    };
 
-                let argument_names = to_sentence(
-                    evaluated
-                        .named
-                        .keys()
-                        .map(|key| format!("${key}", key = key))
-                        .collect(),
-                    "or",
-                );
+                    let argument_names = to_sentence(
+                        evaluated
+                            .named
+                            .keys()
+                            .map(|key| format!("${key}", key = key))
+                            .collect(),
+                        "or",
+                    );
 
-                Err((
-                    format!(
-                        "No {argument_word} named {argument_names}.",
-                        argument_word = argument_word,
-                        argument_names = argument_names
-                    ),
-                    span,
-                )
-                    .into())
+                    Err((
+                        format!(
+                            "No {argument_word} named {argument_names}.",
+                            argument_word = argument_word,
+                            argument_names = argument_names
+                        ),
+                        span,
+                    )
+                        .into())
+                })
             })
         })
     }
@@ -2371,7 +3051,7 @@ impl<'a> Visitor<'a> {
     pub(crate) fn run_function_callable(
         &mut self,
         func: SassFunction,
-        arguments: ArgumentInvocation,
+        arguments: &ArgumentInvocation,
         span: Span,
     ) -> SassResult<Value> {
         self.run_function_callable_with_maybe_evaled(
@@ -2388,14 +3068,43 @@ impl<'a> Visitor<'a> {
         span: Span,
     ) -> SassResult<Value> {
         match func {
-            SassFunction::Builtin(func, _name) => {
+            SassFunction::Builtin(func, name) => {
                 let evaluated = self.eval_maybe_args(arguments, span)?;
-                let val = func.0(evaluated, self)?;
-                Ok(self.without_slash(val))
+
+                let key = CallKey::new(&func, &evaluated);
+
+                if let Some(val) = key.as_ref().and_then(|key| self.builtin_memo.get(key)) {
+                    return Ok(val.clone());
+                }
+
+                // these may return different results each time they're called
+                if matches!(name.as_str(), "random" | "unique-id")
+                    || self
+                        .options
+                        .custom_fns
+                        .values()
+                        .any(|custom| *custom == func)
+                {
+                    self.mark_uncacheable();
+                }
+
+                let warning_count = self.warning_count;
+                let old_builtin = self.current_builtin.replace(name);
+                let val = func.0(evaluated, self);
+                self.current_builtin = old_builtin;
+                let val = self.without_slash(val?);
+
+                if let Some(key) = key {
+                    if self.warning_count == warning_count {
+                        self.builtin_memo.insert(key, &val);
+                    }
+                }
+
+                Ok(val)
             }
             SassFunction::UserDefined(UserDefinedFunction { function, env, .. }) => self
                 .run_user_defined_callable(arguments, function, &env, span, |function, visitor| {
-                    for stmt in function.body.clone() {
+                    for stmt in &function.body {
                         let result = visitor.visit_stmt(stmt)?;
 
                         if let Some(val) = result {
@@ -2414,9 +3123,9 @@ impl<'a> Visitor<'a> {
                 let arguments = match arguments {
                     MaybeEvaledArguments::Invocation(args) => {
                         has_named = !args.named.is_empty() || args.keyword_rest.is_some();
-                        rest = args.rest;
+                        rest = args.rest.as_ref();
                         args.positional
-                            .into_iter()
+                            .iter()
                             .map(|arg| self.evaluate_to_css(arg, QuoteKind::Quoted, span))
                             .collect::<SassResult<Vec<_>>>()?
                     }
@@ -2463,12 +3172,12 @@ impl<'a> Visitor<'a> {
         }
     }
 
-    fn visit_list_expr(&mut self, list: ListExpr) -> SassResult<Value> {
+    fn visit_list_expr(&mut self, list: &ListExpr) -> SassResult<Value> {
         let elems = list
             .elems
-            .into_iter()
+            .iter()
             .map(|e| {
-                let value = self.visit_expr(e.node)?;
+                let value = self.visit_expr(&e.node)?;
                 Ok(value)
             })
             .collect::<SassResult<Vec<_>>>()?;
@@ -2476,7 +3185,7 @@ impl<'a> Visitor<'a> {
         Ok(Value::List(elems, list.separator, list.brackets))
     }
 
-    fn visit_function_call_expr(&mut self, func_call: FunctionCallExpr) -> SassResult<Value> {
+    fn visit_function_call_expr(&mut self, func_call: &FunctionCallExpr) -> SassResult<Value> {
         let name = func_call.name;
 
         let func = match self.env.get_fn(name, func_call.namespace)? {
@@ -2487,8 +3196,16 @@ impl<'a> Visitor<'a> {
                 } else if let Some(f) = GLOBAL_FUNCTIONS.get(name.as_str()) {
                     SassFunction::Builtin(f.clone(), name)
                 } else {
-                    if func_call.namespace.is_some() {
-                        return Err(("Undefined function.", func_call.span).into());
+                    if let Some(namespace) = func_call.namespace {
+                        return Err(undefined_member(
+                            "Undefined function.",
+                            &format!("{}.", namespace.node),
+                            Spanned {
+                                node: name,
+                                span: func_call.span,
+                            },
+                            self.env.module_function_names(namespace.node),
+                        ));
                     }
 
                     SassFunction::Plain { name }
@@ -2498,19 +3215,18 @@ impl<'a> Visitor<'a> {
 
         let old_in_function = self.flags.in_function();
         self.flags.set(ContextFlags::IN_FUNCTION, true);
-        let value =
-            self.run_function_callable(func, (*func_call.arguments).clone(), func_call.span)?;
+        let value = self.run_function_callable(func, &func_call.arguments, func_call.span)?;
         self.flags.set(ContextFlags::IN_FUNCTION, old_in_function);
 
         Ok(value)
     }
 
-    fn visit_interpolated_func_expr(&mut self, func: InterpolatedFunction) -> SassResult<Value> {
+    fn visit_interpolated_func_expr(&mut self, func: &InterpolatedFunction) -> SassResult<Value> {
         let InterpolatedFunction {
-            name,
-            arguments: args,
+            ref name,
+            arguments: ref args,
             span,
-        } = func;
+        } = *func;
         let fn_name = self.perform_interpolation(name, false)?;
 
         if !args.named.is_empty() || args.keyword_rest.is_some() {
@@ -2520,7 +3236,7 @@ impl<'a> Visitor<'a> {
         let mut buffer = format!("{}(", fn_name);
 
         let mut first = true;
-        for arg in args.positional.clone() {
+        for arg in &args.positional {
             if first {
                 first = false;
             } else {
@@ -2530,7 +3246,7 @@ impl<'a> Visitor<'a> {
             buffer.push_str(&evaluated);
         }
 
-        if let Some(rest_arg) = args.rest {
+        if let Some(rest_arg) = &args.rest {
             let rest = self.visit_expr(rest_arg)?;
             if !first {
                 buffer.push_str(", ");
@@ -2550,59 +3266,59 @@ impl<'a> Visitor<'a> {
         }
     }
 
-    fn visit_expr(&mut self, expr: AstExpr) -> SassResult<Value> {
+    fn visit_expr(&mut self, expr: &AstExpr) -> SassResult<Value> {
+        ensure_sufficient_stack(|| self.visit_expr_inner(expr))
+    }
+
+    fn visit_expr_inner(&mut self, expr: &AstExpr) -> SassResult<Value> {
         Ok(match expr {
-            AstExpr::Color(color) => Value::Color(color),
+            AstExpr::Color(color) => Value::Color(Arc::clone(color)),
             AstExpr::Number { n, unit } => Value::Dimension(SassNumber {
-                num: n,
-                unit,
+                num: *n,
+                unit: unit.clone(),
                 as_slash: None,
             }),
             AstExpr::List(list) => self.visit_list_expr(list)?,
-            AstExpr::String(StringExpr(text, quote), ..) => self.visit_string(text, quote)?,
+            AstExpr::String(StringExpr(text, quote), ..) => self.visit_string(text, *quote)?,
             AstExpr::BinaryOp(binop) => self.visit_bin_op(
-                binop.lhs.clone(),
+                &binop.lhs,
                 binop.op,
-                binop.rhs.clone(),
+                &binop.rhs,
                 binop.allows_slash,
                 binop.span,
             )?,
             AstExpr::True => Value::True,
             AstExpr::False => Value::False,
-            AstExpr::Calculation { name, args } => {
-                self.visit_calculation_expr(name, args, self.empty_span)?
+            AstExpr::Calculation { name, args, span } => {
+                self.visit_calculation_expr(*name, args, *span)?
             }
             AstExpr::FunctionCall(func_call) => self.visit_function_call_expr(func_call)?,
-            AstExpr::If(if_expr) => self.visit_ternary((*if_expr).clone())?,
-            AstExpr::InterpolatedFunction(func) => {
-                self.visit_interpolated_func_expr((*func).clone())?
-            }
+            AstExpr::If(if_expr) => self.visit_ternary(if_expr)?,
+            AstExpr::InterpolatedFunction(func) => self.visit_interpolated_func_expr(func)?,
             AstExpr::Map(map) => self.visit_map(map)?,
             AstExpr::Null => Value::Null,
-            AstExpr::Paren(expr) => self.visit_expr((*expr).clone())?,
+            AstExpr::Paren(expr) => self.visit_expr(expr)?,
             AstExpr::ParentSelector => self.visit_parent_selector(),
-            AstExpr::UnaryOp(op, expr, span) => self.visit_unary_op(op, (*expr).clone(), span)?,
-            AstExpr::Variable { name, namespace } => self.env.get_var(name, namespace)?,
-            AstExpr::Supports(condition) => Value::String(
-                self.visit_supports_condition((*condition).clone())?,
-                QuoteKind::None,
-            ),
+            AstExpr::UnaryOp(op, expr, span) => self.visit_unary_op(*op, expr, *span)?,
+            AstExpr::Variable { name, namespace } => self.env.get_var(*name, *namespace)?,
+            AstExpr::Supports(condition) => {
+                Value::String(self.visit_supports_condition(condition)?, QuoteKind::None)
+            }
         })
     }
 
     fn visit_calculation_value(
         &mut self,
-        expr: AstExpr,
+        expr: &AstExpr,
         in_min_or_max: bool,
         span: Span,
     ) -> SassResult<CalculationArg> {
         Ok(match expr {
-            AstExpr::Paren(inner) => match &*inner {
+            AstExpr::Paren(inner) => match &**inner {
                 AstExpr::FunctionCall(FunctionCallExpr { ref name, .. })
                     if name.as_str().to_ascii_lowercase() == "var" =>
                 {
-                    let result =
-                        self.visit_calculation_value((*inner).clone(), in_min_or_max, span)?;
+                    let result = self.visit_calculation_value(inner, in_min_or_max, span)?;
 
                     if let CalculationArg::String(text) = result {
                         CalculationArg::String(format!("({})", text))
@@ -2610,16 +3326,16 @@ impl<'a> Visitor<'a> {
                         result
                     }
                 }
-                _ => self.visit_calculation_value((*inner).clone(), in_min_or_max, span)?,
+                _ => self.visit_calculation_value(inner, in_min_or_max, span)?,
             },
             AstExpr::String(string_expr, _span) => {
                 debug_assert!(string_expr.1 == QuoteKind::None);
-                CalculationArg::Interpolation(self.perform_interpolation(string_expr.0, false)?)
+                CalculationArg::Interpolation(self.perform_interpolation(&string_expr.0, false)?)
             }
             AstExpr::BinaryOp(binop) => SassCalculation::operate_internal(
                 binop.op,
-                self.visit_calculation_value(binop.lhs.clone(), in_min_or_max, span)?,
-                self.visit_calculation_value(binop.rhs.clone(), in_min_or_max, span)?,
+                self.visit_calculation_value(&binop.lhs, in_min_or_max, span)?,
+                self.visit_calculation_value(&binop.rhs, in_min_or_max, span)?,
                 in_min_or_max,
                 !self.flags.in_supports_declaration(),
                 self.options,
@@ -2664,11 +3380,11 @@ impl<'a> Visitor<'a> {
     fn visit_calculation_expr(
         &mut self,
         name: CalculationName,
-        args: Vec<AstExpr>,
+        args: &[AstExpr],
         span: Span,
     ) -> SassResult<Value> {
         let mut args = args
-            .into_iter()
+            .iter()
             .map(|arg| self.visit_calculation_value(arg, name.in_min_or_max(), span))
             .collect::<SassResult<Vec<_>>>()?;
 
@@ -2702,7 +3418,7 @@ impl<'a> Visitor<'a> {
         }
     }
 
-    fn visit_unary_op(&mut self, op: UnaryOp, expr: AstExpr, span: Span) -> SassResult<Value> {
+    fn visit_unary_op(&mut self, op: UnaryOp, expr: &AstExpr, span: Span) -> SassResult<Value> {
         let operand = self.visit_expr(expr)?;
 
         match op {
@@ -2713,29 +3429,25 @@ impl<'a> Visitor<'a> {
         }
     }
 
-    fn visit_ternary(&mut self, if_expr: Ternary) -> SassResult<Value> {
-        if_arguments().verify(if_expr.0.positional.len(), &if_expr.0.named, if_expr.0.span)?;
-
-        let mut positional = if_expr.0.positional;
-        let mut named = if_expr.0.named;
-
-        let condition = if positional.is_empty() {
-            named.remove(&Identifier::from("condition")).unwrap()
-        } else {
-            positional.remove(0)
-        };
+    fn visit_ternary(&mut self, if_expr: &Ternary) -> SassResult<Value> {
+        if_arguments(if_expr.0.span).verify(
+            if_expr.0.positional.len(),
+            &if_expr.0.named,
+            if_expr.0.span,
+        )?;
 
-        let if_true = if positional.is_empty() {
-            named.remove(&Identifier::from("if_true")).unwrap()
-        } else {
-            positional.remove(0)
-        };
+        let mut positional = if_expr.0.positional.iter();
+        let named = &if_expr.0.named;
 
-        let if_false = if positional.is_empty() {
-            named.remove(&Identifier::from("if_false")).unwrap()
-        } else {
-            positional.remove(0)
-        };
+        let condition = positional
+            .next()
+            .unwrap_or_else(|| &named[&Identifier::from("condition")]);
+        let if_true = positional
+            .next()
+            .unwrap_or_else(|| &named[&Identifier::from("if_true")]);
+        let if_false = positional
+            .next()
+            .unwrap_or_else(|| &named[&Identifier::from("if_false")]);
 
         let value = if self.visit_expr(condition)?.is_truthy() {
             self.visit_expr(if_true)?
@@ -2746,33 +3458,27 @@ impl<'a> Visitor<'a> {
         Ok(self.without_slash(value))
     }
 
-    fn visit_string(&mut self, mut text: Interpolation, quote: QuoteKind) -> SassResult<Value> {
+    fn visit_string(&mut self, text: &Interpolation, quote: QuoteKind) -> SassResult<Value> {
         // Don't use [performInterpolation] here because we need to get the raw text
         // from strings, rather than the semantic value.
         let old_in_supports_declaration = self.flags.in_supports_declaration();
         self.flags.set(ContextFlags::IN_SUPPORTS_DECLARATION, false);
 
-        let result = match text.contents.len() {
-            0 => String::new(),
-            1 => match text.contents.pop() {
-                Some(InterpolationPart::String(s)) => s,
-                Some(InterpolationPart::Expr(Spanned { node, span })) => {
-                    match self.visit_expr(node)? {
-                        Value::String(s, ..) => s,
-                        e => self.serialize(e, QuoteKind::None, span)?,
-                    }
-                }
-                None => unreachable!(),
+        let result = match text.contents.as_slice() {
+            [] => String::new(),
+            [InterpolationPart::String(s)] => s.clone(),
+            [InterpolationPart::Expr(Spanned { node, span })] => match self.visit_expr(node)? {
+                Value::String(s, ..) => s,
+                e => self.serialize(e, QuoteKind::None, *span)?,
             },
-            _ => text
-                .contents
-                .into_iter()
+            contents => contents
+                .iter()
                 .map(|part| match part {
-                    InterpolationPart::String(s) => Ok(s),
+                    InterpolationPart::String(s) => Ok(s.clone()),
                     InterpolationPart::Expr(Spanned { node, span }) => {
                         match self.visit_expr(node)? {
                             Value::String(s, ..) => Ok(s),
-                            e => self.serialize(e, QuoteKind::None, span),
+                            e => self.serialize(e, QuoteKind::None, *span),
                         }
                     }
                 })
@@ -2787,13 +3493,13 @@ impl<'a> Visitor<'a> {
         Ok(Value::String(result, quote))
     }
 
-    fn visit_map(&mut self, map: AstSassMap) -> SassResult<Value> {
+    fn visit_map(&mut self, map: &AstSassMap) -> SassResult<Value> {
         let mut sass_map = SassMap::new();
 
-        for pair in map.0 {
-            let key_span = pair.0.span;
-            let key = self.visit_expr(pair.0.node)?;
-            let value = self.visit_expr(pair.1)?;
+        for (key, value) in &map.0 {
+            let key_span = key.span;
+            let key = self.visit_expr(&key.node)?;
+            let value = self.visit_expr(value)?;
 
             if sass_map.get_ref(&key).is_some() {
                 return Err(("Duplicate key.", key_span).into());
@@ -2813,9 +3519,9 @@ impl<'a> Visitor<'a> {
 
     fn visit_bin_op(
         &mut self,
-        lhs: AstExpr,
+        lhs: &AstExpr,
         op: BinaryOp,
-        rhs: AstExpr,
+        rhs: &AstExpr,
         allows_slash: bool,
         span: Span,
     ) -> SassResult<Value> {
@@ -2881,13 +3587,11 @@ impl<'a> Visitor<'a> {
                         span,
                     );
                 } else if left_is_number && right_is_number {
-                    // todo: emit warning here. it prints too frequently, so we do not currently
-                    // self.emit_warning(
-                    //     Cow::Borrowed(format!(
-                    //         "Using / for division outside of calc() is deprecated"
-                    //     )),
-                    //     span,
-                    // );
+                    self.emit_deprecation_warning(
+                        Deprecation::SlashDiv,
+                        "Using / for division outside of calc() is deprecated and will be removed in Dart Sass 2.0.0.\n\nRecommendation: math.div($a, $b) or calc($a / $b)\n\nMore info and automated migrator: https://sass-lang.com/d/slash-div",
+                        span,
+                    )?;
                 }
 
                 div(left, right, self.options, span)?
@@ -2908,7 +3612,7 @@ impl<'a> Visitor<'a> {
         expr.to_css_string(span, self.options.is_compressed())
     }
 
-    pub(crate) fn visit_ruleset(&mut self, ruleset: AstRuleSet) -> SassResult<Option<Value>> {
+    pub(crate) fn visit_ruleset(&mut self, ruleset: &AstRuleSet) -> SassResult<Option<Value>> {
         if self.declaration_name.is_some() {
             return Err((
                 "Style rules may not be used within nested declarations.",
@@ -2923,11 +3627,11 @@ impl<'a> Visitor<'a> {
             ..
         } = ruleset;
 
-        let selector_text = self.interpolation_to_value(ruleset_selector, true, true)?;
+        let (selector_text, selector_map) =
+            self.interpolation_to_value_with_map(ruleset_selector, true, ruleset.selector_span)?;
 
         if self.flags.in_keyframes() {
-            let span = ruleset.selector_span;
-            let sel_toks = Lexer::new_from_string(&selector_text, span);
+            let sel_toks = Lexer::new_from_interpolation(&selector_text, selector_map);
             let parsed_selector =
                 KeyframesSelectorParser::new(sel_toks).parse_keyframes_selector()?;
 
@@ -2953,12 +3657,13 @@ impl<'a> Visitor<'a> {
             return Ok(None);
         }
 
-        let mut parsed_selector = self.parse_selector_from_string(
-            &selector_text,
+        let mut parsed_selector = SelectorParser::new(
+            Lexer::new_from_interpolation(&selector_text, selector_map),
             !self.is_plain_css,
             !self.is_plain_css,
             ruleset.selector_span,
-        )?;
+        )
+        .parse()?;
 
         parsed_selector = parsed_selector.resolve_parent_selectors(
             self.style_rule_ignoring_at_root
@@ -2968,10 +3673,13 @@ impl<'a> Visitor<'a> {
             !self.flags.at_root_excluding_style_rule(),
         )?;
 
-        // todo: _mediaQueries
-        let selector = self
-            .extender
-            .add_selector(parsed_selector, &self.media_queries);
+        let selector = if self.is_plain_css_compilation {
+            ExtendedSelector::new(parsed_selector)
+        } else {
+            // todo: _mediaQueries
+            self.extender
+                .add_selector(parsed_selector, &self.media_queries)?
+        };
 
         let rule = CssStmt::RuleSet {
             selector: selector.clone(),
@@ -3028,11 +3736,51 @@ impl<'a> Visitor<'a> {
         Some(())
     }
 
+    fn lint_declaration_value(&mut self, name: &str, value: &Value, span: Span) {
+        match value {
+            Value::Dimension(SassNumber { num, unit, .. }) => {
+                // the flex-basis in the `flex` shorthand requires a unit in some
+                // browsers, even when it's zero
+                if num.is_zero() && unit.is_length() && name != "flex" {
+                    self.emit_lint_warning(
+                        Lint::ZeroUnit,
+                        &format!(
+                            "Zero lengths don't need a unit, so 0{} may be written as 0.",
+                            unit
+                        ),
+                        span,
+                    );
+                }
+
+                if let Unit::Unknown(..) = unit {
+                    self.emit_lint_warning(
+                        Lint::UnknownUnit,
+                        &format!("Unknown unit {}.", unit),
+                        span,
+                    );
+                }
+            }
+            Value::String(s, QuoteKind::None) if s == "!important" && self.flags.in_mixin() => {
+                self.emit_lint_warning(
+                    Lint::ImportantInMixin,
+                    "!important inside a mixin applies to every style rule that includes the mixin.",
+                    span,
+                );
+            }
+            Value::List(elems, ..) => {
+                for elem in elems {
+                    self.lint_declaration_value(name, elem, span);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn style_rule_exists(&self) -> bool {
         !self.flags.at_root_excluding_style_rule() && self.style_rule_ignoring_at_root.is_some()
     }
 
-    pub(crate) fn visit_style(&mut self, style: AstStyle) -> SassResult<Option<Value>> {
+    pub(crate) fn visit_style(&mut self, style: &AstStyle) -> SassResult<Option<Value>> {
         if !self.style_rule_exists()
             && !self.flags.in_unknown_at_rule()
             && !self.flags.in_keyframes()
@@ -3044,9 +3792,23 @@ impl<'a> Visitor<'a> {
                 .into());
         }
 
+        if !self.style_rule_exists()
+            && !self.flags.in_keyframes()
+            && !self
+                .unknown_at_rule_name
+                .as_deref()
+                .is_some_and(is_descriptor_at_rule)
+        {
+            self.emit_lint_warning(
+                Lint::DeclarationOutsideRule,
+                "This declaration is not inside a style rule, so it will not apply to any elements.",
+                style.span,
+            );
+        }
+
         let is_custom_property = style.is_custom_property();
 
-        let mut name = self.interpolation_to_value(style.name, false, true)?;
+        let mut name = self.interpolation_to_value(&style.name, false, true)?;
 
         if let Some(declaration_name) = &self.declaration_name {
             name = format!("{}-{}", declaration_name, name);
@@ -3054,14 +3816,19 @@ impl<'a> Visitor<'a> {
 
         if let Some(value) = style
             .value
+            .as_ref()
             .map(|s| {
                 SassResult::Ok(Spanned {
-                    node: self.visit_expr(s.node)?,
+                    node: self.visit_expr(&s.node)?,
                     span: s.span,
                 })
             })
             .transpose()?
         {
+            if !is_custom_property && !self.options.lints.is_empty() {
+                self.lint_declaration_value(&name, &value.node, value.span);
+            }
+
             // If the value is an empty list, preserve it, because converting it to CSS
             // will throw an error that we want the user to see.
             if !value.is_blank() || value.is_empty_list() {
@@ -3079,7 +3846,7 @@ impl<'a> Visitor<'a> {
             }
         }
 
-        let children = style.body;
+        let children = &style.body;
 
         if !children.is_empty() {
             let old_declaration_name = self.declaration_name.take();
@@ -3098,3 +3865,50 @@ impl<'a> Visitor<'a> {
         Ok(None)
     }
 }
+
+/// Whether `path` has one of the extensions of a file Sass can load
+fn has_sass_extension(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(OsStr::to_str),
+        Some("scss" | "sass" | "css")
+    )
+}
+
+/// Whether `path` is an `http://` or `https://` URL, which is loaded as it is
+/// rather than relative to the importer
+#[cfg(feature = "http")]
+fn is_url(path: &Path) -> bool {
+    path.to_str().is_some_and(crate::http::is_url)
+}
+
+#[cfg(not(feature = "http"))]
+fn is_url(_path: &Path) -> bool {
+    false
+}
+
+/// Whether the at-rule named `name` contains descriptors, which look like
+/// declarations, rather than style rules
+fn is_descriptor_at_rule(name: &str) -> bool {
+    matches!(
+        name,
+        "font-face"
+            | "page"
+            | "counter-style"
+            | "property"
+            | "font-feature-values"
+            | "swash"
+            | "annotation"
+            | "ornaments"
+            | "stylistic"
+            | "styleset"
+            | "character-variant"
+            | "font-palette-values"
+            | "viewport"
+            | "color-profile"
+            | "view-transition"
+            | "position-try"
+    ) || name.starts_with("top-")
+        || name.starts_with("bottom-")
+        || name.starts_with("left-")
+        || name.starts_with("right-")
+}
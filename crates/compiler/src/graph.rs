@@ -0,0 +1,211 @@
+use std::path::{Path, PathBuf};
+
+use codemap::{CodeMap, Span};
+use indexmap::IndexMap;
+
+#[cfg(feature = "json")]
+use crate::json;
+use crate::{
+    ast::{AstImport, AstStmt, StyleSheet},
+    evaluate::Visitor,
+    lexer::Lexer,
+    parse::{CssParser, SassParser, ScssParser, StylesheetParser},
+    raw_to_parse_error,
+    utils::ensure_sufficient_stack,
+    Error, InputSyntax, Options, Result,
+};
+
+/// The files an entrypoint loads using `@use`, `@forward`, and `@import`,
+/// found by parsing each file without evaluating it
+///
+/// Every load is included, even those within mixins that are never included or
+/// within `@if` rules whose conditions are false. Loads of built-in modules and
+/// plain CSS imports are not included, and neither are stylesheets loaded using
+/// `meta.load-css()`, as their URLs are only known once evaluated.
+///
+/// Files are identified by their canonical paths, as returned by
+/// [`Fs::canonicalize`](crate::Fs::canonicalize).
+///
+/// ```
+/// # use grass_compiler as grass;
+/// fn main() -> Result<(), Box<grass::Error>> {
+///     let graph = grass::dependency_graph("input.scss", &grass::Options::default())?;
+///
+///     for file in graph.files() {
+///         println!("{}", file.display());
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DependencyGraph {
+    entrypoint: PathBuf,
+    /// The files each file loads directly, in the order in which the files
+    /// were first loaded
+    edges: IndexMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl DependencyGraph {
+    /// The canonical path of the entrypoint
+    #[must_use]
+    pub fn entrypoint(&self) -> &Path {
+        &self.entrypoint
+    }
+
+    /// Every file the entrypoint depends on, including the entrypoint itself,
+    /// in the order in which they are first loaded
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.edges.keys().map(PathBuf::as_path)
+    }
+
+    /// The files `file` loads directly, in the order in which it loads them,
+    /// or `None` if `file` is not part of the graph
+    #[must_use]
+    pub fn dependencies_of(&self, file: &Path) -> Option<&[PathBuf]> {
+        self.edges.get(file).map(Vec::as_slice)
+    }
+
+    /// Serialize this graph as JSON
+    ///
+    /// The result is an object with the `entrypoint` and an object mapping each
+    /// file to the array of files it loads directly, in the same order as
+    /// [`DependencyGraph::files`].
+    #[cfg(feature = "json")]
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        json::to_string(&json::DependencyGraph {
+            entrypoint: self.entrypoint.to_string_lossy(),
+            dependencies: json::Dependencies(&self.edges),
+        })
+    }
+}
+
+/// Find the files loaded by `entrypoint` and everything it loads, without
+/// compiling it
+///
+/// See [`DependencyGraph`] for which loads are included. Returns an error if a
+/// file cannot be parsed, or a load cannot be resolved.
+pub fn dependency_graph<P: AsRef<Path>>(
+    entrypoint: P,
+    options: &Options,
+) -> Result<DependencyGraph> {
+    let entrypoint_name = entrypoint.as_ref();
+    let entrypoint = options
+        .fs
+        .canonicalize(entrypoint_name)
+        .unwrap_or_else(|_| entrypoint_name.to_path_buf());
+
+    let mut map = CodeMap::new();
+    let mut edges = IndexMap::new();
+    let mut queue = vec![entrypoint.clone()];
+
+    while let Some(file) = queue.pop() {
+        if edges.contains_key(&file) {
+            continue;
+        }
+
+        // errors in the entrypoint refer to it by the name it was passed as,
+        // as when compiling it
+        let (name, syntax) = if file == entrypoint {
+            (
+                entrypoint_name,
+                options
+                    .input_syntax
+                    .unwrap_or_else(|| InputSyntax::for_path(&file)),
+            )
+        } else {
+            (file.as_path(), InputSyntax::for_path(&file))
+        };
+
+        let stylesheet = parse(&mut map, &file, name, syntax, options)
+            .map_err(|e| raw_to_parse_error(&map, *e, options.unicode_error_messages))?;
+
+        let mut dependencies: Vec<PathBuf> = Vec::new();
+
+        let mut loads = Vec::new();
+        collect_loads(&stylesheet.body, &mut loads);
+
+        for (url, span) in loads {
+            if url.starts_with("sass:") {
+                continue;
+            }
+
+            let dependency = match Visitor::resolve_import(options, &file, Path::new(url)) {
+                Ok(Some((dependency, _))) => {
+                    options.fs.canonicalize(&dependency).unwrap_or(dependency)
+                }
+                Ok(None) => {
+                    return Err(raw_to_parse_error(
+                        &map,
+                        *Box::<Error>::from(("Can't find stylesheet to import.", span)),
+                        options.unicode_error_messages,
+                    ))
+                }
+                Err(message) => {
+                    return Err(raw_to_parse_error(
+                        &map,
+                        *Box::<Error>::from((message, span)),
+                        options.unicode_error_messages,
+                    ))
+                }
+            };
+
+            if !dependencies.contains(&dependency) {
+                dependencies.push(dependency);
+            }
+        }
+
+        // visit dependencies in the order they are loaded
+        queue.extend(dependencies.iter().rev().cloned());
+        edges.insert(file, dependencies);
+    }
+
+    Ok(DependencyGraph { entrypoint, edges })
+}
+
+fn parse(
+    map: &mut CodeMap,
+    path: &Path,
+    name: &Path,
+    syntax: InputSyntax,
+    options: &Options,
+) -> Result<StyleSheet> {
+    let contents = String::from_utf8(options.fs.read(path)?)?;
+    let file = map.add_file(name.to_string_lossy().into_owned(), contents);
+    let lexer = Lexer::new_from_file(&file);
+    let empty_span = file.span.subspan(0, 0);
+
+    match syntax {
+        InputSyntax::Scss => ScssParser::new(lexer, map, options, empty_span, path).__parse(),
+        InputSyntax::Sass => SassParser::new(lexer, map, options, empty_span, path).__parse(),
+        InputSyntax::Css => CssParser::new(lexer, map, options, empty_span, path).__parse(),
+    }
+}
+
+/// The URL and location of every `@use`, `@forward`, and dynamic `@import` in
+/// `body` and the blocks nested within it, in the order in which they appear
+fn collect_loads<'a>(body: &'a [AstStmt], loads: &mut Vec<(&'a str, Span)>) {
+    for stmt in body {
+        match stmt {
+            AstStmt::Use(use_rule) => {
+                loads.push((use_rule.url.to_str().unwrap_or_default(), use_rule.span));
+            }
+            AstStmt::Forward(forward_rule) => loads.push((
+                forward_rule.url.to_str().unwrap_or_default(),
+                forward_rule.span,
+            )),
+            AstStmt::ImportRule(import_rule) => {
+                for import in &import_rule.imports {
+                    if let AstImport::Sass(import) = import {
+                        loads.push((import.url.as_str(), import.span));
+                    }
+                }
+            }
+            _ => {
+                for block in stmt.child_blocks() {
+                    ensure_sufficient_stack(|| collect_loads(block, loads));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use url::Url;
+
+use crate::Fs;
+
+/// The most redirects followed while fetching a single URL
+const MAX_REDIRECTS: usize = 10;
+
+/// A response to an HTTP `GET` request, as returned by an [`HttpClient`]
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    status: u16,
+    location: Option<String>,
+    body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// A response with `status`, a `Location` header if it is a redirect, and
+    /// `body`
+    #[must_use]
+    pub fn new(status: u16, location: Option<String>, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            location,
+            body,
+        }
+    }
+}
+
+/// Performs the HTTP requests made by [`HttpFs`]
+///
+/// Clients should not follow redirects themselves, as [`HttpFs`] needs to know
+/// the final URL of a stylesheet to resolve the relative loads within it.
+///
+/// [`DefaultHttpClient`] supports both HTTP and HTTPS. This trait allows
+/// stylesheets to be loaded through another client, such as one configured
+/// with a proxy or credentials.
+pub trait HttpClient: fmt::Debug {
+    /// Send a `GET` request to `url`
+    ///
+    /// Errors should only be returned if no response was received. Responses
+    /// with error statuses are handled by [`HttpFs`].
+    fn get(&self, url: &str) -> io::Result<HttpResponse>;
+}
+
+/// An HTTP and HTTPS client, built on [`ureq`] and `rustls`
+#[derive(Debug)]
+pub struct DefaultHttpClient {
+    agent: ureq::Agent,
+}
+
+impl Default for DefaultHttpClient {
+    #[inline]
+    fn default() -> Self {
+        Self::with_timeout(Duration::from_secs(30))
+    }
+}
+
+impl DefaultHttpClient {
+    /// A client which waits at most `timeout` to connect, and for each read and
+    /// write
+    ///
+    /// By default, this is 30 seconds.
+    #[must_use]
+    pub fn with_timeout(timeout: Duration) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .redirects(0)
+            .timeout_connect(timeout)
+            .timeout_read(timeout)
+            .timeout_write(timeout)
+            .user_agent(concat!("grass/", env!("CARGO_PKG_VERSION")))
+            .build();
+
+        Self { agent }
+    }
+}
+
+impl HttpClient for DefaultHttpClient {
+    fn get(&self, url: &str) -> io::Result<HttpResponse> {
+        let response = match self
+            .agent
+            .get(url)
+            .set("Accept", "text/css, text/x-scss, text/x-sass, */*")
+            .call()
+        {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(e) => return Err(io::Error::other(e)),
+        };
+
+        let status = response.status();
+        let location = response.header("location").map(ToOwned::to_owned);
+
+        let mut body = Vec::new();
+        response.into_reader().read_to_end(&mut body)?;
+
+        Ok(HttpResponse::new(status, location, body))
+    }
+}
+
+/// The outcome of fetching a URL, including any redirects
+#[derive(Debug, Clone)]
+enum Fetched {
+    /// The final URL, after redirects, and the body of its response
+    Found {
+        url: String,
+        body: Vec<u8>,
+    },
+    NotFound,
+    /// No response was received, or the server responded with an error, so a
+    /// later request may succeed
+    Failed {
+        kind: io::ErrorKind,
+        message: String,
+    },
+}
+
+/// A [`Fs`] which loads `http://` and `https://` URLs, such as those of
+/// CDN-hosted design tokens, and sends every other path to another [`Fs`]
+///
+/// This allows `@use "https://example.com/theme.scss"`, along with the loads
+/// relative to it within that stylesheet. Like any other load, a URL without an
+/// extension is resolved by trying each Sass extension and partial, so giving
+/// the full URL avoids extra requests. Plain-CSS `@import`s of URLs are left
+/// as they are.
+///
+/// Redirects are followed, and relative loads are resolved against the final
+/// URL. Stylesheets, and URLs which were not found, are cached for the lifetime
+/// of the `HttpFs`, so each is only requested once, even across recompilations
+/// in a [`Compiler`](crate::Compiler). Responses with a status of 404 or 410
+/// are treated as missing files. Other failures, such as those to connect or
+/// responses with other statuses, are errors, and are forgotten once reported,
+/// so the URL is requested again the next time it is loaded.
+///
+/// This requires the `http` feature.
+///
+/// ```no_run
+/// # use grass_compiler as grass;
+/// fn main() -> Result<(), Box<grass::Error>> {
+///     let fs = grass::HttpFs::new(&grass::StdFs);
+///
+///     let css = grass::from_string(
+///         "@use 'https://example.com/theme.scss';".to_owned(),
+///         &grass::Options::default().fs(&fs),
+///     )?;
+///     Ok(())
+/// }
+/// ```
+pub struct HttpFs<'a> {
+    fs: &'a dyn Fs,
+    client: Box<dyn HttpClient + 'a>,
+    cache: RefCell<HashMap<String, Fetched>>,
+}
+
+impl fmt::Debug for HttpFs<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpFs")
+            .field("fs", &self.fs)
+            .field("client", &self.client)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> HttpFs<'a> {
+    /// Load URLs with [`DefaultHttpClient`], and every other path with `fs`
+    #[must_use]
+    pub fn new(fs: &'a dyn Fs) -> Self {
+        Self::with_client(fs, DefaultHttpClient::default())
+    }
+
+    /// Load URLs with `client`, and every other path with `fs`
+    #[must_use]
+    pub fn with_client(fs: &'a dyn Fs, client: impl HttpClient + 'a) -> Self {
+        Self {
+            fs,
+            client: Box::new(client),
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Forget every cached response, so that URLs are requested again the next
+    /// time they are loaded
+    pub fn clear_cache(&self) {
+        self.cache.borrow_mut().clear();
+    }
+
+    fn fetch(&self, url: &str) -> Fetched {
+        if let Some(fetched) = self.cache.borrow().get(url) {
+            return fetched.clone();
+        }
+
+        let fetched = self
+            .fetch_uncached(url)
+            .unwrap_or_else(|e| Fetched::Failed {
+                kind: e.kind(),
+                message: e.to_string(),
+            });
+
+        let mut cache = self.cache.borrow_mut();
+
+        match &fetched {
+            // the final url is what the stylesheet is read by
+            Fetched::Found { url: final_url, .. } => {
+                cache.insert(final_url.clone(), fetched.clone());
+                cache.insert(url.to_owned(), fetched.clone());
+            }
+            Fetched::NotFound | Fetched::Failed { .. } => {
+                cache.insert(url.to_owned(), fetched.clone());
+            }
+        }
+
+        fetched
+    }
+
+    fn fetch_uncached(&self, url: &str) -> io::Result<Fetched> {
+        let mut url = parse_url(url)?;
+
+        for _ in 0..=MAX_REDIRECTS {
+            let response = self.client.get(url.as_str())?;
+
+            match (response.status, response.location) {
+                (200, _) => {
+                    return Ok(Fetched::Found {
+                        url: url.into(),
+                        body: response.body,
+                    })
+                }
+                (301 | 302 | 303 | 307 | 308, Some(location)) => {
+                    url = url.join(&location).map_err(|_| invalid_url(&location))?;
+                    url.set_fragment(None);
+                }
+                (404 | 410, _) => return Ok(Fetched::NotFound),
+                (status, _) => {
+                    return Err(io::Error::other(format!(
+                        "{} responded with status {}.",
+                        url, status
+                    )))
+                }
+            }
+        }
+
+        Err(io::Error::other(format!(
+            "Too many redirects while loading {}.",
+            url
+        )))
+    }
+}
+
+/// The URL `path` represents, if it is one
+fn as_url(path: &Path) -> Option<&str> {
+    path.to_str().filter(|path| is_url(path))
+}
+
+/// Whether `path` is an `http://` or `https://` URL
+pub(crate) fn is_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+impl Fs for HttpFs<'_> {
+    fn is_dir(&self, path: &Path) -> bool {
+        as_url(path).is_none() && self.fs.is_dir(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        match as_url(path) {
+            // failures are reported when the file is read
+            Some(url) => !matches!(self.fetch(url), Fetched::NotFound),
+            None => self.fs.is_file(path),
+        }
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let url = match as_url(path) {
+            Some(url) => url,
+            None => return self.fs.read(path),
+        };
+
+        match self.fetch(url) {
+            Fetched::Found { body, .. } => Ok(body),
+            Fetched::NotFound => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} could not be found.", url),
+            )),
+            Fetched::Failed { kind, message } => {
+                // only kept until reported, so that the next load retries
+                self.cache.borrow_mut().remove(url);
+                Err(io::Error::new(kind, message))
+            }
+        }
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        let url = match as_url(path) {
+            Some(url) => url,
+            None => return self.fs.canonicalize(path),
+        };
+
+        match self.fetch(url) {
+            Fetched::Found { url, .. } => Ok(PathBuf::from(url)),
+            _ => Ok(path.to_path_buf()),
+        }
+    }
+}
+
+/// Parse `url`, which must be absolute, without its fragment, as fragments are
+/// never sent
+fn parse_url(url: &str) -> io::Result<Url> {
+    let mut parsed = Url::parse(url).map_err(|_| invalid_url(url))?;
+    parsed.set_fragment(None);
+
+    Ok(parsed)
+}
+
+fn invalid_url(url: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("{} is not a valid URL.", url),
+    )
+}
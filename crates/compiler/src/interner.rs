@@ -3,6 +3,10 @@ use lasso::{Rodeo, Spur};
 use std::cell::RefCell;
 use std::fmt::{self, Display};
 
+// Interned strings are never freed, so the interner of a thread grows with
+// every distinct string interned on it for as long as the thread lives. This
+// includes names written in stylesheets, such as variables, functions, units,
+// and properties, which are kept across compilations in long-running hosts.
 thread_local!(static STRINGS: RefCell<Rodeo<Spur>> = RefCell::new(Rodeo::default()));
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
@@ -0,0 +1,123 @@
+//! The JSON representation of diagnostics, serialized with `serde_json`
+//!
+//! Lines and columns are 1-indexed, matching the human-readable output.
+
+use std::{borrow::Cow, path::PathBuf};
+
+use codemap::SpanLoc;
+use indexmap::IndexMap;
+use serde::{Serialize, Serializer};
+
+use crate::StackFrame;
+
+/// Serialize `value` as a single line of JSON
+pub(crate) fn to_string<T: Serialize>(value: &T) -> String {
+    // these types have no maps with non-string keys, which is the only way
+    // serializing them could fail
+    serde_json::to_string(value).expect("diagnostics are always serializable")
+}
+
+#[derive(Serialize)]
+pub(crate) struct Span<'a> {
+    file: &'a str,
+    start: Position,
+    end: Position,
+}
+
+impl<'a> From<&'a SpanLoc> for Span<'a> {
+    fn from(loc: &'a SpanLoc) -> Self {
+        Self {
+            file: loc.file.name(),
+            start: Position {
+                line: loc.begin.line + 1,
+                column: loc.begin.column + 1,
+            },
+            end: Position {
+                line: loc.end.line + 1,
+                column: loc.end.column + 1,
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Frame<'a> {
+    member: &'a str,
+    span: Span<'a>,
+}
+
+pub(crate) fn trace(trace: &[StackFrame]) -> Vec<Frame<'_>> {
+    trace
+        .iter()
+        .map(|frame| Frame {
+            member: &frame.member,
+            span: Span::from(&frame.location),
+        })
+        .collect()
+}
+
+/// The form of [`Error::to_json`](crate::Error::to_json)
+#[derive(Serialize)]
+pub(crate) struct Error<'a> {
+    pub severity: &'static str,
+    pub message: &'a str,
+    pub span: Option<Span<'a>>,
+    pub trace: Vec<Frame<'a>>,
+}
+
+/// The form of [`Warning::to_json`](crate::Warning::to_json)
+#[derive(Serialize)]
+pub(crate) struct Warning<'a> {
+    pub severity: &'static str,
+    pub message: &'a str,
+    pub deprecation: Option<&'static str>,
+    pub lint: Option<&'static str>,
+    pub span: Span<'a>,
+    pub trace: Vec<Frame<'a>>,
+}
+
+/// The form of [`JsonLogger::debug_to_json`](crate::JsonLogger::debug_to_json)
+#[derive(Serialize)]
+pub(crate) struct Debug<'a> {
+    pub severity: &'static str,
+    pub message: &'a str,
+    pub span: Span<'a>,
+}
+
+/// The form of the count of omitted deprecation warnings written by
+/// [`JsonLogger`](crate::JsonLogger)
+#[derive(Serialize)]
+pub(crate) struct Omitted<'a> {
+    pub severity: &'static str,
+    pub message: &'a str,
+    pub omitted: usize,
+}
+
+/// The form of [`DependencyGraph::to_json`](crate::DependencyGraph::to_json)
+#[derive(Serialize)]
+pub(crate) struct DependencyGraph<'a> {
+    pub entrypoint: Cow<'a, str>,
+    pub dependencies: Dependencies<'a>,
+}
+
+/// Each file mapped to the files it loads, in order
+pub(crate) struct Dependencies<'a>(pub &'a IndexMap<PathBuf, Vec<PathBuf>>);
+
+impl Serialize for Dependencies<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(file, dependencies)| {
+            let dependencies: Vec<Cow<str>> = dependencies
+                .iter()
+                .map(|dependency| dependency.to_string_lossy())
+                .collect();
+
+            (file.to_string_lossy(), dependencies)
+        }))
+    }
+}
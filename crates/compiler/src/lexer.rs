@@ -1,6 +1,9 @@
-use std::{borrow::Cow, iter::Peekable, str::Chars, sync::Arc};
+use std::{borrow::Cow, sync::Arc};
 
 use codemap::{File, Span};
+use memchr::{memchr, memchr2, memchr3};
+
+use crate::ast::InterpolationMap;
 
 const FORM_FEED: char = '\x0C';
 
@@ -10,45 +13,103 @@ pub(crate) struct Token {
     pos: u32,
 }
 
+/// A cursor over the characters of a source text
+///
+/// Tokens are decoded from the source as they are read, rather than being
+/// collected up front. `\r\n`, `\r`, and form feeds are normalized to a single
+/// `\n` token which begins at the start of the line break. Cursor positions
+/// are byte offsets into the source.
 #[derive(Debug, Clone)]
-// todo: remove lifetime as Cow is now superfluous
 pub(crate) struct Lexer<'a> {
-    buf: Cow<'a, [Token]>,
+    source: Cow<'a, str>,
     entire_span: Span,
     cursor: usize,
     /// If the input this lexer is spanned over is larger than the original span.
     /// This is possible due to interpolation.
     is_expanded: bool,
+    /// If this lexer is spanned over the evaluated text of an interpolation, the
+    /// mapping from that text back to the original source
+    interpolation_map: Option<InterpolationMap>,
 }
 
 impl<'a> Lexer<'a> {
     pub fn raw_text(&self, start: usize) -> String {
-        self.buf[start..self.cursor]
-            .iter()
-            .map(|t| t.kind)
-            .collect()
+        let text = &self.source[start..self.cursor];
+
+        if !text.contains(['\r', FORM_FEED]) {
+            return text.to_owned();
+        }
+
+        let mut raw = String::with_capacity(text.len());
+        let mut pos = start;
+
+        while let Some((tok, len)) = self.token_at(pos).filter(|_| pos < self.cursor) {
+            raw.push(tok.kind);
+            pos += len;
+        }
+
+        raw
     }
 
     pub fn next_char_is(&self, c: char) -> bool {
         matches!(self.peek(), Some(Token { kind, .. }) if kind == c)
     }
 
+    /// The token beginning at the byte offset `pos`, along with its length in
+    /// bytes
+    fn token_at(&self, pos: usize) -> Option<(Token, usize)> {
+        let c = self.source[pos..].chars().next()?;
+
+        let (kind, len) = match c {
+            FORM_FEED => ('\n', 1),
+            '\r' if self.source.as_bytes().get(pos + 1) == Some(&b'\n') => ('\n', 2),
+            '\r' => ('\n', 1),
+            c => (c, c.len_utf8()),
+        };
+
+        Some((
+            Token {
+                kind,
+                pos: pos as u32,
+            },
+            len,
+        ))
+    }
+
+    /// The byte offset of the token ending at the byte offset `pos`
+    fn prev_token_start(&self, pos: usize) -> Option<usize> {
+        let c = self.source[..pos].chars().next_back()?;
+        let start = pos - c.len_utf8();
+
+        if c == '\n' && self.source[..start].ends_with('\r') {
+            Some(start - 1)
+        } else {
+            Some(start)
+        }
+    }
+
     /// Gets the span of the character at the given index. If the index is out of
     /// bounds, it returns the span of the last character. If the input is empty,
     /// it returns an empty span
     fn span_at_index(&self, idx: usize) -> Span {
-        if self.is_expanded {
+        if self.is_expanded && self.interpolation_map.is_none() {
             return self.entire_span;
         }
 
-        let (start, len) = match self.buf.get(idx) {
-            Some(tok) => (tok.pos, tok.kind.len_utf8()),
-            None => match self.buf.last() {
-                Some(tok) => (tok.pos, tok.kind.len_utf8()),
-                None => (0, 0),
-            },
+        let tok = self.token_at(idx).or_else(|| {
+            self.prev_token_start(self.source.len())
+                .and_then(|pos| self.token_at(pos))
+        });
+
+        let (start, len) = match tok {
+            Some((tok, ..)) => (tok.pos, tok.kind.len_utf8()),
+            None => (0, 0),
         };
 
+        if let Some(map) = &self.interpolation_map {
+            return map.span_for(start as usize, len);
+        }
+
         self.entire_span
             .subspan(start as u64, start as u64 + len as u64)
     }
@@ -61,7 +122,7 @@ impl<'a> Lexer<'a> {
     }
 
     pub fn prev_span(&self) -> Span {
-        self.span_at_index(self.cursor.saturating_sub(1))
+        self.span_at_index(self.prev_token_start(self.cursor).unwrap_or(0))
     }
 
     pub fn current_span(&self) -> Span {
@@ -69,22 +130,34 @@ impl<'a> Lexer<'a> {
     }
 
     pub fn peek(&self) -> Option<Token> {
-        self.buf.get(self.cursor).copied()
+        self.token_at(self.cursor).map(|(tok, ..)| tok)
     }
 
     /// Peeks the previous token without modifying the peek cursor
     pub fn peek_previous(&mut self) -> Option<Token> {
-        self.buf.get(self.cursor.checked_sub(1)?).copied()
+        self.peek_n_backwards(1)
     }
 
     /// Peeks `n` from current peeked position without modifying cursor
     pub fn peek_n(&self, n: usize) -> Option<Token> {
-        self.buf.get(self.cursor + n).copied()
+        let mut pos = self.cursor;
+
+        for _ in 0..n {
+            pos += self.token_at(pos)?.1;
+        }
+
+        self.token_at(pos).map(|(tok, ..)| tok)
     }
 
     /// Peeks `n` behind current peeked position without modifying cursor
     pub fn peek_n_backwards(&self, n: usize) -> Option<Token> {
-        self.buf.get(self.cursor.checked_sub(n)?).copied()
+        let mut pos = self.cursor;
+
+        for _ in 0..n {
+            pos = self.prev_token_start(pos)?;
+        }
+
+        self.token_at(pos).map(|(tok, ..)| tok)
     }
 
     /// Set cursor to position and reset peek
@@ -95,81 +168,105 @@ impl<'a> Lexer<'a> {
     pub fn cursor(&self) -> usize {
         self.cursor
     }
-}
 
-impl<'a> Iterator for Lexer<'a> {
-    type Item = Token;
+    /// Advance the cursor to the next byte in `needles`, or to the end of the
+    /// input if there is none, returning the text that was passed over
+    ///
+    /// Every needle must be ASCII, so that the cursor always lands on a
+    /// character boundary. Because line breaks are normalized, `needles` must
+    /// contain either all or none of `\n`, `\r`, and form feed.
+    pub fn take_until(&mut self, needles: &[u8]) -> &str {
+        debug_assert!(needles.is_ascii());
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.buf.get(self.cursor).copied().map(|tok| {
-            self.cursor += 1;
-            tok
-        })
-    }
+        let start = self.cursor;
+        let rest = &self.source.as_bytes()[start..];
+        self.cursor += find_any(rest, needles).unwrap_or(rest.len());
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = self.buf.len() - self.cursor;
-        (remaining, Some(remaining))
+        &self.source[start..self.cursor]
     }
-}
 
-/// Lex a string into a series of tokens
-pub(crate) struct TokenLexer<'a> {
-    buf: Peekable<Chars<'a>>,
-    cursor: u32,
+    /// Advance the cursor past every byte for which `pred` returns true,
+    /// returning the text that was passed over
+    ///
+    /// `pred` may only accept ASCII bytes, and must accept either all or none of
+    /// `\n`, `\r`, and form feed.
+    pub fn take_while(&mut self, pred: impl Fn(u8) -> bool) -> &str {
+        let start = self.cursor;
+        let rest = &self.source.as_bytes()[start..];
+        self.cursor += rest.iter().position(|&b| !pred(b)).unwrap_or(rest.len());
+
+        debug_assert!(self.source[start..self.cursor].is_ascii());
+
+        &self.source[start..self.cursor]
+    }
 }
 
-// todo: maybe char indices?
-impl<'a> TokenLexer<'a> {
-    pub fn new(buf: Peekable<Chars<'a>>) -> TokenLexer<'a> {
-        Self { buf, cursor: 0 }
+/// The index of the first byte in `haystack` that is one of `needles`
+fn find_any(haystack: &[u8], needles: &[u8]) -> Option<usize> {
+    match *needles {
+        [] => None,
+        [a] => memchr(a, haystack),
+        [a, b] => memchr2(a, b, haystack),
+        [a, b, c] => memchr3(a, b, c, haystack),
+        [a, b, c, ref rest @ ..] => {
+            // only the text before the first of the first three needles can
+            // contain an earlier match for the rest
+            let end = memchr3(a, b, c, haystack);
+            find_any(&haystack[..end.unwrap_or(haystack.len())], rest).or(end)
+        }
     }
 }
 
-impl<'a> Iterator for TokenLexer<'a> {
+impl<'a> Iterator for Lexer<'a> {
     type Item = Token;
+
     fn next(&mut self) -> Option<Self::Item> {
-        let kind = match self.buf.next()? {
-            FORM_FEED => '\n',
-            '\r' => {
-                if self.buf.peek() == Some(&'\n') {
-                    self.cursor += 1;
-                    self.buf.next();
-                }
-                '\n'
-            }
-            c => c,
-        };
-        let len = kind.len_utf8() as u32;
-        let pos = self.cursor;
+        let (tok, len) = self.token_at(self.cursor)?;
         self.cursor += len;
-        Some(Token { pos, kind })
+        Some(tok)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        self.buf.size_hint()
+        // every token is between 1 and 4 bytes long
+        let remaining = self.source.len() - self.cursor;
+        (remaining.div_ceil(4), Some(remaining))
     }
 }
 
 impl<'a> Lexer<'a> {
-    pub fn new_from_file(file: &Arc<File>) -> Self {
-        let buf = TokenLexer::new(file.source().chars().peekable()).collect();
-        Self::new(buf, file.span, false)
+    pub fn new_from_file(file: &'a Arc<File>) -> Self {
+        Self::new(Cow::Borrowed(file.source()), file.span, false)
     }
 
-    pub fn new_from_string(s: &str, entire_span: Span) -> Self {
+    pub fn new_from_string(s: impl Into<Cow<'a, str>>, entire_span: Span) -> Self {
+        let s = s.into();
         let is_expanded = s.len() as u64 > entire_span.len();
-        let buf = TokenLexer::new(s.chars().peekable()).collect();
 
-        Self::new(buf, entire_span, is_expanded)
+        Self::new(s, entire_span, is_expanded)
+    }
+
+    /// Lex text that was computed at runtime rather than read from a stylesheet,
+    /// such as the contents of a string passed to a function, so every span is
+    /// `entire_span`
+    pub fn new_unmapped(s: impl Into<Cow<'a, str>>, entire_span: Span) -> Self {
+        Self::new(s.into(), entire_span, true)
+    }
+
+    /// Lex the evaluated text of an interpolation, mapping spans back to the
+    /// source the interpolation was parsed from
+    pub fn new_from_interpolation(s: &'a str, map: InterpolationMap) -> Self {
+        let mut lexer = Self::new_from_string(s, map.span());
+        lexer.interpolation_map = Some(map);
+        lexer
     }
 
-    fn new(buf: Vec<Token>, entire_span: Span, is_expanded: bool) -> Self {
+    fn new(source: Cow<'a, str>, entire_span: Span, is_expanded: bool) -> Self {
         Lexer {
-            buf: Cow::Owned(buf),
+            source,
             cursor: 0,
             entire_span,
             is_expanded,
+            interpolation_map: None,
         }
     }
 }
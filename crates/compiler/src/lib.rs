@@ -65,25 +65,46 @@ grass input.scss
 
     unknown_lints,
 )]
+// helpers shared by the builtin functions go unused when some are disabled
+#![cfg_attr(not(feature = "builtin-fns"), allow(dead_code, unused_imports))]
 
-use std::path::Path;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use parse::{CssParser, SassParser, StylesheetParser};
-use sass_ast::StyleSheet;
+use sass_ast::{AstStmt, StyleSheet};
 use serializer::Serializer;
 #[cfg(feature = "wasm-exports")]
 use wasm_bindgen::prelude::*;
 
 use codemap::CodeMap;
 
+pub use crate::cache::ParseCache;
+pub use crate::compiler::Compiler;
+pub use crate::deprecation::Deprecation;
 pub use crate::error::{
-    PublicSassErrorKind as ErrorKind, SassError as Error, SassResult as Result,
+    Label, PublicSassErrorKind as ErrorKind, SassError as Error, SassResult as Result,
 };
 pub use crate::fs::{Fs, NullFs, StdFs};
+pub use crate::graph::{dependency_graph, DependencyGraph};
+#[cfg(feature = "http")]
+pub use crate::http::{DefaultHttpClient, HttpClient, HttpFs, HttpResponse};
+pub use crate::lint::Lint;
+#[cfg(feature = "json")]
+pub use crate::logger::JsonLogger;
+pub use crate::logger::{Logger, NullLogger, StackFrame, StdLogger, Warning};
 pub use crate::options::{InputSyntax, Options, OutputStyle};
+#[cfg(feature = "watch")]
+pub use crate::watch::{CompileResult, WatchOptions};
+use crate::{
+    ast::CssStmt, cache::LentMap, common::Identifier, evaluate::ModuleCache, lexer::Lexer,
+    parse::ScssParser,
+};
 pub use crate::{builtin::Builtin, evaluate::Visitor};
 pub(crate) use crate::{context_flags::ContextFlags, lexer::Token};
-use crate::{lexer::Lexer, parse::ScssParser};
 
 pub mod sass_value {
     pub use crate::{
@@ -104,27 +125,63 @@ pub mod sass_ast {
 
 pub use codemap;
 
+/// Used by the Node.js bindings to call functions defined in JavaScript, and
+/// not part of the stable API
+#[doc(hidden)]
+#[cfg(feature = "custom-builtin-fns")]
+pub mod __internal {
+    pub use crate::utils::signature::{bind_arguments, parse_signature, Parameter};
+
+    use crate::Visitor;
+
+    /// The name of the custom function `visitor` is currently calling
+    pub fn current_builtin<'a>(visitor: &'a Visitor<'_>) -> Option<&'a str> {
+        visitor.current_builtin.as_ref().map(|name| name.as_str())
+    }
+}
+
+pub mod build_helper;
+#[cfg(feature = "plugins")]
+pub mod plugin;
+
 mod ast;
 mod builtin;
+mod cache;
 mod color;
 mod common;
+mod compiler;
 mod context_flags;
+mod deprecation;
 mod error;
 mod evaluate;
 mod fs;
+mod graph;
+#[cfg(feature = "http")]
+mod http;
 mod interner;
+#[cfg(feature = "json")]
+mod json;
 mod lexer;
+mod lint;
+mod logger;
+#[cfg(feature = "pkg-importer")]
+mod node_package;
 mod options;
 mod parse;
+#[cfg(feature = "json")]
+mod postcss;
 mod selector;
 mod serializer;
 mod unit;
 mod utils;
 mod value;
+#[cfg(feature = "wasm-exports")]
+mod wasm;
+#[cfg(feature = "watch")]
+mod watch;
 
 fn raw_to_parse_error(map: &CodeMap, err: Error, unicode: bool) -> Box<Error> {
-    let (message, span) = err.raw();
-    Box::new(Error::from_loc(message, map.look_up_span(span), unicode))
+    Box::new(err.into_parse_error(map, unicode))
 }
 
 pub fn parse_stylesheet<P: AsRef<Path>>(
@@ -132,38 +189,45 @@ pub fn parse_stylesheet<P: AsRef<Path>>(
     file_name: P,
     options: &Options,
 ) -> Result<StyleSheet> {
-    // todo: much of this logic is duplicated in `from_string_with_file_name`
     let mut map = CodeMap::new();
     let path = file_name.as_ref();
     let file = map.add_file(path.to_string_lossy().into_owned(), input);
-    let empty_span = file.span.subspan(0, 0);
-    let lexer = Lexer::new_from_file(&file);
-
-    let input_syntax = options
-        .input_syntax
-        .unwrap_or_else(|| InputSyntax::for_path(path));
 
-    let stylesheet = match input_syntax {
-        InputSyntax::Scss => {
-            ScssParser::new(lexer, &mut map, options, empty_span, file_name.as_ref()).__parse()
-        }
-        InputSyntax::Sass => {
-            SassParser::new(lexer, &mut map, options, empty_span, file_name.as_ref()).__parse()
-        }
-        InputSyntax::Css => {
-            CssParser::new(lexer, &mut map, options, empty_span, file_name.as_ref()).__parse()
-        }
-    };
-
-    let stylesheet = match stylesheet {
-        Ok(v) => v,
-        Err(e) => return Err(raw_to_parse_error(&map, *e, options.unicode_error_messages)),
-    };
-
-    Ok(stylesheet)
+    parse_file(&file, &mut map, path, options, false).map_err(|mut errors| {
+        raw_to_parse_error(&map, errors.swap_remove(0), options.unicode_error_messages)
+    })
 }
 
-fn from_string_with_file_name<P: AsRef<Path>>(
+/// Parse Sass into a JSON representation of its syntax tree, without
+/// evaluating it
+///
+/// This is intended for linters, formatters, and other tools written in
+/// languages which cannot use grass's AST directly. The tree is serialized
+/// with `serde`: every statement and expression is an object whose `type` names
+/// its kind, such as `"RuleSet"`, `"Style"`, or `"BinaryOp"`, and whose `value`
+/// holds its fields. Spans have a `file` and the 1-indexed `line` and `column`
+/// of their `start` and `end`, and nodes stored alongside a span are written as
+/// `{"node": ..., "span": ...}`. Interpolation, such as a selector, is an array of
+/// strings and spanned expressions. The format follows grass's AST, so it is
+/// not yet stable, and may change between releases as the AST does.
+///
+/// This requires the `ast-json` feature.
+///
+/// ```
+/// # use grass_compiler as grass;
+/// fn main() -> Result<(), Box<grass::Error>> {
+///     let json = grass::parse_to_json(
+///         "a { b: 1 + 2 }".to_owned(),
+///         "input.scss",
+///         &grass::Options::default(),
+///     )?;
+///
+///     assert!(json.starts_with(r#"{"type":"StyleSheet","body":[{"type":"RuleSet""#));
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "ast-json")]
+pub fn parse_to_json<P: AsRef<Path>>(
     input: String,
     file_name: P,
     options: &Options,
@@ -171,58 +235,205 @@ fn from_string_with_file_name<P: AsRef<Path>>(
     let mut map = CodeMap::new();
     let path = file_name.as_ref();
     let file = map.add_file(path.to_string_lossy().into_owned(), input);
+
+    let stylesheet = parse_file(&file, &mut map, path, options, false).map_err(|mut errors| {
+        raw_to_parse_error(&map, errors.swap_remove(0), options.unicode_error_messages)
+    })?;
+
+    Ok(ast::to_json(&stylesheet, &file))
+}
+
+/// Parse the entrypoint `file`
+///
+/// If `recover` is set, the parser will continue past syntax errors at statement
+/// boundaries and return every error it encountered. Otherwise, only the first
+/// error is returned. The returned errors have not yet been resolved against `map`
+fn parse_file(
+    file: &Arc<codemap::File>,
+    map: &mut CodeMap,
+    path: &Path,
+    options: &Options,
+    recover: bool,
+) -> std::result::Result<StyleSheet, Vec<Error>> {
     let empty_span = file.span.subspan(0, 0);
-    let lexer = Lexer::new_from_file(&file);
+    let lexer = Lexer::new_from_file(file);
 
     let input_syntax = options
         .input_syntax
         .unwrap_or_else(|| InputSyntax::for_path(path));
 
-    let stylesheet = match input_syntax {
+    let (stylesheet, mut errors) = match input_syntax {
         InputSyntax::Scss => {
-            ScssParser::new(lexer, &mut map, options, empty_span, file_name.as_ref()).__parse()
-        }
-        InputSyntax::Sass => {
-            SassParser::new(lexer, &mut map, options, empty_span, file_name.as_ref()).__parse()
+            let mut parser = ScssParser::new(lexer, map, options, empty_span, path);
+            if recover {
+                parser.recovered_errors = Some(Vec::new());
+            }
+            let stylesheet = parser.__parse();
+            (stylesheet, parser.recovered_errors.unwrap_or_default())
         }
+        // the indented syntax relies on indentation to find statement
+        // boundaries, so we do not attempt to recover from errors there
+        InputSyntax::Sass => (
+            SassParser::new(lexer, map, options, empty_span, path).__parse(),
+            Vec::new(),
+        ),
         InputSyntax::Css => {
-            CssParser::new(lexer, &mut map, options, empty_span, file_name.as_ref()).__parse()
+            let mut parser = CssParser::new(lexer, map, options, empty_span, path);
+            if recover {
+                parser.recovered_errors = Some(Vec::new());
+            }
+            let stylesheet = parser.__parse();
+            (stylesheet, parser.recovered_errors.unwrap_or_default())
         }
     };
 
-    let stylesheet = match stylesheet {
-        Ok(v) => v,
-        Err(e) => return Err(raw_to_parse_error(&map, *e, options.unicode_error_messages)),
-    };
+    match stylesheet {
+        Ok(stylesheet) if errors.is_empty() => Ok(stylesheet),
+        Ok(..) => Err(errors),
+        Err(e) => {
+            errors.push(*e);
+            Err(errors)
+        }
+    }
+}
+
+/// Parse each variable passed to [`Options::define`] as a stylesheet containing
+/// only its declaration, to be evaluated before the entrypoint
+fn parse_defined_variables(map: &mut CodeMap, options: &Options) -> Result<Vec<StyleSheet>> {
+    let mut stylesheets = Vec::new();
+
+    for (name, value) in &options.defined_variables {
+        let path = Path::new("define");
+        let file = map.add_file(
+            path.to_string_lossy().into_owned(),
+            format!("${}: {};", name, value),
+        );
+        let empty_span = file.span.subspan(0, 0);
+
+        let stylesheet =
+            ScssParser::new(Lexer::new_from_file(&file), map, options, empty_span, path)
+                .__parse()?;
+
+        // reject values which close the declaration and continue with other
+        // statements
+        match stylesheet.body.as_slice() {
+            [AstStmt::VariableDecl(decl)] if decl.name == Identifier::from(name) => {}
+            _ => return Err(("Expected a single variable declaration.", file.span).into()),
+        }
 
-    let mut visitor = Visitor::new(path, options, &mut map, empty_span);
-    match visitor.visit_stylesheet(stylesheet) {
-        Ok(_) => {}
-        Err(e) => return Err(raw_to_parse_error(&map, *e, options.unicode_error_messages)),
+        stylesheets.push(stylesheet);
     }
-    let stmts = visitor.finish();
 
-    let mut serializer = Serializer::new(options, &map, false, empty_span);
+    Ok(stylesheets)
+}
+
+/// If `loaded_files` is provided, every file loaded during evaluation is added
+/// to it along with the hash of its contents, even if evaluation fails
+fn from_string_with_file_name<P: AsRef<Path>>(
+    input: String,
+    file_name: P,
+    options: &Options,
+    recover: bool,
+    loaded_files: Option<&mut BTreeMap<PathBuf, u64>>,
+    module_cache: Option<&ModuleCache>,
+) -> std::result::Result<String, Vec<Error>> {
+    evaluate_with_file_name(
+        input,
+        file_name,
+        options,
+        recover,
+        loaded_files,
+        module_cache,
+        |stmts, map, empty_span| {
+            let mut serializer = Serializer::new(options, map, false, empty_span);
+
+            let mut prev_was_group_end = false;
+            let mut prev_requires_semicolon = false;
+            for stmt in stmts {
+                if stmt.is_invisible(options) {
+                    continue;
+                }
+
+                let is_group_end = stmt.is_group_end();
+                let requires_semicolon = Serializer::requires_semicolon(&stmt);
+
+                serializer.visit_group(stmt, prev_was_group_end, prev_requires_semicolon)?;
+
+                prev_was_group_end = is_group_end;
+                prev_requires_semicolon = requires_semicolon;
+            }
+
+            Ok(serializer.finish(prev_requires_semicolon))
+        },
+    )
+}
+
+/// Evaluate `input`, and pass the resulting CSS tree to `write` to be
+/// serialized
+fn evaluate_with_file_name<P: AsRef<Path>>(
+    input: String,
+    file_name: P,
+    options: &Options,
+    recover: bool,
+    loaded_files: Option<&mut BTreeMap<PathBuf, u64>>,
+    module_cache: Option<&ModuleCache>,
+    write: impl FnOnce(Vec<CssStmt>, &CodeMap, codemap::Span) -> Result<String>,
+) -> std::result::Result<String, Vec<Error>> {
+    // stylesheets in the parse cache refer to locations in its map of sources,
+    // so we add our own files to that map when a cache is in use
+    let (mut map, parse_cache_snapshot) = match options.parse_cache {
+        Some(cache) => {
+            let (map, snapshot) = cache.lend();
+            (map, Some(snapshot))
+        }
+        None => (LentMap::from(CodeMap::new()), None),
+    };
+    let map: &mut CodeMap = &mut map;
+
+    let path = file_name.as_ref();
+    let file = map.add_file(path.to_string_lossy().into_owned(), input);
+    let empty_span = file.span.subspan(0, 0);
 
-    let mut prev_was_group_end = false;
-    let mut prev_requires_semicolon = false;
-    for stmt in stmts {
-        if stmt.is_invisible() {
-            continue;
+    let stylesheet = match parse_file(&file, map, path, options, recover) {
+        Ok(v) => v,
+        Err(errors) => {
+            return Err(errors
+                .into_iter()
+                .map(|e| *raw_to_parse_error(map, e, options.unicode_error_messages))
+                .collect())
         }
+    };
 
-        let is_group_end = stmt.is_group_end();
-        let requires_semicolon = Serializer::requires_semicolon(&stmt);
+    let to_error = |map: &CodeMap, e: Box<Error>| {
+        vec![*raw_to_parse_error(map, *e, options.unicode_error_messages)]
+    };
 
-        serializer
-            .visit_group(stmt, prev_was_group_end, prev_requires_semicolon)
-            .map_err(|e| raw_to_parse_error(&map, *e, options.unicode_error_messages))?;
+    let defined_variables = parse_defined_variables(map, options).map_err(|e| to_error(map, e))?;
+
+    let mut visitor = Visitor::new(path, options, map, empty_span);
+    let result = if stylesheet.is_plain_css {
+        // plain CSS can't refer to variables or load modules, so neither the
+        // defined variables nor the module cache are needed
+        visitor.visit_plain_css_stylesheet(&stylesheet)
+    } else {
+        visitor.parse_cache_snapshot = parse_cache_snapshot;
+        visitor.module_cache = module_cache;
+        defined_variables
+            .iter()
+            .try_for_each(|stylesheet| visitor.visit_stylesheet(stylesheet))
+            .and_then(|()| visitor.visit_stylesheet(&stylesheet))
+    };
 
-        prev_was_group_end = is_group_end;
-        prev_requires_semicolon = requires_semicolon;
+    if let Some(loaded_files) = loaded_files {
+        loaded_files.extend(visitor.take_loaded_files());
     }
 
-    Ok(serializer.finish(prev_requires_semicolon))
+    if let Err(e) = result {
+        return Err(to_error(map, e));
+    }
+    let stmts = visitor.finish();
+
+    write(stmts, map, empty_span).map_err(|e| to_error(map, e))
 }
 
 /// Compile CSS from a path
@@ -238,7 +449,49 @@ fn from_string_with_file_name<P: AsRef<Path>>(
 /// ```
 #[inline]
 pub fn from_path<P: AsRef<Path>>(p: P, options: &Options) -> Result<String> {
-    from_string_with_file_name(String::from_utf8(options.fs.read(p.as_ref())?)?, p, options)
+    from_string_with_file_name(
+        String::from_utf8(options.fs.read(p.as_ref())?)?,
+        p,
+        options,
+        false,
+        None,
+        None,
+    )
+    .map_err(|mut errors| Box::new(errors.swap_remove(0)))
+}
+
+/// Compile CSS from a path, reporting every syntax error rather than stopping
+/// at the first one
+///
+/// The parser recovers from errors at statement boundaries and keeps going, so
+/// that all diagnostics can be shown in a single pass. If any syntax errors were
+/// found, the stylesheet is not evaluated and every error is returned. Otherwise,
+/// the stylesheet is compiled as with [`from_path`], and any error encountered
+/// during evaluation is returned on its own.
+///
+/// Error recovery is only supported for the SCSS and CSS syntaxes. Files using
+/// the indented syntax stop at the first error.
+///
+/// ```
+/// # use grass_compiler as grass;
+/// fn main() {
+///     let errors = grass::from_path_with_recovery("input.scss", &grass::Options::default());
+/// }
+/// ```
+#[inline]
+pub fn from_path_with_recovery<P: AsRef<Path>>(
+    p: P,
+    options: &Options,
+) -> std::result::Result<String, Vec<Box<Error>>> {
+    let input = options
+        .fs
+        .read(p.as_ref())
+        .map_err(Box::<Error>::from)
+        .and_then(|bytes| Ok(String::from_utf8(bytes)?))
+        .map_err(|e| vec![e])?;
+
+    from_string_with_file_name(input, p, options, true, None, None)
+        .map_err(|errors| errors.into_iter().map(Box::new).collect())
 }
 
 /// Compile CSS from a string
@@ -253,7 +506,77 @@ pub fn from_path<P: AsRef<Path>>(p: P, options: &Options) -> Result<String> {
 /// ```
 #[inline]
 pub fn from_string<S: Into<String>>(input: S, options: &Options) -> Result<String> {
-    from_string_with_file_name(input.into(), "stdin", options)
+    from_string_with_file_name(input.into(), "stdin", options, false, None, None)
+        .map_err(|mut errors| Box::new(errors.swap_remove(0)))
+}
+
+/// Compile CSS from a string, reporting every syntax error rather than stopping
+/// at the first one
+///
+/// See [`from_path_with_recovery`] for details.
+///
+/// ```
+/// # use grass_compiler as grass;
+/// fn main() {
+///     let errors = grass::from_string_with_recovery(
+///         "a { color: ; } b { color: red; } c { width: (; }".to_string(),
+///         &grass::Options::default(),
+///     )
+///     .unwrap_err();
+///     assert_eq!(errors.len(), 2);
+/// }
+/// ```
+#[inline]
+pub fn from_string_with_recovery<S: Into<String>>(
+    input: S,
+    options: &Options,
+) -> std::result::Result<String, Vec<Box<Error>>> {
+    from_string_with_file_name(input.into(), "stdin", options, true, None, None)
+        .map_err(|errors| errors.into_iter().map(Box::new).collect())
+}
+
+/// Compile Sass into a tree of PostCSS nodes, serialized as JSON
+///
+/// This lets grass slot into JavaScript post-processing pipelines without the
+/// output being reparsed: the result is in the format of PostCSS's
+/// `Node#toJSON()`, and can be turned back into a `Root` with
+/// `postcss.fromJSON(JSON.parse(json))`. Rules, declarations, at-rules, and
+/// comments keep the whitespace grass writes for the current [`OutputStyle`]
+/// in their `raws`, so stringifying the tree gives the same CSS as
+/// [`from_string`], except that compressed output has no byte-order mark.
+/// Nodes do not have a `source`.
+///
+/// ```
+/// # use grass_compiler as grass;
+/// fn main() -> Result<(), Box<grass::Error>> {
+///     let json = grass::to_postcss_json(
+///         "a { b: 1 + 2 }".to_owned(),
+///         "input.scss",
+///         &grass::Options::default(),
+///     )?;
+///
+///     assert!(json.starts_with(r#"{"type":"root","nodes":[{"type":"rule","selector":"a""#));
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "json")]
+pub fn to_postcss_json<P: AsRef<Path>>(
+    input: String,
+    file_name: P,
+    options: &Options,
+) -> Result<String> {
+    evaluate_with_file_name(
+        input,
+        file_name,
+        options,
+        false,
+        None,
+        None,
+        |stmts, map, empty_span| {
+            postcss::PostCssSerializer::new(options, map, empty_span).write_root(&stmts)
+        },
+    )
+    .map_err(|mut errors| Box::new(errors.swap_remove(0)))
 }
 
 #[cfg(feature = "wasm-exports")]
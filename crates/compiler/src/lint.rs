@@ -0,0 +1,63 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// A check for a construct that is valid Sass, but is likely to be a mistake
+///
+/// Lints are not run by default. Each lint may be enabled individually using
+/// [`Options::lint`](crate::Options::lint), after which it is reported as a
+/// [`Warning`](crate::Warning) tagged with the lint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Lint {
+    /// Zero lengths with a unit, such as `0px`, which may be written as `0`
+    ZeroUnit,
+    /// Numbers with units that are not known to CSS, such as `10pz`
+    UnknownUnit,
+    /// Declarations that are not inside a style rule, such as those directly
+    /// inside an unknown at-rule
+    DeclarationOutsideRule,
+    /// `!important` used inside a mixin, which applies it to every style rule
+    /// that includes the mixin
+    ImportantInMixin,
+}
+
+impl Lint {
+    /// Every known lint
+    pub const ALL: &'static [Lint] = &[
+        Lint::ZeroUnit,
+        Lint::UnknownUnit,
+        Lint::DeclarationOutsideRule,
+        Lint::ImportantInMixin,
+    ];
+
+    /// The stable ID of this lint, for example `zero-unit`
+    #[must_use]
+    pub const fn id(self) -> &'static str {
+        match self {
+            Lint::ZeroUnit => "zero-unit",
+            Lint::UnknownUnit => "unknown-unit",
+            Lint::DeclarationOutsideRule => "declaration-outside-rule",
+            Lint::ImportantInMixin => "important-in-mixin",
+        }
+    }
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl FromStr for Lint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lint::ALL
+            .iter()
+            .copied()
+            .find(|lint| lint.id() == s)
+            .ok_or_else(|| format!("Invalid lint \"{}\".", s))
+    }
+}
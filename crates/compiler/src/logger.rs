@@ -0,0 +1,263 @@
+use std::fmt::{self, Debug, Display};
+
+use codemap::SpanLoc;
+
+#[cfg(feature = "json")]
+use crate::json;
+use crate::{Deprecation, Lint};
+
+/// A single frame of the stack trace attached to a [`Warning`]
+#[derive(Debug, Clone)]
+pub struct StackFrame {
+    pub(crate) location: SpanLoc,
+    pub(crate) member: String,
+}
+
+impl StackFrame {
+    /// The location of this frame. For the innermost frame, this is the
+    /// location the warning was emitted from. For all other frames, it is the
+    /// location of the `@include`, function call, or `@import` that led to the
+    /// next frame
+    #[must_use]
+    pub const fn location(&self) -> &SpanLoc {
+        &self.location
+    }
+
+    /// The name of the member containing this frame's location, for example
+    /// `foo()` for a mixin or function named `foo`, or `root stylesheet` at the
+    /// top level
+    #[must_use]
+    pub fn member(&self) -> &str {
+        &self.member
+    }
+}
+
+impl Display for StackFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "./{}:{}:{}  {}",
+            self.location.file.name(),
+            self.location.begin.line + 1,
+            self.location.begin.column + 1,
+            self.member
+        )
+    }
+}
+
+/// A warning emitted during compilation, either by the `@warn` rule or by
+/// `grass` itself
+#[derive(Debug, Clone)]
+pub struct Warning {
+    pub(crate) message: String,
+    pub(crate) location: SpanLoc,
+    pub(crate) trace: Vec<StackFrame>,
+    pub(crate) deprecation: Option<Deprecation>,
+    pub(crate) lint: Option<Lint>,
+}
+
+impl Warning {
+    /// The text of the warning, without any location information
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The location the warning was emitted from
+    #[must_use]
+    pub const fn location(&self) -> &SpanLoc {
+        &self.location
+    }
+
+    /// The stack of mixin includes, function calls, and imports that were
+    /// being evaluated when the warning was emitted, innermost first
+    ///
+    /// The first frame always points to [`Warning::location`].
+    #[must_use]
+    pub fn trace(&self) -> &[StackFrame] {
+        &self.trace
+    }
+
+    /// The deprecation this warning is for, if any
+    #[must_use]
+    pub const fn deprecation(&self) -> Option<Deprecation> {
+        self.deprecation
+    }
+
+    /// The lint that emitted this warning, if any
+    #[must_use]
+    pub const fn lint(&self) -> Option<Lint> {
+        self.lint
+    }
+
+    /// Serialize this warning as a single-line JSON object, suitable for
+    /// consumption by editors and other tooling
+    ///
+    /// The object has the form
+    ///
+    /// ```json
+    /// {
+    ///   "severity": "warning",
+    ///   "message": "...",
+    ///   "deprecation": "slash-div",
+    ///   "lint": null,
+    ///   "span": {
+    ///     "file": "input.scss",
+    ///     "start": { "line": 2, "column": 6 },
+    ///     "end": { "line": 2, "column": 14 }
+    ///   },
+    ///   "trace": [{ "member": "root stylesheet", "span": { ... } }]
+    /// }
+    /// ```
+    ///
+    /// Lines and columns are 1-indexed. `deprecation` is `null` for warnings
+    /// that are not deprecations, and `lint` is `null` for warnings that were
+    /// not emitted by a lint.
+    #[cfg(feature = "json")]
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        json::to_string(&json::Warning {
+            severity: "warning",
+            message: &self.message,
+            deprecation: self.deprecation.map(Deprecation::id),
+            lint: self.lint.map(Lint::id),
+            span: json::Span::from(&self.location),
+            trace: json::trace(&self.trace),
+        })
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.deprecation, self.lint) {
+            (Some(deprecation), ..) => {
+                write!(f, "Deprecation Warning [{}]: {}", deprecation, self.message)?;
+            }
+            (None, Some(lint)) => write!(f, "Warning [{}]: {}", lint, self.message)?,
+            (None, None) => write!(f, "Warning: {}", self.message)?,
+        }
+
+        for frame in &self.trace {
+            write!(f, "\n    {}", frame)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A trait to allow replacing the way warnings and `@debug` output are
+/// reported.
+///
+/// Neither method is called if [`Options::quiet`](crate::Options::quiet) is set.
+pub trait Logger: Debug {
+    /// Called when the `@debug` rule is encountered
+    fn debug(&self, location: SpanLoc, message: &str);
+
+    /// Called when a warning is emitted, either by the `@warn` rule or by
+    /// `grass` itself
+    fn warn(&self, warning: &Warning);
+
+    /// Called once compilation has finished if any deprecation warnings were
+    /// omitted because the same deprecation was warned about too many times
+    ///
+    /// See [`Options::verbose`](crate::Options::verbose). By default, this does
+    /// nothing.
+    fn omitted_deprecations(&self, count: usize) {
+        let _ = count;
+    }
+}
+
+pub(crate) fn omitted_deprecations_message(count: usize) -> String {
+    format!(
+        "{} repetitive deprecation warnings omitted.\nRun in verbose mode to see all warnings.",
+        count
+    )
+}
+
+/// Write all messages to stderr.
+///
+/// This is the default logger implementation.
+#[derive(Debug)]
+pub struct StdLogger;
+
+impl Logger for StdLogger {
+    #[inline]
+    fn debug(&self, location: SpanLoc, message: &str) {
+        eprintln!(
+            "{}:{} DEBUG: {}",
+            location.file.name(),
+            location.begin.line + 1,
+            message
+        );
+    }
+
+    #[inline]
+    fn warn(&self, warning: &Warning) {
+        eprintln!("{}", warning);
+    }
+
+    #[inline]
+    fn omitted_deprecations(&self, count: usize) {
+        eprintln!("{}", omitted_deprecations_message(count));
+    }
+}
+
+/// A logger that silently discards all messages.
+#[derive(Debug)]
+pub struct NullLogger;
+
+impl Logger for NullLogger {
+    #[inline]
+    fn debug(&self, _location: SpanLoc, _message: &str) {}
+
+    #[inline]
+    fn warn(&self, _warning: &Warning) {}
+}
+
+/// Write all messages to stderr as JSON, one object per line.
+///
+/// Warnings are serialized using [`Warning::to_json`]. `@debug` output is
+/// serialized as an object with the severity `debug`, a `message`, and a
+/// `span`. The count of omitted deprecation warnings is serialized as an object
+/// with the severity `info`, a `message`, and the number of warnings `omitted`.
+#[cfg(feature = "json")]
+#[derive(Debug)]
+pub struct JsonLogger;
+
+#[cfg(feature = "json")]
+impl JsonLogger {
+    /// Serialize `@debug` output in the same format as [`JsonLogger::debug`]
+    #[must_use]
+    pub fn debug_to_json(location: &SpanLoc, message: &str) -> String {
+        json::to_string(&json::Debug {
+            severity: "debug",
+            message,
+            span: json::Span::from(location),
+        })
+    }
+}
+
+#[cfg(feature = "json")]
+impl Logger for JsonLogger {
+    #[inline]
+    fn debug(&self, location: SpanLoc, message: &str) {
+        eprintln!("{}", Self::debug_to_json(&location, message));
+    }
+
+    #[inline]
+    fn warn(&self, warning: &Warning) {
+        eprintln!("{}", warning.to_json());
+    }
+
+    #[inline]
+    fn omitted_deprecations(&self, count: usize) {
+        eprintln!(
+            "{}",
+            json::to_string(&json::Omitted {
+                severity: "info",
+                message: &omitted_deprecations_message(count),
+                omitted: count,
+            })
+        );
+    }
+}
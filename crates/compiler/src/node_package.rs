@@ -0,0 +1,400 @@
+//! Resolution of `pkg:` URLs from `node_modules`
+//!
+//! This follows the algorithm of Dart Sass's `NodePackageImporter`: the
+//! package is found in the nearest `node_modules` directory, and the URL's
+//! subpath is resolved with the package's `package.json`.
+//!
+//! <https://github.com/sass/sass/blob/main/accepted/node-package-importer.d.ts.md>
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess};
+
+use crate::{evaluate::Visitor, Options};
+
+/// The `exports` conditions Sass is interested in, in order of precedence
+const CONDITIONS: [&str; 3] = ["sass", "style", "default"];
+
+/// Resolve `url`, the part of a `pkg:` URL after the scheme, as loaded by the
+/// file `importer`
+///
+/// Errors if the package's `exports` are invalid or ambiguous for `url`.
+pub(crate) fn resolve(
+    options: &Options,
+    importer: &Path,
+    url: &str,
+) -> Result<Option<PathBuf>, String> {
+    let Some((name, subpath)) = split_package_name(url) else {
+        return Ok(None);
+    };
+    let Some(root) = find_package_root(options, importer, name) else {
+        return Ok(None);
+    };
+
+    let manifest = options
+        .fs
+        .read(&root.join("package.json"))
+        .ok()
+        .and_then(|contents| serde_json::from_slice::<Json>(&contents).ok());
+
+    let exports = manifest
+        .as_ref()
+        .and_then(|manifest| manifest.get("exports"));
+
+    if let Some(exports) = exports {
+        if let Some(path) = resolve_exports(options, &root, name, exports, subpath)? {
+            return Ok(Some(path));
+        }
+    }
+
+    Ok(match subpath {
+        Some(subpath) => Visitor::resolve_import_path(options, &root.join(subpath)),
+        None => {
+            let manifest_field = |field: &str| {
+                manifest
+                    .as_ref()
+                    .and_then(|manifest| manifest.get(field))
+                    .and_then(Json::as_str)
+                    .map(|path| root.join(path.trim_start_matches("./")))
+                    .filter(|path| options.fs.is_file(path))
+            };
+
+            manifest_field("sass")
+                .or_else(|| manifest_field("style"))
+                .or_else(|| Visitor::resolve_import_path(options, &root.join("index")))
+        }
+    })
+}
+
+/// Split `url` into the name of a package, which may be scoped, and the
+/// subpath within it
+fn split_package_name(url: &str) -> Option<(&str, Option<&str>)> {
+    if url.is_empty() || url.starts_with('/') || url.contains('\\') {
+        return None;
+    }
+
+    let name_len = if url.starts_with('@') {
+        let scope_len = url.find('/')?;
+        scope_len
+            + 1
+            + url[scope_len + 1..]
+                .find('/')
+                .unwrap_or(url.len() - scope_len - 1)
+    } else {
+        url.find('/').unwrap_or(url.len())
+    };
+
+    let (name, rest) = url.split_at(name_len);
+    let subpath = rest.strip_prefix('/').filter(|subpath| !subpath.is_empty());
+
+    if name.ends_with('/') || name.starts_with('.') {
+        return None;
+    }
+
+    Some((name, subpath))
+}
+
+/// The root directory of the package `name`, found in the `node_modules`
+/// directory of the directory containing `importer` or its nearest ancestor
+fn find_package_root(options: &Options, importer: &Path, name: &str) -> Option<PathBuf> {
+    let importer = options
+        .fs
+        .canonicalize(importer)
+        .unwrap_or_else(|_| importer.to_path_buf());
+
+    importer
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .ancestors()
+        .map(|dir| dir.join("node_modules").join(name))
+        .find(|root| options.fs.is_file(&root.join("package.json")))
+}
+
+/// Resolve `subpath` with a package's `exports`, trying each of the paths
+/// Sass would try for a relative load
+///
+/// As in Dart Sass, it's an error for more than one of those paths to exist.
+fn resolve_exports(
+    options: &Options,
+    root: &Path,
+    name: &str,
+    exports: &Json,
+    subpath: Option<&str>,
+) -> Result<Option<PathBuf>, String> {
+    let resolve = |candidates: Vec<String>| -> Result<Option<PathBuf>, String> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for candidate in &candidates {
+            let Some(target) = exports_target(exports, candidate)? else {
+                continue;
+            };
+
+            let path = root.join(target.trim_start_matches("./"));
+            if options.fs.is_file(&path) && !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+
+        if paths.len() > 1 {
+            return Err(format!(
+                "Unable to determine which of multiple potential resolutions found for {} in {} should be used.\n\nFound:\n{}",
+                subpath.unwrap_or("root"),
+                name,
+                paths
+                    .iter()
+                    .map(|path| path.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        }
+
+        Ok(paths.pop())
+    };
+
+    match subpath {
+        None => match resolve(vec![".".to_owned()])? {
+            Some(path) => Ok(Some(path)),
+            None => resolve(candidate_subpaths("index")),
+        },
+        Some(subpath) => match resolve(candidate_subpaths(subpath))? {
+            Some(path) => Ok(Some(path)),
+            None => resolve(candidate_subpaths(&format!("{}/index", subpath))),
+        },
+    }
+}
+
+/// The subpaths, as `exports` keys, that Sass tries for `subpath`: the
+/// subpath itself, with each extension if it has none, and their partials
+fn candidate_subpaths(subpath: &str) -> Vec<String> {
+    let has_extension = [".scss", ".sass", ".css"]
+        .iter()
+        .any(|extension| subpath.ends_with(extension));
+
+    let mut paths = vec![subpath.to_owned()];
+    if !has_extension {
+        for extension in ["scss", "sass", "css"] {
+            paths.push(format!("{}.{}", subpath, extension));
+        }
+    }
+
+    let (dir, basename) = match subpath.rfind('/') {
+        Some(idx) => (&subpath[..=idx], &subpath[idx + 1..]),
+        None => ("", subpath),
+    };
+
+    if !basename.starts_with('_') {
+        let partials: Vec<String> = paths
+            .iter()
+            .map(|path| format!("{}_{}", dir, &path[dir.len()..]))
+            .collect();
+        paths.extend(partials);
+    }
+
+    paths
+        .into_iter()
+        .map(|path| format!("./{}", path))
+        .collect()
+}
+
+/// The path `exports` maps `subpath` to, relative to the package root
+///
+/// `subpath` is either `"."` or begins with `"./"`.
+fn exports_target(exports: &Json, subpath: &str) -> Result<Option<String>, String> {
+    let entries = match exports {
+        // a map of subpaths, rather than of conditions
+        Json::Object(entries) if entries.iter().all(|(key, _)| key.starts_with('.')) => entries,
+        exports if subpath == "." => return conditional_target(exports, subpath, None),
+        _ => return Ok(None),
+    };
+
+    if let Some(target) = exports.get(subpath) {
+        return conditional_target(target, subpath, None);
+    }
+
+    // of the patterns matching `subpath`, the one with the longest prefix wins
+    entries
+        .iter()
+        .filter_map(|(key, target)| {
+            let (prefix, suffix) = key.split_once('*')?;
+            let matched = subpath
+                .strip_prefix(prefix)?
+                .strip_suffix(suffix)
+                .filter(|_| subpath.len() >= key.len() - 1)?;
+
+            Some((prefix.len(), target, matched))
+        })
+        .max_by_key(|(prefix_len, ..)| *prefix_len)
+        .map_or(Ok(None), |(_, target, matched)| {
+            conditional_target(target, subpath, Some(matched))
+        })
+}
+
+/// Resolve an `exports` target for `subpath`, which may be nested within
+/// conditions or be an array of fallbacks, replacing `*` with `pattern_match`
+///
+/// As in Node, a target that isn't relative to the package root, or a target
+/// or pattern match that would leave the package, is an error.
+fn conditional_target(
+    target: &Json,
+    subpath: &str,
+    pattern_match: Option<&str>,
+) -> Result<Option<String>, String> {
+    match target {
+        Json::String(path) => {
+            if !path.starts_with("./") || has_invalid_segment(&path[2..]) {
+                return Err(format!(
+                    "Invalid \"exports\" target \"{}\" defined for \"{}\" in package.json.",
+                    path, subpath
+                ));
+            }
+
+            Ok(Some(match pattern_match {
+                Some(pattern_match) => {
+                    if has_invalid_segment(pattern_match) {
+                        return Err(format!(
+                            "Invalid subpath \"{}\" matched by \"exports\" target \"{}\" in package.json.",
+                            subpath, path
+                        ));
+                    }
+
+                    path.replace('*', pattern_match)
+                }
+                None => path.clone(),
+            }))
+        }
+        Json::Object(conditions) => {
+            for (condition, target) in conditions {
+                if !CONDITIONS.contains(&condition.as_str()) {
+                    continue;
+                }
+
+                if let Some(path) = conditional_target(target, subpath, pattern_match)? {
+                    return Ok(Some(path));
+                }
+            }
+
+            Ok(None)
+        }
+        Json::Array(targets) => {
+            for target in targets {
+                if let Some(path) = conditional_target(target, subpath, pattern_match)? {
+                    return Ok(Some(path));
+                }
+            }
+
+            Ok(None)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Whether `path` has an empty, `.`, `..`, or `node_modules` segment, which
+/// Node doesn't allow in `exports` targets as they may resolve outside of the
+/// package
+fn has_invalid_segment(path: &str) -> bool {
+    path.split(['/', '\\']).any(|segment| {
+        segment.is_empty()
+            || segment == "."
+            || segment == ".."
+            || segment.eq_ignore_ascii_case("node_modules")
+    })
+}
+
+/// A JSON value whose objects keep their keys in the order they were written,
+/// as the conditions of `exports` are tried in that order
+#[derive(Debug)]
+enum Json {
+    /// `null`, a boolean, or a number, none of which are used
+    Other,
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// The value of `key`, if this is an object containing it
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries
+                .iter()
+                .find(|(entry, _)| entry == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Json {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct JsonVisitor;
+
+        impl<'de> de::Visitor<'de> for JsonVisitor {
+            type Value = Json;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a JSON value")
+            }
+
+            fn visit_bool<E>(self, _: bool) -> Result<Json, E> {
+                Ok(Json::Other)
+            }
+
+            fn visit_i64<E>(self, _: i64) -> Result<Json, E> {
+                Ok(Json::Other)
+            }
+
+            fn visit_u64<E>(self, _: u64) -> Result<Json, E> {
+                Ok(Json::Other)
+            }
+
+            fn visit_f64<E>(self, _: f64) -> Result<Json, E> {
+                Ok(Json::Other)
+            }
+
+            fn visit_unit<E>(self) -> Result<Json, E> {
+                Ok(Json::Other)
+            }
+
+            fn visit_str<E>(self, s: &str) -> Result<Json, E> {
+                Ok(Json::String(s.to_owned()))
+            }
+
+            fn visit_string<E>(self, s: String) -> Result<Json, E> {
+                Ok(Json::String(s))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Json, A::Error> {
+                let mut values = Vec::new();
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(Json::Array(values))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Json, A::Error> {
+                let mut entries: Vec<(String, Json)> = Vec::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    // as in JavaScript, the last of any duplicate keys wins,
+                    // but keeps the position of the first
+                    let value = map.next_value()?;
+                    match entries.iter_mut().find(|(entry, _)| *entry == key) {
+                        Some((_, existing)) => *existing = value,
+                        None => entries.push((key, value)),
+                    }
+                }
+                Ok(Json::Object(entries))
+            }
+        }
+
+        deserializer.deserialize_any(JsonVisitor)
+    }
+}
@@ -1,9 +1,11 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
-use crate::{builtin::Builtin, Fs, StdFs};
+#[cfg(feature = "plugins")]
+use crate::plugin::{call_plugin_function, Plugin};
+use crate::{builtin::Builtin, Deprecation, Fs, Lint, Logger, ParseCache, StdFs, StdLogger};
 
 /// Configuration for Sass compilation
 ///
@@ -12,13 +14,27 @@ use crate::{builtin::Builtin, Fs, StdFs};
 #[derive(Debug)]
 pub struct Options<'a> {
     pub(crate) fs: &'a dyn Fs,
+    pub(crate) logger: &'a dyn Logger,
+    pub(crate) parse_cache: Option<&'a ParseCache>,
     pub(crate) style: OutputStyle,
     pub(crate) load_paths: Vec<PathBuf>,
     pub(crate) allows_charset: bool,
     pub(crate) unicode_error_messages: bool,
     pub(crate) quiet: bool,
+    pub(crate) quiet_deps: bool,
+    pub(crate) verbose: bool,
+    pub(crate) warn_duplicate_imports: bool,
+    #[cfg(feature = "pkg-importer")]
+    pub(crate) node_package_importer: bool,
     pub(crate) input_syntax: Option<InputSyntax>,
     pub(crate) custom_fns: HashMap<String, Builtin>,
+    #[cfg(feature = "plugins")]
+    pub(crate) plugins: Vec<&'a Plugin>,
+    pub(crate) silenced_deprecations: HashSet<Deprecation>,
+    pub(crate) future_deprecations: HashSet<Deprecation>,
+    pub(crate) fatal_deprecations: HashSet<Deprecation>,
+    pub(crate) lints: HashSet<Lint>,
+    pub(crate) defined_variables: Vec<(String, String)>,
 }
 
 impl Default for Options<'_> {
@@ -26,13 +42,27 @@ impl Default for Options<'_> {
     fn default() -> Self {
         Self {
             fs: &StdFs,
+            logger: &StdLogger,
+            parse_cache: None,
             style: OutputStyle::Expanded,
             load_paths: Vec::new(),
             allows_charset: true,
             unicode_error_messages: true,
             quiet: false,
+            quiet_deps: false,
+            verbose: false,
+            warn_duplicate_imports: false,
+            #[cfg(feature = "pkg-importer")]
+            node_package_importer: false,
             input_syntax: None,
             custom_fns: HashMap::new(),
+            #[cfg(feature = "plugins")]
+            plugins: Vec::new(),
+            silenced_deprecations: HashSet::new(),
+            future_deprecations: HashSet::new(),
+            fatal_deprecations: HashSet::new(),
+            lints: HashSet::new(),
+            defined_variables: Vec::new(),
         }
     }
 }
@@ -49,6 +79,32 @@ impl<'a> Options<'a> {
         self
     }
 
+    /// This option allows you to control how warnings and `@debug` output are
+    /// reported.
+    ///
+    /// By default, it uses [`StdLogger`], which writes all messages to stderr.
+    /// Each [`Warning`](crate::Warning) carries its location along with the
+    /// stack of `@include`s, function calls, and imports that led to it.
+    #[must_use]
+    #[inline]
+    pub fn logger(mut self, logger: &'a dyn Logger) -> Self {
+        self.logger = logger;
+        self
+    }
+
+    /// This option allows you to reuse parsed stylesheets between compilations.
+    ///
+    /// By default, every file loaded using `@import`, `@use`, or `@forward` is
+    /// lexed and parsed again in each compilation. When compiling with a
+    /// [`ParseCache`], files that have not changed since they were last parsed
+    /// using the same cache are not parsed again.
+    #[must_use]
+    #[inline]
+    pub fn parse_cache(mut self, parse_cache: &'a ParseCache) -> Self {
+        self.parse_cache = Some(parse_cache);
+        self
+    }
+
     /// `grass` currently offers 2 different output styles
     ///
     ///  - [`OutputStyle::Expanded`] writes each selector and declaration on its own line.
@@ -77,6 +133,77 @@ impl<'a> Options<'a> {
         self
     }
 
+    /// This flag tells Sass not to emit deprecation warnings or lints caused
+    /// by dependencies: stylesheets loaded from one of the
+    /// [load paths](Options::load_path), and any stylesheets they load in
+    /// turn.
+    ///
+    /// Warnings from the `@warn` rule are still emitted.
+    ///
+    /// By default, this value is `false` and warnings from dependencies are
+    /// emitted.
+    #[must_use]
+    #[inline]
+    pub const fn quiet_deps(mut self, quiet_deps: bool) -> Self {
+        self.quiet_deps = quiet_deps;
+        self
+    }
+
+    /// This flag tells Sass to emit every deprecation warning, even when
+    /// the same deprecation has already been warned about many times.
+    ///
+    /// By default, only the first 5 warnings for each [`Deprecation`] are
+    /// emitted. Once compilation finishes, the number of warnings that were
+    /// omitted is reported using [`Logger::omitted_deprecations`].
+    #[must_use]
+    #[inline]
+    pub const fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// This flag tells Sass to emit a warning whenever a file that has already
+    /// been imported using `@import` is imported again, whether directly or
+    /// through another imported file.
+    ///
+    /// Each time a file is imported, its CSS is emitted again, so importing the
+    /// same file more than once is a common cause of bloated output. The
+    /// warning points to the duplicate `@import` and names the location at
+    /// which the file was first imported.
+    ///
+    /// By default, this value is `false` and no warning is emitted.
+    #[must_use]
+    #[inline]
+    pub const fn warn_duplicate_imports(mut self, warn_duplicate_imports: bool) -> Self {
+        self.warn_duplicate_imports = warn_duplicate_imports;
+        self
+    }
+
+    /// This flag tells Sass to resolve `pkg:` URLs, such as
+    /// `@use "pkg:bootstrap"`, from the `node_modules` directories of the
+    /// importing file and its ancestors, as Node.js resolves packages.
+    ///
+    /// Within a package, the `"sass"`, `"style"`, and `"default"` conditions
+    /// of its `package.json` `"exports"` are used. A URL with no subpath
+    /// falls back to the `"sass"` and `"style"` fields, and then to an index
+    /// file in the package's root directory. Other subpaths are otherwise
+    /// resolved against the package's root directory, like any other load.
+    ///
+    /// The legacy `~package` convention of webpack's `sass-loader`, as in
+    /// `@import "~bootstrap/scss/bootstrap"`, is resolved in the same way.
+    /// Files loaded from packages are considered dependencies, like those
+    /// loaded from [load paths](Options::load_path).
+    ///
+    /// By default, this value is `false` and `pkg:` URLs can't be loaded.
+    /// This requires the `pkg-importer` feature, which is enabled by default.
+    #[must_use]
+    #[inline]
+    #[cfg(feature = "pkg-importer")]
+    pub const fn node_package_importer(mut self, node_package_importer: bool) -> Self {
+        self.node_package_importer = node_package_importer;
+        self
+    }
+
     /// All Sass implementations allow users to provide
     /// load paths: paths on the filesystem that Sass
     /// will look in when locating modules. For example,
@@ -167,6 +294,115 @@ impl<'a> Options<'a> {
         self
     }
 
+    /// Make the functions defined by a native plugin accessible from within
+    /// Sass, as with custom functions
+    ///
+    /// See the [`plugin`](crate::plugin) module documentation for more
+    /// information. This requires the `plugins` feature.
+    #[must_use]
+    #[cfg(feature = "plugins")]
+    pub fn plugin(mut self, plugin: &'a Plugin) -> Self {
+        for name in plugin.function_names() {
+            self.custom_fns
+                .insert(name.to_owned(), Builtin::new(call_plugin_function));
+        }
+
+        self.plugins.push(plugin);
+        self
+    }
+
+    /// Don't emit warnings for the given deprecation
+    ///
+    /// Unlike [`Options::quiet`], this only affects warnings tagged with this
+    /// [`Deprecation`]. All other warnings are still emitted.
+    #[must_use]
+    #[inline]
+    pub fn silence_deprecation(mut self, deprecation: Deprecation) -> Self {
+        self.silenced_deprecations.insert(deprecation);
+        self
+    }
+
+    /// Opt into warnings for a future deprecation
+    ///
+    /// Warnings for future deprecations are not emitted by default. This has no
+    /// effect for deprecations that are already active, as determined by
+    /// [`Deprecation::is_future`].
+    #[must_use]
+    #[inline]
+    pub fn future_deprecation(mut self, deprecation: Deprecation) -> Self {
+        self.future_deprecations.insert(deprecation);
+        self
+    }
+
+    /// Treat uses of the given deprecated feature as errors rather than
+    /// warnings
+    ///
+    /// This takes precedence over [`Options::silence_deprecation`],
+    /// [`Options::quiet`], and [`Options::quiet_deps`]. Future deprecations
+    /// are only made fatal once they have been opted into using
+    /// [`Options::future_deprecation`].
+    #[must_use]
+    #[inline]
+    pub fn fatal_deprecation(mut self, deprecation: Deprecation) -> Self {
+        self.fatal_deprecations.insert(deprecation);
+        self
+    }
+
+    /// Enable the given lint
+    ///
+    /// Lints check for constructs that are valid Sass, but are likely to be
+    /// mistakes. They are reported as warnings, and none are enabled by default.
+    /// See [`Lint`] for the available lints.
+    #[must_use]
+    #[inline]
+    pub fn lint(mut self, lint: Lint) -> Self {
+        self.lints.insert(lint);
+        self
+    }
+
+    /// Define a global variable in the entrypoint
+    ///
+    /// `name` is the name of the variable, without the leading `$`, and `value`
+    /// is a SassScript expression, such as `production` or `(1px, 2px)`. The
+    /// variable is assigned before the entrypoint is evaluated, so it is
+    /// visible to the entrypoint and the files it imports using `@import`, and
+    /// takes precedence over `!default` declarations of the same variable there.
+    ///
+    /// Variables are assigned in the order in which they are defined, so
+    /// `value` may refer to variables defined previously.
+    ///
+    /// ```
+    /// # use grass_compiler as grass;
+    /// # fn main() -> Result<(), Box<grass::Error>> {
+    /// let options = grass::Options::default().define("env", "production");
+    ///
+    /// assert_eq!(
+    ///     grass::from_string("$env: development !default;\na { env: $env; }", &options)?,
+    ///     "a {\n  env: production;\n}\n"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn define<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.defined_variables.push((name.into(), value.into()));
+        self
+    }
+
+    pub(crate) fn is_deprecation_enabled(&self, deprecation: Deprecation) -> bool {
+        if self.silenced_deprecations.contains(&deprecation) {
+            return false;
+        }
+
+        !deprecation.is_future() || self.future_deprecations.contains(&deprecation)
+    }
+
+    pub(crate) fn is_deprecation_fatal(&self, deprecation: Deprecation) -> bool {
+        self.fatal_deprecations.contains(&deprecation)
+            && (!deprecation.is_future() || self.future_deprecations.contains(&deprecation))
+    }
+
     pub(crate) fn is_compressed(&self) -> bool {
         matches!(self.style, OutputStyle::Compressed)
     }
@@ -185,6 +421,11 @@ pub enum InputSyntax {
     Sass,
 
     /// The plain CSS syntax, which disallows special Sass features.
+    ///
+    /// When the entrypoint uses this syntax, it is evaluated by a minimal pass
+    /// that only emits the validated CSS. Work that is only needed for Sass
+    /// features, such as defining [variables](Options::define), setting up
+    /// modules and tracking selectors for `@extend`, is skipped.
     Css,
 }
 
@@ -11,15 +11,8 @@ pub(crate) trait BaseParser<'a> {
     fn toks_mut(&mut self) -> &mut Lexer<'a>;
 
     fn whitespace_without_comments(&mut self) {
-        while matches!(
-            self.toks().peek(),
-            Some(Token {
-                kind: ' ' | '\t' | '\n',
-                ..
-            })
-        ) {
-            self.toks_mut().next();
-        }
+        self.toks_mut()
+            .take_while(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\r' | b'\x0C'));
     }
 
     fn whitespace(&mut self) -> SassResult<()> {
@@ -56,9 +49,7 @@ pub(crate) trait BaseParser<'a> {
         debug_assert!(self.next_matches("//"));
         self.toks_mut().next();
         self.toks_mut().next();
-        while self.toks().peek().is_some() && !self.toks().next_char_is('\n') {
-            self.toks_mut().next();
-        }
+        self.toks_mut().take_until(b"\n\r\x0C");
         Ok(())
     }
 
@@ -78,9 +69,11 @@ pub(crate) trait BaseParser<'a> {
         self.toks_mut().next();
         self.toks_mut().next();
 
-        while let Some(next) = self.toks_mut().next() {
-            if next.kind != '*' {
-                continue;
+        loop {
+            self.toks_mut().take_until(b"*");
+
+            if !self.scan_char('*') {
+                break;
             }
 
             while self.scan_char('*') {}
@@ -180,7 +173,16 @@ pub(crate) trait BaseParser<'a> {
         normalize: bool,
         unit: bool,
     ) -> SassResult<()> {
-        while let Some(tok) = self.toks().peek() {
+        loop {
+            buffer.push_str(self.toks_mut().take_while(|b| {
+                b.is_ascii_alphanumeric() || (b == b'-' && !unit) || (b == b'_' && !normalize)
+            }));
+
+            let tok = match self.toks().peek() {
+                Some(tok) => tok,
+                None => break,
+            };
+
             if unit && tok.kind == '-' {
                 // Disallow `-` followed by a dot or a digit digit in units.
                 let second = match self.toks().peek_n(1) {
@@ -301,7 +303,20 @@ pub(crate) trait BaseParser<'a> {
 
         let mut found_matching_quote = false;
 
-        while let Some(next) = self.toks().peek() {
+        loop {
+            buffer.push_str(self.toks_mut().take_until(&[
+                quote as u8,
+                b'\\',
+                b'\n',
+                b'\r',
+                b'\x0C',
+            ]));
+
+            let next = match self.toks().peek() {
+                Some(next) => next,
+                None => break,
+            };
+
             if next.kind == quote {
                 self.toks_mut().next();
                 found_matching_quote = true;
@@ -421,7 +436,7 @@ pub(crate) trait BaseParser<'a> {
                         || !self
                             .toks()
                             .peek_n(1)
-                            .map_or(false, |tok| tok.kind.is_ascii_whitespace())
+                            .is_some_and(|tok| tok.kind.is_ascii_whitespace())
                     {
                         buffer.push(c);
                     }
@@ -680,9 +695,10 @@ pub(crate) trait BaseParser<'a> {
             .into())
     }
 
-    // todo: not real impl
     fn expect_done(&mut self) -> SassResult<()> {
-        debug_assert!(self.toks().peek().is_none());
+        if self.toks().peek().is_some() {
+            return Err(("expected no more input.", self.toks().current_span()).into());
+        }
 
         Ok(())
     }
@@ -3,8 +3,12 @@ use std::{collections::BTreeMap, path::Path, sync::Arc};
 use codemap::{CodeMap, Span, Spanned};
 
 use crate::{
-    ast::*, builtin::DISALLOWED_PLAIN_CSS_FUNCTION_NAMES, common::QuoteKind, error::SassResult,
-    lexer::Lexer, ContextFlags, Options,
+    ast::*,
+    builtin::DISALLOWED_PLAIN_CSS_FUNCTION_NAMES,
+    common::QuoteKind,
+    error::{SassError, SassResult},
+    lexer::Lexer,
+    ContextFlags, Options,
 };
 
 use super::{value::ValueParser, BaseParser, StylesheetParser};
@@ -17,6 +21,7 @@ pub(crate) struct CssParser<'a> {
     pub empty_span: Span,
     pub flags: ContextFlags,
     pub options: &'a Options<'a>,
+    pub recovered_errors: Option<Vec<SassError>>,
 }
 
 impl<'a> BaseParser<'a> for CssParser<'a> {
@@ -58,6 +63,10 @@ impl<'a> StylesheetParser<'a> for CssParser<'a> {
         self.options
     }
 
+    fn recovered_errors(&mut self) -> Option<&mut Vec<SassError>> {
+        self.recovered_errors.as_mut()
+    }
+
     fn flags(&self) -> &ContextFlags {
         &self.flags
     }
@@ -122,6 +131,7 @@ impl<'a> CssParser<'a> {
             empty_span,
             flags: ContextFlags::empty(),
             options,
+            recovered_errors: None,
         }
     }
 
@@ -56,6 +56,8 @@ impl<'a> KeyframesSelectorParser<'a> {
             }
         }
 
+        self.expect_done()?;
+
         Ok(selectors)
     }
 
@@ -2,7 +2,10 @@ use std::path::Path;
 
 use codemap::{CodeMap, Span};
 
-use crate::{ast::*, error::SassResult, lexer::Lexer, ContextFlags, Options, Token};
+use crate::{
+    ast::*, error::SassResult, lexer::Lexer, utils::ensure_sufficient_stack, ContextFlags, Options,
+    Token,
+};
 
 use super::{BaseParser, StylesheetParser};
 
@@ -171,16 +174,18 @@ impl<'a> StylesheetParser<'a> for SassParser<'a> {
         &mut self,
         child: fn(&mut Self) -> SassResult<AstStmt>,
     ) -> SassResult<Vec<AstStmt>> {
-        let mut children = Vec::new();
-        self.while_indented_lower(|parser| {
-            if let Some(parsed_child) = parser.parse_child(|parser| Ok(Some(child(parser)?)))? {
-                children.push(parsed_child);
-            }
+        ensure_sufficient_stack(|| {
+            let mut children = Vec::new();
+            self.while_indented_lower(|parser| {
+                if let Some(parsed_child) = parser.parse_child(|parser| Ok(Some(child(parser)?)))? {
+                    children.push(parsed_child);
+                }
 
-            Ok(())
-        })?;
+                Ok(())
+            })?;
 
-        Ok(children)
+            Ok(children)
+        })
     }
 
     fn parse_statements(
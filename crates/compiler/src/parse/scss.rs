@@ -2,7 +2,7 @@ use std::path::Path;
 
 use codemap::{CodeMap, Span};
 
-use crate::{lexer::Lexer, ContextFlags, Options};
+use crate::{error::SassError, lexer::Lexer, ContextFlags, Options};
 
 use super::{BaseParser, StylesheetParser};
 
@@ -14,6 +14,7 @@ pub(crate) struct ScssParser<'a> {
     pub empty_span: Span,
     pub flags: ContextFlags,
     pub options: &'a Options<'a>,
+    pub recovered_errors: Option<Vec<SassError>>,
 }
 
 impl<'a> ScssParser<'a> {
@@ -35,6 +36,7 @@ impl<'a> ScssParser<'a> {
             empty_span,
             flags,
             options,
+            recovered_errors: None,
         }
     }
 }
@@ -70,6 +72,10 @@ impl<'a> StylesheetParser<'a> for ScssParser<'a> {
         self.options
     }
 
+    fn recovered_errors(&mut self) -> Option<&mut Vec<SassError>> {
+        self.recovered_errors.as_mut()
+    }
+
     fn current_indentation(&self) -> usize {
         0
     }
@@ -12,9 +12,11 @@ use codemap::{CodeMap, Span, Spanned};
 use crate::{
     ast::*,
     common::{unvendor, Identifier, QuoteKind},
-    error::SassResult,
+    error::{SassError, SassResult},
     lexer::Lexer,
-    utils::{is_name, is_name_start, is_plain_css_import, opposite_bracket},
+    utils::{
+        ensure_sufficient_stack, is_name, is_name_start, is_plain_css_import, opposite_bracket,
+    },
     ContextFlags, Options, Token,
 };
 
@@ -39,6 +41,12 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
     fn flags(&self) -> &ContextFlags;
     fn flags_mut(&mut self) -> &mut ContextFlags;
 
+    /// The errors recovered from so far, or `None` if this parser should stop
+    /// at the first error
+    fn recovered_errors(&mut self) -> Option<&mut Vec<SassError>> {
+        None
+    }
+
     #[allow(clippy::type_complexity)]
     const IDENTIFIER_LIKE: Option<fn(&mut Self) -> SassResult<Spanned<AstExpr>>> = None;
 
@@ -101,46 +109,59 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
         &mut self,
         child: fn(&mut Self) -> SassResult<AstStmt>,
     ) -> SassResult<Vec<AstStmt>> {
-        self.expect_char('{')?;
-        self.whitespace_without_comments();
-        let mut children = Vec::new();
+        ensure_sufficient_stack(|| {
+            self.expect_char('{')?;
+            self.whitespace_without_comments();
+            let mut children = Vec::new();
 
-        let mut found_matching_brace = false;
+            let mut found_matching_brace = false;
 
-        while let Some(tok) = self.toks().peek() {
-            match tok.kind {
-                '$' => children.push(AstStmt::VariableDecl(
-                    self.parse_variable_declaration_without_namespace(None, None)?,
-                )),
-                '/' => match self.toks().peek_n(1) {
-                    Some(Token { kind: '/', .. }) => {
-                        children.push(self.parse_silent_comment()?);
+            while let Some(tok) = self.toks().peek() {
+                let start = self.toks().cursor();
+                let flags = *self.flags();
+
+                let stmt = match tok.kind {
+                    '$' => self
+                        .parse_variable_declaration_without_namespace(None, None)
+                        .map(AstStmt::VariableDecl),
+                    '/' => match self.toks().peek_n(1) {
+                        Some(Token { kind: '/', .. }) => {
+                            let comment = self.parse_silent_comment();
+                            self.whitespace_without_comments();
+                            comment
+                        }
+                        Some(Token { kind: '*', .. }) => {
+                            let comment = self.parse_loud_comment().map(AstStmt::LoudComment);
+                            self.whitespace_without_comments();
+                            comment
+                        }
+                        _ => child(self),
+                    },
+                    ';' => {
+                        self.toks_mut().next();
                         self.whitespace_without_comments();
+                        continue;
                     }
-                    Some(Token { kind: '*', .. }) => {
-                        children.push(AstStmt::LoudComment(self.parse_loud_comment()?));
-                        self.whitespace_without_comments();
+                    '}' => {
+                        self.expect_char('}')?;
+                        found_matching_brace = true;
+                        break;
                     }
-                    _ => children.push(child(self)?),
-                },
-                ';' => {
-                    self.toks_mut().next();
-                    self.whitespace_without_comments();
-                }
-                '}' => {
-                    self.expect_char('}')?;
-                    found_matching_brace = true;
-                    break;
+                    _ => child(self),
+                };
+
+                match stmt {
+                    Ok(stmt) => children.push(stmt),
+                    Err(e) => self.recover_from_error(e, start, flags)?,
                 }
-                _ => children.push(child(self)?),
             }
-        }
 
-        if !found_matching_brace {
-            return Err(("expected \"}\".", self.toks().current_span()).into());
-        }
+            if !found_matching_brace {
+                return Err(("expected \"}\".", self.toks().current_span()).into());
+            }
 
-        Ok(children)
+            Ok(children)
+        })
     }
 
     fn parse_statements(
@@ -150,40 +171,134 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
         let mut stmts = Vec::new();
         self.whitespace_without_comments();
         while let Some(tok) = self.toks().peek() {
-            match tok.kind {
-                '$' => stmts.push(AstStmt::VariableDecl(
-                    self.parse_variable_declaration_without_namespace(None, None)?,
-                )),
+            let start = self.toks().cursor();
+            let flags = *self.flags();
+
+            let stmt = match tok.kind {
+                '$' => self
+                    .parse_variable_declaration_without_namespace(None, None)
+                    .map(|decl| Some(AstStmt::VariableDecl(decl))),
                 '/' => match self.toks().peek_n(1) {
                     Some(Token { kind: '/', .. }) => {
-                        stmts.push(self.parse_silent_comment()?);
+                        let comment = self.parse_silent_comment().map(Some);
                         self.whitespace_without_comments();
+                        comment
                     }
                     Some(Token { kind: '*', .. }) => {
-                        stmts.push(AstStmt::LoudComment(self.parse_loud_comment()?));
+                        let comment = self
+                            .parse_loud_comment()
+                            .map(|comment| Some(AstStmt::LoudComment(comment)));
                         self.whitespace_without_comments();
+                        comment
                     }
-                    _ => {
-                        if let Some(stmt) = statement(self)? {
-                            stmts.push(stmt);
-                        }
-                    }
+                    _ => statement(self),
                 },
                 ';' => {
                     self.toks_mut().next();
                     self.whitespace_without_comments();
+                    continue;
                 }
-                _ => {
-                    if let Some(stmt) = statement(self)? {
-                        stmts.push(stmt);
-                    }
-                }
+                _ => statement(self),
+            };
+
+            match stmt {
+                Ok(Some(stmt)) => stmts.push(stmt),
+                Ok(None) => {}
+                Err(e) => self.recover_from_error(e, start, flags)?,
             }
         }
 
         Ok(stmts)
     }
 
+    /// If this parser is recovering from errors, records `err` and skips to the
+    /// end of the statement that began at `start`. Otherwise, returns `err`
+    fn recover_from_error(
+        &mut self,
+        err: Box<SassError>,
+        start: usize,
+        flags: ContextFlags,
+    ) -> SassResult<()> {
+        match self.recovered_errors() {
+            Some(errors) => errors.push(*err),
+            None => return Err(err),
+        }
+
+        *self.flags_mut() = flags;
+        self.skip_to_end_of_statement(start);
+        self.whitespace_without_comments();
+
+        Ok(())
+    }
+
+    /// Consumes tokens up to and including the `;` or `}` that ends the current
+    /// statement, stopping before any `}` that closes the enclosing block
+    fn skip_to_end_of_statement(&mut self, start: usize) {
+        let mut depth = 0_usize;
+
+        while let Some(tok) = self.toks().peek() {
+            match tok.kind {
+                quote @ ('"' | '\'') => {
+                    self.toks_mut().next();
+                    while let Some(next) = self.toks_mut().next() {
+                        match next.kind {
+                            '\\' => {
+                                self.toks_mut().next();
+                            }
+                            '\n' => break,
+                            c if c == quote => break,
+                            _ => {}
+                        }
+                    }
+                    continue;
+                }
+                '/' if matches!(self.toks().peek_n(1), Some(Token { kind: '*', .. })) => {
+                    self.toks_mut().next();
+                    self.toks_mut().next();
+                    while let Some(next) = self.toks_mut().next() {
+                        if next.kind == '*' && self.scan_char('/') {
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                '/' if !self.is_plain_css()
+                    && matches!(self.toks().peek_n(1), Some(Token { kind: '/', .. })) =>
+                {
+                    while let Some(next) = self.toks_mut().next() {
+                        if next.kind == '\n' {
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    // always make progress, even if the statement began on a
+                    // stray closing brace
+                    if self.toks().cursor() == start {
+                        self.toks_mut().next();
+                    }
+                    return;
+                }
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.toks_mut().next();
+                        return;
+                    }
+                }
+                ';' if depth == 0 => {
+                    self.toks_mut().next();
+                    return;
+                }
+                _ => {}
+            }
+
+            self.toks_mut().next();
+        }
+    }
+
     // todo: rename
     fn __parse(&mut self) -> SassResult<StyleSheet> {
         let mut style_sheet = StyleSheet::new(
@@ -245,6 +360,7 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
     }
 
     fn parse_argument_declaration(&mut self) -> SassResult<ArgumentDeclaration> {
+        let start = self.toks().cursor();
         self.expect_char('(')?;
         self.whitespace()?;
 
@@ -291,6 +407,7 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
         Ok(ArgumentDeclaration {
             args: arguments,
             rest: rest_argument,
+            span: self.toks_mut().span_from(start),
         })
     }
 
@@ -467,7 +584,9 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
                 .into());
         }
 
+        let value_start = self.toks().cursor();
         let value = self.almost_any_value(false)?;
+        let value_span = self.toks_mut().span_from(value_start);
 
         let is_optional = self.scan_char('!');
 
@@ -479,6 +598,7 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
 
         Ok(AstStmt::Extend(AstExtendRule {
             value,
+            value_span,
             is_optional,
             span: self.toks_mut().span_from(start),
         }))
@@ -580,14 +700,14 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
 
         let children = self.with_children(Self::function_child)?.node;
 
-        Ok(AstStmt::FunctionDecl(AstFunctionDecl {
+        Ok(AstStmt::FunctionDecl(Arc::new(AstFunctionDecl {
             name: Spanned {
                 node: Identifier::from(name),
                 span: name_span,
             },
             arguments,
             body: children,
-        }))
+        })))
     }
 
     fn parse_variable_declaration_with_namespace(&mut self) -> SassResult<AstVariableDecl> {
@@ -646,7 +766,7 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
             "for" => self.parse_for_rule(Self::function_child),
             "if" => self.parse_if_rule(Self::function_child),
             "return" => self.parse_return_rule(),
-            "warn" => self.parse_warn_rule(),
+            "warn" => self.parse_warn_rule(start),
             "while" => self.parse_while_rule(Self::function_child),
             _ => self.parse_disallowed_at_rule(start),
         };
@@ -965,17 +1085,18 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
             None
         };
 
-        let mut content_block: Option<AstContentBlock> = None;
+        let mut content_block: Option<Arc<AstContentBlock>> = None;
 
         if content_args.is_some() || self.looking_at_children()? {
-            let content_args = content_args.unwrap_or_else(ArgumentDeclaration::empty);
+            let content_args = content_args
+                .unwrap_or_else(|| ArgumentDeclaration::empty(self.toks().current_span()));
             let was_in_content_block = self.flags().in_content_block();
             self.flags_mut().set(ContextFlags::IN_CONTENT_BLOCK, true);
             let body = self.with_children(Self::parse_statement)?.node;
-            content_block = Some(AstContentBlock {
+            content_block = Some(Arc::new(AstContentBlock {
                 args: content_args,
                 body,
-            });
+            }));
             self.flags_mut()
                 .set(ContextFlags::IN_CONTENT_BLOCK, was_in_content_block);
         } else {
@@ -1023,7 +1144,21 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
 
         let mut found_match = false;
 
-        while let Some(next) = self.toks().peek() {
+        loop {
+            buffer.add_str(self.toks_mut().take_until(&[
+                quote as u8,
+                b'\\',
+                b'#',
+                b'\n',
+                b'\r',
+                b'\x0C',
+            ]));
+
+            let next = match self.toks().peek() {
+                Some(next) => next,
+                None => break,
+            };
+
             match next.kind {
                 c if c == quote => {
                     self.toks_mut().next();
@@ -1086,7 +1221,7 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
         let args = if self.toks_mut().next_char_is('(') {
             self.parse_argument_declaration()?
         } else {
-            ArgumentDeclaration::empty()
+            ArgumentDeclaration::empty(self.toks().current_span())
         };
 
         if self.flags().in_mixin() || self.flags().in_content_block() {
@@ -1118,12 +1253,12 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
             .set(ContextFlags::FOUND_CONTENT_RULE, old_found_content_rule);
         self.flags_mut().set(ContextFlags::IN_MIXIN, false);
 
-        Ok(AstStmt::Mixin(AstMixin {
+        Ok(AstStmt::Mixin(Arc::new(AstMixin {
             name,
             args,
             body,
             has_content,
-        }))
+        })))
     }
 
     fn _parse_moz_document_rule(&mut self, _name: Interpolation) -> SassResult<AstStmt> {
@@ -1392,12 +1527,12 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
         }))
     }
 
-    fn parse_warn_rule(&mut self) -> SassResult<AstStmt> {
+    fn parse_warn_rule(&mut self, start: usize) -> SassResult<AstStmt> {
         let value = self.parse_expression(None, None, None)?;
         self.expect_statement_separator(Some("@warn rule"))?;
         Ok(AstStmt::Warn(AstWarn {
             value: value.node,
-            span: value.span,
+            span: self.toks().span_from(start),
         }))
     }
 
@@ -1532,7 +1667,14 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
             });
         }
 
-        let base_name = url
+        // `pkg:bootstrap` is namespaced as `bootstrap`
+        let package_url = url
+            .to_str()
+            .and_then(|url| url.strip_prefix("pkg:"))
+            .map(Path::new);
+
+        let base_name = package_url
+            .unwrap_or(url)
             .file_name()
             .map_or_else(OsString::new, ToOwned::to_owned);
         let base_name = base_name.to_string_lossy();
@@ -1546,7 +1688,7 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
             &base_name[start..end]
         };
 
-        let mut toks = Lexer::new_from_string(namespace, url_span);
+        let mut toks = Lexer::new_from_string(namespace.to_owned(), url_span);
 
         // if namespace is empty, avoid attempting to parse an identifier from
         // an empty string, as there will be no span to emit
@@ -1725,7 +1867,7 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
                 // }
                 self.parse_use_rule(start)
             }
-            Some("warn") => self.parse_warn_rule(),
+            Some("warn") => self.parse_warn_rule(start),
             Some("while") => self.parse_while_rule(child),
             Some(..) | None => self.unknown_at_rule(name, start),
         }
@@ -1940,7 +2082,17 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
     }
 
     fn parse_interpolated_identifier_body(&mut self, buffer: &mut Interpolation) -> SassResult<()> {
-        while let Some(next) = self.toks().peek() {
+        loop {
+            buffer.add_str(
+                self.toks_mut()
+                    .take_while(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-'),
+            );
+
+            let next = match self.toks().peek() {
+                Some(next) => next,
+                None => break,
+            };
+
             match next.kind {
                 'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '\u{80}'..=std::char::MAX => {
                     buffer.add_char(next.kind);
@@ -2530,7 +2682,7 @@ pub(crate) trait StylesheetParser<'a>: BaseParser<'a> + Sized {
             "for" => self.parse_for_rule(Self::parse_declaration_child),
             "if" => self.parse_if_rule(Self::parse_declaration_child),
             "include" => self.parse_include_rule(),
-            "warn" => self.parse_warn_rule(),
+            "warn" => self.parse_warn_rule(start),
             "while" => self.parse_while_rule(Self::parse_declaration_child),
             _ => self.parse_disallowed_at_rule(start),
         }
@@ -8,7 +8,7 @@ use crate::{
     common::{unvendor, BinaryOp, Brackets, Identifier, ListSeparator, QuoteKind, UnaryOp},
     error::SassResult,
     unit::Unit,
-    utils::{as_hex, opposite_bracket},
+    utils::{as_hex, ensure_sufficient_stack, opposite_bracket},
     value::{CalculationName, Number},
     ContextFlags, Token,
 };
@@ -50,39 +50,42 @@ impl<'a, 'c, P: StylesheetParser<'a>> ValueParser<'a, 'c, P> {
         inside_bracketed_list: bool,
         single_equals: bool,
     ) -> SassResult<Spanned<AstExpr>> {
-        let start = parser.toks().cursor();
-        let mut value_parser = Self::new(parser, parse_until, inside_bracketed_list, single_equals);
+        ensure_sufficient_stack(|| {
+            let start = parser.toks().cursor();
+            let mut value_parser =
+                Self::new(parser, parse_until, inside_bracketed_list, single_equals);
 
-        if let Some(parse_until) = value_parser.parse_until {
-            if parse_until(parser)? {
-                return Err(("Expected expression.", parser.toks().current_span()).into());
+            if let Some(parse_until) = value_parser.parse_until {
+                if parse_until(parser)? {
+                    return Err(("Expected expression.", parser.toks().current_span()).into());
+                }
             }
-        }
 
-        if value_parser.inside_bracketed_list {
-            let bracket_start = parser.toks().cursor();
+            if value_parser.inside_bracketed_list {
+                let bracket_start = parser.toks().cursor();
 
-            parser.expect_char('[')?;
-            parser.whitespace()?;
+                parser.expect_char('[')?;
+                parser.whitespace()?;
 
-            if parser.scan_char(']') {
-                return Ok(AstExpr::List(ListExpr {
-                    elems: Vec::new(),
-                    separator: ListSeparator::Undecided,
-                    brackets: Brackets::Bracketed,
-                })
-                .span(parser.toks_mut().span_from(bracket_start)));
-            }
-        };
+                if parser.scan_char(']') {
+                    return Ok(AstExpr::List(ListExpr {
+                        elems: Vec::new(),
+                        separator: ListSeparator::Undecided,
+                        brackets: Brackets::Bracketed,
+                    })
+                    .span(parser.toks_mut().span_from(bracket_start)));
+                }
+            };
 
-        value_parser.start = parser.toks().cursor();
+            value_parser.start = parser.toks().cursor();
 
-        value_parser.single_expression = Some(value_parser.parse_single_expression(parser)?);
+            value_parser.single_expression = Some(value_parser.parse_single_expression(parser)?);
 
-        let mut value = value_parser.parse_value(parser)?;
-        value.span = parser.toks_mut().span_from(start);
+            let mut value = value_parser.parse_value(parser)?;
+            value.span = parser.toks_mut().span_from(start);
 
-        Ok(value)
+            Ok(value)
+        })
     }
 
     pub fn new(
@@ -1718,11 +1721,14 @@ impl<'a, 'c, P: StylesheetParser<'a>> ValueParser<'a, 'c, P> {
             "calc" => {
                 let args = ValueParser::parse_calculation_arguments(parser, Some(1), start)?;
 
+                let span = parser.toks_mut().span_from(start);
+
                 AstExpr::Calculation {
                     name: CalculationName::Calc,
                     args,
+                    span,
                 }
-                .span(parser.toks_mut().span_from(start))
+                .span(span)
             }
             "min" | "max" => {
                 // min() and max() are parsed as calculations if possible, and otherwise
@@ -1737,6 +1743,8 @@ impl<'a, 'c, P: StylesheetParser<'a>> ValueParser<'a, 'c, P> {
                     }
                 };
 
+                let span = parser.toks_mut().span_from(start);
+
                 AstExpr::Calculation {
                     name: if name == "min" {
                         CalculationName::Min
@@ -1744,16 +1752,20 @@ impl<'a, 'c, P: StylesheetParser<'a>> ValueParser<'a, 'c, P> {
                         CalculationName::Max
                     },
                     args,
+                    span,
                 }
-                .span(parser.toks_mut().span_from(start))
+                .span(span)
             }
             "clamp" => {
                 let args = ValueParser::parse_calculation_arguments(parser, Some(3), start)?;
+                let span = parser.toks_mut().span_from(start);
+
                 AstExpr::Calculation {
                     name: CalculationName::Clamp,
                     args,
+                    span,
                 }
-                .span(parser.toks_mut().span_from(start))
+                .span(span)
             }
             _ => return Ok(None),
         }))
@@ -0,0 +1,509 @@
+//! Custom functions loaded at runtime from native plugins
+//!
+//! A plugin is a shared library (a `.so`, `.dylib`, or `.dll`) exporting a
+//! `grass_plugin` function, which returns a table of the Sass functions it
+//! defines. This allows site-specific functions to be added to a prebuilt
+//! `grass` binary with `--plugin`, or to any program embedding grass with
+//! [`Options::plugin`](crate::Options::plugin), without recompiling it.
+//!
+//! In C, a plugin looks like:
+//!
+//! ```c
+//! #include <stddef.h>
+//! #include <stdint.h>
+//! #include <stdlib.h>
+//! #include <string.h>
+//!
+//! typedef int32_t (*GrassPluginCall)(const char *const *args, size_t arg_count, char **result);
+//!
+//! typedef struct {
+//!     const char *signature;
+//!     GrassPluginCall call;
+//! } GrassPluginFunction;
+//!
+//! typedef struct {
+//!     uint32_t abi_version;
+//!     const GrassPluginFunction *functions;
+//!     size_t function_count;
+//!     void (*free_result)(char *result);
+//! } GrassPlugin;
+//!
+//! static int32_t brand_color(const char *const *args, size_t arg_count, char **result) {
+//!     *result = strdup(args[0] != NULL && strcmp(args[0], "dark") == 0 ? "#111" : "#e0245e");
+//!     return 0;
+//! }
+//!
+//! static const GrassPluginFunction FUNCTIONS[] = {
+//!     {"brand-color($variant: null)", brand_color},
+//! };
+//!
+//! static const GrassPlugin PLUGIN = {1, FUNCTIONS, 1, free};
+//!
+//! const GrassPlugin *grass_plugin(void) { return &PLUGIN; }
+//! ```
+//!
+//! Each function is declared with a signature such as `foo($a, $b: 1px,
+//! $rest...)`, and arguments are bound to its parameters as they would be for
+//! a function defined in Sass. Values are passed as Sass source: each argument
+//! is written as by `meta.inspect()`, an omitted argument with a default value
+//! is passed as a null pointer, and a rest parameter is passed as a single
+//! list. A function returns `0` and sets `*result` to the Sass source of the
+//! value it returns, or returns any other number and sets `*result` to an error
+//! message. `*result` is passed to `free_result`, if it is not null, once grass
+//! has read it.
+//!
+//! Plugins written in Rust can use [`PluginTable`] and [`PluginFunction`] in
+//! place of these declarations.
+
+use std::{
+    ffi::{CStr, CString},
+    fmt, io,
+    os::raw::{c_char, c_int, c_void},
+    path::{Path, PathBuf},
+    ptr,
+};
+
+use codemap::Span;
+
+use crate::{
+    ast::ArgumentResult,
+    common::{Brackets, Identifier, ListSeparator},
+    error::SassResult,
+    evaluate::Visitor,
+    utils::{parse_signature, Parameter},
+    value::Value,
+};
+
+/// The version of the plugin interface described in the [module
+/// documentation](self), which plugins must set as
+/// [`PluginTable::abi_version`]
+pub const ABI_VERSION: u32 = 1;
+
+/// The name of the function every plugin exports, which takes no arguments and
+/// returns a pointer to its [`PluginTable`]
+pub const ENTRY_POINT: &str = "grass_plugin";
+
+/// The implementation of a plugin function
+///
+/// This is passed `arg_count` arguments, and a pointer to write its result or
+/// error message to.
+pub type PluginCall = unsafe extern "C" fn(
+    args: *const *const c_char,
+    arg_count: usize,
+    result: *mut *mut c_char,
+) -> c_int;
+
+/// A Sass function defined by a plugin
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PluginFunction {
+    /// The function's name and parameters, such as `foo($a, $b: 1px)`, as a
+    /// NUL-terminated UTF-8 string
+    pub signature: *const c_char,
+    pub call: PluginCall,
+}
+
+/// The table of functions returned by a plugin's entry point, which must
+/// remain valid for as long as the plugin is loaded
+#[repr(C)]
+#[derive(Debug)]
+pub struct PluginTable {
+    /// Must be [`ABI_VERSION`]
+    pub abi_version: u32,
+    pub functions: *const PluginFunction,
+    pub function_count: usize,
+    /// Frees the results and error messages returned by the plugin's functions
+    pub free_result: Option<unsafe extern "C" fn(result: *mut c_char)>,
+}
+
+// allows tables to be declared as statics by plugins written in Rust
+unsafe impl Sync for PluginFunction {}
+unsafe impl Sync for PluginTable {}
+
+struct LoadedFunction {
+    name: String,
+    parameters: Vec<Parameter>,
+    call: PluginCall,
+}
+
+/// A plugin loaded from a shared library, which is unloaded when dropped
+pub struct Plugin {
+    path: PathBuf,
+    functions: Vec<LoadedFunction>,
+    free_result: Option<unsafe extern "C" fn(result: *mut c_char)>,
+    // only held to keep the library loaded, and dropped last, as the
+    // functions point into it
+    _library: Library,
+}
+
+// `Plugin::load` requires that the plugin's functions may be called from any
+// thread
+unsafe impl Send for Plugin {}
+unsafe impl Sync for Plugin {}
+
+impl fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Plugin")
+            .field("path", &self.path)
+            .field("functions", &self.function_names().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Plugin {
+    /// Load the plugin in the shared library at `path`
+    ///
+    /// # Safety
+    ///
+    /// Loading a library runs its initialization code, and its functions are
+    /// called without any checks beyond those of its [`PluginTable`], so the
+    /// library must be a grass plugin implementing the interface described in
+    /// the [module documentation](self). Its functions may be called from
+    /// several threads at once.
+    pub unsafe fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let library = Library::open(path)?;
+
+        let entry_point = library.symbol(ENTRY_POINT).ok_or_else(|| {
+            invalid(format!(
+                "plugins must export a function named {}",
+                ENTRY_POINT
+            ))
+        })?;
+        let entry_point: unsafe extern "C" fn() -> *const PluginTable =
+            std::mem::transmute(entry_point);
+
+        let table = match entry_point().as_ref() {
+            Some(table) => table,
+            None => return Err(invalid(format!("{} returned a null pointer", ENTRY_POINT))),
+        };
+
+        if table.abi_version != ABI_VERSION {
+            return Err(invalid(format!(
+                "the plugin was built for version {} of the plugin interface, but only version {} is supported",
+                table.abi_version, ABI_VERSION
+            )));
+        }
+
+        let entries = if table.function_count == 0 {
+            &[]
+        } else if table.functions.is_null() {
+            return Err(invalid("the function table is a null pointer".to_owned()));
+        } else {
+            std::slice::from_raw_parts(table.functions, table.function_count)
+        };
+
+        let mut functions = Vec::with_capacity(entries.len());
+
+        for entry in entries {
+            if entry.signature.is_null() {
+                return Err(invalid(
+                    "a function's signature is a null pointer".to_owned(),
+                ));
+            }
+
+            let signature = CStr::from_ptr(entry.signature)
+                .to_str()
+                .map_err(|_| invalid("a function's signature is not valid UTF-8".to_owned()))?;
+            let (name, parameters) = parse_signature(signature);
+
+            if name.is_empty() {
+                return Err(invalid(format!("{:?} is not a valid signature", signature)));
+            }
+
+            functions.push(LoadedFunction {
+                // looked up as written in stylesheets, where `_` and `-` are
+                // interchangeable
+                name: Identifier::from(name).as_str().to_owned(),
+                parameters,
+                call: entry.call,
+            });
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            functions,
+            free_result: table.free_result,
+            _library: library,
+        })
+    }
+
+    /// The path the plugin was loaded from
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The names of the functions the plugin defines
+    pub fn function_names(&self) -> impl Iterator<Item = &str> {
+        self.functions.iter().map(|function| function.name.as_str())
+    }
+
+    fn function(&self, name: &str) -> Option<&LoadedFunction> {
+        self.functions
+            .iter()
+            .rev()
+            .find(|function| function.name == name)
+    }
+
+    /// Call `function` with the Sass source of each argument, returning the
+    /// source of its result or its error message
+    fn call(
+        &self,
+        function: &LoadedFunction,
+        arguments: &[Option<CString>],
+    ) -> Result<String, String> {
+        let pointers: Vec<*const c_char> = arguments
+            .iter()
+            .map(|argument| argument.as_ref().map_or(ptr::null(), |arg| arg.as_ptr()))
+            .collect();
+
+        let mut result: *mut c_char = ptr::null_mut();
+
+        // safety: `Plugin::load` requires that this is a valid plugin function
+        let status = unsafe { (function.call)(pointers.as_ptr(), pointers.len(), &mut result) };
+
+        let text = if result.is_null() {
+            None
+        } else {
+            let text = unsafe { CStr::from_ptr(result) }
+                .to_string_lossy()
+                .into_owned();
+
+            if let Some(free_result) = self.free_result {
+                unsafe { free_result(result) };
+            }
+
+            Some(text)
+        };
+
+        match (status, text) {
+            (0, Some(text)) => Ok(text),
+            (0, None) => Err("The plugin function returned no value.".to_owned()),
+            (_, Some(message)) => Err(message),
+            (status, None) => Err(format!(
+                "The plugin function failed with status {}.",
+                status
+            )),
+        }
+    }
+}
+
+/// The implementation of every function registered by
+/// [`Options::plugin`](crate::Options::plugin), which calls the plugin
+/// function of the name it was called by
+pub(crate) fn call_plugin_function(
+    mut args: ArgumentResult,
+    visitor: &mut Visitor,
+) -> SassResult<Value> {
+    let span = args.span();
+    let name = match visitor.current_builtin {
+        Some(name) => name,
+        None => return Err(("Undefined function.", span).into()),
+    };
+
+    // later plugins take precedence, as they do in `Options::custom_fns`
+    let options = visitor.options;
+    let found = options
+        .plugins
+        .iter()
+        .rev()
+        .find_map(|plugin| Some((plugin, plugin.function(name.as_str())?)));
+
+    let (plugin, function) = match found {
+        Some(found) => found,
+        None => return Err(("Undefined function.", span).into()),
+    };
+
+    let arguments = bind_arguments(&function.parameters, &mut args, span)?;
+
+    let returned = plugin
+        .call(function, &arguments)
+        .map_err(|message| (message, span))?;
+
+    visitor.evaluate_source(&returned, span).map_err(|e| {
+        (
+            format!(
+                "Function {} returned invalid Sass: {}",
+                name,
+                e.raw_message().unwrap_or("Expected expression.")
+            ),
+            span,
+        )
+            .into()
+    })
+}
+
+/// The Sass source of the argument passed for each of `parameters`, or `None`
+/// for omitted arguments with default values
+fn bind_arguments(
+    parameters: &[Parameter],
+    args: &mut ArgumentResult,
+    span: Span,
+) -> SassResult<Vec<Option<CString>>> {
+    if !parameters.iter().any(|parameter| parameter.is_rest) {
+        args.max_args(parameters.len())?;
+    }
+
+    let source = |value: &Value| -> SassResult<Option<CString>> {
+        CString::new(value.inspect(span)?).map(Some).map_err(|_| {
+            (
+                "Plugin function arguments can't contain null characters.",
+                span,
+            )
+                .into()
+        })
+    };
+
+    let mut arguments = Vec::with_capacity(parameters.len());
+
+    for (idx, parameter) in parameters.iter().enumerate() {
+        if parameter.is_rest {
+            let rest = args.positional.split_off(idx.min(args.positional.len()));
+            if let Some((name, _)) = args.named.iter().next() {
+                return Err((format!("No argument named ${}.", name), span).into());
+            }
+
+            let separator = match args.separator {
+                ListSeparator::Undecided => ListSeparator::Comma,
+                separator => separator,
+            };
+
+            arguments.push(source(&Value::List(rest, separator, Brackets::None))?);
+            break;
+        }
+
+        match args.get(idx, parameter.name.as_str()) {
+            Some(value) => arguments.push(source(&value.node)?),
+            None if parameter.has_default => arguments.push(None),
+            None => return Err((format!("Missing argument ${}.", parameter.name), span).into()),
+        }
+    }
+
+    if let Some((name, _)) = args.named.iter().next() {
+        return Err((format!("No argument named ${}.", name), span).into());
+    }
+
+    Ok(arguments)
+}
+
+/// A handle to a shared library opened with `dlopen`
+#[cfg(unix)]
+struct Library(*mut c_void);
+
+#[cfg(unix)]
+impl Library {
+    unsafe fn open(path: &Path) -> io::Result<Self> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        let handle = libc::dlopen(path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL);
+
+        if handle.is_null() {
+            return Err(io::Error::other(last_dl_error()));
+        }
+
+        Ok(Self(handle))
+    }
+
+    unsafe fn symbol(&self, name: &str) -> Option<*mut c_void> {
+        let name = CString::new(name).ok()?;
+        let symbol = libc::dlsym(self.0, name.as_ptr());
+
+        if symbol.is_null() {
+            None
+        } else {
+            Some(symbol)
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Library {
+    fn drop(&mut self) {
+        unsafe { libc::dlclose(self.0) };
+    }
+}
+
+#[cfg(unix)]
+unsafe fn last_dl_error() -> String {
+    let error = libc::dlerror();
+
+    if error.is_null() {
+        "failed to load library".to_owned()
+    } else {
+        CStr::from_ptr(error).to_string_lossy().into_owned()
+    }
+}
+
+/// A handle to a shared library opened with `LoadLibraryW`
+#[cfg(windows)]
+struct Library(*mut c_void);
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn LoadLibraryW(file_name: *const u16) -> *mut c_void;
+    fn GetProcAddress(module: *mut c_void, name: *const c_char) -> *mut c_void;
+    fn FreeLibrary(module: *mut c_void) -> i32;
+}
+
+#[cfg(windows)]
+impl Library {
+    unsafe fn open(path: &Path) -> io::Result<Self> {
+        use std::os::windows::ffi::OsStrExt;
+
+        let path: Vec<u16> = path
+            .as_os_str()
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+
+        let handle = LoadLibraryW(path.as_ptr());
+
+        if handle.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self(handle))
+    }
+
+    unsafe fn symbol(&self, name: &str) -> Option<*mut c_void> {
+        let name = CString::new(name).ok()?;
+        let symbol = GetProcAddress(self.0, name.as_ptr());
+
+        if symbol.is_null() {
+            None
+        } else {
+            Some(symbol)
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Library {
+    fn drop(&mut self) {
+        unsafe { FreeLibrary(self.0) };
+    }
+}
+
+/// Shared libraries can't be loaded on this platform
+#[cfg(not(any(unix, windows)))]
+struct Library;
+
+#[cfg(not(any(unix, windows)))]
+impl Library {
+    unsafe fn open(_path: &Path) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "plugins can't be loaded on this platform",
+        ))
+    }
+
+    unsafe fn symbol(&self, _name: &str) -> Option<*mut c_void> {
+        None
+    }
+}
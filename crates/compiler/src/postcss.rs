@@ -0,0 +1,384 @@
+//! Serialization of the evaluated CSS tree as PostCSS nodes
+//!
+//! The output matches what PostCSS's `Node#toJSON()` produces, and can be
+//! revived with `postcss.fromJSON()`. Every node's `raws` hold the whitespace
+//! grass would have written around it in the current output style, so that
+//! stringifying the tree with PostCSS gives the same CSS as compiling with
+//! grass.
+
+use codemap::{CodeMap, Span};
+use serde::Serialize;
+
+use crate::{
+    ast::{CssStmt, Style},
+    error::SassResult,
+    json,
+    serializer::{
+        serialize_comment, serialize_media_queries, serialize_selector_list, serialize_value,
+    },
+    Options,
+};
+
+/// A PostCSS node, in the form of `Node#toJSON()`
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Node {
+    Root {
+        nodes: Vec<Node>,
+        raws: Raws,
+    },
+    Rule {
+        selector: String,
+        nodes: Vec<Node>,
+        raws: Raws,
+    },
+    Decl {
+        prop: String,
+        value: String,
+        #[serde(skip_serializing_if = "is_false")]
+        important: bool,
+        raws: Raws,
+    },
+    #[serde(rename = "atrule")]
+    AtRule {
+        name: String,
+        params: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        nodes: Option<Vec<Node>>,
+        raws: Raws,
+    },
+    Comment {
+        text: String,
+        raws: Raws,
+    },
+}
+
+/// The whitespace and punctuation around a node, each of which is only
+/// written for the kinds of node that have it
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Raws {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after_name: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    left: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    right: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    between: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    semicolon: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    important: Option<String>,
+}
+
+pub(crate) struct PostCssSerializer<'a> {
+    options: &'a Options<'a>,
+    map: &'a CodeMap,
+    span: Span,
+    indentation: usize,
+}
+
+impl<'a> PostCssSerializer<'a> {
+    pub fn new(options: &'a Options<'a>, map: &'a CodeMap, span: Span) -> Self {
+        Self {
+            options,
+            map,
+            span,
+            indentation: 0,
+        }
+    }
+
+    pub fn write_root(mut self, stmts: &[CssStmt]) -> SassResult<String> {
+        let json = json::to_string(&self.root(stmts, false)?);
+
+        // the non-ascii characters requiring a charset can only be found once
+        // the tree is written
+        let needs_charset =
+            !json.is_ascii() && self.options.allows_charset && !self.options.is_compressed();
+
+        if needs_charset {
+            return Ok(json::to_string(&self.root(stmts, true)?));
+        }
+
+        Ok(json)
+    }
+
+    fn root(&mut self, stmts: &[CssStmt], charset: bool) -> SassResult<Node> {
+        let mut nodes = Vec::new();
+
+        if charset {
+            nodes.push(Node::AtRule {
+                name: "charset".to_owned(),
+                params: "\"UTF-8\"".to_owned(),
+                nodes: None,
+                raws: Raws {
+                    before: Some(String::new()),
+                    after_name: Some(" "),
+                    between: Some(""),
+                    ..Raws::default()
+                },
+            });
+        }
+
+        let options = self.options;
+        let mut prev_was_group_end = false;
+
+        for stmt in stmts.iter().filter(|stmt| !stmt.is_invisible(options)) {
+            let before = match (nodes.is_empty(), prev_was_group_end) {
+                _ if self.options.is_compressed() => "",
+                (true, _) => "",
+                (false, false) => "\n",
+                (false, true) => "\n\n",
+            };
+
+            nodes.push(self.node(stmt, before)?);
+            prev_was_group_end = stmt.is_group_end();
+        }
+
+        let after = if nodes.is_empty() || self.options.is_compressed() {
+            ""
+        } else {
+            "\n"
+        };
+
+        Ok(Node::Root {
+            nodes,
+            raws: Raws {
+                semicolon: Some(true),
+                after: Some(after.to_owned()),
+                ..Raws::default()
+            },
+        })
+    }
+
+    fn node(&mut self, stmt: &CssStmt, before: &str) -> SassResult<Node> {
+        match stmt {
+            CssStmt::RuleSet { selector, body, .. } => {
+                let selector =
+                    serialize_selector_list(&selector.as_selector_list(), self.options, self.span);
+                self.rule(selector, body, before)
+            }
+            CssStmt::KeyframesRuleSet(keyframes_rule_set) => {
+                let selector = keyframes_rule_set
+                    .selector
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                self.rule(selector, &keyframes_rule_set.body, before)
+            }
+            CssStmt::Style(style) => self.decl(style, before),
+            CssStmt::Media(media_rule, ..) => {
+                let params = serialize_media_queries(&media_rule.query, self.options, self.span);
+                self.at_rule("media", params, Some(&media_rule.body), before)
+            }
+            CssStmt::UnknownAtRule(unknown_at_rule, ..) => {
+                let body = if unknown_at_rule.has_body {
+                    Some(unknown_at_rule.body.as_slice())
+                } else {
+                    None
+                };
+
+                self.at_rule(
+                    &unknown_at_rule.name,
+                    unknown_at_rule.params.clone(),
+                    body,
+                    before,
+                )
+            }
+            CssStmt::Supports(supports_rule, _) => self.at_rule(
+                "supports",
+                supports_rule.params.clone(),
+                Some(&supports_rule.body),
+                before,
+            ),
+            CssStmt::Import(import, modifiers) => {
+                let params = match modifiers {
+                    Some(modifiers) => format!("{} {}", import, modifiers),
+                    None => import.clone(),
+                };
+                self.at_rule("import", params, None, before)
+            }
+            CssStmt::Comment(comment, span) => self.comment(comment, *span, before),
+        }
+    }
+
+    fn rule(&mut self, selector: String, body: &[CssStmt], before: &str) -> SassResult<Node> {
+        let nodes = self.nodes(body)?;
+
+        Ok(Node::Rule {
+            selector,
+            nodes,
+            raws: Raws {
+                before: Some(before.to_owned()),
+                between: Some(optional_space(self.options)),
+                ..self.block_raws(body)
+            },
+        })
+    }
+
+    fn decl(&mut self, style: &Style, before: &str) -> SassResult<Node> {
+        let mut value = serialize_value(&style.value.node, self.options, style.value.span)?;
+
+        // postcss stores `!important` separately from the value
+        let mut important = None;
+        if !style.declared_as_custom_property && value.ends_with("!important") {
+            let without_flag = value.len() - "!important".len();
+            let start = value[..without_flag].trim_end().len();
+
+            if start > 0 {
+                important = Some(value.split_off(start));
+            }
+        }
+
+        let between = if style.declared_as_custom_property || self.options.is_compressed() {
+            ":"
+        } else {
+            ": "
+        };
+
+        Ok(Node::Decl {
+            prop: style.property.resolve_ref().to_owned(),
+            value,
+            important: important.is_some(),
+            raws: Raws {
+                before: Some(before.to_owned()),
+                between: Some(between),
+                important,
+                ..Raws::default()
+            },
+        })
+    }
+
+    fn at_rule(
+        &mut self,
+        name: &str,
+        params: String,
+        body: Option<&[CssStmt]>,
+        before: &str,
+    ) -> SassResult<Node> {
+        let nodes = body.map(|body| self.nodes(body)).transpose()?;
+
+        let block_raws = match body {
+            // a body with no visible children is written as ` {}`, even when
+            // compressed
+            Some(body) if body.iter().all(|stmt| stmt.is_invisible(self.options)) => Raws {
+                between: Some(" "),
+                semicolon: Some(false),
+                after: Some(String::new()),
+                ..Raws::default()
+            },
+            Some(body) => Raws {
+                between: Some(optional_space(self.options)),
+                ..self.block_raws(body)
+            },
+            None => Raws {
+                between: Some(""),
+                ..Raws::default()
+            },
+        };
+
+        let raws = Raws {
+            before: Some(before.to_owned()),
+            after_name: Some(if params.is_empty() { "" } else { " " }),
+            ..block_raws
+        };
+
+        Ok(Node::AtRule {
+            name: name.to_owned(),
+            params,
+            nodes,
+            raws,
+        })
+    }
+
+    fn comment(&mut self, comment: &str, span: Span, before: &str) -> SassResult<Node> {
+        let comment = serialize_comment(comment, self.options, self.map, span)?;
+        let inner = comment
+            .strip_prefix("/*")
+            .and_then(|comment| comment.strip_suffix("*/"))
+            .unwrap_or(&comment);
+
+        let text = inner.trim();
+        let (left, right) = if text.is_empty() {
+            (inner, "")
+        } else {
+            let left = &inner[..inner.len() - inner.trim_start().len()];
+            let right = &inner[inner.trim_end().len()..];
+            (left, right)
+        };
+
+        Ok(Node::Comment {
+            text: text.to_owned(),
+            raws: Raws {
+                before: Some(before.to_owned()),
+                left: Some(left.to_owned()),
+                right: Some(right.to_owned()),
+                ..Raws::default()
+            },
+        })
+    }
+
+    /// The `nodes` of a node whose children are `body`
+    fn nodes(&mut self, body: &[CssStmt]) -> SassResult<Vec<Node>> {
+        self.indentation += 2;
+        let before = if self.options.is_compressed() {
+            String::new()
+        } else {
+            format!("\n{}", " ".repeat(self.indentation))
+        };
+
+        let options = self.options;
+        let nodes = body
+            .iter()
+            .filter(|stmt| !stmt.is_invisible(options))
+            .map(|stmt| self.node(stmt, &before))
+            .collect();
+
+        self.indentation -= 2;
+
+        nodes
+    }
+
+    /// The `semicolon` and `after` raws of a node whose children are `body`,
+    /// at least one of which is visible
+    fn block_raws(&self, body: &[CssStmt]) -> Raws {
+        if self.options.is_compressed() {
+            // grass omits only the semicolon of the very last child, even if it
+            // is not written
+            let semicolon = !matches!(
+                body.last(),
+                Some(last) if !last.is_invisible(self.options) && !matches!(last, CssStmt::Comment(..))
+            );
+
+            Raws {
+                semicolon: Some(semicolon),
+                after: Some(String::new()),
+                ..Raws::default()
+            }
+        } else {
+            Raws {
+                semicolon: Some(true),
+                after: Some(format!("\n{}", " ".repeat(self.indentation))),
+                ..Raws::default()
+            }
+        }
+    }
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+fn optional_space(options: &Options) -> &'static str {
+    if options.is_compressed() {
+        ""
+    } else {
+        " "
+    }
+}
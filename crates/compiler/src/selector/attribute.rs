@@ -57,6 +57,16 @@ fn attribute_name(parser: &mut SelectorParser) -> SassResult<QualifiedName> {
         });
     }
 
+    if next.kind == '|' {
+        parser.toks.next();
+
+        let ident = parser.parse_identifier(false, false)?;
+        return Ok(QualifiedName {
+            ident,
+            namespace: Namespace::Empty,
+        });
+    }
+
     let name_or_namespace = parser.parse_identifier(false, false)?;
     match parser.toks.peek() {
         Some(v) if v.kind != '|' => {
@@ -127,14 +137,18 @@ impl Attribute {
         let op = attribute_operator(parser)?;
         parser.whitespace_without_comments();
 
-        let peek = parser
-            .toks
-            .peek()
-            .ok_or_else(|| ("expected more input.", parser.toks.current_span()))?;
-
-        let value = match peek.kind {
-            '\'' | '"' => parser.parse_string()?,
-            _ => parser.parse_identifier(false, false)?,
+        let value = if parser.looking_at_identifier() {
+            parser.parse_identifier(false, false)?
+        } else if matches!(
+            parser.toks.peek(),
+            Some(Token {
+                kind: '\'' | '"',
+                ..
+            })
+        ) {
+            parser.parse_string()?
+        } else {
+            return Err(("Expected string.", parser.toks.current_span()).into());
         };
         parser.whitespace_without_comments();
 
@@ -12,6 +12,7 @@ use super::{unify_complex, ComplexSelector, ComplexSelectorComponent};
 use crate::{
     common::{Brackets, ListSeparator, QuoteKind},
     error::SassResult,
+    utils::ensure_sufficient_stack,
     value::Value,
 };
 
@@ -19,7 +20,7 @@ use crate::{
 ///
 /// A selector list is composed of `ComplexSelector`s. It matches an element
 /// that matches any of the component selectors.
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub(crate) struct SelectorList {
     /// The components of this selector.
     ///
@@ -28,9 +29,18 @@ pub(crate) struct SelectorList {
     pub span: Span,
 }
 
+impl Clone for SelectorList {
+    fn clone(&self) -> Self {
+        ensure_sufficient_stack(|| Self {
+            components: self.components.clone(),
+            span: self.span,
+        })
+    }
+}
+
 impl PartialEq for SelectorList {
     fn eq(&self, other: &SelectorList) -> bool {
-        self.components == other.components
+        ensure_sufficient_stack(|| self.components == other.components)
     }
 }
 
@@ -38,42 +48,46 @@ impl Eq for SelectorList {}
 
 impl Hash for SelectorList {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.components.hash(state);
+        ensure_sufficient_stack(|| self.components.hash(state));
     }
 }
 
 impl fmt::Display for SelectorList {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let complexes = self.components.iter().filter(|c| !c.is_invisible());
+        ensure_sufficient_stack(|| {
+            let complexes = self.components.iter().filter(|c| !c.is_invisible());
 
-        let mut first = true;
+            let mut first = true;
 
-        for complex in complexes {
-            if first {
-                first = false;
-            } else {
-                f.write_char(',')?;
-                if complex.line_break {
-                    f.write_char('\n')?;
+            for complex in complexes {
+                if first {
+                    first = false;
                 } else {
-                    f.write_char(' ')?;
+                    f.write_char(',')?;
+                    if complex.line_break {
+                        f.write_char('\n')?;
+                    } else {
+                        f.write_char(' ')?;
+                    }
                 }
+                write!(f, "{}", complex)?;
             }
-            write!(f, "{}", complex)?;
-        }
-        Ok(())
+            Ok(())
+        })
     }
 }
 
 impl SelectorList {
     pub fn is_invisible(&self) -> bool {
-        self.components.iter().all(ComplexSelector::is_invisible)
+        ensure_sufficient_stack(|| self.components.iter().all(ComplexSelector::is_invisible))
     }
 
     pub fn contains_parent_selector(&self) -> bool {
-        self.components
-            .iter()
-            .any(ComplexSelector::contains_parent_selector)
+        ensure_sufficient_stack(|| {
+            self.components
+                .iter()
+                .any(ComplexSelector::contains_parent_selector)
+        })
     }
 
     pub const fn new(span: Span) -> Self {
@@ -159,103 +173,111 @@ impl SelectorList {
         parent: Option<Self>,
         implicit_parent: bool,
     ) -> SassResult<Self> {
-        let parent = match parent {
-            Some(p) => p,
-            None => {
-                if !self.contains_parent_selector() {
-                    return Ok(self);
+        ensure_sufficient_stack(|| {
+            let parent = match parent {
+                Some(p) => p,
+                None => {
+                    if !self.contains_parent_selector() {
+                        return Ok(self);
+                    }
+                    return Err((
+                        "Top-level selectors may not contain the parent selector \"&\".",
+                        self.span,
+                    )
+                        .into());
                 }
-                return Err((
-                    "Top-level selectors may not contain the parent selector \"&\".",
-                    self.span,
-                )
-                    .into());
-            }
-        };
+            };
 
-        Ok(Self {
-            components: flatten_vertically(
-                self.components
-                    .into_iter()
-                    .map(|complex| {
-                        if !complex.contains_parent_selector() {
-                            if !implicit_parent {
-                                return Ok(vec![complex]);
+            Ok(Self {
+                components: flatten_vertically(
+                    self.components
+                        .into_iter()
+                        .map(|complex| {
+                            if !complex.contains_parent_selector() {
+                                if !implicit_parent {
+                                    return Ok(vec![complex]);
+                                }
+                                return Ok(parent
+                                    .clone()
+                                    .components
+                                    .into_iter()
+                                    .map(move |parent_complex| {
+                                        let mut components = parent_complex.components;
+                                        components.append(&mut complex.components.clone());
+                                        ComplexSelector::new(
+                                            components,
+                                            complex.line_break || parent_complex.line_break,
+                                        )
+                                    })
+                                    .collect());
                             }
-                            return Ok(parent
-                                .clone()
-                                .components
-                                .into_iter()
-                                .map(move |parent_complex| {
-                                    let mut components = parent_complex.components;
-                                    components.append(&mut complex.components.clone());
-                                    ComplexSelector::new(
-                                        components,
-                                        complex.line_break || parent_complex.line_break,
-                                    )
-                                })
-                                .collect());
-                        }
 
-                        let mut new_complexes: Vec<Vec<ComplexSelectorComponent>> =
-                            vec![Vec::new()];
-                        let mut line_breaks = vec![false];
+                            let mut new_complexes: Vec<Vec<ComplexSelectorComponent>> =
+                                vec![Vec::new()];
+                            let mut line_breaks = vec![false];
 
-                        for component in complex.components {
-                            if component.is_compound() {
-                                let resolved = match component
-                                    .clone()
-                                    .resolve_parent_selectors(self.span, parent.clone())?
-                                {
-                                    Some(r) => r,
-                                    None => {
-                                        for new_complex in &mut new_complexes {
-                                            new_complex.push(component.clone());
+                            for component in complex.components {
+                                if component.is_compound() {
+                                    let resolved = match component
+                                        .clone()
+                                        .resolve_parent_selectors(self.span, parent.clone())?
+                                    {
+                                        Some(r) => r,
+                                        None => {
+                                            for new_complex in &mut new_complexes {
+                                                new_complex.push(component.clone());
+                                            }
+                                            continue;
+                                        }
+                                    };
+
+                                    let previous_complexes = mem::take(&mut new_complexes);
+                                    let previous_line_breaks = mem::take(&mut line_breaks);
+
+                                    for (i, new_complex) in
+                                        previous_complexes.into_iter().enumerate()
+                                    {
+                                        // todo: use .get(i)
+                                        let line_break = previous_line_breaks[i];
+                                        for mut resolved_complex in resolved.clone() {
+                                            let mut new_this_complex = new_complex.clone();
+                                            new_this_complex
+                                                .append(&mut resolved_complex.components);
+                                            new_complexes.push(mem::take(&mut new_this_complex));
+                                            line_breaks
+                                                .push(line_break || resolved_complex.line_break);
                                         }
-                                        continue;
                                     }
-                                };
-
-                                let previous_complexes = mem::take(&mut new_complexes);
-                                let previous_line_breaks = mem::take(&mut line_breaks);
-
-                                for (i, new_complex) in previous_complexes.into_iter().enumerate() {
-                                    // todo: use .get(i)
-                                    let line_break = previous_line_breaks[i];
-                                    for mut resolved_complex in resolved.clone() {
-                                        let mut new_this_complex = new_complex.clone();
-                                        new_this_complex.append(&mut resolved_complex.components);
-                                        new_complexes.push(mem::take(&mut new_this_complex));
-                                        line_breaks.push(line_break || resolved_complex.line_break);
+                                } else {
+                                    for new_complex in &mut new_complexes {
+                                        new_complex.push(component.clone());
                                     }
                                 }
-                            } else {
-                                for new_complex in &mut new_complexes {
-                                    new_complex.push(component.clone());
-                                }
                             }
-                        }
 
-                        let mut i = 0;
-                        Ok(new_complexes
-                            .into_iter()
-                            .map(|new_complex| {
-                                i += 1;
-                                ComplexSelector::new(new_complex, line_breaks[i - 1])
-                            })
-                            .collect())
-                    })
-                    .collect::<SassResult<Vec<Vec<ComplexSelector>>>>()?,
-            ),
-            span: self.span,
+                            let mut i = 0;
+                            Ok(new_complexes
+                                .into_iter()
+                                .map(|new_complex| {
+                                    i += 1;
+                                    ComplexSelector::new(new_complex, line_breaks[i - 1])
+                                })
+                                .collect())
+                        })
+                        .collect::<SassResult<Vec<Vec<ComplexSelector>>>>()?,
+                ),
+                span: self.span,
+            })
         })
     }
 
     pub fn is_superselector(&self, other: &Self) -> bool {
-        other.components.iter().all(|complex1| {
-            self.components
-                .iter()
-                .any(|complex2| complex2.is_super_selector(complex1))
+        ensure_sufficient_stack(|| {
+            other.components.iter().all(|complex1| {
+                self.components
+                    .iter()
+                    .any(|complex2| complex2.is_super_selector(complex1))
+            })
         })
     }
 }
@@ -1,6 +1,9 @@
 use codemap::Span;
 
-use crate::{common::unvendor, error::SassResult, lexer::Lexer, parse::BaseParser, Token};
+use crate::{
+    common::unvendor, error::SassResult, lexer::Lexer, parse::BaseParser,
+    utils::ensure_sufficient_stack, Token,
+};
 
 use super::{
     Attribute, Combinator, ComplexSelector, ComplexSelectorComponent, CompoundSelector, Namespace,
@@ -68,11 +71,20 @@ impl<'a> SelectorParser<'a> {
     pub fn parse(mut self) -> SassResult<SelectorList> {
         let tmp = self.parse_selector_list()?;
         if self.toks.peek().is_some() {
-            return Err(("expected selector.", self.span).into());
+            return Err(("expected selector.", self.toks.current_span()).into());
         }
         Ok(tmp)
     }
 
+    /// Parses a single compound selector, such as `a.b:c`
+    pub fn parse_compound(mut self) -> SassResult<CompoundSelector> {
+        let compound = self.parse_compound_selector()?;
+        if self.toks.peek().is_some() {
+            return Err(("expected selector.", self.toks.current_span()).into());
+        }
+        Ok(compound)
+    }
+
     fn parse_selector_list(&mut self) -> SassResult<SelectorList> {
         let mut components = vec![self.parse_complex_selector(false)?];
 
@@ -151,7 +163,7 @@ impl<'a> SelectorParser<'a> {
                         self.parse_compound_selector()?,
                     ));
                     if let Some(Token { kind: '&', .. }) = self.toks.peek() {
-                        return Err(("\"&\" may only used at the beginning of a compound selector.", self.span).into());
+                        return Err(("\"&\" may only used at the beginning of a compound selector.", self.toks.current_span()).into());
                     }
                 }
                 Some(..) => {
@@ -162,7 +174,7 @@ impl<'a> SelectorParser<'a> {
                         self.parse_compound_selector()?,
                     ));
                     if let Some(Token { kind: '&', .. }) = self.toks.peek() {
-                        return Err(("\"&\" may only used at the beginning of a compound selector.", self.span).into());
+                        return Err(("\"&\" may only used at the beginning of a compound selector.", self.toks.current_span()).into());
                     }
                 }
                 None => break,
@@ -170,7 +182,7 @@ impl<'a> SelectorParser<'a> {
         }
 
         if components.is_empty() {
-            return Err(("expected selector.", self.span).into());
+            return Err(("expected selector.", self.toks.current_span()).into());
         }
 
         Ok(ComplexSelector::new(components, line_break))
@@ -200,19 +212,29 @@ impl<'a> SelectorParser<'a> {
             Some(Token { kind: '.', .. }) => self.parse_class_selector(),
             Some(Token { kind: '#', .. }) => self.parse_id_selector(),
             Some(Token { kind: '%', .. }) => {
+                let start = self.toks.cursor();
+                let selector = self.parse_placeholder_selector()?;
                 if !self.allows_placeholder {
-                    return Err(("Placeholder selectors aren't allowed here.", self.span).into());
+                    return Err((
+                        "Placeholder selectors aren't allowed here.",
+                        self.toks.span_from(start),
+                    )
+                        .into());
                 }
-                self.parse_placeholder_selector()
+                Ok(selector)
             }
             Some(Token { kind: ':', .. }) => self.parse_pseudo_selector(),
             Some(Token { kind: '&', .. }) => {
-                let allows_parent = allows_parent.unwrap_or(self.allows_parent);
-                if !allows_parent {
-                    return Err(("Parent selectors aren't allowed here.", self.span).into());
+                let start = self.toks.cursor();
+                let selector = self.parse_parent_selector()?;
+                if !allows_parent.unwrap_or(self.allows_parent) {
+                    return Err((
+                        "Parent selectors aren't allowed here.",
+                        self.toks.span_from(start),
+                    )
+                        .into());
                 }
-
-                self.parse_parent_selector()
+                Ok(selector)
             }
             _ => self.parse_type_or_universal_selector(),
         }
@@ -236,84 +258,87 @@ impl<'a> SelectorParser<'a> {
     }
 
     fn parse_pseudo_selector(&mut self) -> SassResult<SimpleSelector> {
-        self.toks.next();
-        let element = self.scan_char(':');
-        let name = self.parse_identifier(false, false)?;
+        ensure_sufficient_stack(|| {
+            self.toks.next();
+            let element = self.scan_char(':');
+            let name = self.parse_identifier(false, false)?;
 
-        match self.toks.peek() {
-            Some(Token { kind: '(', .. }) => self.toks.next(),
-            _ => {
-                return Ok(SimpleSelector::Pseudo(Pseudo {
-                    is_class: !element && !is_fake_pseudo_element(&name),
-                    name,
-                    selector: None,
-                    is_syntactic_class: !element,
-                    argument: None,
-                    span: self.span,
-                }));
-            }
-        };
+            match self.toks.peek() {
+                Some(Token { kind: '(', .. }) => self.toks.next(),
+                _ => {
+                    return Ok(SimpleSelector::Pseudo(Pseudo {
+                        is_class: !element && !is_fake_pseudo_element(&name),
+                        name: Pseudo::name_from(name),
+                        selector: None,
+                        is_syntactic_class: !element,
+                        argument: None,
+                        span: self.span,
+                    }));
+                }
+            };
 
-        self.whitespace()?;
+            self.whitespace()?;
+
+            let unvendored = unvendor(&name);
 
-        let unvendored = unvendor(&name);
+            let mut argument: Option<Box<str>> = None;
+            let mut selector: Option<Box<SelectorList>> = None;
 
-        let mut argument: Option<Box<str>> = None;
-        let mut selector: Option<Box<SelectorList>> = None;
+            if element {
+                // todo: lowercase?
+                if SELECTOR_PSEUDO_ELEMENTS.contains(&unvendored) {
+                    selector = Some(Box::new(self.parse_selector_list()?));
+                    self.whitespace()?;
+                } else {
+                    argument = Some(self.declaration_value(true)?.into_boxed_str());
+                }
 
-        if element {
-            // todo: lowercase?
-            if SELECTOR_PSEUDO_ELEMENTS.contains(&unvendored) {
+                self.expect_char(')')?;
+            } else if SELECTOR_PSEUDO_CLASSES.contains(&unvendored) {
                 selector = Some(Box::new(self.parse_selector_list()?));
                 self.whitespace()?;
-            } else {
-                argument = Some(self.declaration_value(true)?.into_boxed_str());
-            }
+                self.expect_char(')')?;
+            } else if unvendored == "nth-child" || unvendored == "nth-last-child" {
+                let mut this_arg = self.parse_a_n_plus_b()?;
+                self.whitespace()?;
 
-            self.expect_char(')')?;
-        } else if SELECTOR_PSEUDO_CLASSES.contains(&unvendored) {
-            selector = Some(Box::new(self.parse_selector_list()?));
-            self.whitespace()?;
-            self.expect_char(')')?;
-        } else if unvendored == "nth-child" || unvendored == "nth-last-child" {
-            let mut this_arg = self.parse_a_n_plus_b()?;
-            self.whitespace()?;
+                let last_was_whitespace = matches!(
+                    self.toks.peek_n_backwards(1),
+                    Some(Token {
+                        kind: ' ' | '\t' | '\n' | '\r',
+                        ..
+                    })
+                );
+                if last_was_whitespace && !matches!(self.toks.peek(), Some(Token { kind: ')', .. }))
+                {
+                    self.expect_identifier("of", false)?;
+                    this_arg.push_str(" of");
+                    self.whitespace()?;
+                    selector = Some(Box::new(self.parse_selector_list()?));
+                }
 
-            let last_was_whitespace = matches!(
-                self.toks.peek_n_backwards(1),
-                Some(Token {
-                    kind: ' ' | '\t' | '\n' | '\r',
-                    ..
-                })
-            );
-            if last_was_whitespace && !matches!(self.toks.peek(), Some(Token { kind: ')', .. })) {
-                self.expect_identifier("of", false)?;
-                this_arg.push_str(" of");
-                self.whitespace()?;
-                selector = Some(Box::new(self.parse_selector_list()?));
+                self.expect_char(')')?;
+                argument = Some(this_arg.into_boxed_str());
+            } else {
+                argument = Some(
+                    self.declaration_value(true)?
+                        .trim_end()
+                        .to_owned()
+                        .into_boxed_str(),
+                );
+
+                self.expect_char(')')?;
             }
 
-            self.expect_char(')')?;
-            argument = Some(this_arg.into_boxed_str());
-        } else {
-            argument = Some(
-                self.declaration_value(true)?
-                    .trim_end()
-                    .to_owned()
-                    .into_boxed_str(),
-            );
-
-            self.expect_char(')')?;
-        }
-
-        Ok(SimpleSelector::Pseudo(Pseudo {
-            is_class: !element && !is_fake_pseudo_element(&name),
-            name,
-            selector,
-            is_syntactic_class: !element,
-            argument,
-            span: self.span,
-        }))
+            Ok(SimpleSelector::Pseudo(Pseudo {
+                is_class: !element && !is_fake_pseudo_element(&name),
+                name: Pseudo::name_from(name),
+                selector,
+                is_syntactic_class: !element,
+                argument,
+                span: self.span,
+            }))
+        })
     }
 
     fn parse_parent_selector(&mut self) -> SassResult<SimpleSelector> {
@@ -434,7 +459,7 @@ impl<'a> SelectorParser<'a> {
                 }
             }
             Some(..) => self.expect_ident_char('n', false)?,
-            None => return Err(("expected more input.", self.span).into()),
+            None => return Err(("expected more input.", self.toks.current_span()).into()),
         }
 
         buf.push('n');
@@ -449,9 +474,9 @@ impl<'a> SelectorParser<'a> {
             self.whitespace()?;
             match self.toks.peek() {
                 Some(t) if !t.kind.is_ascii_digit() => {
-                    return Err(("Expected a number.", self.span).into())
+                    return Err(("Expected a number.", self.toks.current_span()).into())
                 }
-                None => return Err(("Expected a number.", self.span).into()),
+                None => return Err(("Expected a number.", self.toks.current_span()).into()),
                 Some(..) => {}
             }
 
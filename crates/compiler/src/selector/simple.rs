@@ -1,11 +1,15 @@
 use std::{
+    borrow::Cow,
     fmt::{self, Write},
     hash::{Hash, Hasher},
 };
 
 use codemap::Span;
 
-use crate::{common::unvendor, error::SassResult};
+use crate::{
+    common::{known_name, unvendor},
+    error::SassResult,
+};
 
 use super::{
     Attribute, ComplexSelector, ComplexSelectorComponent, CompoundSelector, Namespace,
@@ -21,6 +25,67 @@ const SUBSELECTOR_PSEUDOS: [&str; 6] = [
     "nth-last-child",
 ];
 
+/// Pseudo selectors common enough that their names are borrowed from here,
+/// rather than allocated for each selector
+static KNOWN_PSEUDO_NAMES: phf::Set<&'static str> = phf::phf_set! {
+    "active",
+    "after",
+    "any",
+    "any-link",
+    "backdrop",
+    "before",
+    "checked",
+    "current",
+    "default",
+    "dir",
+    "disabled",
+    "empty",
+    "enabled",
+    "first-child",
+    "first-letter",
+    "first-line",
+    "first-of-type",
+    "focus",
+    "focus-visible",
+    "focus-within",
+    "has",
+    "host",
+    "host-context",
+    "hover",
+    "in-range",
+    "indeterminate",
+    "invalid",
+    "is",
+    "lang",
+    "last-child",
+    "last-of-type",
+    "link",
+    "marker",
+    "matches",
+    "not",
+    "nth-child",
+    "nth-last-child",
+    "nth-last-of-type",
+    "nth-of-type",
+    "only-child",
+    "only-of-type",
+    "optional",
+    "out-of-range",
+    "part",
+    "placeholder",
+    "placeholder-shown",
+    "read-only",
+    "read-write",
+    "required",
+    "root",
+    "selection",
+    "slotted",
+    "target",
+    "valid",
+    "visited",
+    "where",
+};
+
 const BASE_SPECIFICITY: i32 = 1000;
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -126,7 +191,7 @@ impl SimpleSelector {
             | Self::Class(..)
             | Self::Attribute(..) => false,
             Self::Pseudo(Pseudo { name, selector, .. }) => {
-                name != "not" && selector.as_ref().map_or(false, |sel| sel.is_invisible())
+                name != "not" && selector.as_ref().is_some_and(|sel| sel.is_invisible())
             }
             Self::Placeholder(..) => true,
             Self::Parent(..) => unreachable!("parent selectors should be resolved at this point"),
@@ -136,16 +201,13 @@ impl SimpleSelector {
     pub fn add_suffix(&mut self, suffix: &str, span: Span) -> SassResult<()> {
         match self {
             Self::Type(name) => name.ident.push_str(suffix),
-            Self::Placeholder(name)
-            | Self::Id(name)
-            | Self::Class(name)
-            | Self::Pseudo(Pseudo {
+            Self::Placeholder(name) | Self::Id(name) | Self::Class(name) => name.push_str(suffix),
+            Self::Pseudo(Pseudo {
                 name,
                 argument: None,
                 selector: None,
                 ..
-            }) => name.push_str(suffix),
-            // todo: add test for this?
+            }) => name.to_mut().push_str(suffix),
             _ => return Err((format!("Invalid parent selector \"{}\"", self), span).into()),
         };
         Ok(())
@@ -167,6 +229,14 @@ impl SimpleSelector {
         matches!(self, Self::Id(..))
     }
 
+    fn is_host(&self) -> bool {
+        matches!(self, Self::Pseudo(Pseudo { is_class: true, name, .. }) if name == "host")
+    }
+
+    fn is_host_context(&self) -> bool {
+        matches!(self, Self::Pseudo(Pseudo { is_class: true, name, .. }) if name == "host-context")
+    }
+
     pub fn is_type(&self) -> bool {
         matches!(self, Self::Type(..))
     }
@@ -200,7 +270,11 @@ impl SimpleSelector {
     /// Returns `None` if unification is impossible—for example, if there are
     /// multiple ID selectors.
     fn unify_default(self, mut compound: Vec<Self>) -> Option<Vec<Self>> {
-        if compound.len() == 1 && compound[0].is_universal() {
+        if compound.len() == 1
+            && (compound[0].is_universal()
+                || compound[0].is_host()
+                || compound[0].is_host_context())
+        {
             return compound.swap_remove(0).unify(vec![self]);
         }
         if compound.contains(&self) {
@@ -316,8 +390,29 @@ impl SimpleSelector {
     }
 
     fn unify_pseudo(self, mut compound: Vec<Self>) -> Option<Vec<Self>> {
-        if compound.len() == 1 && compound[0].is_universal() {
-            return compound.remove(0).unify(vec![self]);
+        if let Self::Pseudo(Pseudo { name, .. }) = &self {
+            if matches!(&**name, "host" | "host-context") {
+                // `:host` and `:host-context` only match the shadow host, so the only
+                // selectors they can be unified with are ones that may also match it
+                if !compound.iter().all(|simple| {
+                    simple.is_host()
+                        || matches!(
+                            simple,
+                            Self::Pseudo(Pseudo {
+                                selector: Some(..),
+                                ..
+                            })
+                        )
+                }) {
+                    return None;
+                }
+            } else if compound.len() == 1
+                && (compound[0].is_universal()
+                    || compound[0].is_host()
+                    || compound[0].is_host_context())
+            {
+                return compound.remove(0).unify(vec![self]);
+            }
         }
         if compound.contains(&self) {
             return Some(compound);
@@ -333,12 +428,36 @@ impl SimpleSelector {
             }) = simple
             {
                 // A given compound selector may only contain one pseudo element. If
-                // `compound` has a different one than `self`, unification fails.
-                if let Self::Pseudo(Pseudo {
-                    is_class: false, ..
-                }) = self
+                // `compound` has a different one than `self`, unification fails,
+                // unless both are `::slotted()`, which match the elements
+                // matched by both of their arguments.
+                if let Self::Pseudo(
+                    pseudo @ Pseudo {
+                        is_class: false, ..
+                    },
+                ) = &self
                 {
-                    return None;
+                    let slotted = match (&simple, pseudo.selector.as_deref()) {
+                        (Self::Pseudo(other), Some(selector))
+                            if pseudo.normalized_name() == "slotted"
+                                && other.name == pseudo.name =>
+                        {
+                            other
+                                .selector
+                                .as_deref()
+                                .and_then(|other| other.clone().unify(selector))
+                                .map(|unified| {
+                                    Self::Pseudo(
+                                        other.clone().with_selector(Some(Box::new(unified))),
+                                    )
+                                })
+                        }
+                        _ => None,
+                    };
+
+                    result.push(slotted?);
+                    added_self = true;
+                    continue;
                 }
 
                 // Otherwise, this is a pseudo selector and should come before pseduo
@@ -356,9 +475,29 @@ impl SimpleSelector {
         Some(result)
     }
 
+    /// Whether this matches every element `other` matches, taking namespaces
+    /// into account, without looking within selector pseudo-classes
+    fn is_super_selector_of_simple(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Universal(Namespace::Asterisk), _) => true,
+            (
+                Self::Universal(namespace),
+                Self::Type(QualifiedName {
+                    namespace: other, ..
+                }),
+            )
+            | (Self::Universal(namespace), Self::Universal(other)) => namespace == other,
+            (Self::Type(name), Self::Type(other)) => {
+                name.ident == other.ident
+                    && (name.namespace == Namespace::Asterisk || name.namespace == other.namespace)
+            }
+            _ => self == other,
+        }
+    }
+
     pub fn is_super_selector_of_compound(&self, compound: &CompoundSelector) -> bool {
         compound.components.iter().any(|their_simple| {
-            if self == their_simple {
+            if self.is_super_selector_of_simple(their_simple) {
                 return true;
             }
             if let SimpleSelector::Pseudo(Pseudo {
@@ -392,7 +531,7 @@ impl SimpleSelector {
 #[derive(Clone, Debug)]
 pub(crate) struct Pseudo {
     /// The name of this selector.
-    pub name: String,
+    pub name: Cow<'static, str>,
 
     /// Whether this is a pseudo-class selector.
     ///
@@ -480,6 +619,10 @@ impl fmt::Display for Pseudo {
 }
 
 impl Pseudo {
+    pub fn name_from(name: String) -> Cow<'static, str> {
+        known_name(name, &KNOWN_PSEUDO_NAMES)
+    }
+
     /// Returns whether `pseudo1` is a superselector of `compound2`.
     ///
     /// That is, whether `pseudo1` matches every element that `compound2` matches, as well
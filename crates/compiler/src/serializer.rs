@@ -11,7 +11,7 @@ use crate::{
         Combinator, ComplexSelector, ComplexSelectorComponent, CompoundSelector, Namespace, Pseudo,
         SelectorList, SimpleSelector,
     },
-    utils::hex_char_for,
+    utils::{ensure_sufficient_stack, hex_char_for},
     value::{
         fuzzy_equals, ArgList, CalculationArg, CalculationName, SassCalculation, SassFunction,
         SassMap, SassNumber, Value,
@@ -120,6 +120,64 @@ pub(crate) fn inspect_number(
     Ok(serializer.finish_for_expr())
 }
 
+pub(crate) fn serialize_media_queries(
+    queries: &[MediaQuery],
+    options: &Options,
+    span: Span,
+) -> String {
+    let map = CodeMap::new();
+    let mut serializer = Serializer::new(options, &map, false, span);
+
+    if let Some((last, rest)) = queries.split_last() {
+        for query in rest {
+            serializer.write_media_query(query);
+            serializer.write_comma_separator();
+        }
+
+        serializer.write_media_query(last);
+    }
+
+    serializer.finish_for_expr()
+}
+
+/// Reindent `comment`, which begins at `span`, as it would be written at the
+/// top level of a stylesheet
+pub(crate) fn serialize_comment(
+    comment: &str,
+    options: &Options,
+    map: &CodeMap,
+    span: Span,
+) -> SassResult<String> {
+    let mut serializer = Serializer::new(options, map, false, span);
+
+    serializer.write_comment(comment, span)?;
+
+    Ok(serializer.finish_for_expr())
+}
+
+/// Whether `c` is in one of Unicode's private use areas
+///
+/// These are mostly used for icon fonts, so they're escaped in expanded output
+/// where they would otherwise be invisible
+fn is_private_use(c: char) -> bool {
+    matches!(c as u32, 0xE000..=0xF8FF | 0xF0000..=0x10FFFF)
+}
+
+/// Write `c` as a CSS escape, followed by a space if `next` would otherwise be
+/// read as part of it
+fn write_escape(buffer: &mut Vec<u8>, c: char, next: Option<char>) {
+    buffer.push(b'\\');
+    write!(buffer, "{:x}", c as u32).unwrap();
+
+    if matches!(next, Some(next) if next.is_ascii_hexdigit() || next == ' ' || next == '\t') {
+        buffer.push(b' ');
+    }
+}
+
+fn push_char(buffer: &mut Vec<u8>, c: char) {
+    buffer.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+}
+
 pub(crate) struct Serializer<'a> {
     indentation: usize,
     options: &'a Options<'a>,
@@ -275,22 +333,40 @@ impl<'a> Serializer<'a> {
     }
 
     fn write_selector_list(&mut self, list: &SelectorList) {
-        let complexes = list.components.iter().filter(|c| !c.is_invisible());
+        ensure_sufficient_stack(|| {
+            let complexes = list.components.iter().filter(|c| !c.is_invisible());
 
-        let mut first = true;
+            let mut first = true;
 
-        for complex in complexes {
-            if first {
-                first = false;
-            } else {
-                self.buffer.push(b',');
-                if complex.line_break {
-                    self.write_newline();
+            for complex in complexes {
+                if first {
+                    first = false;
                 } else {
-                    self.write_optional_space();
+                    self.buffer.push(b',');
+                    if complex.line_break {
+                        self.write_newline();
+                    } else {
+                        self.write_optional_space();
+                    }
                 }
+                self.write_complex_selector(complex);
             }
-            self.write_complex_selector(complex);
+        });
+    }
+
+    /// Write each of `items` directly to the buffer, separated by `separator`
+    fn write_with_separator<T>(
+        &mut self,
+        items: impl Iterator<Item = T>,
+        separator: &str,
+        mut write: impl FnMut(&mut Vec<u8>, T),
+    ) {
+        for (idx, item) in items.enumerate() {
+            if idx != 0 {
+                self.buffer.extend_from_slice(separator.as_bytes());
+            }
+
+            write(&mut self.buffer, item);
         }
     }
 
@@ -365,8 +441,7 @@ impl<'a> Serializer<'a> {
                     self.buffer.push(b' ');
                 }
 
-                // todo: avoid allocation with `write_binary_operator` method
-                self.buffer.extend_from_slice(op.to_string().as_bytes());
+                write!(&mut self.buffer, "{}", op)?;
 
                 if operator_whitespace {
                     self.buffer.push(b' ');
@@ -535,8 +610,9 @@ impl<'a> Serializer<'a> {
                 .extend_from_slice(condition["(not ".len()..condition.len() - 1].as_bytes());
         } else {
             let operator = if query.conjunction { " and " } else { " or " };
-            self.buffer
-                .extend_from_slice(query.conditions.join(operator).as_bytes());
+            self.write_with_separator(query.conditions.iter(), operator, |buffer, condition| {
+                buffer.extend_from_slice(condition.as_bytes());
+            });
         }
     }
 
@@ -826,19 +902,24 @@ impl<'a> Serializer<'a> {
         let mut after_newline = false;
         self.buffer.reserve(string.len());
 
-        for c in string.bytes() {
+        let mut iter = string.chars().peekable();
+        while let Some(c) = iter.next() {
             match c {
-                b'\n' => {
+                '\n' => {
                     self.buffer.push(b' ');
                     after_newline = true;
                 }
-                b' ' => {
+                ' ' => {
                     if !after_newline {
                         self.buffer.push(b' ');
                     }
                 }
                 _ => {
-                    self.buffer.push(c);
+                    if !self.options.is_compressed() && is_private_use(c) {
+                        write_escape(&mut self.buffer, c, iter.peek().copied());
+                    } else {
+                        push_char(&mut self.buffer, c);
+                    }
                     after_newline = false;
                 }
             }
@@ -854,10 +935,10 @@ impl<'a> Serializer<'a> {
         if force_double_quote {
             buffer.push(b'"');
         }
-        let mut iter = string.as_bytes().iter().copied().peekable();
+        let mut iter = string.chars().peekable();
         while let Some(c) = iter.next() {
             match c {
-                b'\'' => {
+                '\'' => {
                     if force_double_quote {
                         buffer.push(b'\'');
                     } else if has_double_quote {
@@ -868,7 +949,7 @@ impl<'a> Serializer<'a> {
                         buffer.push(b'\'');
                     }
                 }
-                b'"' => {
+                '"' => {
                     if force_double_quote {
                         buffer.push(b'\\');
                         buffer.push(b'"');
@@ -880,27 +961,17 @@ impl<'a> Serializer<'a> {
                         buffer.push(b'"');
                     }
                 }
-                b'\x00'..=b'\x08' | b'\x0A'..=b'\x1F' => {
-                    buffer.push(b'\\');
-                    if c as u32 > 0xF {
-                        buffer.push(hex_char_for(c as u32 >> 4) as u8);
-                    }
-                    buffer.push(hex_char_for(c as u32 & 0xF) as u8);
-
-                    let next = match iter.peek() {
-                        Some(v) => *v,
-                        None => break,
-                    };
-
-                    if next.is_ascii_hexdigit() || next == b' ' || next == b'\t' {
-                        buffer.push(b' ');
-                    }
+                '\x00'..='\x08' | '\x0A'..='\x1F' => {
+                    write_escape(&mut buffer, c, iter.peek().copied());
                 }
-                b'\\' => {
+                '\\' => {
                     buffer.push(b'\\');
                     buffer.push(b'\\');
                 }
-                _ => buffer.push(c),
+                _ if !self.options.is_compressed() && is_private_use(c) => {
+                    write_escape(&mut buffer, c, iter.peek().copied());
+                }
+                _ => push_char(&mut buffer, c),
             }
         }
 
@@ -1006,16 +1077,11 @@ impl<'a> Serializer<'a> {
             self.buffer.extend_from_slice(line.trim_start().as_bytes());
         }
 
-        let lines = lines
-            .map(|line| {
-                let diff = (line.len() - line.trim_start().len()).saturating_sub(col);
-                format!("{}{}", " ".repeat(diff), line.trim_start())
-            })
-            .collect::<Vec<String>>()
-            .join("\n");
-
-        if !lines.is_empty() {
-            write!(&mut self.buffer, "\n{}", lines)?;
+        for line in lines {
+            let diff = (line.len() - line.trim_start().len()).saturating_sub(col);
+            self.buffer.push(b'\n');
+            self.buffer.resize(self.buffer.len() + diff, b' ');
+            self.buffer.extend_from_slice(line.trim_start().as_bytes());
         }
 
         Ok(())
@@ -1038,6 +1104,7 @@ impl<'a> Serializer<'a> {
 
         self.indentation += self.indent_width;
 
+        children.retain(|child| !child.is_invisible(self.options));
         let last = children.pop();
 
         for child in children {
@@ -1109,75 +1176,77 @@ impl<'a> Serializer<'a> {
 
     /// Returns whether or not text was written
     fn visit_stmt(&mut self, stmt: CssStmt) -> SassResult<bool> {
-        if stmt.is_invisible() {
-            return Ok(false);
-        }
+        ensure_sufficient_stack(|| {
+            if stmt.is_invisible(self.options) {
+                return Ok(false);
+            }
 
-        match stmt {
-            CssStmt::RuleSet { selector, body, .. } => {
-                self.write_indentation();
-                self.write_selector_list(&selector.as_selector_list());
+            match stmt {
+                CssStmt::RuleSet { selector, body, .. } => {
+                    self.write_indentation();
+                    self.write_selector_list(&selector.as_selector_list());
 
-                self.write_children(body)?;
-            }
-            CssStmt::Media(media_rule, ..) => {
-                self.write_indentation();
-                self.buffer.extend_from_slice(b"@media ");
+                    self.write_children(body)?;
+                }
+                CssStmt::Media(media_rule, ..) => {
+                    self.write_indentation();
+                    self.buffer.extend_from_slice(b"@media ");
 
-                if let Some((last, rest)) = media_rule.query.split_last() {
-                    for query in rest {
-                        self.write_media_query(query);
+                    if let Some((last, rest)) = media_rule.query.split_last() {
+                        for query in rest {
+                            self.write_media_query(query);
 
-                        self.buffer.push(b',');
+                            self.buffer.push(b',');
 
-                        self.write_optional_space();
+                            self.write_optional_space();
+                        }
+
+                        self.write_media_query(last);
                     }
 
-                    self.write_media_query(last);
+                    self.write_children(media_rule.body)?;
                 }
+                CssStmt::UnknownAtRule(unknown_at_rule, ..) => {
+                    self.write_indentation();
+                    self.buffer.push(b'@');
+                    self.buffer
+                        .extend_from_slice(unknown_at_rule.name.as_bytes());
+
+                    if !unknown_at_rule.params.is_empty() {
+                        write!(&mut self.buffer, " {}", unknown_at_rule.params)?;
+                    }
 
-                self.write_children(media_rule.body)?;
-            }
-            CssStmt::UnknownAtRule(unknown_at_rule, ..) => {
-                self.write_indentation();
-                self.buffer.push(b'@');
-                self.buffer
-                    .extend_from_slice(unknown_at_rule.name.as_bytes());
-
-                if !unknown_at_rule.params.is_empty() {
-                    write!(&mut self.buffer, " {}", unknown_at_rule.params)?;
-                }
+                    if !unknown_at_rule.has_body {
+                        debug_assert!(unknown_at_rule.body.is_empty());
+                        return Ok(true);
+                    } else if unknown_at_rule
+                        .body
+                        .iter()
+                        .all(|stmt| stmt.is_invisible(self.options))
+                    {
+                        self.buffer.extend_from_slice(b" {}");
+                        return Ok(true);
+                    }
 
-                if !unknown_at_rule.has_body {
-                    debug_assert!(unknown_at_rule.body.is_empty());
-                    return Ok(true);
-                } else if unknown_at_rule.body.iter().all(CssStmt::is_invisible) {
-                    self.buffer.extend_from_slice(b" {}");
-                    return Ok(true);
+                    self.write_children(unknown_at_rule.body)?;
                 }
-
-                self.write_children(unknown_at_rule.body)?;
-            }
-            CssStmt::Style(style) => self.write_style(style)?,
-            CssStmt::Comment(comment, span) => self.write_comment(&comment, span)?,
-            CssStmt::KeyframesRuleSet(keyframes_rule_set) => {
-                self.write_indentation();
-                // todo: i bet we can do something like write_with_separator to avoid extra allocation
-                let selector = keyframes_rule_set
-                    .selector
-                    .into_iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<String>>()
-                    .join(", ");
-
-                self.buffer.extend_from_slice(selector.as_bytes());
-
-                self.write_children(keyframes_rule_set.body)?;
+                CssStmt::Style(style) => self.write_style(style)?,
+                CssStmt::Comment(comment, span) => self.write_comment(&comment, span)?,
+                CssStmt::KeyframesRuleSet(keyframes_rule_set) => {
+                    self.write_indentation();
+                    self.write_with_separator(
+                        keyframes_rule_set.selector.iter(),
+                        ", ",
+                        |buffer, selector| write!(buffer, "{}", selector).unwrap(),
+                    );
+
+                    self.write_children(keyframes_rule_set.body)?;
+                }
+                CssStmt::Import(import, modifier) => self.write_import(&import, modifier)?,
+                CssStmt::Supports(supports_rule, _) => self.write_supports_rule(supports_rule)?,
             }
-            CssStmt::Import(import, modifier) => self.write_import(&import, modifier)?,
-            CssStmt::Supports(supports_rule, _) => self.write_supports_rule(supports_rule)?,
-        }
 
-        Ok(true)
+            Ok(true)
+        })
     }
 }
@@ -121,6 +121,28 @@ pub(crate) fn are_any_convertible(units1: &[Unit], units2: &[Unit]) -> bool {
     false
 }
 
+/// The factor by which to multiply a product of `from` units to get a product
+/// of `to` units, pairing each unit in `to` with a convertible unit in `from`
+fn units_conversion_factor(mut from: Vec<Unit>, to: &[Unit]) -> Option<f64> {
+    if from.len() != to.len() {
+        return None;
+    }
+
+    let mut factor = 1.0;
+
+    for unit in to {
+        let (idx, unit_factor) = from
+            .iter()
+            .enumerate()
+            .find_map(|(idx, from)| Some((idx, from.conversion_factor(unit)?)))?;
+
+        factor *= unit_factor;
+        from.remove(idx);
+    }
+
+    Some(factor)
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub(crate) enum UnitKind {
     Absolute,
@@ -163,10 +185,44 @@ impl Unit {
         matches!(self, Unit::Complex(complex) if complex.numer.len() != 1 || !complex.denom.is_empty())
     }
 
+    /// Whether this is a unit of length, such as `px` or `em`
+    pub(crate) fn is_length(&self) -> bool {
+        matches!(
+            self.kind(),
+            UnitKind::Absolute | UnitKind::FontRelative | UnitKind::ViewportRelative
+        )
+    }
+
+    /// The factor by which to multiply a number with this unit to convert it
+    /// to `to`, or `None` if the units can't be converted
+    ///
+    /// Complex units are converted by converting each of their numerator and
+    /// denominator units individually, so `px/s` can be converted to `in/ms`
+    pub(crate) fn conversion_factor(&self, to: &Unit) -> Option<f64> {
+        if self == to {
+            return Some(1.0);
+        }
+
+        if self.is_complex() || to.is_complex() {
+            let (from_numer, from_denom) = self.clone().numer_and_denom();
+            let (to_numer, to_denom) = to.clone().numer_and_denom();
+
+            return Some(
+                units_conversion_factor(from_numer, &to_numer)?
+                    / units_conversion_factor(from_denom, &to_denom)?,
+            );
+        }
+
+        UNIT_CONVERSION_TABLE.get(to)?.get(self).copied()
+    }
+
     pub(crate) fn comparable(&self, other: &Unit) -> bool {
-        if other == &Unit::None {
+        if self == &Unit::None || other == &Unit::None {
             return true;
         }
+        if self.is_complex() || other.is_complex() {
+            return self.conversion_factor(other).is_some();
+        }
         match self.kind() {
             UnitKind::FontRelative | UnitKind::ViewportRelative | UnitKind::Other => self == other,
             UnitKind::None => true,
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use codemap::{CodeMap, File, LineCol, Span, SpanLoc};
+
+/// Look up the file, line, and column range represented by `span`
+///
+/// Unlike [`CodeMap::look_up_span`], this treats `\r\n`, `\r`, and `\u{c}` as
+/// line breaks in addition to `\n`, matching the CSS definition of a newline.
+/// Columns count Unicode code points rather than bytes.
+pub(crate) fn look_up_span(map: &CodeMap, span: Span) -> SpanLoc {
+    look_up_span_in_file(map.find_file(span.low()), span)
+}
+
+/// Like [`look_up_span`], for a `span` within `file`
+pub(crate) fn look_up_span_in_file(file: &Arc<File>, span: Span) -> SpanLoc {
+    let file = Arc::clone(file);
+
+    let begin = find_line_col(file.source(), (span.low() - file.span.low()) as usize);
+    let end = find_line_col(file.source(), (span.high() - file.span.low()) as usize);
+
+    SpanLoc { file, begin, end }
+}
+
+/// The text of the 0-indexed `line` of `file`, without its line break, using
+/// the same definition of a line as [`look_up_span`]
+pub(crate) fn source_line(file: &File, line: usize) -> &str {
+    let source = file.source();
+
+    let mut current_line = 0;
+    let mut line_start = 0;
+
+    for (idx, c) in source.char_indices() {
+        if !is_line_break(source, idx, c) {
+            continue;
+        }
+
+        if current_line == line {
+            return source[line_start..idx].trim_end_matches('\r');
+        }
+
+        current_line += 1;
+        line_start = idx + c.len_utf8();
+    }
+
+    if current_line == line {
+        &source[line_start..]
+    } else {
+        ""
+    }
+}
+
+fn find_line_col(source: &str, offset: usize) -> LineCol {
+    let mut line = 0;
+    let mut column = 0;
+
+    for (idx, c) in source[..offset].char_indices() {
+        if is_line_break(source, idx, c) {
+            line += 1;
+            column = 0;
+        } else {
+            column += 1;
+        }
+    }
+
+    LineCol { line, column }
+}
+
+/// Whether the character `c` at byte offset `idx` ends a line
+///
+/// The `\r` in `\r\n` is not considered a line break on its own, so that the
+/// pair only ends a single line.
+fn is_line_break(source: &str, idx: usize, c: char) -> bool {
+    match c {
+        '\n' | '\u{c}' => true,
+        '\r' => !source[idx + 1..].starts_with('\n'),
+        _ => false,
+    }
+}
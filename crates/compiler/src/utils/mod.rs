@@ -1,11 +1,38 @@
 pub(crate) use chars::*;
+pub(crate) use location::*;
 pub(crate) use map_view::*;
+#[cfg(any(feature = "wasm-exports", feature = "plugins"))]
+pub(crate) use signature::*;
 pub(crate) use strings::*;
 
 mod chars;
+mod location;
 mod map_view;
+#[cfg(any(
+    feature = "wasm-exports",
+    feature = "plugins",
+    feature = "custom-builtin-fns"
+))]
+pub(crate) mod signature;
 mod strings;
 
+/// The amount of stack that must remain before we allocate a new segment
+const STACK_RED_ZONE: usize = 256 * 1024;
+
+/// The size of each new stack segment
+const STACK_SEGMENT_SIZE: usize = 4 * 1024 * 1024;
+
+/// Run `f`, first moving to a new heap-allocated stack segment if we are close
+/// to exhausting the current one.
+///
+/// This should wrap each point where the parser or evaluator recurses into a
+/// nested block or expression, so that deeply nested (but legal) stylesheets
+/// don't overflow the stack
+#[inline]
+pub(crate) fn ensure_sufficient_stack<T>(f: impl FnOnce() -> T) -> T {
+    stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT_SIZE, f)
+}
+
 #[allow(clippy::case_sensitive_file_extension_comparisons)]
 pub(crate) fn is_plain_css_import(url: &str) -> bool {
     if url.len() < 5 {
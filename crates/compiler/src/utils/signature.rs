@@ -0,0 +1,100 @@
+use crate::{ast::ArgumentResult, common::Brackets, error::SassResult, value::Value};
+
+/// A parameter in the signature of a function defined outside of Sass, such
+/// as a JavaScript function or a plugin function
+#[derive(Debug)]
+pub struct Parameter {
+    pub name: String,
+    pub has_default: bool,
+    pub is_rest: bool,
+}
+
+/// Split a signature such as `foo($a, $b: 1px, $rest...)` into the name of the
+/// function and its parameters
+pub fn parse_signature(signature: &str) -> (String, Vec<Parameter>) {
+    let (name, rest) = match signature.split_once('(') {
+        Some((name, rest)) => (name, rest.trim_end().strip_suffix(')').unwrap_or(rest)),
+        None => (signature, ""),
+    };
+
+    // default values may themselves contain commas within parentheses
+    let mut parameters = Vec::new();
+    let mut depth = 0_usize;
+    let mut start = 0;
+    for (idx, c) in rest
+        .char_indices()
+        .chain(std::iter::once((rest.len(), ',')))
+    {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                let parameter = rest[start..idx.min(rest.len())].trim();
+                start = idx + 1;
+
+                if parameter.is_empty() {
+                    continue;
+                }
+
+                let (name, has_default) = match parameter.split_once(':') {
+                    Some((name, _)) => (name.trim(), true),
+                    None => (parameter, false),
+                };
+                let (name, is_rest) = match name.strip_suffix("...") {
+                    Some(name) => (name, true),
+                    None => (name, false),
+                };
+
+                parameters.push(Parameter {
+                    name: name.trim_start_matches('$').to_owned(),
+                    has_default,
+                    is_rest,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    (name.trim().to_owned(), parameters)
+}
+
+/// The argument passed for each of `parameters`, or `None` for omitted
+/// arguments with default values
+///
+/// A rest parameter is bound to a list of the remaining positional arguments.
+pub fn bind_arguments(
+    parameters: &[Parameter],
+    args: &mut ArgumentResult,
+) -> SassResult<Vec<Option<Value>>> {
+    let span = args.span();
+
+    if !parameters.iter().any(|parameter| parameter.is_rest) {
+        args.max_args(parameters.len())?;
+    }
+
+    let mut arguments = Vec::with_capacity(parameters.len());
+
+    for (idx, parameter) in parameters.iter().enumerate() {
+        if parameter.is_rest {
+            let rest = args.positional.split_off(idx.min(args.positional.len()));
+            if let Some((name, _)) = args.named.iter().next() {
+                return Err((format!("No argument named ${}.", name), span).into());
+            }
+
+            arguments.push(Some(Value::List(rest, args.separator, Brackets::None)));
+            break;
+        }
+
+        match args.get(idx, parameter.name.as_str()) {
+            Some(value) => arguments.push(Some(value.node)),
+            None if parameter.has_default => arguments.push(None),
+            None => return Err((format!("Missing argument ${}.", parameter.name), span).into()),
+        }
+    }
+
+    if let Some((name, _)) = args.named.iter().next() {
+        return Err((format!("No argument named ${}.", name), span).into());
+    }
+
+    Ok(arguments)
+}
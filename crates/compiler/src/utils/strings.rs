@@ -1,33 +1,124 @@
+use std::{iter::Peekable, str::Chars};
+
 use super::{is_name, is_name_start};
 
+/// Whether `s` is a CSS identifier, and so may be written without quotes
 pub(crate) fn is_ident(s: &str) -> bool {
     let mut chars = s.chars().peekable();
+
+    if chars.peek() == Some(&'-') {
+        chars.next();
+    }
+
     match chars.next() {
-        Some(c) if is_name_start(c) && !c.is_numeric() => {}
+        Some('\\') if consume_escape(&mut chars) => {}
+        Some('-') => {}
+        Some(c) if c != '\\' && is_name_start(c) && !c.is_numeric() => {}
         Some(..) | None => return false,
     }
+
     while let Some(c) = chars.next() {
         if c == '\\' {
-            for _ in 0..6 {
-                let next = match chars.next() {
-                    Some(t) => t,
-                    None => return true,
-                };
-                if !next.is_ascii_hexdigit() {
+            if !consume_escape(&mut chars) {
+                return false;
+            }
+        } else if !is_name(c) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Consume the rest of an escape sequence following a backslash, returning
+/// whether it was valid
+fn consume_escape(chars: &mut Peekable<Chars>) -> bool {
+    match chars.next() {
+        Some(c) if c.is_ascii_hexdigit() => {
+            for _ in 0..5 {
+                if !chars.peek().is_some_and(char::is_ascii_hexdigit) {
                     break;
                 }
+                chars.next();
             }
-            match chars.peek() {
-                Some(c) if c.is_whitespace() => {
-                    chars.next();
-                }
-                _ => {}
-            };
+
+            if chars.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+                chars.next();
+            }
+
+            true
+        }
+        Some('\n' | '\r' | '\x0C') | None => false,
+        Some(..) => true,
+    }
+}
+
+/// The candidate that `name` is most likely a misspelling of, if any
+///
+/// Candidates are compared using their edit distance from `name`, which must be
+/// at most a third of the length of `name`. Ties are broken in favor of the
+/// candidate that comes first.
+pub(crate) fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = name.chars().count().max(3) / 3;
+
+    let mut closest: Option<(usize, &str)> = None;
+
+    for candidate in candidates {
+        if candidate == name {
             continue;
         }
-        if !is_name(c) {
-            return false;
+
+        let distance = edit_distance(name, candidate);
+
+        if distance > max_distance {
+            continue;
+        }
+
+        match closest {
+            Some((closest_distance, ..)) if closest_distance <= distance => {}
+            _ => closest = Some((distance, candidate)),
         }
     }
-    true
+
+    closest.map(|(_, candidate)| candidate)
+}
+
+/// The number of insertions, deletions, substitutions, and transpositions of
+/// adjacent characters needed to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    // distances[i][j] is the distance between the first `i` characters of `a`
+    // and the first `j` characters of `b`
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution_cost = usize::from(a[i - 1] != b[j - 1]);
+
+            let mut distance = (distances[i - 1][j - 1] + substitution_cost)
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
 }
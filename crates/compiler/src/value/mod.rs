@@ -7,7 +7,7 @@ use crate::{
     common::{BinaryOp, Brackets, ListSeparator, QuoteKind},
     error::SassResult,
     evaluate::Visitor,
-    selector::Selector,
+    selector::{CompoundSelector, Selector},
     serializer::{inspect_value, serialize_value},
     unit::Unit,
     utils::is_special_function,
@@ -73,6 +73,8 @@ impl PartialEq for Value {
                         true
                     }
                 }
+                // an empty list is equal to an empty map
+                Value::Map(map2) => list1.is_empty() && map2.is_empty(),
                 _ => false,
             },
             Value::Null => matches!(other, Value::Null),
@@ -85,13 +87,12 @@ impl PartialEq for Value {
                     false
                 }
             }
-            Value::Map(map1) => {
-                if let Value::Map(map2) = other {
-                    map1 == map2
-                } else {
-                    false
-                }
-            }
+            Value::Map(map1) => match other {
+                Value::Map(map2) => map1 == map2,
+                Value::List(list2, ..) => map1.is_empty() && list2.is_empty(),
+                Value::ArgList(list2) => map1.is_empty() && list2.is_empty(),
+                _ => false,
+            },
             Value::Color(color1) => {
                 if let Value::Color(color2) = other {
                     color1 == color2
@@ -114,6 +115,7 @@ impl PartialEq for Value {
 
                     true
                 }
+                Value::Map(map2) => list1.is_empty() && map2.is_empty(),
                 _ => false,
             },
         }
@@ -425,6 +427,7 @@ impl Value {
                         false
                     }
                 }
+                Value::Map(map2) => !list1.is_empty() || !map2.is_empty(),
                 _ => true,
             },
             s => s != other,
@@ -475,6 +478,23 @@ impl Value {
         )?))
     }
 
+    pub(crate) fn to_compound_selector(
+        self,
+        visitor: &mut Visitor,
+        name: &str,
+        allows_parent: bool,
+        span: Span,
+    ) -> SassResult<CompoundSelector> {
+        let string = match self.clone().selector_string()? {
+            Some(v) => v,
+            None => return Err((format!("${}: {} is not a valid selector: it must be a string,\n a list of strings, or a list of lists of strings.", name, self.inspect(span)?), span).into()),
+        };
+
+        visitor
+            .parse_compound_selector_from_string(&string, allows_parent, span)
+            .map_err(|err| err.with_argument_name(name))
+    }
+
     fn selector_string(self) -> SassResult<Option<String>> {
         Ok(Some(match self {
             Value::String(text, ..) => text,
@@ -6,10 +6,7 @@ use std::{
     },
 };
 
-use crate::{
-    error::SassResult,
-    unit::{Unit, UNIT_CONVERSION_TABLE},
-};
+use crate::{error::SassResult, unit::Unit};
 
 use codemap::Span;
 
@@ -161,7 +158,7 @@ impl Number {
 
         debug_assert!(from.comparable(to), "from: {:?}, to: {:?}", from, to);
 
-        Number(self.0 * UNIT_CONVERSION_TABLE[to][from])
+        Number(self.0 * from.conversion_factor(to).unwrap())
     }
 }
 
@@ -8,7 +8,7 @@ use codemap::Span;
 use crate::{
     error::SassResult,
     serializer::{inspect_float, inspect_number},
-    unit::{are_any_convertible, known_compatibilities_by_unit, Unit, UNIT_CONVERSION_TABLE},
+    unit::{are_any_convertible, known_compatibilities_by_unit, Unit},
     Options,
 };
 
@@ -22,11 +22,7 @@ pub struct SassNumber {
 }
 
 pub(crate) fn conversion_factor(from: &Unit, to: &Unit) -> Option<f64> {
-    if from == to {
-        return Some(1.0);
-    }
-
-    UNIT_CONVERSION_TABLE.get(to)?.get(from).copied()
+    from.conversion_factor(to)
 }
 
 impl SassNumber {
@@ -0,0 +1,772 @@
+//! JavaScript bindings modelled on the modern API of the `sass` npm package
+//!
+//! `compile(path, options)` and `compileString(source, options)` accept the
+//! same options object as their `sass` counterparts, and return an object of
+//! the form `{ css, loadedUrls }`. Errors are thrown as `Error`s, with the
+//! `sassMessage` and `span` properties of a Sass exception.
+//!
+//! The following options are supported: `style`, `syntax`, `url`, `loadPaths`,
+//! `charset`, `quietDeps`, `verbose`, `alertUnicode`, `silenceDeprecations`,
+//! `futureDeprecations`, `fatalDeprecations`, `importers`, `functions`, and
+//! `logger`. Everything is synchronous, so importers and functions may not
+//! return promises.
+//!
+//! Importers are objects with `canonicalize(url, context)` and
+//! `load(canonicalUrl)` methods, through which every file is read, as there is
+//! no file system to fall back to. `canonicalize` is called with each path
+//! `grass` tries while resolving a load, including partials and the
+//! `.scss`, `.sass`, and `.css` extensions, so importers should only
+//! canonicalize URLs of files that exist. The syntax of a loaded file is
+//! determined by the extension of its canonical URL.
+//!
+//! Functions are passed an array of their arguments, in the order of their
+//! signature, and return a value. Sass values are represented as `null`,
+//! booleans, and objects with a `type` of `"number"` (`value` and `unit`),
+//! `"string"` (`text` and `quoted`), `"color"` (`red`, `green`, `blue`, and
+//! `alpha`), `"list"` (`contents`, `separator`, and `brackets`), or `"map"`
+//! (`contents`, an array of key-value pairs). Functions may also return plain
+//! numbers and strings, which become unitless numbers and quoted strings.
+//! Default values in signatures are not evaluated, so missing optional
+//! arguments are passed as `null`.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    io,
+    path::{Path, PathBuf},
+};
+
+use codemap::{Span, SpanLoc, Spanned};
+use wasm_bindgen::{prelude::*, JsCast};
+
+use crate::{
+    ast::ArgumentResult,
+    builtin::Builtin,
+    color::Color,
+    common::{Brackets, ListSeparator, QuoteKind},
+    error::SassResult,
+    evaluate::Visitor,
+    from_string_with_file_name, json,
+    unit::Unit,
+    utils::{bind_arguments, parse_signature, Parameter},
+    value::{Number, SassMap, SassNumber, Value},
+    Deprecation, Error, Fs, InputSyntax, Logger, Options, OutputStyle, Warning,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = Function)]
+    type JsFunction;
+
+    #[wasm_bindgen(method, catch, js_name = call)]
+    fn call1(this: &JsFunction, this_arg: &JsValue, arg: &JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = call)]
+    fn call2(
+        this: &JsFunction,
+        this_arg: &JsValue,
+        arg1: &JsValue,
+        arg2: &JsValue,
+    ) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_name = Array)]
+    type JsArray;
+
+    #[wasm_bindgen(constructor, js_class = "Array")]
+    fn new() -> JsArray;
+
+    #[wasm_bindgen(method, js_class = "Array")]
+    fn push(this: &JsArray, value: &JsValue) -> u32;
+
+    #[wasm_bindgen(js_name = Object)]
+    type JsObject;
+
+    #[wasm_bindgen(constructor, js_class = "Object")]
+    fn new() -> JsObject;
+
+    #[wasm_bindgen(js_name = Error)]
+    type Exception;
+
+    #[wasm_bindgen(constructor, js_class = "Error")]
+    fn new(message: &str) -> Exception;
+
+    #[wasm_bindgen(js_namespace = Reflect, js_name = get, catch)]
+    fn reflect_get(target: &JsValue, key: &JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_namespace = Reflect, js_name = set)]
+    fn reflect_set(target: &JsValue, key: &JsValue, value: &JsValue) -> bool;
+
+    #[wasm_bindgen(js_namespace = Object, js_name = keys)]
+    fn object_keys(object: &JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = JSON, js_name = parse)]
+    fn json_parse(text: &str) -> JsValue;
+
+    #[wasm_bindgen(js_name = String)]
+    fn js_string(value: &JsValue) -> String;
+
+    #[wasm_bindgen(js_namespace = console, js_name = warn)]
+    fn console_warn(message: &str);
+}
+
+/// Compile the file at `path`, which is loaded through `options.importers`
+#[wasm_bindgen]
+pub fn compile(path: String, options: JsValue) -> Result<JsValue, JsValue> {
+    let compiler = JsCompiler::new(&options);
+    let options = compiler.options();
+
+    let input = options
+        .fs
+        .read(Path::new(&path))
+        .map_err(|e| throw(&Box::<Error>::from(e)))?;
+    let input = String::from_utf8(input).map_err(|e| throw(&Box::<Error>::from(e)))?;
+
+    compiler.compile(input, &path, &options)
+}
+
+/// Compile `source`, which is SCSS unless `options.syntax` is passed
+#[wasm_bindgen(js_name = compileString)]
+pub fn compile_string(source: String, options: JsValue) -> Result<JsValue, JsValue> {
+    let compiler = JsCompiler::new(&options);
+    let mut options = compiler.options();
+
+    if let Some(syntax) = get(&compiler.js_options, "syntax").and_then(|v| v.as_string()) {
+        options = options.input_syntax(match syntax.as_str() {
+            "indented" => InputSyntax::Sass,
+            "css" => InputSyntax::Css,
+            _ => InputSyntax::Scss,
+        });
+    }
+
+    let url =
+        get(&compiler.js_options, "url").map_or_else(|| "stdin".to_owned(), |v| js_string(&v));
+
+    compiler.compile(source, &url, &options)
+}
+
+/// The state of a single call to `compile` or `compileString`
+struct JsCompiler {
+    js_options: JsValue,
+    fs: JsFs,
+    logger: JsLogger,
+    /// Unregisters the functions passed in `options.functions` once compilation
+    /// has finished
+    _functions: FunctionsGuard,
+}
+
+impl JsCompiler {
+    fn new(options: &JsValue) -> Self {
+        let js_options = if options.is_object() {
+            options.clone()
+        } else {
+            JsObject::new().into()
+        };
+
+        let importers = get(&js_options, "importers").map_or_else(Vec::new, |v| array(&v));
+        let logger = get(&js_options, "logger");
+        let functions = get(&js_options, "functions");
+
+        Self {
+            fs: JsFs {
+                importers,
+                canonical_urls: RefCell::new(HashMap::new()),
+            },
+            logger: JsLogger(logger),
+            _functions: FunctionsGuard::register(functions.as_ref()),
+            js_options,
+        }
+    }
+
+    fn options(&self) -> Options<'_> {
+        let js_options = &self.js_options;
+        let flag = |name: &str, default: bool| {
+            get(js_options, name)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(default)
+        };
+
+        let load_paths: Vec<String> = get(js_options, "loadPaths")
+            .map_or_else(Vec::new, |v| array(&v))
+            .iter()
+            .map(js_string)
+            .collect();
+
+        let mut options = Options::default()
+            .fs(&self.fs)
+            .logger(&self.logger)
+            .load_paths(&load_paths)
+            .style(
+                match get(js_options, "style")
+                    .and_then(|v| v.as_string())
+                    .as_deref()
+                {
+                    Some("compressed") => OutputStyle::Compressed,
+                    _ => OutputStyle::Expanded,
+                },
+            )
+            .allows_charset(flag("charset", true))
+            .quiet_deps(flag("quietDeps", false))
+            .verbose(flag("verbose", false))
+            .unicode_error_messages(flag("alertUnicode", true));
+
+        for deprecation in deprecations(js_options, "silenceDeprecations") {
+            options = options.silence_deprecation(deprecation);
+        }
+
+        for deprecation in deprecations(js_options, "futureDeprecations") {
+            options = options.future_deprecation(deprecation);
+        }
+
+        for deprecation in deprecations(js_options, "fatalDeprecations") {
+            options = options.fatal_deprecation(deprecation);
+        }
+
+        for name in FUNCTIONS.with(|functions| {
+            functions
+                .borrow()
+                .last()
+                .map_or_else(Vec::new, |functions| functions.keys().cloned().collect())
+        }) {
+            options
+                .custom_fns
+                .insert(name, Builtin::new(call_js_function));
+        }
+
+        options
+    }
+
+    fn compile(&self, input: String, url: &str, options: &Options) -> Result<JsValue, JsValue> {
+        let mut loaded_files = BTreeMap::new();
+
+        let css =
+            from_string_with_file_name(input, url, options, false, Some(&mut loaded_files), None)
+                .map_err(|mut errors| throw(&errors.swap_remove(0)))?;
+
+        let loaded_urls = JsArray::new();
+        loaded_urls.push(&JsValue::from_str(url));
+        for path in loaded_files.keys() {
+            loaded_urls.push(&JsValue::from_str(&path.to_string_lossy()));
+        }
+
+        let result: JsValue = JsObject::new().into();
+        set(&result, "css", &JsValue::from_str(&css));
+        set(&result, "loadedUrls", &loaded_urls);
+
+        Ok(result)
+    }
+}
+
+/// The deprecations passed in `options[name]`, either as ids or as objects with
+/// an `id`, ignoring those which are not known
+fn deprecations(options: &JsValue, name: &str) -> Vec<Deprecation> {
+    get(options, name)
+        .map_or_else(Vec::new, |v| array(&v))
+        .iter()
+        .filter_map(|deprecation| {
+            let id = get(deprecation, "id").unwrap_or_else(|| deprecation.clone());
+            js_string(&id).parse().ok()
+        })
+        .collect()
+}
+
+/// `object[key]`, or `None` if it is `null` or `undefined`
+fn get(object: &JsValue, key: &str) -> Option<JsValue> {
+    if !object.is_object() && !object.is_function() {
+        return None;
+    }
+
+    reflect_get(object, &JsValue::from_str(key))
+        .ok()
+        .filter(|value| !value.is_null() && !value.is_undefined())
+}
+
+fn set(object: &JsValue, key: &str, value: &JsValue) {
+    reflect_set(object, &JsValue::from_str(key), value);
+}
+
+/// The elements of the array-like `value`
+fn array(value: &JsValue) -> Vec<JsValue> {
+    let len = get(value, "length").and_then(|v| v.as_f64()).unwrap_or(0.0) as u32;
+
+    (0..len)
+        .map(|idx| reflect_get(value, &JsValue::from(idx)).unwrap_or(JsValue::UNDEFINED))
+        .collect()
+}
+
+/// Call `object[method](args...)`, returning `None` if there is no such method
+fn call_method(
+    object: &JsValue,
+    method: &str,
+    args: &[&JsValue],
+) -> Option<Result<JsValue, JsValue>> {
+    let function: JsFunction = get(object, method)
+        .filter(JsValue::is_function)?
+        .unchecked_into();
+
+    Some(match args {
+        [arg] => function.call1(object, arg),
+        [arg1, arg2] => function.call2(object, arg1, arg2),
+        _ => unreachable!("methods are called with one or two arguments"),
+    })
+}
+
+/// The message of a value thrown by JavaScript
+fn thrown_message(thrown: &JsValue) -> String {
+    get(thrown, "message").map_or_else(|| js_string(thrown), |message| js_string(&message))
+}
+
+/// An `Error` describing `e`, with the `sassMessage` and `span` of a Sass
+/// exception
+fn throw(e: &Error) -> JsValue {
+    let exception: JsValue = Exception::new(&e.to_string()).into();
+    let json = json_parse(&e.to_json());
+
+    if let Some(message) = get(&json, "message") {
+        set(&exception, "sassMessage", &message);
+    }
+    set(
+        &exception,
+        "span",
+        &get(&json, "span").unwrap_or(JsValue::NULL),
+    );
+    set(
+        &exception,
+        "sassStack",
+        &get(&json, "trace").unwrap_or(JsValue::NULL),
+    );
+
+    exception
+}
+
+/// Reads files through the importers passed in `options.importers`
+#[derive(Debug)]
+struct JsFs {
+    importers: Vec<JsValue>,
+    /// The index of the importer which canonicalized each path, and the
+    /// canonical URL it returned, or `None` if no importer recognized it
+    canonical_urls: RefCell<HashMap<PathBuf, Option<(usize, JsValue)>>>,
+}
+
+impl JsFs {
+    fn resolve(&self, path: &Path) -> io::Result<Option<(usize, JsValue)>> {
+        if let Some(resolved) = self.canonical_urls.borrow().get(path) {
+            return Ok(resolved.clone());
+        }
+
+        let url = JsValue::from_str(&path.to_string_lossy());
+        let context = json_parse("{\"fromImport\":false}");
+
+        let mut resolved = None;
+        for (idx, importer) in self.importers.iter().enumerate() {
+            match call_method(importer, "canonicalize", &[&url, &context]) {
+                Some(Ok(canonical)) if !canonical.is_null() && !canonical.is_undefined() => {
+                    resolved = Some((idx, canonical));
+                    break;
+                }
+                Some(Err(thrown)) => return Err(io::Error::other(thrown_message(&thrown))),
+                _ => {}
+            }
+        }
+
+        self.canonical_urls
+            .borrow_mut()
+            .insert(path.to_path_buf(), resolved.clone());
+
+        Ok(resolved)
+    }
+}
+
+impl Fs for JsFs {
+    fn is_dir(&self, _path: &Path) -> bool {
+        false
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        matches!(self.resolve(path), Ok(Some(..)))
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let not_found = || {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No importer could load \"{}\".", path.display()),
+            )
+        };
+
+        let (idx, canonical) = self.resolve(path)?.ok_or_else(not_found)?;
+
+        let result = match call_method(&self.importers[idx], "load", &[&canonical]) {
+            Some(Ok(result)) => result,
+            Some(Err(thrown)) => return Err(io::Error::other(thrown_message(&thrown))),
+            None => return Err(not_found()),
+        };
+
+        match get(&result, "contents").and_then(|contents| contents.as_string()) {
+            Some(contents) => Ok(contents.into_bytes()),
+            None => Err(not_found()),
+        }
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        match self.resolve(path)? {
+            Some((_, canonical)) => Ok(PathBuf::from(js_string(&canonical))),
+            None => Ok(path.to_path_buf()),
+        }
+    }
+}
+
+/// Passes messages to `options.logger`, or to the console if it has no method
+/// for them
+#[derive(Debug)]
+struct JsLogger(Option<JsValue>);
+
+impl JsLogger {
+    fn call(&self, method: &str, message: &str, options: &JsValue) -> bool {
+        match &self.0 {
+            Some(logger) => {
+                call_method(logger, method, &[&JsValue::from_str(message), options]).is_some()
+            }
+            None => false,
+        }
+    }
+}
+
+impl Logger for JsLogger {
+    fn debug(&self, location: SpanLoc, message: &str) {
+        let span = json::to_string(&json::Span::from(&location));
+
+        let options: JsValue = JsObject::new().into();
+        set(&options, "span", &json_parse(&span));
+
+        if !self.call("debug", message, &options) {
+            console_warn(&format!(
+                "{}:{} DEBUG: {}",
+                location.file.name(),
+                location.begin.line + 1,
+                message
+            ));
+        }
+    }
+
+    fn warn(&self, warning: &Warning) {
+        let json = json_parse(&warning.to_json());
+
+        let options: JsValue = JsObject::new().into();
+        set(
+            &options,
+            "deprecation",
+            &JsValue::from_bool(warning.deprecation().is_some()),
+        );
+        if let Some(deprecation) = warning.deprecation() {
+            let deprecation_type: JsValue = JsObject::new().into();
+            set(
+                &deprecation_type,
+                "id",
+                &JsValue::from_str(deprecation.id()),
+            );
+            set(&options, "deprecationType", &deprecation_type);
+        }
+        set(
+            &options,
+            "span",
+            &get(&json, "span").unwrap_or(JsValue::NULL),
+        );
+        set(
+            &options,
+            "stack",
+            &get(&json, "trace").unwrap_or(JsValue::NULL),
+        );
+
+        if !self.call("warn", warning.message(), &options) {
+            console_warn(&warning.to_string());
+        }
+    }
+}
+
+/// The parameters and implementation of each function passed in
+/// `options.functions`, by name
+type Functions = HashMap<String, (Vec<Parameter>, JsFunction)>;
+
+thread_local! {
+    /// The functions passed to each call to `compile` or `compileString` which
+    /// is in progress, innermost last
+    static FUNCTIONS: RefCell<Vec<Functions>> = const { RefCell::new(Vec::new()) };
+}
+
+struct FunctionsGuard;
+
+impl FunctionsGuard {
+    fn register(functions: Option<&JsValue>) -> Self {
+        let mut by_name = HashMap::new();
+
+        if let Some(functions) = functions {
+            for signature in array(&object_keys(functions)) {
+                let signature = js_string(&signature);
+                let function = match get(functions, &signature).filter(JsValue::is_function) {
+                    Some(function) => function.unchecked_into::<JsFunction>(),
+                    None => continue,
+                };
+
+                let (name, parameters) = parse_signature(&signature);
+                by_name.insert(name, (parameters, function));
+            }
+        }
+
+        FUNCTIONS.with(|functions| functions.borrow_mut().push(by_name));
+        Self
+    }
+}
+
+impl Drop for FunctionsGuard {
+    fn drop(&mut self) {
+        FUNCTIONS.with(|functions| functions.borrow_mut().pop());
+    }
+}
+
+/// The implementation of every function passed in `options.functions`, which
+/// calls the function the builtin was called by
+fn call_js_function(mut args: ArgumentResult, visitor: &mut Visitor) -> SassResult<Value> {
+    let span = args.span();
+    let name = match visitor.current_builtin {
+        Some(name) => name.to_string(),
+        None => return Err(("Undefined function.", span).into()),
+    };
+
+    FUNCTIONS.with(|functions| {
+        let functions = functions.borrow();
+        let (parameters, function) = match functions.last().and_then(|f| f.get(&name)) {
+            Some(function) => function,
+            None => return Err(("Undefined function.", span).into()),
+        };
+
+        let js_args = JsArray::new();
+        for argument in bind_arguments(parameters, &mut args)? {
+            match argument {
+                Some(value) => js_args.push(&to_js(&value, span)?),
+                None => js_args.push(&JsValue::NULL),
+            };
+        }
+
+        let returned = function
+            .call1(&JsValue::UNDEFINED, &js_args)
+            .map_err(|thrown| Box::<Error>::from((thrown_message(&thrown), span)))?;
+
+        from_js(&returned, span)
+    })
+}
+
+/// Represent `value` as described in the module documentation
+fn to_js(value: &Value, span: Span) -> SassResult<JsValue> {
+    let object: JsValue = JsObject::new().into();
+
+    let list = |elems: &[Value], separator: ListSeparator, brackets: Brackets| {
+        let contents = JsArray::new();
+        for elem in elems {
+            contents.push(&to_js(elem, span)?);
+        }
+
+        set(&object, "type", &JsValue::from_str("list"));
+        set(&object, "contents", &contents);
+        set(
+            &object,
+            "separator",
+            &match separator {
+                ListSeparator::Comma => JsValue::from_str(","),
+                ListSeparator::Space => JsValue::from_str(" "),
+                ListSeparator::Slash => JsValue::from_str("/"),
+                ListSeparator::Undecided => JsValue::NULL,
+            },
+        );
+        set(
+            &object,
+            "brackets",
+            &JsValue::from_bool(brackets == Brackets::Bracketed),
+        );
+
+        SassResult::Ok(())
+    };
+
+    match value {
+        Value::True => return Ok(JsValue::TRUE),
+        Value::False => return Ok(JsValue::FALSE),
+        Value::Null => return Ok(JsValue::NULL),
+        Value::Dimension(number) => {
+            set(&object, "type", &JsValue::from_str("number"));
+            set(&object, "value", &JsValue::from_f64(number.num.0));
+            set(
+                &object,
+                "unit",
+                &JsValue::from_str(&number.unit.to_string()),
+            );
+        }
+        Value::String(text, quotes) => {
+            set(&object, "type", &JsValue::from_str("string"));
+            set(&object, "text", &JsValue::from_str(text));
+            set(
+                &object,
+                "quoted",
+                &JsValue::from_bool(*quotes == QuoteKind::Quoted),
+            );
+        }
+        Value::Color(color) => {
+            set(&object, "type", &JsValue::from_str("color"));
+            set(&object, "red", &JsValue::from_f64(color.red().0));
+            set(&object, "green", &JsValue::from_f64(color.green().0));
+            set(&object, "blue", &JsValue::from_f64(color.blue().0));
+            set(&object, "alpha", &JsValue::from_f64(color.alpha().0));
+        }
+        Value::List(elems, separator, brackets) => list(elems, *separator, *brackets)?,
+        Value::ArgList(args) => list(&args.elems, args.separator, Brackets::None)?,
+        Value::Map(map) => {
+            let contents = JsArray::new();
+            for (key, value) in map.iter() {
+                let pair = JsArray::new();
+                pair.push(&to_js(&key.node, span)?);
+                pair.push(&to_js(value, span)?);
+                contents.push(&pair);
+            }
+
+            set(&object, "type", &JsValue::from_str("map"));
+            set(&object, "contents", &contents);
+        }
+        // calculations are passed as their CSS representation
+        Value::Calculation(..) => {
+            set(&object, "type", &JsValue::from_str("string"));
+            set(&object, "text", &JsValue::from_str(&value.inspect(span)?));
+            set(&object, "quoted", &JsValue::FALSE);
+        }
+        Value::FunctionRef(..) => {
+            return Err((
+                format!(
+                    "{} can't be passed to a JavaScript function.",
+                    value.inspect(span)?
+                ),
+                span,
+            )
+                .into())
+        }
+    }
+
+    Ok(object)
+}
+
+/// The Sass value represented by `value`, as described in the module
+/// documentation
+fn from_js(value: &JsValue, span: Span) -> SassResult<Value> {
+    if value.is_null() || value.is_undefined() {
+        return Ok(Value::Null);
+    }
+
+    if let Some(value) = value.as_bool() {
+        return Ok(Value::bool(value));
+    }
+
+    if let Some(value) = value.as_f64() {
+        return Ok(Value::Dimension(SassNumber::new_unitless(value)));
+    }
+
+    if let Some(text) = value.as_string() {
+        return Ok(Value::String(text, QuoteKind::Quoted));
+    }
+
+    let number = |name: &str| {
+        get(value, name)
+            .and_then(|v| v.as_f64())
+            .ok_or_else(|| -> Box<Error> {
+                (
+                    format!("Expected JavaScript value to have a numeric `{}`.", name),
+                    span,
+                )
+                    .into()
+            })
+    };
+
+    let contents = || get(value, "contents").map_or_else(Vec::new, |contents| array(&contents));
+
+    Ok(
+        match get(value, "type").and_then(|v| v.as_string()).as_deref() {
+            Some("number") => {
+                let unit = get(value, "unit").map_or_else(String::new, |unit| js_string(&unit));
+
+                Value::Dimension(SassNumber {
+                    num: Number(number("value")?),
+                    unit: if unit.is_empty() {
+                        Unit::None
+                    } else {
+                        Unit::from(unit)
+                    },
+                    as_slash: None,
+                })
+            }
+            Some("string") => Value::String(
+                get(value, "text").map_or_else(String::new, |text| js_string(&text)),
+                if get(value, "quoted")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true)
+                {
+                    QuoteKind::Quoted
+                } else {
+                    QuoteKind::None
+                },
+            ),
+            Some("color") => Value::Color(std::sync::Arc::new(Color::from_rgba(
+                Number(number("red")?),
+                Number(number("green")?),
+                Number(number("blue")?),
+                Number(get(value, "alpha").and_then(|v| v.as_f64()).unwrap_or(1.0)),
+            ))),
+            Some("list") => Value::List(
+                contents()
+                    .iter()
+                    .map(|elem| from_js(elem, span))
+                    .collect::<SassResult<Vec<_>>>()?,
+                match get(value, "separator")
+                    .and_then(|v| v.as_string())
+                    .as_deref()
+                {
+                    Some(",") => ListSeparator::Comma,
+                    Some(" ") => ListSeparator::Space,
+                    Some("/") => ListSeparator::Slash,
+                    _ => ListSeparator::Undecided,
+                },
+                if get(value, "brackets")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false)
+                {
+                    Brackets::Bracketed
+                } else {
+                    Brackets::None
+                },
+            ),
+            Some("map") => {
+                let mut map = SassMap::new();
+                for pair in contents() {
+                    let pair = array(&pair);
+                    let (key, value) =
+                        match pair.as_slice() {
+                            [key, value] => (from_js(key, span)?, from_js(value, span)?),
+                            _ => return Err((
+                                "Expected the contents of a JavaScript map to be key-value pairs.",
+                                span,
+                            )
+                                .into()),
+                        };
+
+                    map.insert(Spanned { node: key, span }, value);
+                }
+
+                Value::Map(map)
+            }
+            _ => {
+                return Err((
+                    format!(
+                        "Expected JavaScript function to return a Sass value, but got {}.",
+                        js_string(value)
+                    ),
+                    span,
+                )
+                    .into())
+            }
+        },
+    )
+}
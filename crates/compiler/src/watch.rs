@@ -0,0 +1,358 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{Compiler, Error, Options, Result};
+
+/// How to watch files for changes in [`Compiler::watch`]
+///
+/// ```
+/// # use grass_compiler as grass;
+/// # use std::time::Duration;
+/// let watch_options = grass::WatchOptions::default()
+///     .poll(true)
+///     .interval(Duration::from_millis(500));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    interval: Duration,
+    poll: bool,
+    compare_contents: bool,
+    stop: Option<Arc<AtomicBool>>,
+}
+
+impl Default for WatchOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(100),
+            poll: false,
+            compare_contents: false,
+            stop: None,
+        }
+    }
+}
+
+impl WatchOptions {
+    /// How often to check watched files for changes when polling, and
+    /// otherwise how often to check whether [`WatchOptions::stop_flag`] has been
+    /// set
+    ///
+    /// By default, this is 100 milliseconds.
+    #[must_use]
+    #[inline]
+    pub const fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Check watched files for changes every [`WatchOptions::interval`], rather
+    /// than being notified of changes by the operating system
+    ///
+    /// Notifications are not sent for some network filesystems and Docker bind
+    /// mounts, so polling is needed to watch files on them. If the operating
+    /// system's notifications are not available, files are polled regardless.
+    ///
+    /// By default, this is false.
+    #[must_use]
+    #[inline]
+    pub const fn poll(mut self, poll: bool) -> Self {
+        self.poll = poll;
+        self
+    }
+
+    /// Check for changes by reading the contents of watched files through
+    /// [`Options::fs`], rather than comparing their modification times
+    ///
+    /// Modification times and notifications come from disk, so this must be
+    /// enabled to watch files served by a custom [`Fs`](crate::Fs). It also
+    /// detects changes on filesystems where modification times may not be
+    /// updated. This implies [`WatchOptions::poll`].
+    ///
+    /// By default, this is false.
+    #[must_use]
+    #[inline]
+    pub const fn compare_contents(mut self, compare_contents: bool) -> Self {
+        self.compare_contents = compare_contents;
+        self
+    }
+
+    /// Stop watching once `stop` is set, which is checked every
+    /// [`WatchOptions::interval`] while waiting for changes
+    ///
+    /// This lets another thread, such as a GUI's, stop the watcher while no
+    /// files are changing and so the callback is not being called.
+    ///
+    /// ```
+    /// # use grass_compiler as grass;
+    /// # use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+    /// let stop = Arc::new(AtomicBool::new(false));
+    /// let watch_options = grass::WatchOptions::default().stop_flag(Arc::clone(&stop));
+    ///
+    /// // later, from any thread
+    /// stop.store(true, Ordering::Relaxed);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn stop_flag(mut self, stop: Arc<AtomicBool>) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stop
+            .as_ref()
+            .is_some_and(|stop| stop.load(Ordering::Relaxed))
+    }
+}
+
+/// The outcome of compiling an entrypoint in [`Compiler::watch`]
+#[derive(Debug)]
+pub struct CompileResult {
+    entrypoint: PathBuf,
+    result: Result<String>,
+    duration: Duration,
+}
+
+impl CompileResult {
+    /// The entrypoint, as it was passed to [`Compiler::watch`]
+    #[must_use]
+    pub fn entrypoint(&self) -> &Path {
+        &self.entrypoint
+    }
+
+    /// The compiled CSS, or `None` if compilation failed
+    #[must_use]
+    pub fn css(&self) -> Option<&str> {
+        self.result.as_deref().ok()
+    }
+
+    /// The error compilation failed with, or `None` if it succeeded
+    #[must_use]
+    pub fn error(&self) -> Option<&Error> {
+        self.result.as_ref().err().map(|e| &**e)
+    }
+
+    /// How long compilation took
+    #[must_use]
+    pub const fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The compiled CSS, or the error compilation failed with
+    pub fn into_result(self) -> Result<String> {
+        self.result
+    }
+}
+
+impl Compiler {
+    /// Compile each of `entrypoints`, and then recompile those affected each
+    /// time any of the files they loaded change
+    ///
+    /// `callback` is passed the result of compiling every entrypoint, and
+    /// afterwards the results of recompiling just the entrypoints which changed,
+    /// in the order they were passed. Compilation errors are passed to
+    /// `callback` rather than stopping the watcher. Watching continues until
+    /// `callback` returns [`ControlFlow::Break`], whose value is returned, or
+    /// until [`WatchOptions::stop_flag`] is set, in which case `None` is
+    /// returned.
+    ///
+    /// Changes are reported by the operating system through `notify`, unless
+    /// [`WatchOptions::poll`] is set. As with [`Compiler::recompile_if_changed`],
+    /// only the files that were actually loaded are watched.
+    ///
+    /// ```no_run
+    /// # use grass_compiler as grass;
+    /// # use std::ops::ControlFlow;
+    /// let compiler = grass::Compiler::new();
+    ///
+    /// let css = compiler.watch(
+    ///     &["input.scss"],
+    ///     &grass::Options::default(),
+    ///     &grass::WatchOptions::default(),
+    ///     |results| {
+    ///         for result in results {
+    ///             match result.into_result() {
+    ///                 Ok(css) => println!("{}", css),
+    ///                 Err(e) => eprintln!("{}", e),
+    ///             }
+    ///         }
+    ///
+    ///         ControlFlow::<()>::Continue(())
+    ///     },
+    /// );
+    ///
+    /// // without a stop flag, watching only ends when `callback` breaks
+    /// assert!(css.is_none());
+    /// ```
+    pub fn watch<P: AsRef<Path>, B>(
+        &self,
+        entrypoints: &[P],
+        options: &Options,
+        watch_options: &WatchOptions,
+        mut callback: impl FnMut(Vec<CompileResult>) -> ControlFlow<B>,
+    ) -> Option<B> {
+        loop {
+            let mut results = Vec::new();
+            let mut dependencies = Vec::new();
+
+            for entrypoint in entrypoints {
+                let entrypoint = entrypoint.as_ref();
+                let start = Instant::now();
+
+                let result = match self.recompile_if_changed(entrypoint, options) {
+                    Ok(Some(css)) => Some(Ok(css)),
+                    Ok(None) => None,
+                    Err(e) => Some(Err(e)),
+                };
+
+                if let Some(result) = result {
+                    results.push(CompileResult {
+                        entrypoint: entrypoint.to_path_buf(),
+                        result,
+                        duration: start.elapsed(),
+                    });
+                }
+
+                dependencies.extend(
+                    self.dependencies(entrypoint, options)
+                        .unwrap_or_else(|| vec![entrypoint.to_path_buf()]),
+                );
+            }
+
+            // set up before calling `callback`, so that changes made while it
+            // runs are not missed
+            let mut notifications = if watch_options.poll || watch_options.compare_contents {
+                None
+            } else {
+                native_watcher(&dependencies)
+            };
+
+            if !results.is_empty() {
+                if let ControlFlow::Break(value) = callback(results) {
+                    return Some(value);
+                }
+            }
+
+            // when polling modification times, those of the dependencies when
+            // their contents were last found to be unchanged
+            let mut unchanged_times = None;
+            // files may have changed while they were being compiled, before
+            // they were watched
+            let mut may_have_changed = true;
+
+            loop {
+                if watch_options.is_stopped() {
+                    return None;
+                }
+
+                if may_have_changed {
+                    let changed = if notifications.is_some() || watch_options.compare_contents {
+                        self.any_changed(entrypoints, options)
+                    } else {
+                        // read before the contents are compared, so that files
+                        // changed afterwards are noticed by the next check
+                        let times = modification_times(&dependencies);
+
+                        unchanged_times.as_ref() != Some(&times) && {
+                            let changed = self.any_changed(entrypoints, options);
+                            unchanged_times = Some(times);
+                            changed
+                        }
+                    };
+
+                    if changed {
+                        break;
+                    }
+                }
+
+                may_have_changed = match &notifications {
+                    Some((_, events)) => match events.recv_timeout(watch_options.interval) {
+                        Ok(..) => {
+                            // an edit often produces several events at once
+                            while events.try_recv().is_ok() {}
+                            true
+                        }
+                        Err(RecvTimeoutError::Timeout) => false,
+                        // the watcher has stopped sending events, so poll
+                        // instead rather than checking again straight away
+                        Err(RecvTimeoutError::Disconnected) => {
+                            notifications = None;
+                            true
+                        }
+                    },
+                    None => {
+                        thread::sleep(watch_options.interval);
+                        true
+                    }
+                };
+            }
+        }
+    }
+
+    /// Whether the contents of any of the files loaded by the last compilation
+    /// of any of `entrypoints` differ from those which were compiled
+    ///
+    /// This compares against the contents recorded when the files were loaded,
+    /// so that changes made while they were being compiled are not missed.
+    fn any_changed<P: AsRef<Path>>(&self, entrypoints: &[P], options: &Options) -> bool {
+        entrypoints
+            .iter()
+            .any(|entrypoint| self.has_changed(entrypoint.as_ref(), options))
+    }
+}
+
+type Events = Receiver<notify::Result<notify::Event>>;
+
+/// A watcher notified of changes to the directories containing `paths`, and
+/// the receiver it sends events to, or `None` if the operating system's
+/// notifications are unavailable
+///
+/// Directories are watched rather than the files themselves so that files
+/// which editors save by replacing them, or which are recreated after being
+/// deleted, are still watched. Events are only used to decide when to compare
+/// the states of the files again, so unrelated events are harmless.
+fn native_watcher(paths: &[PathBuf]) -> Option<(RecommendedWatcher, Events)> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).ok()?;
+
+    let directories: BTreeSet<&Path> = paths
+        .iter()
+        .map(|path| match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        })
+        .collect();
+
+    for dir in directories {
+        // directories which don't exist can't be watched, but neither can the
+        // files in them have been loaded
+        let _ = watcher.watch(dir, RecursiveMode::NonRecursive);
+    }
+
+    Some((watcher, receiver))
+}
+
+/// The modification time of each of `paths`, or `None` for paths whose
+/// metadata could not be read
+fn modification_times(paths: &[PathBuf]) -> BTreeMap<PathBuf, Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| {
+            let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok();
+            (path.clone(), modified)
+        })
+        .collect()
+}
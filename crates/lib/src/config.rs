@@ -0,0 +1,259 @@
+//! Defaults for the CLI read from `grass.toml`, or from the `grass` field of
+//! `package.json`
+//!
+//! Both files accept the same options:
+//!
+//! ```toml
+//! load-paths = ["node_modules", "src/vendor"]
+//! style = "compressed"
+//! silence-deprecations = ["import"]
+//! compile = ["src/app.scss:dist/app.css", "src/themes:dist/themes"]
+//! ```
+//!
+//! Paths are relative to the directory containing the file. Options passed on
+//! the command line take precedence, and inputs passed on the command line
+//! replace those in `compile`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use grass::Deprecation;
+use serde::Deserialize;
+
+use crate::{split_pair, SourceMapUrls, Style};
+
+/// The name of the configuration file searched for by [`Config::find`]
+pub(crate) const CONFIG_FILE: &str = "grass.toml";
+
+#[derive(Debug, Default)]
+pub(crate) struct Config {
+    pub load_paths: Vec<PathBuf>,
+    pub style: Option<Style>,
+    pub silenced_deprecations: Vec<Deprecation>,
+    /// `input:output` pairs, resolved relative to the directory containing the
+    /// configuration file
+    pub compile: Vec<(String, String)>,
+}
+
+/// The options in a configuration file, as written
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    load_paths: Vec<String>,
+    style: Option<String>,
+    #[serde(default)]
+    silence_deprecations: Vec<String>,
+    #[serde(default)]
+    compile: Vec<String>,
+    // grass does not yet emit source maps, so like the corresponding flags
+    // these are validated but otherwise ignored
+    #[allow(dead_code)]
+    source_map: Option<bool>,
+    #[allow(dead_code)]
+    embed_sources: Option<bool>,
+    #[allow(dead_code)]
+    embed_source_map: Option<bool>,
+    source_map_urls: Option<String>,
+}
+
+impl Config {
+    /// Find the configuration for `dir`, which is in the nearest `grass.toml`,
+    /// or `package.json` with a `grass` field, in `dir` or any of its ancestors
+    pub fn find(dir: &Path) -> Result<Option<Self>, String> {
+        for dir in dir.ancestors() {
+            let toml = dir.join(CONFIG_FILE);
+            if toml.is_file() {
+                return Self::load(&toml).map(Some);
+            }
+
+            let package = dir.join("package.json");
+            if package.is_file() {
+                let file = match read_package_json(&package)? {
+                    Some(file) => file,
+                    None => continue,
+                };
+
+                return Self::from_file(file, dir)
+                    .map(Some)
+                    .map_err(|e| format!("{}: {}", package.display(), e));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Load the configuration in `path`, which is read as JSON if its
+    /// extension is `.json`, and as TOML otherwise
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+        let file = if path.extension().map_or(false, |ext| ext == "json") {
+            read_package_json(path)?.unwrap_or_default()
+        } else {
+            toml::from_str(&read(path)?).map_err(|e| format!("{}: {}", path.display(), e))?
+        };
+
+        Self::from_file(file, dir).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    fn from_file(file: ConfigFile, dir: &Path) -> Result<Self, String> {
+        let mut config = Self {
+            load_paths: file.load_paths.iter().map(|path| dir.join(path)).collect(),
+            ..Self::default()
+        };
+
+        if let Some(style) = file.style {
+            config.style =
+                Some(Style::from_str(&style, true).map_err(|e| format!("invalid `style`: {}", e))?);
+        }
+
+        for id in file.silence_deprecations {
+            config.silenced_deprecations.push(id.parse()?);
+        }
+
+        for pair in file.compile {
+            let (input, output) = split_pair(&pair)
+                .ok_or_else(|| format!("\"{}\" in `compile` must be an input:output pair", pair))?;
+
+            config.compile.push((
+                dir.join(input).to_string_lossy().into_owned(),
+                dir.join(output).to_string_lossy().into_owned(),
+            ));
+        }
+
+        if let Some(urls) = file.source_map_urls {
+            SourceMapUrls::from_str(&urls, true)
+                .map_err(|e| format!("invalid `source-map-urls`: {}", e))?;
+        }
+
+        Ok(config)
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// The `grass` field of the `package.json` at `path`, if it has one
+fn read_package_json(path: &Path) -> Result<Option<ConfigFile>, String> {
+    let error = |e: serde_json::Error| format!("{}: {}", path.display(), e);
+
+    let package: serde_json::Value = serde_json::from_str(&read(path)?).map_err(error)?;
+
+    match package.get("grass") {
+        Some(grass @ serde_json::Value::Object(..)) => {
+            ConfigFile::deserialize(grass).map(Some).map_err(error)
+        }
+        Some(..) => Err(format!(
+            "{}: the `grass` field must be an object",
+            path.display()
+        )),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use serde::Deserialize;
+
+    use super::{Config, ConfigFile};
+    use crate::Style;
+
+    fn toml(input: &str) -> Result<Config, String> {
+        let file: ConfigFile = toml::from_str(input).map_err(|e| e.message().to_owned())?;
+        Config::from_file(file, Path::new("project"))
+    }
+
+    #[test]
+    fn toml_syntax() {
+        let config = toml(concat!(
+            "# comment\n",
+            "\"style\" = '''compressed''' # trailing\n",
+            "\n",
+            "compile = [\n",
+            "  'a.scss:a.css', # first\n",
+            "  \"b:c\",\n",
+            "]\n",
+            "source-map = false\r\n",
+        ))
+        .unwrap();
+
+        assert_eq!(config.style, Some(Style::Compressed));
+        assert_eq!(config.compile.len(), 2);
+    }
+
+    #[test]
+    fn toml_errors() {
+        let error = |input: &str| toml(input).unwrap_err();
+
+        assert!(error("[grass]\n").starts_with("unknown field `grass`"));
+        assert_eq!(
+            error("style = 1\n"),
+            "invalid type: integer `1`, expected a string"
+        );
+        assert_eq!(
+            error("style = \"a\"\nstyle = \"b\""),
+            "duplicate key `style` in document root"
+        );
+    }
+
+    #[test]
+    fn package_json() {
+        let package: serde_json::Value = serde_json::from_str(
+            "{\"name\": \"app\", \"version\": 1.0e3, \"private\": null, \"grass\": {\"load-paths\": [\"\\u00e9\\ud83d\\ude00\"], \"style\": \"compressed\"}}",
+        )
+        .unwrap();
+
+        let file = ConfigFile::deserialize(&package["grass"]).unwrap();
+        let config = Config::from_file(file, Path::new("project")).unwrap();
+
+        assert_eq!(config.style, Some(Style::Compressed));
+        assert_eq!(
+            config.load_paths,
+            vec![Path::new("project").join("\u{e9}\u{1f600}")]
+        );
+    }
+
+    #[test]
+    fn options() {
+        let config = toml(
+            "style = \"compressed\"\nload-paths = [\"vendor\"]\nsilence-deprecations = [\"import\"]\ncompile = [\"src:dist\"]\nsource-map-urls = \"absolute\"",
+        )
+        .unwrap();
+
+        assert_eq!(config.style, Some(Style::Compressed));
+        assert_eq!(config.load_paths, vec![Path::new("project").join("vendor")]);
+        assert_eq!(config.silenced_deprecations.len(), 1);
+        assert_eq!(
+            config.compile,
+            vec![(
+                Path::new("project")
+                    .join("src")
+                    .to_string_lossy()
+                    .into_owned(),
+                Path::new("project")
+                    .join("dist")
+                    .to_string_lossy()
+                    .into_owned(),
+            )]
+        );
+
+        let error = |input: &str| toml(input).unwrap_err();
+
+        assert!(error("styles = \"compressed\"").starts_with("unknown field `styles`"));
+        assert_eq!(
+            error("load-paths = \"vendor\""),
+            "invalid type: string \"vendor\", expected a sequence"
+        );
+        assert_eq!(
+            error("compile = [\"a.scss\"]"),
+            "\"a.scss\" in `compile` must be an input:output pair"
+        );
+    }
+}
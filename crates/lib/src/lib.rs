@@ -65,10 +65,22 @@ grass input.scss
     unknown_lints,
 )]
 
+#[cfg(feature = "ast-json")]
+pub use grass_compiler::parse_to_json;
+#[cfg(feature = "plugins")]
+pub use grass_compiler::plugin;
 pub use grass_compiler::{
-    from_path, from_string, Error, ErrorKind, Fs, InputSyntax, NullFs, Options, OutputStyle,
-    Result, StdFs,
+    build_helper, codemap, dependency_graph, from_path, from_path_with_recovery, from_string,
+    from_string_with_recovery, Compiler, DependencyGraph, Deprecation, Error, ErrorKind, Fs,
+    InputSyntax, Label, Lint, Logger, NullFs, NullLogger, Options, OutputStyle, ParseCache, Result,
+    StackFrame, StdFs, StdLogger, Warning,
 };
+#[cfg(feature = "json")]
+pub use grass_compiler::{to_postcss_json, JsonLogger};
+#[cfg(feature = "watch")]
+pub use grass_compiler::{CompileResult, WatchOptions};
+#[cfg(feature = "http")]
+pub use grass_compiler::{DefaultHttpClient, HttpClient, HttpFs, HttpResponse};
 
 /// Include CSS in your binary at compile time from a Sass source file
 ///
@@ -1,12 +1,48 @@
 use std::{
-    fs::OpenOptions,
+    fs,
     io::{stdin, stdout, Read, Write},
-    path::Path,
+    panic,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
 };
+#[cfg(feature = "watch")]
+use std::{ops::ControlFlow, time::Duration};
 
-use clap::{builder::PossibleValue, value_parser, Arg, ArgAction, Command, ValueEnum};
+use clap::{
+    builder::PossibleValue, error::ErrorKind, parser::ValueSource, value_parser, Arg, ArgAction,
+    ArgMatches, Command, ValueEnum,
+};
+
+#[cfg(feature = "plugins")]
+use grass::plugin::Plugin;
+use grass::{
+    build_helper, codemap::SpanLoc, dependency_graph, from_path, from_string, Deprecation, Error,
+    InputSyntax, JsonLogger, Lint, Logger, Options, OutputStyle, ParseCache, StdLogger, Warning,
+};
+#[cfg(feature = "watch")]
+use grass::{Compiler, WatchOptions};
+use serde::Serialize;
+
+use config::{Config, CONFIG_FILE};
+
+mod config;
+
+/// How often to check watched files for changes, unless `--poll-interval` is
+/// passed
+#[cfg(feature = "watch")]
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The stack size of the threads which compile files concurrently, matching
+/// the usual size of the main thread's stack
+const WORKER_STACK_SIZE: usize = 8 * 1024 * 1024;
 
-use grass::{from_path, from_string, Options, OutputStyle};
+/// The exit code for invalid arguments, matching `EX_USAGE` from `sysexits.h`
+const EXIT_USAGE: i32 = 64;
+
+/// The exit code for stylesheets which failed to compile, matching
+/// `EX_DATAERR` from `sysexits.h`
+const EXIT_COMPILE_ERROR: i32 = 65;
 
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
 pub enum Style {
@@ -27,6 +63,27 @@ impl ValueEnum for Style {
     }
 }
 
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Syntax {
+    Scss,
+    Sass,
+    Css,
+}
+
+impl ValueEnum for Syntax {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Scss, Self::Sass, Self::Css]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Scss => PossibleValue::new("scss"),
+            Self::Sass => PossibleValue::new("sass").alias("indented"),
+            Self::Css => PossibleValue::new("css"),
+        })
+    }
+}
+
 #[derive(Eq, PartialEq, Debug, Clone)]
 pub enum SourceMapUrls {
     Relative,
@@ -46,11 +103,53 @@ impl ValueEnum for SourceMapUrls {
     }
 }
 
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum MessageFormat {
+    Human,
+    Json,
+}
+
+impl ValueEnum for MessageFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Human, Self::Json]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Human => PossibleValue::new("human"),
+            Self::Json => PossibleValue::new("json"),
+        })
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum DepsFormat {
+    Text,
+    Json,
+    Makefile,
+}
+
+impl ValueEnum for DepsFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Text, Self::Json, Self::Makefile]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Self::Text => PossibleValue::new("text"),
+            Self::Json => PossibleValue::new("json"),
+            Self::Makefile => PossibleValue::new("makefile"),
+        })
+    }
+}
+
 fn cli() -> Command {
     Command::new("grass")
         .version(env!("CARGO_PKG_VERSION"))
         .about("A Sass compiler written purely in Rust")
         .disable_version_flag(true)
+        .propagate_version(true)
+        .args_conflicts_with_subcommands(true)
         .arg(
             Arg::new("version")
                 .action(ArgAction::Version)
@@ -66,9 +165,20 @@ fn cli() -> Command {
         )
         .arg(
             Arg::new("INDENTED")
+                .action(ArgAction::SetTrue)
                 .long("indented")
                 .hide(true)
-                .help("Use the indented syntax for input from stdin"),
+                .help("Use the indented syntax for input from stdin")
+                .conflicts_with("STDIN_SYNTAX"),
+        )
+        .arg(
+            Arg::new("STDIN_SYNTAX")
+                .long("stdin-syntax")
+                .help("The syntax of the stylesheet read from stdin")
+                .default_value("scss")
+                .ignore_case(true)
+                .num_args(1)
+                .value_parser(value_parser!(Syntax)),
         )
         .arg(
             Arg::new("LOAD_PATH")
@@ -78,6 +188,38 @@ fn cli() -> Command {
                 .action(ArgAction::Append)
                 .value_parser(value_parser!(String))
                 .num_args(1)
+                .global(true)
+        )
+        .arg(
+            Arg::new("PKG_IMPORTER")
+                .long("pkg-importer")
+                .value_name("TYPE")
+                .help("Resolve `pkg:` URLs, and `~package` URLs, from the nearest node_modules directory.")
+                .value_parser(["node"])
+                .num_args(1)
+                .global(true)
+        )
+        .arg(
+            Arg::new("DEFINE")
+                .long("define")
+                .value_name("NAME:VALUE")
+                .help("Define a global variable in the entrypoint, such as `env:production`. The value is parsed as SassScript. May be passed multiple times.")
+                .action(ArgAction::Append)
+                .value_parser(|s: &str| {
+                    split_define(s).ok_or("expected a variable name and value separated by `:`")
+                })
+                .num_args(1)
+                .global(true)
+        )
+        .arg(
+            Arg::new("PLUGIN")
+                .long("plugin")
+                .value_name("PATH")
+                .help("Load custom functions from a native plugin, a shared library exporting `grass_plugin`. May be passed multiple times.")
+                .action(ArgAction::Append)
+                .value_parser(value_parser!(String))
+                .num_args(1)
+                .global(true)
         )
         .arg(
             Arg::new("STYLE")
@@ -97,17 +239,34 @@ fn cli() -> Command {
                 .long("no-charset")
                 .help("Don't emit a @charset or BOM for CSS with non-ASCII characters."),
         )
+        .arg(
+            Arg::new("OUT_DIR")
+                .short('d')
+                .long("out-dir")
+                .help("The directory to write the CSS for each input to. Required when an input is a glob pattern, unless it is part of an input:output pair.")
+                .value_parser(value_parser!(String))
+                .num_args(1)
+                .conflicts_with("STDIN"),
+        )
         .arg(
             Arg::new("UPDATE")
                 .long("update")
                 .hide(true)
                 .help("Only compile out-of-date stylesheets."),
         )
+        .arg(
+            Arg::new("ERROR_CSS")
+                .action(ArgAction::SetTrue)
+                .long("error-css")
+                .help("When an error occurs, emit a stylesheet describing it. Defaults to true when compiling to a file.")
+                .overrides_with("NO_ERROR_CSS"),
+        )
         .arg(
             Arg::new("NO_ERROR_CSS")
+                .action(ArgAction::SetTrue)
                 .long("no-error-css")
-                .hide(true)
-                .help("When an error occurs, don't emit a stylesheet describing it."),
+                .help("When an error occurs, don't emit a stylesheet describing it.")
+                .overrides_with("ERROR_CSS"),
         )
         // Source maps
         .arg(
@@ -141,22 +300,64 @@ fn cli() -> Command {
         // Other
         .arg(
             Arg::new("WATCH")
+                .action(ArgAction::SetTrue)
                 .long("watch")
-                .hide(true)
-                .help("Watch stylesheets and recompile when they change."),
+                .help("Watch stylesheets and recompile when they change.")
+                .conflicts_with("STDIN"),
         )
         .arg(
             Arg::new("POLL")
+                .action(ArgAction::SetTrue)
                 .long("poll")
-                .hide(true)
-                .help("Manually check for changes rather than using a native watcher. Only valid with --watch.")
+                .help("Check for changes by periodically reading the contents of watched files, rather than being notified of them by the operating system, which may not happen on network filesystems and Docker bind mounts. Only valid with --watch.")
                 .requires("WATCH"),
         )
+        .arg(
+            Arg::new("POLL_INTERVAL")
+                .long("poll-interval")
+                .value_name("MILLISECONDS")
+                .help("How often to check watched files for changes with --poll. Defaults to 100. Only valid with --watch.")
+                .value_parser(value_parser!(u64).range(1..))
+                .num_args(1)
+                .requires("WATCH"),
+        )
+        .arg(
+            Arg::new("JOBS")
+                .short('j')
+                .long("jobs")
+                .help("How many files to compile concurrently. Defaults to the number of CPU cores.")
+                .value_parser(value_parser!(u64).range(1..))
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("CONFIG")
+                .long("config")
+                .help("Read default options from this file rather than the nearest grass.toml, or package.json with a `grass` field.")
+                .value_parser(value_parser!(String))
+                .num_args(1)
+                .global(true),
+        )
+        .arg(
+            Arg::new("NO_CONFIG")
+                .action(ArgAction::SetTrue)
+                .long("no-config")
+                .help("Don't read default options from grass.toml or package.json.")
+                .conflicts_with("CONFIG")
+                .global(true),
+        )
+        .arg(
+            Arg::new("STOP_ON_ERROR")
+                .action(ArgAction::SetTrue)
+                .long("stop-on-error")
+                .help("Don't compile more files once an error is encountered.")
+                .overrides_with("NO_STOP_ON_ERROR")
+        )
         .arg(
             Arg::new("NO_STOP_ON_ERROR")
+                .action(ArgAction::SetTrue)
                 .long("no-stop-on-error")
-                .hide(true)
-                .help("Continue to compile more files after error is encountered.")
+                .help("Continue to compile more files after an error is encountered. This is the default.")
+                .overrides_with("STOP_ON_ERROR")
         )
         .arg(
             Arg::new("INTERACTIVE")
@@ -174,8 +375,8 @@ fn cli() -> Command {
         )
         .arg(
             Arg::new("VERBOSE")
+                .action(ArgAction::SetTrue)
                 .long("verbose")
-                .hide(true)
                 .help("Print all deprecation warnings even when they're repetitive.")
         )
         .arg(
@@ -192,14 +393,57 @@ fn cli() -> Command {
                 .help("Don't print warnings."),
         )
         .arg(
-            Arg::new("INPUT")
-                .value_parser(value_parser!(String))
-                .required_unless_present("STDIN")
-                .help("Sass files"),
+            Arg::new("QUIET_DEPS")
+                .action(ArgAction::SetTrue)
+                .long("quiet-deps")
+                .help("Don't print deprecation warnings for dependencies."),
         )
         .arg(
-            Arg::new("OUTPUT")
-                .help("Output CSS file")
+            Arg::new("SILENCE_DEPRECATION")
+                .long("silence-deprecation")
+                .help("Deprecations to ignore. May be passed multiple times.")
+                .action(ArgAction::Append)
+                .value_parser(|s: &str| s.parse::<Deprecation>())
+                .num_args(1)
+        )
+        .arg(
+            Arg::new("FUTURE_DEPRECATION")
+                .long("future-deprecation")
+                .help("Opt in to a deprecation early. May be passed multiple times.")
+                .action(ArgAction::Append)
+                .value_parser(|s: &str| s.parse::<Deprecation>())
+                .num_args(1)
+        )
+        .arg(
+            Arg::new("FATAL_DEPRECATION")
+                .long("fatal-deprecation")
+                .help("Deprecations to treat as errors. May be passed multiple times.")
+                .action(ArgAction::Append)
+                .value_parser(|s: &str| s.parse::<Deprecation>())
+                .num_args(1)
+        )
+        .arg(
+            Arg::new("LINT")
+                .long("lint")
+                .help("Warn about suspicious constructs. May be passed multiple times.")
+                .action(ArgAction::Append)
+                .value_parser(|s: &str| s.parse::<Lint>())
+                .num_args(1)
+        )
+        .arg(
+            Arg::new("MESSAGE_FORMAT")
+                .long("message-format")
+                .help("How to print errors and warnings. `json` prints one JSON object per line, followed by a summary of the files compiled.")
+                .default_value("human")
+                .ignore_case(true)
+                .num_args(1)
+                .value_parser(value_parser!(MessageFormat)),
+        )
+        .arg(
+            Arg::new("INPUT")
+                .value_parser(value_parser!(String))
+                .num_args(1..)
+                .help("The Sass file to compile, or `-` to read from stdin, and the CSS file to write, or any number of `input:output` pairs of files, directories, or glob patterns such as `themes/*.scss`. If no CSS file is given, it is written to stdout. If no inputs are given, those in the `compile` option of grass.toml are used"),
         )
 
         // Hidden, legacy arguments
@@ -209,70 +453,1102 @@ fn cli() -> Command {
                 .hide(true)
                 .num_args(1)
         )
+        .subcommand(
+            Command::new("deps")
+                .about("Print the files a stylesheet loads, without compiling it")
+                .arg(
+                    Arg::new("FORMAT")
+                        .long("format")
+                        .help("How to print the dependencies. `text` prints one file per line, `json` prints the files each file loads, and `makefile` prints a rule for use as a Make depfile.")
+                        .default_value("text")
+                        .ignore_case(true)
+                        .num_args(1)
+                        .value_parser(value_parser!(DepsFormat)),
+                )
+                .arg(
+                    Arg::new("TARGET")
+                        .long("target")
+                        .help("The target of the rule printed by `--format=makefile`. Defaults to the input with a .css extension.")
+                        .value_parser(value_parser!(String))
+                        .num_args(1),
+                )
+                .arg(
+                    Arg::new("INPUT")
+                        .value_parser(value_parser!(String))
+                        .required(true)
+                        .num_args(1)
+                        .help("The Sass file to find the dependencies of"),
+                ),
+        )
 }
 
 fn main() -> std::io::Result<()> {
-    let matches = cli().get_matches();
+    let mut cmd = cli();
+    let matches = cmd
+        .try_get_matches_from_mut(std::env::args_os())
+        .unwrap_or_else(|e| usage_error(e));
 
-    let load_paths = matches
-        .get_many::<String>("LOAD_PATH")
-        .map_or_else(Vec::new, |vals| vals.map(Path::new).collect());
+    if let Some(("deps", matches)) = matches.subcommand() {
+        return deps(&mut cmd, matches);
+    }
+
+    let config = load_config(&mut cmd, &matches)?;
+    let plugins = load_plugins(&mut cmd, &matches);
+
+    let reporter = Reporter {
+        message_format: *matches.get_one::<MessageFormat>("MESSAGE_FORMAT").unwrap(),
+        error_css: if matches.get_flag("NO_ERROR_CSS") {
+            Some(false)
+        } else if matches.get_flag("ERROR_CSS") {
+            Some(true)
+        } else {
+            None
+        },
+        warnings: AtomicUsize::new(0),
+    };
+
+    let mut options = build_options(&matches, &config, &plugins, &reporter);
+
+    let positional: Vec<&String> = matches
+        .get_many::<String>("INPUT")
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let reads_stdin =
+        matches.get_flag("STDIN") || positional.first().map_or(false, |arg| *arg == "-");
+
+    if reads_stdin {
+        if matches.get_flag("WATCH") {
+            usage_error(cmd.error(
+                ErrorKind::ArgumentConflict,
+                "--watch may not be used when reading from stdin.",
+            ));
+        }
+
+        let outputs = if matches.get_flag("STDIN") {
+            &positional[..]
+        } else {
+            &positional[1..]
+        };
+
+        let output = match outputs {
+            [] => None,
+            [output] => Some(Path::new(output.as_str())),
+            _ => usage_error(cmd.error(
+                ErrorKind::TooManyValues,
+                "Only the output file may be passed when reading from stdin.",
+            )),
+        };
+
+        let syntax = if matches.get_flag("INDENTED") {
+            Syntax::Sass
+        } else {
+            *matches.get_one::<Syntax>("STDIN_SYNTAX").unwrap()
+        };
+
+        options = options.input_syntax(match syntax {
+            Syntax::Scss => InputSyntax::Scss,
+            Syntax::Sass => InputSyntax::Sass,
+            Syntax::Css => InputSyntax::Css,
+        });
+
+        let mut buffer = String::new();
+        stdin().read_to_string(&mut buffer)?;
+
+        return match from_string(buffer, &options) {
+            Ok(css) => {
+                write_css(output, &css)?;
+                reporter.summary(1, 0);
+                Ok(())
+            }
+            Err(e) => {
+                reporter.error(&e, output)?;
+                reporter.summary(0, 1);
+                std::process::exit(EXIT_COMPILE_ERROR)
+            }
+        };
+    }
+
+    let out_dir = matches.get_one::<String>("OUT_DIR").map(Path::new);
+
+    let targets = if positional.is_empty() {
+        if config.compile.is_empty() {
+            usage_error(cmd.error(
+                ErrorKind::MissingRequiredArgument,
+                format!(
+                    "No input files were passed, and none are configured in {}.",
+                    CONFIG_FILE
+                ),
+            ));
+        }
+
+        let mut targets = Vec::new();
+        for (input, output) in &config.compile {
+            pair_targets(&mut cmd, input, output, &mut targets)?;
+        }
+        targets
+    } else {
+        parse_targets(&mut cmd, &positional, out_dir)?
+    };
+
+    if matches.get_flag("WATCH") {
+        #[cfg(feature = "watch")]
+        {
+            let parse_cache = ParseCache::new();
+
+            let interval = matches
+                .get_one::<u64>("POLL_INTERVAL")
+                .map_or(DEFAULT_POLL_INTERVAL, |&ms| Duration::from_millis(ms));
+
+            return watch(
+                &targets,
+                &options.parse_cache(&parse_cache),
+                &reporter,
+                matches.get_flag("POLL"),
+                interval,
+            );
+        }
+
+        #[cfg(not(feature = "watch"))]
+        missing_feature(&mut cmd, "--watch", "watch");
+    }
+
+    let jobs = matches.get_one::<u64>("JOBS").map_or_else(
+        || thread::available_parallelism().map_or(1, usize::from),
+        |&jobs| usize::try_from(jobs).unwrap_or(usize::MAX),
+    );
+
+    let next = AtomicUsize::new(0);
+    let compiled = AtomicUsize::new(0);
+    let failed = AtomicUsize::new(0);
+    let stop_on_error = matches.get_flag("STOP_ON_ERROR");
+
+    // each worker takes the next target until none are left. `Options` and
+    // `ParseCache` may not be shared between threads, so each worker has its
+    // own, and files loaded by several of the targets compiled by the same
+    // worker are only parsed once
+    let compile_targets = || -> std::io::Result<()> {
+        let parse_cache = ParseCache::new();
+        let options =
+            build_options(&matches, &config, &plugins, &reporter).parse_cache(&parse_cache);
+
+        while !(stop_on_error && failed.load(Ordering::Relaxed) > 0) {
+            let target = match targets.get(next.fetch_add(1, Ordering::Relaxed)) {
+                Some(target) => target,
+                None => break,
+            };
+
+            match from_path(&target.input, &options) {
+                Ok(css) => {
+                    write_css(target.output.as_deref(), &css)?;
+                    compiled.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(e) => {
+                    reporter.error(&e, target.output.as_deref())?;
+                    failed.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+
+        Ok(())
+    };
+
+    thread::scope(|scope| {
+        let workers = (0..jobs.clamp(1, targets.len().max(1)))
+            .map(|_| {
+                thread::Builder::new()
+                    .stack_size(WORKER_STACK_SIZE)
+                    .spawn_scoped(scope, compile_targets)
+            })
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        workers
+            .into_iter()
+            .try_for_each(|worker| worker.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+    })?;
+
+    let failed = failed.into_inner();
+    reporter.summary(compiled.into_inner(), failed);
+
+    if failed > 0 {
+        std::process::exit(EXIT_COMPILE_ERROR);
+    }
+
+    Ok(())
+}
+
+/// Read the config passed with `--config`, or found in the current directory or
+/// its ancestors
+fn load_config(cmd: &mut Command, matches: &ArgMatches) -> std::io::Result<Config> {
+    Ok(match matches.get_one::<String>("CONFIG") {
+        Some(path) => Config::load(Path::new(path)).map(Some),
+        None if matches.get_flag("NO_CONFIG") => Ok(None),
+        None => Config::find(&std::env::current_dir()?),
+    }
+    .unwrap_or_else(|e| usage_error(cmd.error(ErrorKind::InvalidValue, e)))
+    .unwrap_or_default())
+}
+
+/// Stands in for [`grass::plugin::Plugin`] when grass is built without the
+/// `plugins` feature, in which case `--plugin` is rejected
+#[cfg(not(feature = "plugins"))]
+enum Plugin {}
+
+/// Load each plugin passed with `--plugin`
+#[cfg(feature = "plugins")]
+fn load_plugins(cmd: &mut Command, matches: &ArgMatches) -> Vec<Plugin> {
+    let mut plugins = Vec::new();
+
+    for path in matches.get_many::<String>("PLUGIN").into_iter().flatten() {
+        // safety: the user vouches for the libraries they ask us to load
+        match unsafe { Plugin::load(path) } {
+            Ok(plugin) => plugins.push(plugin),
+            Err(e) => usage_error(cmd.error(
+                ErrorKind::InvalidValue,
+                format!("Failed to load plugin {}: {}", path, e),
+            )),
+        }
+    }
+
+    plugins
+}
+
+#[cfg(not(feature = "plugins"))]
+fn load_plugins(cmd: &mut Command, matches: &ArgMatches) -> Vec<Plugin> {
+    if matches.contains_id("PLUGIN") {
+        missing_feature(cmd, "--plugin", "plugins");
+    }
+
+    Vec::new()
+}
+
+/// Print the files the input loads, in the format passed with `--format`
+fn deps(cmd: &mut Command, matches: &ArgMatches) -> std::io::Result<()> {
+    let config = load_config(cmd, matches)?;
+    let plugins = load_plugins(cmd, matches);
+
+    let options = load_options(matches, &config, &plugins);
+
+    let input = Path::new(matches.get_one::<String>("INPUT").unwrap());
+
+    let graph = match dependency_graph(input, &options) {
+        Ok(graph) => graph,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(EXIT_COMPILE_ERROR);
+        }
+    };
+
+    // print paths relative to the current directory where possible, as the
+    // graph holds canonical paths
+    let current_dir = std::env::current_dir().and_then(fs::canonicalize)?;
+    let display = |path: &Path| -> String {
+        path.strip_prefix(&current_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned()
+    };
+
+    let output = match *matches.get_one::<DepsFormat>("FORMAT").unwrap() {
+        DepsFormat::Text => graph
+            .files()
+            .map(|file| display(file) + "\n")
+            .collect::<String>(),
+        DepsFormat::Json => graph.to_json() + "\n",
+        DepsFormat::Makefile => {
+            let target = matches
+                .get_one::<String>("TARGET")
+                .map_or_else(|| input.with_extension("css"), PathBuf::from);
+
+            let files: Vec<String> = graph.files().map(display).collect();
+            depfile(&target.to_string_lossy(), &files)
+        }
+    };
 
-    let style = match &matches.get_one::<Style>("STYLE").unwrap() {
+    stdout().write_all(output.as_bytes())
+}
+
+/// A Makefile rule making `target` depend on `files`, the first of which is
+/// the entrypoint, followed by an empty rule for each other file, so that
+/// deleting one does not break the build
+fn depfile(target: &str, files: &[String]) -> String {
+    fn escape(path: &str) -> String {
+        let mut escaped = String::with_capacity(path.len());
+
+        for c in path.chars() {
+            match c {
+                ' ' | '#' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                '$' => escaped.push_str("$$"),
+                _ => escaped.push(c),
+            }
+        }
+
+        escaped
+    }
+
+    let mut rule = escape(target);
+    rule.push(':');
+
+    for file in files {
+        rule.push(' ');
+        rule.push_str(&escape(file));
+    }
+
+    rule.push('\n');
+
+    for file in files.iter().skip(1) {
+        rule.push('\n');
+        rule.push_str(&escape(file));
+        rule.push_str(":\n");
+    }
+
+    rule
+}
+
+/// The options to compile with, given the arguments and the config, which are
+/// shared by every file compiled
+fn build_options<'a>(
+    matches: &ArgMatches,
+    config: &Config,
+    plugins: &'a [Plugin],
+    reporter: &'a Reporter,
+) -> Options<'a> {
+    // options passed on the command line take precedence over the config
+    let style = match matches.value_source("STYLE") {
+        Some(ValueSource::CommandLine) => *matches.get_one::<Style>("STYLE").unwrap(),
+        _ => config
+            .style
+            .unwrap_or(*matches.get_one::<Style>("STYLE").unwrap()),
+    };
+
+    let style = match style {
         Style::Expanded => OutputStyle::Expanded,
         Style::Compressed => OutputStyle::Compressed,
     };
 
-    let options = &Options::default()
-        .load_paths(&load_paths)
+    let mut options = load_options(matches, config, plugins)
         .style(style)
         .quiet(matches.get_flag("QUIET"))
+        .quiet_deps(matches.get_flag("QUIET_DEPS"))
+        .verbose(matches.get_flag("VERBOSE"))
         .unicode_error_messages(!matches.get_flag("NO_UNICODE"))
-        .allows_charset(!matches.get_flag("NO_CHARSET"));
-
-    let (mut stdout_write, mut file_write);
-    let buf_out: &mut dyn Write = if let Some(path) = matches.get_one::<String>("OUTPUT") {
-        file_write = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(path)?;
-        &mut file_write
+        .allows_charset(!matches.get_flag("NO_CHARSET"))
+        .logger(reporter);
+
+    for &deprecation in matches
+        .get_many::<Deprecation>("SILENCE_DEPRECATION")
+        .into_iter()
+        .flatten()
+        .chain(&config.silenced_deprecations)
+    {
+        options = options.silence_deprecation(deprecation);
+    }
+
+    for &deprecation in matches
+        .get_many::<Deprecation>("FUTURE_DEPRECATION")
+        .into_iter()
+        .flatten()
+    {
+        options = options.future_deprecation(deprecation);
+    }
+
+    for &deprecation in matches
+        .get_many::<Deprecation>("FATAL_DEPRECATION")
+        .into_iter()
+        .flatten()
+    {
+        options = options.fatal_deprecation(deprecation);
+    }
+
+    for &lint in matches.get_many::<Lint>("LINT").into_iter().flatten() {
+        options = options.lint(lint);
+    }
+
+    options
+}
+
+/// The options which determine which files a stylesheet loads, shared by
+/// compiling and by `grass deps`
+fn load_options<'a>(matches: &ArgMatches, config: &Config, plugins: &'a [Plugin]) -> Options<'a> {
+    let load_paths = matches
+        .get_many::<String>("LOAD_PATH")
+        .map_or_else(Vec::new, |vals| vals.map(Path::new).collect());
+
+    let mut options = Options::default()
+        .load_paths(&load_paths)
+        .load_paths(&config.load_paths)
+        .node_package_importer(matches.contains_id("PKG_IMPORTER"));
+
+    for (name, value) in matches
+        .get_many::<(String, String)>("DEFINE")
+        .into_iter()
+        .flatten()
+    {
+        options = options.define(name.as_str(), value.as_str());
+    }
+
+    for plugin in plugins {
+        #[cfg(feature = "plugins")]
+        {
+            options = options.plugin(plugin);
+        }
+
+        #[cfg(not(feature = "plugins"))]
+        match *plugin {}
+    }
+
+    options
+}
+
+/// Split the argument to `--define` into the variable's name, without a leading
+/// `$`, and its value
+fn split_define(arg: &str) -> Option<(String, String)> {
+    let (name, value) = arg.split_once(':')?;
+    let name = name.strip_prefix('$').unwrap_or(name);
+
+    if name.is_empty() || value.trim().is_empty() {
+        return None;
+    }
+
+    Some((name.to_owned(), value.to_owned()))
+}
+
+/// Write `css` to `output`, creating its parent directories if they don't
+/// exist, or to stdout if `output` is `None`
+fn write_css(output: Option<&Path>, css: &str) -> std::io::Result<()> {
+    match output {
+        Some(path) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            fs::write(path, css)
+        }
+        None => stdout().write_all(css.as_bytes()),
+    }
+}
+
+/// The stylesheets to compile given the positional arguments, which are either
+/// an input file and an optional output file, or any number of
+/// `input:output` pairs of files, directories, or glob patterns
+///
+/// If `out_dir` is given, every argument is an input, and its CSS is written
+/// to `out_dir` instead
+fn parse_targets(
+    cmd: &mut Command,
+    args: &[&String],
+    out_dir: Option<&Path>,
+) -> std::io::Result<Vec<Target>> {
+    let mut targets = Vec::new();
+
+    if let Some(out_dir) = out_dir {
+        for arg in args {
+            if split_pair(arg).is_some() {
+                usage_error(cmd.error(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "\"{}\" is an input:output pair, which may not be used with --out-dir.",
+                        arg
+                    ),
+                ));
+            }
+
+            let input = Path::new(arg.as_str());
+
+            if is_glob(arg) {
+                glob_targets(cmd, arg, out_dir, &mut targets)?;
+            } else if input.is_dir() {
+                directory_targets(input, out_dir, &mut targets)?;
+            } else {
+                targets.push(Target {
+                    output: Some(
+                        out_dir
+                            .join(input.file_name().unwrap_or_default())
+                            .with_extension("css"),
+                    ),
+                    input: input.to_path_buf(),
+                });
+            }
+        }
+
+        return Ok(targets);
+    }
+
+    if !args.iter().any(|arg| split_pair(arg).is_some()) {
+        let (input, output) = match args {
+            [input] => (input, None),
+            [input, output] => (input, Some(PathBuf::from(output))),
+            _ => usage_error(cmd.error(
+                    ErrorKind::TooManyValues,
+                    "Only two positional arguments may be passed, unless they are all input:output pairs.",
+                )),
+        };
+
+        if Path::new(input).is_dir() {
+            usage_error(cmd.error(
+                ErrorKind::InvalidValue,
+                format!(
+                    "\"{}\" is a directory, and must be passed as an input:output pair.",
+                    input
+                ),
+            ));
+        }
+
+        if is_glob(input) {
+            usage_error(cmd.error(
+                ErrorKind::InvalidValue,
+                format!(
+                    "\"{}\" is a glob pattern, and must be passed with --out-dir or as an input:output pair.",
+                    input
+                ),
+            ));
+        }
+
+        targets.push(Target {
+            input: PathBuf::from(input),
+            output,
+        });
+
+        return Ok(targets);
+    }
+
+    for arg in args {
+        let (input, output) = match split_pair(arg) {
+            Some(pair) => pair,
+            None => usage_error(cmd.error(
+                ErrorKind::InvalidValue,
+                format!(
+                    "\"{}\" must be an input:output pair, as other arguments are.",
+                    arg
+                ),
+            )),
+        };
+
+        pair_targets(cmd, input, output, &mut targets)?;
+    }
+
+    Ok(targets)
+}
+
+/// Add the targets for an `input:output` pair of files, directories, or a glob
+/// pattern and a directory
+fn pair_targets(
+    cmd: &mut Command,
+    input: &str,
+    output: &str,
+    targets: &mut Vec<Target>,
+) -> std::io::Result<()> {
+    if is_glob(input) {
+        glob_targets(cmd, input, Path::new(output), targets)
+    } else if Path::new(input).is_dir() {
+        directory_targets(Path::new(input), Path::new(output), targets)
     } else {
-        stdout_write = stdout();
-        &mut stdout_write
+        targets.push(Target {
+            input: PathBuf::from(input),
+            output: Some(PathBuf::from(output)),
+        });
+
+        Ok(())
+    }
+}
+
+/// A stylesheet to compile, and the file to write its CSS to
+struct Target {
+    input: PathBuf,
+    /// If `None`, the CSS is written to stdout
+    output: Option<PathBuf>,
+}
+
+/// Split an `input:output` pair, ignoring the colons in Windows drive letters
+fn split_pair(arg: &str) -> Option<(&str, &str)> {
+    let is_drive_letter = |s: &str| {
+        let bytes = s.as_bytes();
+        bytes.len() > 2
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && matches!(bytes[2], b'/' | b'\\')
     };
 
-    buf_out.write_all(
-        if let Some(name) = matches.get_one::<String>("INPUT") {
-            from_path(name, options)
-        } else if matches.get_flag("STDIN") {
-            from_string(
-                {
-                    let mut buffer = String::new();
-                    stdin().read_to_string(&mut buffer)?;
-                    buffer
-                },
-                options,
-            )
-        } else {
-            unreachable!()
+    let mut start = 0;
+    while let Some(idx) = arg[start..].find(':') {
+        let idx = start + idx;
+
+        if idx == 1 && is_drive_letter(arg) {
+            start = idx + 1;
+            continue;
         }
-        .unwrap_or_else(|e| {
-            eprintln!("{}", e);
-            std::process::exit(1)
-        })
-        .as_bytes(),
-    )?;
+
+        return Some((&arg[..idx], &arg[idx + 1..]));
+    }
+
+    None
+}
+
+/// Add a target for every Sass file within `input`, writing its CSS to the same
+/// relative location within `output`
+///
+/// Partials, whose names begin with `_`, are skipped.
+fn directory_targets(
+    input: &Path,
+    output: &Path,
+    targets: &mut Vec<Target>,
+) -> std::io::Result<()> {
+    let mut entrypoints = Vec::new();
+    build_helper::find_entrypoints(input, &mut entrypoints)?;
+
+    for entrypoint in entrypoints {
+        let relative = entrypoint.strip_prefix(input).unwrap_or(&entrypoint);
+
+        targets.push(Target {
+            output: Some(output.join(relative).with_extension("css")),
+            input: entrypoint,
+        });
+    }
+
+    Ok(())
+}
+
+/// Whether `arg` should be expanded as a glob pattern
+///
+/// A file whose name contains glob metacharacters is compiled as-is if it
+/// exists.
+fn is_glob(arg: &str) -> bool {
+    arg.contains(['*', '?', '[']) && !Path::new(arg).exists()
+}
+
+/// Add a target for each file matching `pattern`, writing its CSS to the
+/// location in `output` corresponding to its path relative to the directory
+/// the pattern starts in
+///
+/// As in shells, `*` and `?` match within a single component and don't match a
+/// leading `.`, and `**` matches any number of directories. Partials, whose
+/// names start with an underscore, are skipped.
+fn glob_targets(
+    cmd: &mut Command,
+    pattern: &str,
+    output: &Path,
+    targets: &mut Vec<Target>,
+) -> std::io::Result<()> {
+    let is_separator = |c: char| c == '/' || (cfg!(windows) && c == '\\');
+
+    // the leading components of the pattern without any metacharacters
+    // name the directory to search in
+    let mut base_len = 0;
+    for (idx, c) in pattern.char_indices() {
+        if matches!(c, '*' | '?' | '[') {
+            break;
+        }
+
+        if is_separator(c) {
+            base_len = idx + c.len_utf8();
+        }
+    }
+
+    let base = Path::new(&pattern[..base_len]);
+
+    let options = glob::MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: true,
+    };
+
+    let paths = match glob::glob_with(pattern, options) {
+        Ok(paths) => paths,
+        Err(e) => usage_error(cmd.error(
+            ErrorKind::InvalidValue,
+            format!("Invalid glob pattern \"{}\": {}.", pattern, e),
+        )),
+    };
+
+    let mut matched_any = false;
+
+    for path in paths {
+        let path = path?;
+
+        if !path.is_file()
+            || path
+                .file_name()
+                .map_or(false, |name| name.to_string_lossy().starts_with('_'))
+        {
+            continue;
+        }
+
+        let relative = path.strip_prefix(base).unwrap_or(&path);
+
+        targets.push(Target {
+            output: Some(output.join(relative).with_extension("css")),
+            input: path,
+        });
+
+        matched_any = true;
+    }
+
+    if !matched_any {
+        usage_error(cmd.error(
+            ErrorKind::InvalidValue,
+            format!("No stylesheets match \"{}\".", pattern),
+        ));
+    }
+
     Ok(())
 }
 
+/// Print `err` and exit with [`EXIT_USAGE`], unless it is a request for help
+/// or version information
+fn usage_error(err: clap::Error) -> ! {
+    if !err.use_stderr() {
+        err.exit();
+    }
+
+    let _ = err.print();
+    std::process::exit(EXIT_USAGE)
+}
+
+/// Exit with a usage error for `flag`, which is only supported when grass is
+/// built with `feature`
+#[cfg(any(not(feature = "watch"), not(feature = "plugins")))]
+fn missing_feature(cmd: &mut Command, flag: &str, feature: &str) -> ! {
+    usage_error(cmd.error(
+        ErrorKind::InvalidValue,
+        format!(
+            "{} requires grass to be built with the `{}` feature.",
+            flag, feature
+        ),
+    ))
+}
+
+/// Prints errors and warnings in the requested format, and reports
+/// stylesheets which failed to compile
+#[derive(Debug)]
+struct Reporter {
+    message_format: MessageFormat,
+    /// Whether to write a stylesheet describing the error in place of the
+    /// output, or `None` to do so only when compiling to a file
+    error_css: Option<bool>,
+    /// The number of warnings printed since the last summary
+    warnings: AtomicUsize,
+}
+
+impl Reporter {
+    /// Print `e`, and write a stylesheet describing it to `output` if
+    /// requested
+    fn error(&self, e: &Error, output: Option<&Path>) -> std::io::Result<()> {
+        match self.message_format {
+            MessageFormat::Human => eprintln!("{}", e),
+            MessageFormat::Json => eprintln!("{}", e.to_json()),
+        }
+
+        if self.error_css.unwrap_or(output.is_some()) {
+            write_css(output, &error_css(&e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    fn logger(&self) -> &'static dyn Logger {
+        match self.message_format {
+            MessageFormat::Human => &StdLogger,
+            MessageFormat::Json => &JsonLogger,
+        }
+    }
+
+    /// With `--message-format=json`, print the number of stylesheets which
+    /// compiled and failed, and the number of warnings printed, since the last
+    /// summary
+    fn summary(&self, compiled: usize, failed: usize) {
+        if self.message_format != MessageFormat::Json {
+            return;
+        }
+
+        #[derive(Serialize)]
+        struct Summary {
+            severity: &'static str,
+            message: String,
+            summary: Counts,
+        }
+
+        #[derive(Serialize)]
+        struct Counts {
+            compiled: usize,
+            failed: usize,
+            warnings: usize,
+        }
+
+        let warnings = self.warnings.swap(0, Ordering::Relaxed);
+
+        let summary = Summary {
+            severity: "info",
+            message: format!(
+                "{} compiled, {} failed, {} {}.",
+                compiled,
+                failed,
+                warnings,
+                if warnings == 1 { "warning" } else { "warnings" },
+            ),
+            summary: Counts {
+                compiled,
+                failed,
+                warnings,
+            },
+        };
+
+        eprintln!(
+            "{}",
+            serde_json::to_string(&summary).expect("the summary is always serializable")
+        );
+    }
+}
+
+impl Logger for Reporter {
+    fn debug(&self, location: SpanLoc, message: &str) {
+        self.logger().debug(location, message);
+    }
+
+    fn warn(&self, warning: &Warning) {
+        self.warnings.fetch_add(1, Ordering::Relaxed);
+        self.logger().warn(warning);
+    }
+
+    fn omitted_deprecations(&self, count: usize) {
+        self.logger().omitted_deprecations(count);
+    }
+}
+
+/// A stylesheet which displays `message` at the top of the page, used in place
+/// of the output of a stylesheet which failed to compile
+fn error_css(message: &str) -> String {
+    let mut css = String::from("/* ");
+
+    for (idx, line) in message.trim_end().lines().enumerate() {
+        if idx != 0 {
+            css.push_str("\n * ");
+        }
+
+        css.push_str(&line.replace("*/", "*\u{2215}"));
+    }
+
+    css.push_str(" */\n\nbody::before {\n");
+    css.push_str(
+        "  font-family: \"Source Code Pro\", \"SF Mono\", Monaco, Inconsolata, \"Fira Mono\",\n      \"Droid Sans Mono\", monospace, monospace;\n",
+    );
+    css.push_str("  white-space: pre;\n");
+    css.push_str("  display: block;\n");
+    css.push_str("  padding: 1em;\n");
+    css.push_str("  margin-bottom: 1em;\n");
+    css.push_str("  border-bottom: 2px solid black;\n");
+    css.push_str("  content: \"");
+
+    for c in message.trim_end().chars() {
+        match c {
+            '"' | '\\' => {
+                css.push('\\');
+                css.push(c);
+            }
+            ' '..='~' => css.push(c),
+            // a single space after an escape is consumed as part of it
+            c => css.push_str(&format!("\\{:x} ", c as u32)),
+        }
+    }
+
+    css.push_str("\";\n}\n");
+
+    css
+}
+
+/// Compile each of `targets`, and then recompile them each time they or any
+/// of the files they loaded change, until the process is killed
+///
+/// Changes are reported by the operating system or, if `poll` is set, found by
+/// comparing the contents of the files every `interval`. Errors are printed,
+/// but do not stop the watcher.
+#[cfg(feature = "watch")]
+fn watch(
+    targets: &[Target],
+    options: &Options,
+    reporter: &Reporter,
+    poll: bool,
+    interval: Duration,
+) -> std::io::Result<()> {
+    let inputs: Vec<&Path> = targets
+        .iter()
+        .map(|target| target.input.as_path())
+        .collect();
+    let watch_options = WatchOptions::default()
+        .interval(interval)
+        .poll(poll)
+        .compare_contents(poll);
+
+    Compiler::new()
+        .watch(&inputs, options, &watch_options, |results| {
+            let mut compiled = 0;
+            let mut failed = 0;
+
+            for result in results {
+                for target in targets.iter().filter(|t| t.input == result.entrypoint()) {
+                    let written = match result.css() {
+                        Some(css) => write_css(target.output.as_deref(), css),
+                        None => reporter.error(result.error().unwrap(), target.output.as_deref()),
+                    };
+
+                    if let Err(e) = written {
+                        return ControlFlow::Break(Err(e));
+                    }
+                }
+
+                match result.css() {
+                    Some(..) => {
+                        compiled += 1;
+
+                        if reporter.message_format == MessageFormat::Human {
+                            eprintln!(
+                                "Compiled {} in {:.2?}.",
+                                result.entrypoint().display(),
+                                result.duration()
+                            );
+                        }
+                    }
+                    None => failed += 1,
+                }
+            }
+
+            reporter.summary(compiled, failed);
+
+            ControlFlow::Continue(())
+        })
+        .unwrap_or(Ok(()))
+}
+
 #[cfg(test)]
 mod test {
-    use crate::cli;
+    use std::{fs, path::Path};
+
+    use crate::{cli, depfile, error_css, glob_targets, split_define, split_pair};
 
     #[test]
     fn verify() {
         cli().debug_assert();
     }
+
+    #[test]
+    fn deps_accepts_load_options() {
+        let matches = cli()
+            .try_get_matches_from([
+                "grass",
+                "deps",
+                "--define",
+                "env:production",
+                "--plugin",
+                "tokens.so",
+                "a.scss",
+            ])
+            .unwrap();
+        let (_, matches) = matches.subcommand().unwrap();
+
+        assert_eq!(
+            matches
+                .get_many::<(String, String)>("DEFINE")
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec![&("env".to_owned(), "production".to_owned())]
+        );
+        assert_eq!(
+            matches.get_one::<String>("PLUGIN").map(String::as_str),
+            Some("tokens.so")
+        );
+    }
+
+    #[test]
+    fn split_pairs() {
+        assert_eq!(split_pair("a.scss"), None);
+        assert_eq!(split_pair("a.scss:b.css"), Some(("a.scss", "b.css")));
+        assert_eq!(split_pair("src/:dist/"), Some(("src/", "dist/")));
+        assert_eq!(
+            split_pair(r"C:\src\a.scss:D:\dist\a.css"),
+            Some((r"C:\src\a.scss", r"D:\dist\a.css"))
+        );
+        assert_eq!(split_pair(r"C:\src\a.scss"), None);
+    }
+
+    #[test]
+    fn glob_skips_dotfiles_and_partials() {
+        let dir = std::env::temp_dir().join(format!("grass-glob-{}", std::process::id()));
+        for file in ["a.scss", ".b.scss", "_c.scss", "d/e.scss", "d/.f/g.scss"] {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let mut targets = Vec::new();
+        let pattern = format!("{}/**/*.scss", dir.display());
+        glob_targets(&mut cli(), &pattern, Path::new("out"), &mut targets).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let outputs: Vec<_> = targets
+            .into_iter()
+            .map(|target| target.output.unwrap())
+            .collect();
+        assert_eq!(
+            outputs,
+            vec![Path::new("out/a.css"), Path::new("out/d/e.css")]
+        );
+    }
+
+    #[test]
+    fn error_css_escapes_message() {
+        assert_eq!(
+            error_css("Error: \"*/\" \\ \u{2575}\n"),
+            "/* Error: \"*\u{2215}\" \\ \u{2575} */
+
+body::before {
+  font-family: \"Source Code Pro\", \"SF Mono\", Monaco, Inconsolata, \"Fira Mono\",
+      \"Droid Sans Mono\", monospace, monospace;
+  white-space: pre;
+  display: block;
+  padding: 1em;
+  margin-bottom: 1em;
+  border-bottom: 2px solid black;
+  content: \"Error: \\\"*/\\\" \\\\ \\2575 \";
+}
+"
+        );
+    }
+
+    #[test]
+    fn split_defines() {
+        assert_eq!(
+            split_define("env:production"),
+            Some(("env".to_owned(), "production".to_owned()))
+        );
+        assert_eq!(
+            split_define("$url:'http://example.com'"),
+            Some(("url".to_owned(), "'http://example.com'".to_owned()))
+        );
+        assert_eq!(split_define("env"), None);
+        assert_eq!(split_define(":production"), None);
+        assert_eq!(split_define("env: "), None);
+    }
+
+    #[test]
+    fn depfile_escapes_paths() {
+        assert_eq!(
+            depfile(
+                "out dir/a.css",
+                &[
+                    "a.scss".to_owned(),
+                    "my partials/_b$.scss".to_owned(),
+                    "#c.scss".to_owned(),
+                ]
+            ),
+            "out\\ dir/a.css: a.scss my\\ partials/_b$$.scss \\#c.scss
+
+my\\ partials/_b$$.scss:
+
+\\#c.scss:
+"
+        );
+    }
 }
@@ -156,6 +156,46 @@ test!(
     "a {\n  color: 'foo' + 1px;\n}\n",
     "a {\n  color: \"foo1px\";\n}\n"
 );
+test!(
+    number_unitless_plus_dblquoted,
+    "a {\n  color: 1 + \"foo\";\n}\n",
+    "a {\n  color: \"1foo\";\n}\n"
+);
+test!(
+    number_unit_plus_dblquoted,
+    "a {\n  color: 1px + \"foo\";\n}\n",
+    "a {\n  color: \"1pxfoo\";\n}\n"
+);
+test!(
+    number_unit_plus_unquoted,
+    "a {\n  color: 1px + foo;\n}\n",
+    "a {\n  color: 1pxfoo;\n}\n"
+);
+test!(
+    number_plus_quoted_number,
+    "a {\n  color: 1 + \"1\";\n}\n",
+    "a {\n  color: \"11\";\n}\n"
+);
+test!(
+    null_plus_dblquoted,
+    "a {\n  color: null + \"foo\";\n}\n",
+    "a {\n  color: \"foo\";\n}\n"
+);
+test!(
+    null_plus_unquoted,
+    "a {\n  color: null + foo;\n}\n",
+    "a {\n  color: foo;\n}\n"
+);
+test!(
+    interpolated_plus_dblquoted_is_unquoted,
+    "a {\n  color: #{foo} + \"bar\";\n}\n",
+    "a {\n  color: foobar;\n}\n"
+);
+test!(
+    quoted_plus_interpolated_is_quoted,
+    "a {\n  color: \"foo\" + #{bar};\n}\n",
+    "a {\n  color: \"foobar\";\n}\n"
+);
 test!(
     true_plus_false,
     "a {\n  color: true + false;\n}\n",
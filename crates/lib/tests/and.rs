@@ -57,3 +57,23 @@ error!(
     properly_bubbles_error_when_invalid_char_after_and,
     "a {\n  color: false and? foo;\n}\n", "Error: Expected expression."
 );
+test!(
+    null_guard_does_not_evaluate_rhs,
+    "$x: null;\na {\n  color: inspect($x and map-get($x, k));\n}\n",
+    "a {\n  color: null;\n}\n"
+);
+test!(
+    null_guard_evaluates_rhs_when_lhs_is_truthy,
+    "$x: (k: v);\na {\n  color: $x and map-get($x, k);\n}\n",
+    "a {\n  color: v;\n}\n"
+);
+test!(
+    short_circuits_user_defined_function,
+    "@function foo() {\n  @error \"evaluated\";\n}\n\na {\n  color: false and foo();\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    short_circuits_after_not,
+    "@function foo() {\n  @error \"evaluated\";\n}\n\na {\n  color: not true and foo();\n}\n",
+    "a {\n  color: false;\n}\n"
+);
@@ -140,3 +140,28 @@ error!(
     }",
     "Error: () isn't a valid CSS value."
 );
+test!(
+    type_of_arglist,
+    "@function foo($a...) {\n  @return type-of($a);\n}\n\na {\n  color: foo(1, 2);\n}\n",
+    "a {\n  color: arglist;\n}\n"
+);
+test!(
+    nth_of_arglist,
+    "@function foo($a...) {\n  @return nth($a, 2);\n}\n\na {\n  color: foo(1, 2, 3);\n}\n",
+    "a {\n  color: 2;\n}\n"
+);
+test!(
+    keyword_args_round_trip_through_spread,
+    "@use \"sass:meta\";\n\n@function inner($a, $b: 0, $rest...) {\n  @return inspect(($a, $b, meta.keywords($rest)));\n}\n\n@function outer($args...) {\n  @return inner($args...);\n}\n\na {\n  color: outer(1, $b: 2, $c: 3);\n}\n",
+    "a {\n  color: 1, 2, (c: 3);\n}\n"
+);
+test!(
+    keyword_args_in_mixin,
+    "@use \"sass:meta\";\n\n@mixin foo($args...) {\n  color: inspect(meta.keywords($args));\n  length: length($args);\n}\n\na {\n  @include foo(1, $b: 2);\n}\n",
+    "a {\n  color: (b: 2);\n  length: 1;\n}\n"
+);
+error!(
+    unused_keyword_args_is_error,
+    "@function foo($a...) {\n  @return nth($a, 1);\n}\n\na {\n  color: foo(1, $c: 3);\n}\n",
+    "Error: No argument named $c."
+);
@@ -351,3 +351,80 @@ error!(
     }",
     "Error: No arguments named $b, $c or $d."
 );
+test!(
+    mixin_keyword_args_out_of_order,
+    "@mixin foo($a, $b) {
+        color: $a $b;
+    }
+
+    a {
+        @include foo($b: 2, $a: 1);
+    }",
+    "a {\n  color: 1 2;\n}\n"
+);
+test!(
+    mixin_default_arg_references_earlier_arg,
+    "@mixin foo($a, $b: $a * 2) {
+        color: $a $b;
+    }
+
+    a {
+        @include foo(3);
+    }",
+    "a {\n  color: 3 6;\n}\n"
+);
+test!(
+    function_keyword_arg_skips_default,
+    "@function foo($a: 1, $b: 2, $c: 3) {
+        @return $a $b $c;
+    }
+
+    a {
+        color: foo($c: 4);
+    }",
+    "a {\n  color: 1 2 4;\n}\n"
+);
+error!(
+    mixin_arg_passed_by_position_and_name,
+    "@mixin foo($a, $b: 1) {
+        color: $a;
+    }
+
+    a {
+        @include foo(1, $a: 2);
+    }",
+    "Error: Argument $a was passed both by position and by name."
+);
+error!(
+    mixin_missing_arg,
+    "@mixin foo($a, $b: 1) {
+        color: $a;
+    }
+
+    a {
+        @include foo($b: 2);
+    }",
+    "Error: Missing argument $a."
+);
+error!(
+    mixin_too_many_positional_args,
+    "@mixin foo($a, $b: 1) {
+        color: $a;
+    }
+
+    a {
+        @include foo(1, 2, 3);
+    }",
+    "Error: Only 2 arguments allowed, but 3 were passed."
+);
+error!(
+    mixin_unknown_keyword_arg,
+    "@mixin foo($a) {
+        color: $a;
+    }
+
+    a {
+        @include foo($a: 1, $b: 2);
+    }",
+    "Error: No argument named $b."
+);
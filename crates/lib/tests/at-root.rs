@@ -297,3 +297,8 @@ error!(
     query_is_empty_parens_after_interpolation_is_resolved,
     "@at-root (#{null}) {}", r#"Error: Expected "without"."#
 );
+#[test]
+fn error_in_interpolated_query_points_to_interpolated_expression() {
+    let input = "a {\n  @at-root (#{\"wit\"}: media) {\n    b {\n      c: d;\n    }\n  }\n}\n";
+    assert_eq!(macros::error_location(input), ((1, 12), (1, 20)));
+}
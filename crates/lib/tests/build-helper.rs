@@ -0,0 +1,67 @@
+use std::fs;
+
+#[macro_use]
+mod macros;
+
+#[test]
+fn compile_dir_writes_non_partials() {
+    let src = tempfile::tempdir().unwrap();
+    let out = tempfile::tempdir().unwrap();
+
+    fs::write(src.path().join("_colors.scss"), "$red: red;").unwrap();
+    fs::write(
+        src.path().join("main.scss"),
+        "@use 'colors';\na { color: colors.$red; }",
+    )
+    .unwrap();
+    fs::create_dir(src.path().join("nested")).unwrap();
+    fs::write(src.path().join("nested/other.sass"), "b\n  c: d").unwrap();
+    fs::write(src.path().join("notes.txt"), "not sass").unwrap();
+
+    let outputs =
+        grass::build_helper::compile_dir(src.path(), out.path(), &grass::Options::default())
+            .unwrap();
+
+    assert_eq!(
+        outputs,
+        vec![
+            out.path().join("main.css"),
+            out.path().join("nested/other.css")
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(out.path().join("main.css")).unwrap(),
+        "a {\n  color: red;\n}\n"
+    );
+    assert_eq!(
+        fs::read_to_string(out.path().join("nested/other.css")).unwrap(),
+        "b {\n  c: d;\n}\n"
+    );
+}
+
+#[test]
+fn compile_dir_reports_errors() {
+    let src = tempfile::tempdir().unwrap();
+    let out = tempfile::tempdir().unwrap();
+
+    fs::write(src.path().join("main.scss"), "a { b: 1s + 1px }").unwrap();
+
+    let err = grass::build_helper::compile_dir(src.path(), out.path(), &grass::Options::default())
+        .unwrap_err();
+
+    assert_eq!(err.message(), "Incompatible units px and s.");
+    assert!(!out.path().join("main.css").exists());
+}
+
+#[test]
+fn out_dir_must_be_set() {
+    // integration tests are not run from a build script
+    if std::env::var_os("OUT_DIR").is_some() {
+        return;
+    }
+
+    assert!(
+        grass::build_helper::compile_file_to_out_dir("input.scss", &grass::Options::default())
+            .is_err()
+    );
+}
@@ -38,3 +38,46 @@ error!(
     clamp_last_non_compatible,
     "a {\n  color: clamp(1px, 2px, 3deg);\n}\n", "Error: 1px and 3deg are incompatible."
 );
+test!(
+    clamp_value_below_min,
+    "a {\n  color: clamp(1px, 0px, 3px);\n}\n",
+    "a {\n  color: 1px;\n}\n"
+);
+test!(
+    clamp_value_above_max,
+    "a {\n  color: clamp(1px, 5px, 3px);\n}\n",
+    "a {\n  color: 3px;\n}\n"
+);
+test!(
+    clamp_contains_var,
+    "a {\n  color: clamp(1px, var(--a), 3px);\n}\n",
+    "a {\n  color: clamp(1px, var(--a), 3px);\n}\n"
+);
+test!(
+    clamp_single_var_arg,
+    "a {\n  color: clamp(var(--a));\n}\n",
+    "a {\n  color: clamp(var(--a));\n}\n"
+);
+test!(
+    clamp_two_args_with_var,
+    "a {\n  color: clamp(1px, var(--a));\n}\n",
+    "a {\n  color: clamp(1px, var(--a));\n}\n"
+);
+test!(
+    clamp_contains_interpolation,
+    "a {\n  color: clamp(#{1px}, 2px, 3px);\n}\n",
+    "a {\n  color: clamp(1px, 2px, 3px);\n}\n"
+);
+error!(
+    clamp_two_args,
+    "a {\n  color: clamp(1px, 2px);\n}\n", "Error: 3 arguments required, but only 2 were passed."
+);
+error!(
+    clamp_four_args,
+    "a {\n  color: clamp(1px, 2px, 3px, 4px);\n}\n",
+    "Error: expected \"+\", \"-\", \"*\", \"/\", or \")\"."
+);
+error!(
+    clamp_value_non_compatible,
+    "a {\n  color: clamp(1px, 2s, 3px);\n}\n", "Error: 1px and 2s are incompatible."
+);
@@ -772,3 +772,20 @@ error!(
     "a {\n  color: mix(red, blue, (1/0));\n}\n",
     "Error: $weight: Expected Infinity to be within 0 and 100."
 );
+test!(
+    repeated_calls_with_identical_arguments,
+    "$brand: #3366cc;\na {\n  color: darken($brand, 10%);\n  background: darken($brand, 10%);\n  border-color: darken(#3366cc, 10%);\n}\n",
+    "a {\n  color: #2952a3;\n  background: #2952a3;\n  border-color: #2952a3;\n}\n"
+);
+#[test]
+fn repeated_calls_emit_deprecation_warnings_each_time() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "a {\n  color: adjust-hue(red, 10deg);\n  color: adjust-hue(red, 10deg);\n}\n",
+        &grass::Options::default()
+            .logger(&logger)
+            .future_deprecation(grass::Deprecation::ColorFunctions),
+    )
+    .unwrap();
+    assert_eq!(logger.warnings.into_inner().len(), 2);
+}
@@ -437,3 +437,13 @@ error!(
     complement_no_args,
     "a {\n  color: complement();\n}\n", "Error: Missing argument $color."
 );
+test!(
+    chained_adjust_hue_full_turn_does_not_drift,
+    "$c: hsl(120, 50%, 33.3%);\n@for $i from 1 through 20 {\n  $c: adjust-hue($c, 18deg);\n}\n\na {\n  color: $c;\n  color: hue($c);\n  color: lightness($c);\n}\n",
+    "a {\n  color: #2a7f2a;\n  color: 120deg;\n  color: 33.3%;\n}\n"
+);
+test!(
+    chained_lighten_retains_fractional_lightness,
+    "a {\n  color: lightness(lighten(lighten(hsl(0, 100%, 10.1%), 10.1%), 10.1%));\n}\n",
+    "a {\n  color: 30.3%;\n}\n"
+);
@@ -173,3 +173,17 @@ test!(
 test!(silent_comment_as_child, "a {\n// silent\n}\n", "");
 test!(single_hash_in_loud_comment, "/*#*/", "/*#*/\n");
 error!(unclosed_loud_comment, "/*", "Error: expected more input.");
+test!(
+    silent_comment_ends_at_carriage_return,
+    "// silent\ra {\n  color: red;\n}\n",
+    "a {\n  color: red;\n}\n"
+);
+test!(
+    silent_comment_ends_at_form_feed,
+    "// silent\x0Ca {\n  color: red;\n}\n",
+    "a {\n  color: red;\n}\n"
+);
+error!(
+    unclosed_loud_comment_ending_in_asterisks,
+    "a { color: red; } /* foo **", "Error: expected more input."
+);
@@ -50,12 +50,29 @@ test!(
     grass::Options::default().style(grass::OutputStyle::Compressed)
 );
 test!(
-    #[ignore = "regress not emitting the trailing semicolon here"]
     removes_multiline_comment_after_style,
     "a {\n  color: red;\n  /* abc */\n}\n",
     "a{color:red}",
     grass::Options::default().style(grass::OutputStyle::Compressed)
 );
+test!(
+    removes_ruleset_containing_only_comment,
+    "a {\n  /* abc */\n}\nb {\n  color: red;\n}\n",
+    "b{color:red}",
+    grass::Options::default().style(grass::OutputStyle::Compressed)
+);
+test!(
+    removes_media_containing_only_comment,
+    "@media screen {\n  a {\n    /* abc */\n  }\n}\nb {\n  color: red;\n}\n",
+    "b{color:red}",
+    grass::Options::default().style(grass::OutputStyle::Compressed)
+);
+test!(
+    keeps_ruleset_containing_only_preserved_comment,
+    "a {\n  /*! abc */\n}\n",
+    "a{/*! abc */}",
+    grass::Options::default().style(grass::OutputStyle::Compressed)
+);
 test!(
     removes_multiline_comment_between_styles,
     "a {\n  color: red;\n  /* abc */\n  color: green;\n}\n",
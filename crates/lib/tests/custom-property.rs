@@ -102,3 +102,48 @@ error!(
     }",
     "Error: Custom property values may not be empty."
 );
+test!(
+    var_in_calc_arithmetic,
+    "a {\n  color: calc(var(--w) * 2);\n}\n",
+    "a {\n  color: calc(var(--w) * 2);\n}\n"
+);
+test!(
+    var_with_fallback_in_calc,
+    "a {\n  color: calc(var(--a, 1px) + 2px);\n}\n",
+    "a {\n  color: calc(var(--a, 1px) + 2px);\n}\n"
+);
+test!(
+    var_in_nested_calc_parens,
+    "a {\n  color: calc((var(--a) + 1px) * 2);\n}\n",
+    "a {\n  color: calc((var(--a) + 1px) * 2);\n}\n"
+);
+test!(
+    var_stored_in_sass_variable,
+    "$a: var(--a, 1px);\na {\n  color: $a;\n}\n",
+    "a {\n  color: var(--a, 1px);\n}\n"
+);
+test!(
+    var_in_space_separated_list,
+    "$a: var(--a, 1px);\na {\n  color: $a 2px;\n}\n",
+    "a {\n  color: var(--a, 1px) 2px;\n}\n"
+);
+test!(
+    var_survives_list_functions,
+    "$a: var(--a, 1px);\na {\n  color: nth(join($a, 3px), 1);\n}\n",
+    "a {\n  color: var(--a, 1px);\n}\n"
+);
+test!(
+    var_in_css_max,
+    "a {\n  color: max(var(--a), 1px);\n}\n",
+    "a {\n  color: max(var(--a), 1px);\n}\n"
+);
+test!(
+    var_in_clamp,
+    "a {\n  color: clamp(var(--a), 1px, 2px);\n}\n",
+    "a {\n  color: clamp(var(--a), 1px, 2px);\n}\n"
+);
+test!(
+    crlf_and_form_feed_in_value,
+    "a {\r\n  --a: {\r\n    b: c\u{c}  };\r\n}\r\n",
+    "a {\n  --a: { b: c };\n}\n"
+);
@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+
+use macros::TestFs;
+
+#[macro_use]
+mod macros;
+
+fn files(graph: &grass::DependencyGraph) -> Vec<&Path> {
+    graph.files().collect()
+}
+
+#[test]
+fn finds_use_forward_and_import() {
+    let mut fs = TestFs::new();
+
+    fs.add_file("input.scss", "@use 'a';\n@forward 'b';\n@import 'c';");
+    fs.add_file("_a.scss", "");
+    fs.add_file("_b.scss", "");
+    fs.add_file("_c.scss", "");
+
+    let graph = grass::dependency_graph("input.scss", &grass::Options::default().fs(&fs)).unwrap();
+
+    assert_eq!(graph.entrypoint(), Path::new("input.scss"));
+    assert_eq!(
+        files(&graph),
+        vec![
+            Path::new("input.scss"),
+            Path::new("_a.scss"),
+            Path::new("_b.scss"),
+            Path::new("_c.scss"),
+        ]
+    );
+}
+
+#[test]
+fn finds_transitive_dependencies_in_load_order() {
+    let mut fs = TestFs::new();
+
+    fs.add_file("input.scss", "@use 'a';\n@use 'c';");
+    fs.add_file("_a.scss", "@use 'b';");
+    fs.add_file("_b.scss", "");
+    fs.add_file("_c.scss", "@use 'b';");
+
+    let graph = grass::dependency_graph("input.scss", &grass::Options::default().fs(&fs)).unwrap();
+
+    assert_eq!(
+        files(&graph),
+        vec![
+            Path::new("input.scss"),
+            Path::new("_a.scss"),
+            Path::new("_b.scss"),
+            Path::new("_c.scss"),
+        ]
+    );
+    assert_eq!(
+        graph.dependencies_of(Path::new("_c.scss")),
+        Some(&[PathBuf::from("_b.scss")][..])
+    );
+    assert_eq!(graph.dependencies_of(Path::new("_b.scss")), Some(&[][..]));
+    assert_eq!(graph.dependencies_of(Path::new("_d.scss")), None);
+}
+
+#[test]
+fn finds_nested_imports() {
+    let mut fs = TestFs::new();
+
+    fs.add_file(
+        "input.scss",
+        "a {\n  @import 'a';\n}\n@media print {\n  @import 'b';\n}",
+    );
+    fs.add_file("_a.scss", "");
+    fs.add_file("_b.scss", "");
+
+    let graph = grass::dependency_graph("input.scss", &grass::Options::default().fs(&fs)).unwrap();
+
+    assert_eq!(
+        files(&graph),
+        vec![
+            Path::new("input.scss"),
+            Path::new("_a.scss"),
+            Path::new("_b.scss"),
+        ]
+    );
+}
+
+#[test]
+fn ignores_builtin_modules_and_plain_css_imports() {
+    let mut fs = TestFs::new();
+
+    fs.add_file(
+        "input.scss",
+        "@use 'sass:math';\n@import 'foo.css';\n@import url(bar);",
+    );
+
+    let graph = grass::dependency_graph("input.scss", &grass::Options::default().fs(&fs)).unwrap();
+
+    assert_eq!(files(&graph), vec![Path::new("input.scss")]);
+}
+
+#[test]
+fn handles_cycles() {
+    let mut fs = TestFs::new();
+
+    fs.add_file("input.scss", "@import 'a';");
+    fs.add_file("_a.scss", "@import 'input';");
+
+    let graph = grass::dependency_graph("input.scss", &grass::Options::default().fs(&fs)).unwrap();
+
+    assert_eq!(
+        files(&graph),
+        vec![Path::new("input.scss"), Path::new("_a.scss")]
+    );
+    assert_eq!(
+        graph.dependencies_of(Path::new("_a.scss")),
+        Some(&[PathBuf::from("input.scss")][..])
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn to_json() {
+    let mut fs = TestFs::new();
+
+    fs.add_file("input.scss", "@use 'a';");
+    fs.add_file("_a.scss", "");
+
+    let graph = grass::dependency_graph("input.scss", &grass::Options::default().fs(&fs)).unwrap();
+
+    assert_eq!(
+        graph.to_json(),
+        r#"{"entrypoint":"input.scss","dependencies":{"input.scss":["_a.scss"],"_a.scss":[]}}"#
+    );
+}
+
+#[test]
+fn missing_dependency_is_error() {
+    let mut fs = TestFs::new();
+
+    fs.add_file("input.scss", "@use 'a';");
+
+    let err = grass::dependency_graph("input.scss", &grass::Options::default().fs(&fs))
+        .unwrap_err()
+        .to_string();
+
+    assert!(
+        err.starts_with("Error: Can't find stylesheet to import.\n"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn deep_import_chain() {
+    let depth = 2_000;
+    let mut fs = TestFs::new();
+
+    fs.add_file("input.scss", "@import '0';");
+    for i in 0..depth {
+        fs.add_file(format!("_{}.scss", i), format!("@import '{}';", i + 1));
+    }
+    fs.add_file(format!("_{}.scss", depth), "");
+
+    let graph = grass::dependency_graph("input.scss", &grass::Options::default().fs(&fs)).unwrap();
+
+    assert_eq!(graph.files().count(), depth + 2);
+}
+
+#[test]
+fn load_within_deeply_nested_blocks() {
+    let depth = 100_000;
+    let mut fs = TestFs::new();
+
+    fs.add_file(
+        "input.scss",
+        format!(
+            "a {{ {}@import 'a';{} }}",
+            "@media screen {".repeat(depth),
+            "}".repeat(depth)
+        ),
+    );
+    fs.add_file("_a.scss", "");
+
+    let graph = grass::dependency_graph("input.scss", &grass::Options::default().fs(&fs)).unwrap();
+
+    assert_eq!(
+        files(&graph),
+        vec![Path::new("input.scss"), Path::new("_a.scss")]
+    );
+}
@@ -287,3 +287,78 @@ error!(
     color_div_number,
     "a {\n  color: red / 1;\n}\n", r#"Error: Undefined operation "red / 1"."#
 );
+test!(
+    different_units_produce_complex_unit,
+    "@use 'sass:math';\na {\n  color: inspect(math.div(10px, 2s));\n}\n",
+    "a {\n  color: 5px/s;\n}\n"
+);
+test!(
+    unitless_div_unit_produces_inverse_unit,
+    "@use 'sass:math';\na {\n  color: inspect(math.div(1, 2px));\n}\n",
+    "a {\n  color: 0.5px^-1;\n}\n"
+);
+test!(
+    complex_unit_div_cancels_shared_unit,
+    "@use 'sass:math';\na {\n  color: math.div(2px * 3s, 1s);\n}\n",
+    "a {\n  color: 6px;\n}\n"
+);
+test!(
+    complex_unit_div_cancels_compatible_unit,
+    "@use 'sass:math';\na {\n  color: math.div(1in * 1s, 1px);\n}\n",
+    "a {\n  color: 96s;\n}\n"
+);
+test!(
+    complex_unit_div_identical_complex_unit,
+    "@use 'sass:math';\na {\n  color: math.div(1px * 1em, 1em * 1px);\n}\n",
+    "a {\n  color: 1;\n}\n"
+);
+error!(
+    complex_unit_is_not_valid_css,
+    "@use 'sass:math';\na {\n  color: math.div(10px, 2s);\n}\n",
+    "Error: 5px/s isn't a valid CSS value."
+);
+test!(
+    font_shorthand_preserves_slash,
+    "a {\n  font: italic bold 12px/30px Georgia, serif;\n}\n",
+    "a {\n  font: italic bold 12px/30px Georgia, serif;\n}\n"
+);
+test!(
+    grid_area_preserves_slash,
+    "a {\n  grid-area: 1 / 3;\n}\n",
+    "a {\n  grid-area: 1/3;\n}\n"
+);
+test!(
+    aspect_ratio_preserves_slash,
+    "a {\n  aspect-ratio: 16 / 9;\n}\n",
+    "a {\n  aspect-ratio: 16/9;\n}\n"
+);
+test!(
+    negative_number_preserves_slash,
+    "a {\n  font: -12px/1.5;\n}\n",
+    "a {\n  font: -12px/1.5;\n}\n"
+);
+test!(
+    interpolation_preserves_slash,
+    "a {\n  font: #{12px}/1.5;\n}\n",
+    "a {\n  font: 12px/1.5;\n}\n"
+);
+test!(
+    variable_operand_divides,
+    "$line-height: 1.5;\na {\n  font: 12px/$line-height;\n}\n",
+    "a {\n  font: 8px;\n}\n"
+);
+test!(
+    parenthesized_slash_divides,
+    "a {\n  font: (12px/1.5);\n  grid-area: (1 / 2) (3 / 4);\n}\n",
+    "a {\n  font: 8px;\n  grid-area: 0.5 0.75;\n}\n"
+);
+test!(
+    slash_is_removed_when_passed_to_function,
+    "a {\n  font: inspect(12px/1.5);\n}\n",
+    "a {\n  font: 8px;\n}\n"
+);
+test!(
+    slash_is_removed_by_arithmetic,
+    "a {\n  font: 12px/1.5 + 1;\n}\n",
+    "a {\n  font: 9px;\n}\n"
+);
@@ -274,3 +274,43 @@ test!(
     }",
     "a {\n  color: true;\n}\n"
 );
+test!(
+    maps_differ_only_in_order_eq,
+    "a {\n  color: (a: 1, b: 2) == (b: 2, a: 1);\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    lists_differ_only_in_brackets_eq,
+    "a {\n  color: [1 2] == (1 2);\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    nested_lists_eq,
+    "a {\n  color: (1 (2 3)) == (1 (2 3));\n  color: (1 (2 3)) == (1 (2, 3));\n}\n",
+    "a {\n  color: true;\n  color: false;\n}\n"
+);
+test!(
+    empty_map_eq_empty_list,
+    "a {\n  color: map-remove((a: 1), a) == ();\n  color: () == map-remove((a: 1), a);\n  color: [] == map-remove((a: 1), a);\n}\n",
+    "a {\n  color: true;\n  color: true;\n  color: true;\n}\n"
+);
+test!(
+    empty_map_ne_empty_list,
+    "a {\n  color: map-remove((a: 1), a) != ();\n  color: () != map-remove((a: 1), a);\n}\n",
+    "a {\n  color: false;\n  color: false;\n}\n"
+);
+test!(
+    empty_map_eq_empty_arglist,
+    "@function foo($a...) {\n  @return $a;\n}\n\na {\n  color: map-remove((a: 1), a) == foo();\n  color: foo() == map-remove((a: 1), a);\n}\n",
+    "a {\n  color: true;\n  color: true;\n}\n"
+);
+test!(
+    non_empty_map_ne_single_element_list,
+    "a {\n  color: (1,) == (a: 1);\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    unquoted_string_case_sensitive_eq,
+    "a {\n  color: \"A\" == a;\n  color: ABC == abc;\n}\n",
+    "a {\n  color: false;\n  color: false;\n}\n"
+);
@@ -277,3 +277,356 @@ error!(
     nothing_after_bang_in_space_separated_list,
     "a { color: a !", r#"Error: Expected "important"."#
 );
+
+#[test]
+fn renders_single_line_span() {
+    let err =
+        grass::from_string("a {\n  b: 1px + 1s;\n}\n", &grass::Options::default()).unwrap_err();
+    assert_eq!(
+        "Error: Incompatible units s and px.\n  ╷\n2 │   b: 1px + 1s;\n  │      ^^^^^^^^\n  ╵\n./stdin:2:6\n",
+        err.to_string()
+    );
+}
+
+#[test]
+fn renders_empty_span_with_single_caret() {
+    let err = grass::from_string("a { b: c", &grass::Options::default()).unwrap_err();
+    assert_eq!(
+        "Error: expected \"}\".\n  ╷\n1 │ a { b: c\n  │        ^\n  ╵\n./stdin:1:8\n",
+        err.to_string()
+    );
+}
+
+#[test]
+fn renders_multi_line_span() {
+    let err =
+        grass::from_string("a {\n  b: 1px +\n  1s;\n}\n", &grass::Options::default()).unwrap_err();
+    assert_eq!(
+        "Error: Incompatible units s and px.\n  ╷\n2 │     b: 1px +\n  │ ┌──────^\n3 │ │   1s;\n  │ └────^\n  ╵\n./stdin:2:6\n",
+        err.to_string()
+    );
+}
+
+#[test]
+fn renders_multi_line_span_without_unicode() {
+    let err = grass::from_string(
+        "a {\n  b: 1px +\n  1s;\n}\n",
+        &grass::Options::default().unicode_error_messages(false),
+    )
+    .unwrap_err();
+    assert_eq!(
+        "Error: Incompatible units s and px.\n  ,\n2 |     b: 1px +\n  | ,------^\n3 | |   1s;\n  | '----^\n  '\n  stdin 2:6  root stylesheet\n",
+        err.to_string()
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn error_to_json() {
+    let err =
+        grass::from_string("a {\n  b: \"c\\\"\" + ;\n}\n", &grass::Options::default()).unwrap_err();
+    assert_eq!(
+        err.to_json(),
+        r#"{"severity":"error","message":"Expected expression.","span":{"file":"stdin","start":{"line":2,"column":6},"end":{"line":2,"column":14}},"trace":[]}"#
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn io_error_to_json_has_no_span() {
+    let err = grass::from_path("does-not-exist.scss", &grass::Options::default()).unwrap_err();
+    assert!(err
+        .to_json()
+        .starts_with(r#"{"severity":"error","message":"#));
+    assert!(err.to_json().ends_with(r#","span":null,"trace":[]}"#));
+}
+
+#[test]
+fn renders_trace_for_error_in_callable() {
+    let err = grass::from_string(
+        "@function foo($a) {\n  @return unit($a);\n}\n@mixin bar {\n  b: foo(c);\n}\na {\n  @include bar;\n}\n",
+        &grass::Options::default(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        "Error: $number: c is not a number.\n  ╷\n2 │   @return unit($a);\n  │           ^^^^^^^^\n  ╵\n./stdin:2:11  foo()\n./stdin:5:6  bar()\n./stdin:8:12  root stylesheet\n",
+        err.to_string()
+    );
+}
+
+#[test]
+fn renders_trace_without_unicode() {
+    let err = grass::from_string(
+        "@mixin foo {\n  @error \"bar\";\n}\na {\n  @include foo;\n}\n",
+        &grass::Options::default().unicode_error_messages(false),
+    )
+    .unwrap_err();
+    assert_eq!(
+        "Error: \"bar\"\n  ,\n2 |   @error \"bar\";\n  |          ^^^^^\n  '\n  stdin 2:10  foo()\n  stdin 5:12  root stylesheet\n",
+        err.to_string()
+    );
+}
+
+#[test]
+fn trace_is_empty_for_error_at_root() {
+    let err = grass::from_string("a {\n  b: $c;\n}\n", &grass::Options::default()).unwrap_err();
+    assert!(err.trace().is_empty());
+}
+
+#[test]
+fn trace_includes_imports() {
+    let mut fs = macros::TestFs::new();
+    fs.add_file("a.scss", "a {\n  b: $c;\n}\n");
+    let err = grass::from_string("@import \"a\";", &grass::Options::default().fs(&fs)).unwrap_err();
+    assert_eq!(
+        err.trace()
+            .iter()
+            .map(|frame| (frame.member(), frame.location().file.name()))
+            .collect::<Vec<_>>(),
+        vec![("@import", "a.scss"), ("root stylesheet", "stdin")]
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn error_to_json_includes_trace() {
+    let err = grass::from_string(
+        "@mixin foo {\n  @error \"bar\";\n}\na {\n  @include foo;\n}\n",
+        &grass::Options::default(),
+    )
+    .unwrap_err();
+    assert!(err.to_json().ends_with(concat!(
+        r#""trace":[{"member":"foo()","span":{"file":"stdin","start":{"line":2,"column":10},"end":{"line":2,"column":15}}},"#,
+        r#"{"member":"root stylesheet","span":{"file":"stdin","start":{"line":5,"column":12},"end":{"line":5,"column":15}}}]}"#
+    )));
+}
+
+#[test]
+fn column_counts_code_points() {
+    assert_eq!(
+        macros::error_location("a {\n  b: \"é€😀\" + $c;\n}\n"),
+        ((1, 13), (1, 15))
+    );
+}
+#[test]
+fn crlf_is_a_single_line_break() {
+    assert_eq!(
+        macros::error_location("a {\r\n  b: $c;\r\n}\r\n"),
+        ((1, 5), (1, 7))
+    );
+}
+#[test]
+fn lone_cr_is_a_line_break() {
+    assert_eq!(
+        macros::error_location("a {\r  b: $c;\r}\r"),
+        ((1, 5), (1, 7))
+    );
+}
+#[test]
+fn form_feed_is_a_line_break() {
+    assert_eq!(
+        macros::error_location("a {\u{c}  b: $c;\u{c}}\u{c}"),
+        ((1, 5), (1, 7))
+    );
+}
+#[test]
+fn selector_error_points_to_offending_char() {
+    assert_eq!(macros::error_location(", a {x: y}"), ((0, 0), (0, 1)));
+    assert_eq!(macros::error_location("a/b {x: y}"), ((0, 1), (0, 2)));
+    assert_eq!(
+        macros::error_location("a:nth-child(2n+) {x: y}"),
+        ((0, 15), (0, 16))
+    );
+}
+#[test]
+fn selector_error_inside_interpolation_points_to_offending_char() {
+    assert_eq!(
+        macros::error_location("#{\"a\"}/b {x: y}"),
+        ((0, 6), (0, 7))
+    );
+}
+#[test]
+fn calculation_error_points_to_calculation() {
+    assert_eq!(
+        macros::error_location("a {\n  b: min(1px, 1s);\n}\n"),
+        ((1, 5), (1, 17))
+    );
+}
+#[test]
+fn plain_css_selector_error_points_to_offending_selector() {
+    let options = grass::Options::default().input_syntax(grass::InputSyntax::Css);
+    let location = |input: &str| {
+        let err = grass::from_string(input.to_owned(), &options).unwrap_err();
+        let span = err.span().unwrap();
+        (
+            (span.begin.line, span.begin.column),
+            (span.end.line, span.end.column),
+        )
+    };
+    assert_eq!(location("a &.b {x: y}"), ((0, 2), (0, 3)));
+    assert_eq!(location("a %b {x: y}"), ((0, 2), (0, 4)));
+}
+#[test]
+fn renders_line_after_lone_cr() {
+    let err =
+        grass::from_string("a {\r  b: \"é\" $c;\r}\r", &grass::Options::default()).unwrap_err();
+    assert_eq!(
+        "Error: Undefined variable.\n  ╷\n2 │   b: \"é\" $c;\n  │          ^^\n  ╵\n./stdin:2:10\n",
+        err.to_string()
+    );
+}
+#[test]
+fn renders_multi_line_span_ending_at_end_of_line_with_multibyte_chars() {
+    let err =
+        grass::from_string("a {\n  b: 1px +\n  1é\n}\n", &grass::Options::default()).unwrap_err();
+    assert_eq!(
+        "Error: Incompatible units é and px.\n  ╷\n2 │     b: 1px +\n  │ ┌──────^\n3 │ │   1é\n  │ └───\n  ╵\n./stdin:2:6\n",
+        err.to_string()
+    );
+}
+
+#[test]
+fn message_and_span_are_exposed() {
+    let err = grass::from_string("a {\n  b: $c;\n}\n", &grass::Options::default()).unwrap_err();
+    assert_eq!(err.message(), "Undefined variable.");
+    let span = err.span().unwrap();
+    assert_eq!((span.begin.line, span.begin.column), (1, 5));
+    assert_eq!((span.end.line, span.end.column), (1, 7));
+    assert_eq!(err.primary_label(), None);
+    assert!(err.secondary_labels().is_empty());
+}
+#[test]
+fn io_error_has_no_span() {
+    let err = grass::from_path("does-not-exist.scss", &grass::Options::default()).unwrap_err();
+    assert!(err.span().is_none());
+    assert!(!err.message().is_empty());
+}
+#[test]
+fn argument_error_labels_invocation_and_declaration() {
+    let err = grass::from_string(
+        "@function foo($a, $b) {\n  @return $a;\n}\na {\n  b: foo(1);\n}\n",
+        &grass::Options::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.message(), "Missing argument $b.");
+    assert_eq!(err.primary_label(), Some("invocation"));
+    let span = err.span().unwrap();
+    assert_eq!((span.begin.line, span.begin.column), (4, 5));
+
+    let labels = err.secondary_labels();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].message(), "declaration");
+    let location = labels[0].location();
+    assert_eq!((location.begin.line, location.begin.column), (0, 13));
+    assert_eq!((location.end.line, location.end.column), (0, 21));
+}
+#[test]
+fn mixin_argument_error_labels_declaration() {
+    let err = grass::from_string(
+        "@mixin foo {\n  b: c;\n}\na {\n  @include foo(1);\n}\n",
+        &grass::Options::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.message(), "Only 0 arguments allowed, but 1 was passed.");
+    assert_eq!(err.secondary_labels()[0].message(), "declaration");
+    assert_eq!(err.secondary_labels()[0].location().begin.line, 0);
+}
+#[test]
+fn extend_across_media_queries_labels_target_selector() {
+    let err = grass::from_string(
+        "a {\n  b: c;\n}\n@media screen {\n  .d {\n    @extend a;\n  }\n}\n",
+        &grass::Options::default(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.message(),
+        "You may not @extend selectors across media queries."
+    );
+    let span = err.span().unwrap();
+    assert_eq!((span.begin.line, span.begin.column), (5, 4));
+
+    let labels = err.secondary_labels();
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].message(), "target selector");
+    let location = labels[0].location();
+    assert_eq!((location.begin.line, location.begin.column), (0, 0));
+}
+#[test]
+fn error_in_body_of_callable_has_no_labels() {
+    let err = grass::from_string(
+        "@function foo($a) {\n  @return $b;\n}\na {\n  b: foo(1);\n}\n",
+        &grass::Options::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.primary_label(), None);
+    assert!(err.secondary_labels().is_empty());
+}
+
+fn error_message(input: &str) -> String {
+    grass::from_string(input.to_owned(), &grass::Options::default())
+        .unwrap_err()
+        .message()
+        .into_owned()
+}
+
+#[test]
+fn suggests_similar_variable() {
+    assert_eq!(
+        error_message("$primary-color: red;\na {\n  b: $primary-colour;\n}\n"),
+        "Undefined variable.\n\nDid you mean $primary-color?"
+    );
+}
+#[test]
+fn suggests_similar_local_variable() {
+    assert_eq!(
+        error_message("@mixin foo($width) {\n  a: $widht;\n}\na {\n  @include foo(1px);\n}\n"),
+        "Undefined variable.\n\nDid you mean $width?"
+    );
+}
+#[test]
+fn does_not_suggest_dissimilar_variable() {
+    assert_eq!(
+        error_message("$primary-color: red;\na {\n  b: $secondary;\n}\n"),
+        "Undefined variable."
+    );
+}
+#[test]
+fn suggests_similar_mixin() {
+    assert_eq!(
+        error_message("@mixin button-style {\n  a: b;\n}\na {\n  @include buton-style;\n}\n"),
+        "Undefined mixin.\n\nDid you mean button-style?"
+    );
+}
+#[test]
+fn suggests_similar_module_function() {
+    assert_eq!(
+        error_message("@use \"sass:math\";\na {\n  b: math.dvi(1, 2);\n}\n"),
+        "Undefined function.\n\nDid you mean math.div?"
+    );
+}
+#[test]
+fn suggests_similar_module_variable() {
+    assert_eq!(
+        error_message("@use \"sass:math\";\na {\n  b: math.$pj;\n}\n"),
+        "Undefined variable.\n\nDid you mean math.$pi?"
+    );
+}
+#[test]
+fn suggests_similar_namespace() {
+    assert_eq!(
+        error_message("@use \"sass:math\";\na {\n  b: mth.div(1, 2);\n}\n"),
+        "There is no module with the namespace \"mth\".\n\nDid you mean \"math\"?"
+    );
+}
+#[test]
+fn renders_suggestion_before_snippet() {
+    let err = grass::from_string(
+        "$a-b: red;\na {\n  b: $a-c;\n}\n",
+        &grass::Options::default(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        "Error: Undefined variable.\n\nDid you mean $a-b?\n  ╷\n3 │   b: $a-c;\n  │      ^^^^\n  ╵\n./stdin:3:6\n",
+        err.to_string()
+    );
+}
@@ -1921,6 +1921,61 @@ test!(
     r#"a {b: selector-extend(".c:where(d)", ":where(d)", "d.e")}"#,
     "a {\n  b: .c:where(d);\n}\n"
 );
+test!(
+    extend_inside_is,
+    ".a:is(.b) {\n  x: y;\n}\n\n.c {\n  @extend .b;\n}\n",
+    ".a:is(.b, .c) {\n  x: y;\n}\n"
+);
+test!(
+    extend_inside_where_complex,
+    ".a:where(.b .d) {\n  x: y;\n}\n\n.c {\n  @extend .b;\n}\n",
+    ".a:where(.b .d, .c .d) {\n  x: y;\n}\n"
+);
+test!(
+    extend_inside_has_relative,
+    ".a:has(> .b) {\n  x: y;\n}\n\n.c {\n  @extend .b;\n}\n",
+    ".a:has(> .b, > .c) {\n  x: y;\n}\n"
+);
+test!(
+    extend_inside_slotted,
+    "::slotted(.a) {\n  x: y;\n}\n\n.b {\n  @extend .a;\n}\n",
+    "::slotted(.a, .b) {\n  x: y;\n}\n"
+);
+test!(
+    extend_unifies_slotted_arguments,
+    ".a::slotted(.b) {\n  x: y;\n}\n\n.c::slotted(.d) {\n  @extend .a;\n}\n",
+    ".a::slotted(.b), .c::slotted(.b.d) {\n  x: y;\n}\n"
+);
+test!(
+    extend_does_not_unify_part_arguments,
+    ".a::part(b) {\n  x: y;\n}\n\n.c::part(d) {\n  @extend .a;\n}\n",
+    ".a::part(b) {\n  x: y;\n}\n"
+);
+test!(
+    extend_inside_nth_child_of,
+    ":nth-child(2n+1 of .a) {\n  x: y;\n}\n\n.b {\n  @extend .a;\n}\n",
+    ":nth-child(2n+1 of .a, .b) {\n  x: y;\n}\n"
+);
+test!(
+    extend_inside_not_complex,
+    ":not(a > .b) {\n  x: y;\n}\n\n.c {\n  @extend .b;\n}\n",
+    ":not(a > .b):not(a > .c) {\n  x: y;\n}\n"
+);
+test!(
+    extend_inside_not_list,
+    ":not(a > .b, .c) {\n  x: y;\n}\n\n.d {\n  @extend .b;\n}\n",
+    ":not(a > .b, a > .d, .c) {\n  x: y;\n}\n"
+);
+test!(
+    extend_inside_not_drops_complex_extender_of_compound,
+    ":not(.b) {\n  x: y;\n}\n\n.c .d {\n  @extend .b;\n}\n",
+    ":not(.b) {\n  x: y;\n}\n"
+);
+test!(
+    extend_inside_nested_is,
+    "a:is(.b:is(.c)) {\n  x: y;\n}\n\n.d {\n  @extend .c;\n}\n",
+    "a:is(.b:is(.c, .d)) {\n  x: y;\n}\n"
+);
 error!(
     extend_optional_keyword_not_complete,
     "a {
@@ -1936,7 +1991,6 @@ error!(
     "Error: Parent selectors aren't allowed here."
 );
 error!(
-    #[ignore = "we do not currently respect this"]
     extend_across_media_boundary,
     "a {
         display: none;
@@ -1949,6 +2003,77 @@ error!(
     }",
     "Error: You may not @extend selectors across media queries."
 );
+error!(
+    extend_across_media_boundary_target_after_extend,
+    "@media print {
+        .bar {
+            @extend .foo;
+        }
+    }
+
+    @media screen {
+        .foo {
+            a: b;
+        }
+    }",
+    "Error: You may not @extend selectors across media queries."
+);
+error!(
+    extend_from_nested_media_query,
+    "@media screen {
+        .foo {
+            a: b;
+        }
+
+        @media (min-width: 1px) {
+            .bar {
+                @extend .foo;
+            }
+        }
+    }",
+    "Error: You may not @extend selectors across media queries."
+);
+error!(
+    optional_extend_across_media_boundary,
+    "@media screen {
+        .bar {
+            @extend .foo !optional;
+        }
+    }
+
+    .foo {
+        a: b;
+    }",
+    "Error: You may not @extend selectors across media queries."
+);
+test!(
+    extender_pseudo_class_goes_before_target_pseudo_element,
+    ".a::before {
+        x: y;
+    }
+
+    .b:hover {
+        @extend .a;
+    }",
+    ".a::before, .b:hover::before {\n  x: y;\n}\n"
+);
+test!(
+    extend_from_root_into_media,
+    ".foo {
+        a: b;
+    }
+
+    @media screen {
+        .foo {
+            c: d;
+        }
+    }
+
+    .bar {
+        @extend .foo;
+    }",
+    ".foo, .bar {\n  a: b;\n}\n\n@media screen {\n  .foo, .bar {\n    c: d;\n  }\n}\n"
+);
 error!(
     #[ignore = "we do not error for this"]
     extend_target_does_not_exist,
@@ -1976,6 +2101,11 @@ error!(
     extend_at_root_of_document,
     "@extend a;", "Error: @extend may only be used within style rules."
 );
+#[test]
+fn error_in_interpolated_selector_points_to_interpolated_expression() {
+    let input = "a {\n  @extend #{\"%\"};\n}\n";
+    assert_eq!(macros::error_location(input), ((1, 12), (1, 15)));
+}
 
 // todo: extend_loop (massive test)
 // todo: extend tests in folders
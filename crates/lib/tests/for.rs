@@ -242,3 +242,23 @@ test!(
     }",
     ""
 );
+test!(
+    beyond_i32_max,
+    "@for $i from 2147483647 through 2147483648 {\n  a {\n    color: $i;\n  }\n}\n",
+    "a {\n  color: 2147483647;\n}\n\na {\n  color: 2147483648;\n}\n"
+);
+test!(
+    through_to_i64_max_does_not_overflow,
+    "@for $i from 1e300 through 1e300 {\n  a {\n    color: $i;\n  }\n}\n",
+    "a {\n  color: 9223372036854775808;\n}\n"
+);
+test!(
+    through_to_i64_min_does_not_overflow,
+    "@for $i from -1e300 through -1e300 {\n  a {\n    color: $i;\n  }\n}\n",
+    "a {\n  color: -9223372036854775808;\n}\n"
+);
+test!(
+    exclusive_descending,
+    "@for $i from 3 to 1 {\n  a {\n    color: $i;\n  }\n}\n",
+    "a {\n  color: 3;\n}\n\na {\n  color: 2;\n}\n"
+);
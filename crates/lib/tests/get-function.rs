@@ -1,3 +1,5 @@
+use macros::TestFs;
+
 #[macro_use]
 mod macros;
 
@@ -124,3 +126,45 @@ test!(
     "@use 'sass:math';\na {\n  color: call(get-function(cos, $module: math), 2);\n}\n",
     "a {\n  color: -0.4161468365;\n}\n"
 );
+test!(
+    stored_in_variable_and_passed_to_mixin,
+    "@function double($a) {\n  @return $a * 2;\n}\n\n@mixin apply($fn) {\n  color: call($fn, 2);\n}\n\n$fn: get-function(\"double\");\n\na {\n  @include apply($fn);\n}\n",
+    "a {\n  color: 4;\n}\n"
+);
+test!(
+    stored_in_map,
+    "@function double($a) {\n  @return $a * 2;\n}\n\n$fns: (\"double\": get-function(\"double\"));\n\na {\n  color: call(map-get($fns, \"double\"), 3);\n}\n",
+    "a {\n  color: 6;\n}\n"
+);
+
+#[test]
+fn closes_over_defining_module() {
+    let mut fs = TestFs::new();
+
+    fs.add_file(
+        "_a.scss",
+        r#"
+        $-factor: 3;
+
+        @function scale($a) {
+            @return $a * $-factor;
+        }
+    "#,
+    );
+
+    let input = r#"
+        @use "sass:meta";
+        @use "a";
+
+        $-factor: 10;
+
+        a {
+            color: meta.call(meta.get-function("scale", $module: "a"), 2);
+        }
+    "#;
+
+    assert_eq!(
+        "a {\n  color: 6;\n}\n",
+        &grass::from_string(input.to_string(), &grass::Options::default().fs(&fs)).expect(input)
+    );
+}
@@ -0,0 +1,327 @@
+#![cfg(feature = "http")]
+
+use std::{
+    cell::RefCell,
+    io::{self, BufRead, BufReader, Write},
+    net::TcpListener,
+    rc::Rc,
+    thread,
+};
+
+use grass::{HttpClient, HttpFs, HttpResponse};
+
+#[macro_use]
+mod macros;
+
+/// Serves fixed responses, recording every URL requested
+#[derive(Debug, Default)]
+struct MockClient {
+    responses: Vec<(&'static str, u16, Option<&'static str>, &'static str)>,
+    requests: Rc<RefCell<Vec<String>>>,
+}
+
+impl MockClient {
+    fn respond(
+        mut self,
+        url: &'static str,
+        status: u16,
+        location: Option<&'static str>,
+        body: &'static str,
+    ) -> Self {
+        self.responses.push((url, status, location, body));
+        self
+    }
+}
+
+impl HttpClient for MockClient {
+    fn get(&self, url: &str) -> io::Result<HttpResponse> {
+        self.requests.borrow_mut().push(url.to_owned());
+
+        Ok(match self.responses.iter().find(|(u, ..)| *u == url) {
+            Some((_, status, location, body)) => HttpResponse::new(
+                *status,
+                location.map(ToOwned::to_owned),
+                body.as_bytes().to_vec(),
+            ),
+            None => HttpResponse::new(404, None, Vec::new()),
+        })
+    }
+}
+
+#[test]
+fn use_https_url() {
+    let fs = HttpFs::with_client(
+        &grass::StdFs,
+        MockClient::default().respond(
+            "https://example.com/theme.scss",
+            200,
+            None,
+            "$primary: red;",
+        ),
+    );
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        grass::from_string(
+            "@use 'https://example.com/theme.scss';\na { color: theme.$primary; }".to_owned(),
+            &grass::Options::default().fs(&fs)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn relative_loads_resolve_against_url() {
+    let fs = HttpFs::with_client(
+        &grass::StdFs,
+        MockClient::default()
+            .respond(
+                "https://example.com/tokens/index.scss",
+                200,
+                None,
+                "@forward '../colors/_brand.scss';",
+            )
+            .respond(
+                "https://example.com/colors/_brand.scss",
+                200,
+                None,
+                "$brand: blue;",
+            ),
+    );
+
+    assert_eq!(
+        "a {\n  color: blue;\n}\n",
+        grass::from_string(
+            "@use 'https://example.com/tokens/index.scss' as t;\na { color: t.$brand; }".to_owned(),
+            &grass::Options::default().fs(&fs)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn url_without_extension_is_resolved() {
+    let fs = HttpFs::with_client(
+        &grass::StdFs,
+        MockClient::default().respond("https://example.com/_theme.scss", 200, None, "a { b: c; }"),
+    );
+
+    assert_eq!(
+        "a {\n  b: c;\n}\n",
+        grass::from_string(
+            "@use 'https://example.com/theme';".to_owned(),
+            &grass::Options::default().fs(&fs)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn redirects_are_followed() {
+    let fs = HttpFs::with_client(
+        &grass::StdFs,
+        MockClient::default()
+            .respond(
+                "https://example.com/latest/theme.scss",
+                302,
+                Some("/v2/theme.scss"),
+                "",
+            )
+            .respond(
+                "https://example.com/v2/theme.scss",
+                301,
+                Some("https://cdn.example.com/v2/theme.scss"),
+                "",
+            )
+            .respond(
+                "https://cdn.example.com/v2/theme.scss",
+                200,
+                None,
+                "@use 'colors';\na { color: colors.$red; }",
+            )
+            .respond(
+                "https://cdn.example.com/v2/colors.scss",
+                200,
+                None,
+                "$red: red;",
+            ),
+    );
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        grass::from_string(
+            "@use 'https://example.com/latest/theme.scss';".to_owned(),
+            &grass::Options::default().fs(&fs)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn redirect_loop_is_error() {
+    let fs = HttpFs::with_client(
+        &grass::StdFs,
+        MockClient::default().respond(
+            "https://example.com/theme.scss",
+            307,
+            Some("theme.scss"),
+            "",
+        ),
+    );
+
+    let err = grass::from_string(
+        "@use 'https://example.com/theme.scss';".to_owned(),
+        &grass::Options::default().fs(&fs),
+    )
+    .unwrap_err();
+
+    assert!(err.message().contains("Too many redirects"), "{}", err);
+}
+
+#[test]
+fn responses_are_cached() {
+    let requests = Rc::new(RefCell::new(Vec::new()));
+    let client = MockClient {
+        requests: Rc::clone(&requests),
+        ..MockClient::default()
+    }
+    .respond("https://example.com/theme.scss", 200, None, "a { b: c; }");
+
+    let fs = HttpFs::with_client(&grass::StdFs, client);
+    let options = grass::Options::default().fs(&fs);
+
+    let compile = || {
+        grass::from_string(
+            "@use 'https://example.com/theme.scss';".to_owned(),
+            &options,
+        )
+        .unwrap();
+
+        requests
+            .borrow()
+            .iter()
+            .filter(|url| *url == "https://example.com/theme.scss")
+            .count()
+    };
+
+    assert_eq!(compile(), 1);
+    assert_eq!(compile(), 1);
+
+    fs.clear_cache();
+    assert_eq!(compile(), 2);
+}
+
+#[test]
+fn missing_url_is_error() {
+    let fs = HttpFs::with_client(&grass::StdFs, MockClient::default());
+
+    let err = grass::from_string(
+        "@use 'https://example.com/theme.scss';".to_owned(),
+        &grass::Options::default().fs(&fs),
+    )
+    .unwrap_err();
+
+    assert_eq!(err.message(), "Can't find stylesheet to import.");
+}
+
+#[test]
+fn plain_css_import_of_url_is_not_fetched() {
+    let requests = Rc::new(RefCell::new(Vec::new()));
+    let client = MockClient {
+        requests: Rc::clone(&requests),
+        ..MockClient::default()
+    };
+    let fs = HttpFs::with_client(&grass::StdFs, client);
+
+    assert_eq!(
+        "@import 'https://example.com/theme.css';\n",
+        grass::from_string(
+            "@import 'https://example.com/theme.css';".to_owned(),
+            &grass::Options::default().fs(&fs)
+        )
+        .unwrap()
+    );
+    assert!(requests.borrow().is_empty());
+}
+
+#[test]
+fn default_client_fetches_over_http() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+
+            let mut request_line = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            reader.read_line(&mut request_line).unwrap();
+
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header.trim().is_empty() {
+                    break;
+                }
+            }
+
+            let response = if request_line.starts_with("GET /theme.scss ") {
+                "HTTP/1.1 301 Moved Permanently\r\nLocation: /v2/theme.scss\r\nContent-Length: 0\r\n\r\n"
+            } else if request_line.starts_with("GET /v2/theme.scss ") {
+                // chunked, as many CDNs respond
+                "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n7\r\n$red: r\r\n4\r\ned;\n\r\n0\r\n\r\n"
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n"
+            };
+
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    let fs = HttpFs::new(&grass::StdFs);
+    let input = format!(
+        "@use 'http://127.0.0.1:{}/theme.scss';\na {{ color: theme.$red; }}",
+        port
+    );
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        grass::from_string(input, &grass::Options::default().fs(&fs)).unwrap()
+    );
+}
+
+#[test]
+fn failures_are_not_cached() {
+    #[derive(Debug, Default)]
+    struct FlakyClient {
+        requests: RefCell<usize>,
+    }
+
+    impl HttpClient for FlakyClient {
+        fn get(&self, _url: &str) -> io::Result<HttpResponse> {
+            *self.requests.borrow_mut() += 1;
+
+            match *self.requests.borrow() {
+                1 => Err(io::Error::new(io::ErrorKind::TimedOut, "timed out")),
+                2 => Ok(HttpResponse::new(503, None, Vec::new())),
+                _ => Ok(HttpResponse::new(200, None, b"a { b: c; }".to_vec())),
+            }
+        }
+    }
+
+    let fs = HttpFs::with_client(&grass::StdFs, FlakyClient::default());
+    let options = grass::Options::default().fs(&fs);
+    let compile = || {
+        grass::from_string(
+            "@use 'https://example.com/theme.scss';".to_owned(),
+            &options,
+        )
+    };
+
+    assert!(compile().unwrap_err().message().contains("timed out"));
+    assert!(compile()
+        .unwrap_err()
+        .message()
+        .contains("responded with status 503"));
+    assert_eq!("a {\n  b: c;\n}\n", compile().unwrap());
+}
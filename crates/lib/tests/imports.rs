@@ -1,6 +1,10 @@
-use std::{io::Write, path::Path};
+use std::{
+    cell::{Cell, RefCell},
+    io::Write,
+    path::Path,
+};
 
-use macros::TestFs;
+use macros::{TestFs, TestLogger};
 
 #[macro_use]
 mod macros;
@@ -675,6 +679,602 @@ error!(
     "Error: This at-rule is not allowed here."
 );
 
+#[test]
+fn duplicate_import_warns_when_enabled() {
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a { color: red; }");
+
+    let logger = TestLogger::new();
+
+    grass::from_string(
+        "@import \"a\";\n@import \"a\";\n",
+        &grass::Options::default()
+            .fs(&fs)
+            .logger(&logger)
+            .warn_duplicate_imports(true),
+    )
+    .unwrap();
+
+    let warnings = logger.warnings.into_inner();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].message(),
+        "This file was already imported at stdin:1:9, so its CSS will be emitted again."
+    );
+    assert_eq!(warnings[0].location().begin.line, 1);
+}
+
+#[test]
+fn transitive_duplicate_import_warns_at_each_import_site() {
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a { color: red; }");
+    fs.add_file("b.scss", "@import \"a\";");
+    fs.add_file("c.scss", "@import \"a\";");
+
+    let logger = TestLogger::new();
+
+    grass::from_string(
+        "@import \"a\";\n@import \"b\";\n@import \"c\";\n",
+        &grass::Options::default()
+            .fs(&fs)
+            .logger(&logger)
+            .warn_duplicate_imports(true),
+    )
+    .unwrap();
+
+    assert_eq!(
+        logger
+            .warnings
+            .into_inner()
+            .iter()
+            .map(|warning| warning.location().file.name().to_owned())
+            .collect::<Vec<_>>(),
+        vec!["b.scss", "c.scss"]
+    );
+}
+
+#[test]
+fn duplicate_import_does_not_warn_by_default() {
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a { color: red; }");
+
+    let logger = TestLogger::new();
+
+    grass::from_string(
+        "@import \"a\";\n@import \"a\";\n",
+        &grass::Options::default().fs(&fs).logger(&logger),
+    )
+    .unwrap();
+
+    assert!(logger.warnings.into_inner().is_empty());
+}
+
+#[test]
+fn distinct_imports_do_not_warn() {
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a { color: red; }");
+    fs.add_file("b.scss", "b { color: red; }");
+
+    let logger = TestLogger::new();
+
+    grass::from_string(
+        "@import \"a\";\n@import \"b\";\n",
+        &grass::Options::default()
+            .fs(&fs)
+            .logger(&logger)
+            .warn_duplicate_imports(true),
+    )
+    .unwrap();
+
+    assert!(logger.warnings.into_inner().is_empty());
+}
+
+#[test]
+fn parse_cache_reuses_stylesheets_between_compilations() {
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a { color: red; }");
+    fs.add_file("b.scss", "b { color: blue; }");
+
+    let cache = grass::ParseCache::new();
+    let options = grass::Options::default().fs(&fs).parse_cache(&cache);
+
+    for _ in 0..2 {
+        assert_eq!(
+            "a {\n  color: red;\n}\n\nb {\n  color: blue;\n}\n",
+            &grass::from_string("@use \"a\";\n@use \"b\";\n".to_owned(), &options)
+                .expect("compiled successfully")
+        );
+    }
+
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn parse_cache_reparses_changed_files() {
+    let cache = grass::ParseCache::new();
+
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a { color: red; }");
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &grass::from_string(
+            "@use \"a\";".to_owned(),
+            &grass::Options::default().fs(&fs).parse_cache(&cache)
+        )
+        .expect("compiled successfully")
+    );
+
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a { color: blue; }");
+    assert_eq!(
+        "a {\n  color: blue;\n}\n",
+        &grass::from_string(
+            "@use \"a\";".to_owned(),
+            &grass::Options::default().fs(&fs).parse_cache(&cache)
+        )
+        .expect("compiled successfully")
+    );
+
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn parse_cache_preserves_locations_of_cached_stylesheets() {
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a {\n  @warn \"foo\";\n}\n");
+
+    let cache = grass::ParseCache::new();
+
+    for _ in 0..2 {
+        let logger = TestLogger::new();
+        grass::from_string(
+            "@use \"a\";".to_owned(),
+            &grass::Options::default()
+                .fs(&fs)
+                .logger(&logger)
+                .parse_cache(&cache),
+        )
+        .unwrap();
+
+        let warnings = logger.warnings.into_inner();
+        assert_eq!(warnings[0].location().file.name(), "a.scss");
+        assert_eq!(warnings[0].location().begin.line, 1);
+    }
+}
+
+#[test]
+fn parse_cache_preserves_locations_after_files_change() {
+    let cache = grass::ParseCache::new();
+
+    for i in 0..8 {
+        let mut fs = TestFs::new();
+        fs.add_file(
+            "a.scss",
+            format!("{}a {{\n  @warn \"foo\";\n}}\n", "\n".repeat(i)),
+        );
+        fs.add_file("b.scss", "b {\n  @warn \"bar\";\n}\n");
+
+        let logger = TestLogger::new();
+        grass::from_string(
+            "@use \"a\";\n@use \"b\";".to_owned(),
+            &grass::Options::default()
+                .fs(&fs)
+                .logger(&logger)
+                .parse_cache(&cache),
+        )
+        .unwrap();
+
+        let warnings = logger.warnings.into_inner();
+        assert_eq!(warnings[0].location().file.name(), "a.scss");
+        assert_eq!(warnings[0].location().begin.line, i + 1);
+        assert_eq!(warnings[1].location().file.name(), "b.scss");
+        assert_eq!(warnings[1].location().begin.line, 1);
+    }
+
+    assert_eq!(cache.len(), 2);
+}
+
+/// Compiles another stylesheet using the same cache whenever a file is read
+#[derive(Debug)]
+struct ReentrantFs<'a> {
+    fs: TestFs,
+    cache: &'a grass::ParseCache,
+}
+
+impl grass::Fs for ReentrantFs<'_> {
+    fn is_dir(&self, path: &Path) -> bool {
+        self.fs.is_dir(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.fs.is_file(path)
+    }
+
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        if path == Path::new("a.scss") {
+            assert_eq!(
+                "b {\n  color: blue;\n}\n",
+                grass::from_string(
+                    "@use \"b\";".to_owned(),
+                    &grass::Options::default()
+                        .fs(&self.fs)
+                        .parse_cache(self.cache)
+                )
+                .unwrap()
+            );
+        }
+
+        self.fs.read(path)
+    }
+}
+
+#[test]
+fn parse_cache_is_reentrant() {
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a { color: red; }");
+    fs.add_file("b.scss", "b { color: blue; }");
+
+    let cache = grass::ParseCache::new();
+    let fs = ReentrantFs { fs, cache: &cache };
+    let options = grass::Options::default().fs(&fs).parse_cache(&cache);
+
+    for _ in 0..2 {
+        assert_eq!(
+            "b {\n  color: blue;\n}\n\na {\n  color: red;\n}\n",
+            grass::from_string("@use \"b\";\n@use \"a\";".to_owned(), &options).unwrap()
+        );
+    }
+
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn parse_cache_does_not_cache_parse_errors() {
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a {");
+
+    let cache = grass::ParseCache::new();
+
+    assert!(grass::from_string(
+        "@use \"a\";".to_owned(),
+        &grass::Options::default().fs(&fs).parse_cache(&cache)
+    )
+    .is_err());
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn parse_cache_clear() {
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a { color: red; }");
+
+    let cache = grass::ParseCache::new();
+    grass::from_string(
+        "@use \"a\";".to_owned(),
+        &grass::Options::default().fs(&fs).parse_cache(&cache),
+    )
+    .unwrap();
+    assert_eq!(cache.len(), 1);
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn parse_cache_directory_is_shared_between_caches() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a {\n  @warn \"foo\";\n  color: red;\n}\n");
+
+    for _ in 0..2 {
+        let cache = grass::ParseCache::with_directory(dir.path());
+        let logger = TestLogger::new();
+
+        assert_eq!(
+            "a {\n  color: red;\n}\n",
+            &grass::from_string(
+                "@use \"a\";".to_owned(),
+                &grass::Options::default()
+                    .fs(&fs)
+                    .logger(&logger)
+                    .parse_cache(&cache)
+            )
+            .expect("compiled successfully")
+        );
+
+        let warnings = logger.warnings.into_inner();
+        assert_eq!(warnings[0].location().file.name(), "a.scss");
+        assert_eq!(warnings[0].location().begin.line, 1);
+    }
+
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn parse_cache_directory_reparses_changed_files() {
+    let dir = tempfile::tempdir().unwrap();
+
+    for (input, output) in [
+        ("a { color: red; }", "a {\n  color: red;\n}\n"),
+        ("a { color: blue; }", "a {\n  color: blue;\n}\n"),
+    ] {
+        let mut fs = TestFs::new();
+        fs.add_file("a.scss", input);
+
+        assert_eq!(
+            output,
+            grass::from_string(
+                "@use \"a\";".to_owned(),
+                &grass::Options::default()
+                    .fs(&fs)
+                    .parse_cache(&grass::ParseCache::with_directory(dir.path()))
+            )
+            .expect("compiled successfully")
+        );
+    }
+}
+
+#[test]
+fn parse_cache_directory_ignores_corrupt_files() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "a { color: red; }");
+
+    let compile = || {
+        grass::from_string(
+            "@use \"a\";".to_owned(),
+            &grass::Options::default()
+                .fs(&fs)
+                .parse_cache(&grass::ParseCache::with_directory(dir.path())),
+        )
+        .expect("compiled successfully")
+    };
+
+    compile();
+
+    for entry in std::fs::read_dir(dir.path()).unwrap() {
+        let path = entry.unwrap().path();
+        let mut contents = std::fs::read(&path).unwrap();
+        contents.truncate(contents.len() - 3);
+        std::fs::write(&path, contents).unwrap();
+    }
+
+    assert_eq!("a {\n  color: red;\n}\n", &compile());
+}
+
+#[test]
+fn compiler_records_dependencies() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"a\";");
+    fs.add_file("a.scss", "@import \"b\";");
+    fs.add_file("b.scss", "b { color: red; }");
+
+    let compiler = grass::Compiler::new();
+    let options = grass::Options::default().fs(&fs);
+
+    assert_eq!(compiler.dependencies("input.scss", &options), None);
+
+    compiler.compile("input.scss", &options).unwrap();
+
+    assert_eq!(
+        compiler.dependencies("input.scss", &options),
+        Some(vec![
+            Path::new("a.scss").to_path_buf(),
+            Path::new("b.scss").to_path_buf(),
+            Path::new("input.scss").to_path_buf(),
+        ])
+    );
+}
+
+#[test]
+fn compiler_skips_unchanged_entrypoints() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"a\";");
+    fs.add_file("a.scss", "a { color: red; }");
+
+    let compiler = grass::Compiler::new();
+    let options = grass::Options::default().fs(&fs);
+
+    assert_eq!(
+        compiler
+            .recompile_if_changed("input.scss", &options)
+            .unwrap(),
+        Some("a {\n  color: red;\n}\n".to_owned())
+    );
+    assert_eq!(
+        compiler
+            .recompile_if_changed("input.scss", &options)
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn compiler_recompiles_when_dependency_changes() {
+    let compiler = grass::Compiler::new();
+
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"a\";");
+    fs.add_file("a.scss", "a { color: red; }");
+    compiler
+        .compile("input.scss", &grass::Options::default().fs(&fs))
+        .unwrap();
+
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"a\";");
+    fs.add_file("a.scss", "a { color: blue; }");
+    assert_eq!(
+        compiler
+            .recompile_if_changed("input.scss", &grass::Options::default().fs(&fs))
+            .unwrap(),
+        Some("a {\n  color: blue;\n}\n".to_owned())
+    );
+}
+
+#[test]
+fn compiler_always_recompiles_failed_entrypoints() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"a\";");
+    fs.add_file("a.scss", "a { color: $b; }");
+
+    let compiler = grass::Compiler::new();
+    let options = grass::Options::default().fs(&fs);
+
+    assert!(compiler.compile("input.scss", &options).is_err());
+    assert!(compiler
+        .recompile_if_changed("input.scss", &options)
+        .is_err());
+    assert_eq!(
+        compiler.dependencies("input.scss", &options),
+        Some(vec![
+            Path::new("a.scss").to_path_buf(),
+            Path::new("input.scss").to_path_buf(),
+        ])
+    );
+}
+
+/// A file system which counts how many times files named `lib` are searched for
+#[derive(Debug)]
+struct LookupCountingFs {
+    fs: RefCell<TestFs>,
+    lib_lookups: Cell<usize>,
+}
+
+impl LookupCountingFs {
+    fn new(fs: TestFs) -> Self {
+        Self {
+            fs: RefCell::new(fs),
+            lib_lookups: Cell::new(0),
+        }
+    }
+
+    fn take_lib_lookups(&self) -> usize {
+        self.lib_lookups.replace(0)
+    }
+}
+
+impl grass::Fs for LookupCountingFs {
+    fn is_file(&self, path: &Path) -> bool {
+        if path.to_string_lossy().contains("lib") {
+            self.lib_lookups.set(self.lib_lookups.get() + 1);
+        }
+
+        self.fs.borrow().is_file(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.fs.borrow().is_dir(path)
+    }
+
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        self.fs.borrow().read(path)
+    }
+}
+
+#[test]
+fn compiler_reuses_unchanged_modules() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"a\";\na { x: a.$x; }");
+    fs.add_file("_a.scss", "@use \"lib\";\n$x: lib.$y + 1;");
+    fs.add_file("_lib.scss", "$y: 1;");
+    let fs = LookupCountingFs::new(fs);
+
+    let compiler = grass::Compiler::new();
+    let parse_cache = grass::ParseCache::new();
+    let options = grass::Options::default().fs(&fs).parse_cache(&parse_cache);
+
+    // the first compilation parses and evaluates each file, and the modules
+    // it evaluates are then cached
+    assert_eq!(
+        compiler.compile("input.scss", &options).unwrap(),
+        "a {\n  x: 2;\n}\n"
+    );
+    assert_ne!(fs.take_lib_lookups(), 0);
+
+    assert_eq!(
+        compiler.compile("input.scss", &options).unwrap(),
+        "a {\n  x: 2;\n}\n"
+    );
+    assert_eq!(fs.take_lib_lookups(), 0);
+
+    fs.fs.borrow_mut().add_file("_lib.scss", "$y: 2;");
+    assert_eq!(
+        compiler
+            .recompile_if_changed("input.scss", &options)
+            .unwrap(),
+        Some("a {\n  x: 3;\n}\n".to_owned())
+    );
+    assert_ne!(fs.take_lib_lookups(), 0);
+}
+
+#[test]
+fn compiler_resets_reused_module_variables() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"a\";\na.$n: a.$n + 1;\na { n: a.$n; }");
+    fs.add_file("_a.scss", "$n: 1;");
+
+    let compiler = grass::Compiler::new();
+    let parse_cache = grass::ParseCache::new();
+    let options = grass::Options::default().fs(&fs).parse_cache(&parse_cache);
+
+    for _ in 0..4 {
+        assert_eq!(
+            compiler.compile("input.scss", &options).unwrap(),
+            "a {\n  n: 2;\n}\n"
+        );
+    }
+}
+
+#[test]
+fn compiler_reused_modules_report_errors_in_their_files() {
+    let compiler = grass::Compiler::new();
+    let parse_cache = grass::ParseCache::new();
+
+    // each entrypoint loads another file, so that the files in the parse
+    // cache move further from those in the first compilation
+    for input in [
+        "@use \"a\";\na { b: a.f(); }",
+        "@use \"b\";\n@use \"a\";\na { b: a.f(); }",
+        "@use \"b\";\n@use \"a\";\n\na { b: a.f(); }",
+    ] {
+        let mut fs = TestFs::new();
+        fs.add_file("input.scss", input);
+        fs.add_file("_a.scss", "@function f() {\n  @error \"f\";\n}");
+        fs.add_file("_b.scss", "$b: 1;");
+        let options = grass::Options::default().fs(&fs).parse_cache(&parse_cache);
+
+        let error = compiler.compile("input.scss", &options).unwrap_err();
+        assert!(error.to_string().contains("_a.scss:2:10  f()"), "{}", error);
+    }
+}
+
+#[test]
+fn compiler_does_not_reuse_modules_with_effects() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "@use \"a\";\n@use \"b\";");
+    fs.add_file("_a.scss", "a { color: red; }");
+    fs.add_file("_b.scss", "@warn \"b\";\n$x: 1;");
+
+    let compiler = grass::Compiler::new();
+    let parse_cache = grass::ParseCache::new();
+    let logger = TestLogger::new();
+    let options = grass::Options::default()
+        .fs(&fs)
+        .parse_cache(&parse_cache)
+        .logger(&logger);
+
+    for _ in 0..3 {
+        assert_eq!(
+            compiler.compile("input.scss", &options).unwrap(),
+            "a {\n  color: red;\n}\n"
+        );
+    }
+
+    assert_eq!(logger.warnings.borrow().len(), 3);
+}
+
 // todo: edge case tests for plain css imports moved to top
 // todo: test for calling paths, e.g. `grass b\index.scss`
 // todo: test for absolute paths (how?)
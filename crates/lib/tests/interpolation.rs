@@ -80,3 +80,33 @@ error!(
     error_message_when_at_start_of_value,
     "a {\n  color: #{2px*5px};\n}\n", "Error: 10px*px isn't a valid CSS value."
 );
+test!(
+    interpolation_inside_quoted_string_inside_interpolation,
+    "a {\n  color: #{ \"a#{ 1 + 1 }b\" };\n}\n",
+    "a {\n  color: a2b;\n}\n"
+);
+test!(
+    triply_nested_interpolation_in_quoted_strings,
+    "a {\n  color: \"x#{\"y#{\"z#{1 + 2}\"}\"}\";\n}\n",
+    "a {\n  color: \"xyz3\";\n}\n"
+);
+test!(
+    directly_nested_interpolation,
+    "a {\n  color: #{#{#{1}}};\n}\n",
+    "a {\n  color: 1;\n}\n"
+);
+test!(
+    nested_interpolation_in_binary_op,
+    "a {\n  color: #{\"a\" + #{\"b\"}};\n}\n",
+    "a {\n  color: ab;\n}\n"
+);
+test!(
+    nested_interpolation_of_null,
+    "a {\n  color: \"a#{ \"b#{ null }c\" }d\";\n}\n",
+    "a {\n  color: \"abcd\";\n}\n"
+);
+test!(
+    nested_interpolation_inner_is_evaluated_first,
+    "$a: 1;\na {\n  color: #{\"#{$a + 1}\" + \"#{$a}\"};\n}\n",
+    "a {\n  color: 21;\n}\n"
+);
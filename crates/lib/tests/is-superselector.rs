@@ -172,19 +172,16 @@ test!(
     "a {\n  color: true;\n}\n"
 );
 test!(
-    #[ignore = "https://github.com/sass/dart-sass/issues/789"]
     simple_type_universal_namespace_and_explicit_namespace,
     "a {\n  color: is-superselector(\"*|c\", \"d|c\");\n}\n",
     "a {\n  color: true;\n}\n"
 );
 test!(
-    #[ignore = "https://github.com/sass/dart-sass/issues/789"]
     simple_type_universal_namespace_and_implicit_namespace,
     "a {\n  color: is-superselector(\"*|c\", \"c\");\n}\n",
     "a {\n  color: true;\n}\n"
 );
 test!(
-    #[ignore = "https://github.com/sass/dart-sass/issues/789"]
     simple_type_universal_namespace_and_empty_namespace,
     "a {\n  color: is-superselector(\"*|c\", \"|c\");\n}\n",
     "a {\n  color: true;\n}\n"
@@ -194,6 +191,31 @@ test!(
     "a {\n  color: is-superselector(\"*|c\", \"*|c\");\n}\n",
     "a {\n  color: true;\n}\n"
 );
+test!(
+    simple_universal_universal_namespace_and_explicit_namespace,
+    "a {\n  color: is-superselector(\"*|*\", \"c|d\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    simple_universal_explicit_namespace_and_same_namespace,
+    "a {\n  color: is-superselector(\"c|*\", \"c|d.e\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    simple_universal_explicit_namespace_and_different_namespace,
+    "a {\n  color: is-superselector(\"c|*\", \"e|d\");\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    simple_universal_implicit_namespace_and_type,
+    "a {\n  color: is-superselector(\"*\", \"c\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    simple_universal_implicit_namespace_and_explicit_namespace,
+    "a {\n  color: is-superselector(\"*\", \"c|d\");\n}\n",
+    "a {\n  color: false;\n}\n"
+);
 test!(
     simple_pseudo_no_args_equal,
     "a {\n  color: is-superselector(\":c\", \":c\");\n}\n",
@@ -349,6 +371,91 @@ test!(
     "a {\n  color: is-superselector(\":has(c d, e f)\", \"c d, e f\");\n}\n",
     "a {\n  color: false;\n}\n"
 );
+test!(
+    psuedo_is_bare_sub,
+    "a {\n  color: is-superselector(\":is(c, d)\", \"c\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    psuedo_where_bare_sub_complex,
+    "a {\n  color: is-superselector(\":where(c d)\", \"e c d\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    bare_super_psuedo_is_sub,
+    "a {\n  color: is-superselector(\"c\", \":is(c.d, c)\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    psuedo_is_child_combinator_not_descendant,
+    "a {\n  color: is-superselector(\":is(c > d)\", \"c d\");\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    psuedo_nth_child_of_superset,
+    "a {\n  color: is-superselector(\":nth-child(2n+1 of c, d)\", \":nth-child(2n+1 of c.e)\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    psuedo_nth_child_of_subset,
+    "a {\n  color: is-superselector(\":nth-child(2n+1 of c.e)\", \":nth-child(2n+1 of c, d)\");\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    psuedo_nth_child_of_different_argument,
+    "a {\n  color: is-superselector(\":nth-child(2n+1 of c)\", \":nth-child(2n of c)\");\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    psuedo_nth_child_of_different_name,
+    "a {\n  color: is-superselector(\":nth-child(2n+1 of c)\", \":nth-last-child(2n+1 of c)\");\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    bare_super_psuedo_nth_last_child_of_sub,
+    "a {\n  color: is-superselector(\"c\", \":nth-last-child(1 of c.d)\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    psuedo_not_superset,
+    "a {\n  color: is-superselector(\":not(c > d)\", \":not(c > d, e f)\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    psuedo_not_subset,
+    "a {\n  color: is-superselector(\":not(c > d, e f)\", \":not(c > d)\");\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    psuedo_not_descendant_and_child,
+    "a {\n  color: is-superselector(\":not(c > d)\", \":not(c d)\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    psuedo_not_child_and_descendant,
+    "a {\n  color: is-superselector(\":not(c d)\", \":not(c > d)\");\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    psuedo_not_different_type,
+    "a {\n  color: is-superselector(\":not(c d)\", \"e.f\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    psuedo_not_same_type,
+    "a {\n  color: is-superselector(\":not(c d)\", \"d\");\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    psuedo_not_different_ids,
+    "a {\n  color: is-superselector(\":not(#c, #d)\", \"#e\");\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    psuedo_not_class,
+    "a {\n  color: is-superselector(\":not(#c, .d)\", \"#e\");\n}\n",
+    "a {\n  color: false;\n}\n"
+);
 test!(
     psuedo_has_prefix_superset,
     "a {\n  color: is-superselector(\":-pfx-has(c d, e f, g h)\", \":-pfx-has(c d.i, e j f)\");\n}\n",
@@ -334,6 +334,55 @@ error!(
     }",
     r#"Error: Expected number."#
 );
+error!(
+    selector_not_keyframe_selector,
+    "@keyframes foo {
+        .a {
+            color: red;
+        }
+    }",
+    r#"Error: expected "%"."#
+);
+error!(
+    selector_unknown_identifier,
+    "@keyframes foo {
+        middle {
+            color: red;
+        }
+    }",
+    r#"Error: Expected "to" or "from"."#
+);
+error!(
+    selectors_not_separated_by_comma,
+    "@keyframes foo {
+        from to {
+            color: red;
+        }
+    }",
+    r#"Error: expected no more input."#
+);
+test!(
+    interpolated_selector_list,
+    "$steps: \"FROM, 50%\";
+
+    @keyframes foo {
+        #{$steps}, #{25 * 4}% {
+            color: red;
+        }
+    }",
+    "@keyframes foo {\n  from, 50%, 100% {\n    color: red;\n  }\n}\n"
+);
+error!(
+    interpolated_selector_not_keyframe_selector,
+    "$step: a b;
+
+    @keyframes foo {
+        #{$step} {
+            color: red;
+        }
+    }",
+    r#"Error: Expected "to" or "from"."#
+);
 
 // todo: span for this
 // @keyframes foo {
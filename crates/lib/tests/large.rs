@@ -240,3 +240,75 @@ a {
 }"#,
     "a {\n  color: 0 1;\n  color: 1 2;\n  color: 0 1;\n}\n"
 );
+
+#[test]
+fn deeply_nested_style_rules() {
+    let depth = 1_000;
+    let input = format!("{}color: red;{}", "a {".repeat(depth), "}".repeat(depth));
+    let mut selector = "a ".repeat(depth);
+    selector.pop();
+    assert_eq!(
+        format!("{} {{\n  color: red;\n}}\n", selector),
+        grass::from_string(input, &grass::Options::default()).unwrap()
+    );
+}
+
+#[test]
+fn deeply_nested_control_flow() {
+    let depth = 10_000;
+    let input = format!(
+        "a {{ {}color: red;{} }}",
+        "@if true {".repeat(depth),
+        "}".repeat(depth)
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        grass::from_string(input, &grass::Options::default()).unwrap()
+    );
+}
+
+#[test]
+fn deeply_nested_parentheses() {
+    let depth = 10_000;
+    let input = format!(
+        "a {{ color: {}red{}; }}",
+        "(".repeat(depth),
+        ")".repeat(depth)
+    );
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        grass::from_string(input, &grass::Options::default()).unwrap()
+    );
+}
+
+#[test]
+fn deeply_nested_media_queries() {
+    let depth = 2_000;
+    let input = format!(
+        "a {{ {}color: red;{} }}",
+        "@media screen {".repeat(depth),
+        "}".repeat(depth)
+    );
+    assert_eq!(
+        "@media screen {\n  a {\n    color: red;\n  }\n}\n",
+        grass::from_string(input, &grass::Options::default()).unwrap()
+    );
+}
+
+#[test]
+fn deeply_nested_pseudo_selectors() {
+    let depth = 2_000;
+    let input = format!(
+        "{}a{} {{ color: red; }}",
+        ":not(".repeat(depth),
+        ")".repeat(depth)
+    );
+    assert_eq!(
+        format!(
+            "{}a{} {{\n  color: red;\n}}\n",
+            ":not(".repeat(depth),
+            ")".repeat(depth)
+        ),
+        grass::from_string(input, &grass::Options::default()).unwrap()
+    );
+}
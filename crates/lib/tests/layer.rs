@@ -0,0 +1,107 @@
+#[macro_use]
+mod macros;
+
+test!(statement, "@layer a;", "@layer a;\n");
+test!(statement_list, "@layer a, b;", "@layer a, b;\n");
+test!(dotted_name, "@layer a.b;", "@layer a.b;\n");
+test!(
+    block,
+    "@layer a {
+        b {
+            c: d;
+        }
+    }",
+    "@layer a {\n  b {\n    c: d;\n  }\n}\n"
+);
+test!(
+    anonymous_block,
+    "@layer {
+        a {
+            b: c;
+        }
+    }",
+    "@layer {\n  a {\n    b: c;\n  }\n}\n"
+);
+test!(
+    nested_layers,
+    "@layer a {
+        @layer b {
+            c {
+                d: e;
+            }
+        }
+    }",
+    "@layer a {\n  @layer b {\n    c {\n      d: e;\n    }\n  }\n}\n"
+);
+test!(
+    interpolated_name,
+    "$name: foo;
+
+    @layer #{$name}-bar {
+        a {
+            b: c;
+        }
+    }",
+    "@layer foo-bar {\n  a {\n    b: c;\n  }\n}\n"
+);
+test!(
+    interpolated_statement_list,
+    "$names: a, b;
+    @layer #{$names};",
+    "@layer a, b;\n"
+);
+test!(
+    bubbles_out_of_style_rule,
+    "a {
+        @layer b {
+            c: d;
+
+            e {
+                f: g;
+            }
+        }
+    }",
+    "@layer b {\n  a {\n    c: d;\n  }\n  a e {\n    f: g;\n  }\n}\n"
+);
+test!(
+    statement_inside_style_rule,
+    "a {
+        @layer b;
+        c: d;
+    }",
+    "a {\n  @layer b;\n  c: d;\n}\n"
+);
+test!(
+    inside_media,
+    "@media screen {
+        @layer a {
+            b {
+                c: d;
+            }
+        }
+    }",
+    "@media screen {\n  @layer a {\n    b {\n      c: d;\n    }\n  }\n}\n"
+);
+test!(
+    parent_selector_inside_layer,
+    "a {
+        @layer b {
+            &:hover {
+                c: d;
+            }
+        }
+    }",
+    "@layer b {\n  a:hover {\n    c: d;\n  }\n}\n"
+);
+test!(
+    compressed,
+    "@layer a, b;
+
+    @layer a {
+        b {
+            c: d;
+        }
+    }",
+    "@layer a, b;@layer a{b{c:d}}",
+    grass::Options::default().style(grass::OutputStyle::Compressed)
+);
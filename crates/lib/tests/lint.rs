@@ -0,0 +1,163 @@
+#[macro_use]
+mod macros;
+
+use grass::Lint;
+
+/// The lint and (1-indexed) line of each warning emitted with every lint
+/// enabled
+fn lints(input: &str) -> Vec<(Option<Lint>, usize)> {
+    lints_with_options(input, grass::Options::default(), Lint::ALL)
+}
+
+fn lints_with_options(
+    input: &str,
+    mut options: grass::Options,
+    enabled: &[Lint],
+) -> Vec<(Option<Lint>, usize)> {
+    let logger = macros::TestLogger::new();
+
+    for &lint in enabled {
+        options = options.lint(lint);
+    }
+
+    grass::from_string(input.to_owned(), &options.logger(&logger)).unwrap();
+
+    logger
+        .warnings
+        .into_inner()
+        .iter()
+        .map(|warning| (warning.lint(), warning.location().begin.line + 1))
+        .collect()
+}
+
+#[test]
+fn no_lints_enabled_by_default() {
+    assert_eq!(
+        lints_with_options("a {\n  margin: 0px;\n}\n", grass::Options::default(), &[]),
+        vec![]
+    );
+}
+#[test]
+fn zero_unit() {
+    assert_eq!(
+        lints("a {\n  margin: 0px 1px;\n}\n"),
+        vec![(Some(Lint::ZeroUnit), 2)]
+    );
+}
+#[test]
+fn zero_unit_ignores_unitless_zero_and_non_lengths() {
+    assert_eq!(
+        lints("a {\n  margin: 0;\n  transition-delay: 0s;\n  width: 0%;\n}\n"),
+        vec![]
+    );
+}
+#[test]
+fn zero_unit_ignores_flex_shorthand() {
+    assert_eq!(lints("a {\n  flex: 1 1 0px;\n}\n"), vec![]);
+}
+#[test]
+fn zero_unit_ignores_custom_properties() {
+    assert_eq!(lints("a {\n  --a: 0px;\n}\n"), vec![]);
+}
+#[test]
+fn unknown_unit() {
+    assert_eq!(
+        lints("a {\n  width: 10pz;\n}\n"),
+        vec![(Some(Lint::UnknownUnit), 2)]
+    );
+}
+#[test]
+fn unknown_unit_display_includes_id() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "a {\n  width: 10pz;\n}\n",
+        &grass::Options::default()
+            .logger(&logger)
+            .lint(Lint::UnknownUnit),
+    )
+    .unwrap();
+    assert!(logger.warnings.into_inner()[0]
+        .to_string()
+        .starts_with("Warning [unknown-unit]: Unknown unit pz."));
+}
+#[test]
+fn declaration_outside_rule() {
+    assert_eq!(
+        lints("@foo {\n  color: red;\n}\n"),
+        vec![(Some(Lint::DeclarationOutsideRule), 2)]
+    );
+}
+#[test]
+fn declaration_outside_rule_ignores_descriptors() {
+    assert_eq!(
+        lints("@font-face {\n  font-family: a;\n}\n@page {\n  margin: 1in;\n  @top-left {\n    content: \"a\";\n  }\n}\n"),
+        vec![]
+    );
+}
+#[test]
+fn declaration_outside_rule_ignores_keyframes() {
+    assert_eq!(
+        lints("@keyframes a {\n  from {\n    top: 1px;\n  }\n}\n"),
+        vec![]
+    );
+}
+#[test]
+fn declaration_outside_rule_ignores_at_rule_in_style_rule() {
+    assert_eq!(lints("a {\n  @foo {\n    color: red;\n  }\n}\n"), vec![]);
+}
+#[test]
+fn important_in_mixin() {
+    assert_eq!(
+        lints("@mixin foo {\n  color: red !important;\n}\na {\n  @include foo;\n}\n"),
+        vec![(Some(Lint::ImportantInMixin), 2)]
+    );
+}
+#[test]
+fn important_outside_mixin() {
+    assert_eq!(lints("a {\n  color: red !important;\n}\n"), vec![]);
+}
+#[test]
+fn lints_are_individually_enabled() {
+    assert_eq!(
+        lints_with_options(
+            "a {\n  margin: 0px;\n  width: 10pz;\n}\n",
+            grass::Options::default(),
+            &[Lint::UnknownUnit]
+        ),
+        vec![(Some(Lint::UnknownUnit), 3)]
+    );
+}
+#[test]
+fn lints_are_silenced_by_quiet() {
+    assert_eq!(
+        lints_with_options(
+            "a {\n  margin: 0px;\n}\n",
+            grass::Options::default().quiet(true),
+            Lint::ALL
+        ),
+        vec![]
+    );
+}
+#[test]
+fn lint_from_str() {
+    assert_eq!("zero-unit".parse::<Lint>(), Ok(Lint::ZeroUnit));
+    assert_eq!(
+        "zero-units".parse::<Lint>(),
+        Err("Invalid lint \"zero-units\".".to_owned())
+    );
+}
+#[test]
+#[cfg(feature = "json")]
+fn lint_warning_to_json_includes_id() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "a {\n  margin: 0px;\n}\n",
+        &grass::Options::default()
+            .logger(&logger)
+            .lint(Lint::ZeroUnit),
+    )
+    .unwrap();
+    assert!(logger.warnings.into_inner()[0]
+        .to_json()
+        .contains(r#""deprecation":null,"lint":"zero-unit""#));
+}
@@ -635,3 +635,38 @@ test!(
     "a {\n  color: a b;\n}\n"
 );
 test!(zip_no_args, "a {\n  color: zip();\n}\n", "");
+test!(
+    nested_bracketed_lists,
+    "a {\n  color: [[a] b];\n}\n",
+    "a {\n  color: [[a] b];\n}\n"
+);
+test!(
+    bracketed_list_of_comma_separated_space_lists,
+    "a {\n  color: [a b, c d];\n}\n",
+    "a {\n  color: [a b, c d];\n}\n"
+);
+test!(
+    append_to_empty_bracketed,
+    "a {\n  color: append([], a);\n}\n",
+    "a {\n  color: [a];\n}\n"
+);
+test!(
+    nth_bracketed,
+    "a {\n  color: nth([a b], 2);\n}\n",
+    "a {\n  color: b;\n}\n"
+);
+test!(
+    length_bracketed,
+    "a {\n  color: length([a, b, c]);\n}\n",
+    "a {\n  color: 3;\n}\n"
+);
+test!(
+    separator_bracketed_comma_list,
+    "a {\n  color: list-separator([a, b]);\n}\n",
+    "a {\n  color: comma;\n}\n"
+);
+test!(
+    join_unbracketed_with_bracketed_is_unbracketed,
+    "a {\n  color: join(a, [b]);\n}\n",
+    "a {\n  color: a b;\n}\n"
+);
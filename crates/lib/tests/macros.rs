@@ -1,10 +1,11 @@
 use std::{
     borrow::Cow,
+    cell::{Cell, RefCell},
     collections::BTreeMap,
     path::{Path, PathBuf},
 };
 
-use grass::Fs;
+use grass::{codemap::SpanLoc, Fs, Logger, Warning};
 
 #[macro_export]
 macro_rules! test {
@@ -142,9 +143,8 @@ impl TestFs {
         }
     }
 
-    pub fn add_file(&mut self, name: &'static str, contents: &'static str) {
-        self.files
-            .insert(PathBuf::from(name), Cow::Borrowed(contents));
+    pub fn add_file(&mut self, name: impl Into<PathBuf>, contents: impl Into<Cow<'static, str>>) {
+        self.files.insert(name.into(), contents.into());
     }
 }
 
@@ -162,3 +162,51 @@ impl Fs for TestFs {
         Ok(self.files.get(path).unwrap().as_bytes().to_vec())
     }
 }
+
+/// The (0-indexed) start and end of the error emitted when compiling `input`,
+/// as `((line, column), (line, column))`
+#[allow(unused)]
+pub fn error_location(input: &str) -> ((usize, usize), (usize, usize)) {
+    match grass::from_string(input.to_owned(), &grass::Options::default())
+        .unwrap_err()
+        .kind()
+    {
+        grass::ErrorKind::ParseError { loc, .. } => (
+            (loc.begin.line, loc.begin.column),
+            (loc.end.line, loc.end.column),
+        ),
+        e => panic!("unexpected error {:?}", e),
+    }
+}
+
+/// Records every message it receives, so that tests can make assertions about
+/// warnings and `@debug` output
+#[derive(Debug, Default)]
+pub struct TestLogger {
+    pub warnings: RefCell<Vec<Warning>>,
+    pub debugs: RefCell<Vec<(SpanLoc, String)>>,
+    pub omitted_deprecations: Cell<Option<usize>>,
+}
+
+#[allow(unused)]
+impl TestLogger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Logger for TestLogger {
+    fn debug(&self, location: SpanLoc, message: &str) {
+        self.debugs
+            .borrow_mut()
+            .push((location, message.to_owned()));
+    }
+
+    fn warn(&self, warning: &Warning) {
+        self.warnings.borrow_mut().push(warning.clone());
+    }
+
+    fn omitted_deprecations(&self, count: usize) {
+        self.omitted_deprecations.set(Some(count));
+    }
+}
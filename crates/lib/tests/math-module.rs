@@ -628,3 +628,8 @@ test!(
 );
 
 // todo: atan+asin with unitful NaN
+test!(
+    unit_of_equal_numbers_with_different_units,
+    "@use 'sass:math';\na {\n  color: math.unit(1in);\n  color: math.unit(96px);\n}\n",
+    "a {\n  color: \"in\";\n  color: \"px\";\n}\n"
+);
@@ -521,7 +521,6 @@ test!(
     "@media (foo) {\n  a {\n    color: red;\n  }\n  a {\n    color: red;\n  }\n}\n@media (foo) and (prefers-reduced-motion: reduce) {\n  a {\n    transition: none;\n  }\n}\n"
 );
 test!(
-    #[ignore = "our is_invisible_check inside css tree is flawed here"]
     doesnt_split_child_nodes_when_leading_but_invisible_media,
     "@media (foo) {
         @media (prefers-reduced-motion: reduce) {}
@@ -546,7 +545,6 @@ test!(
     "@media (url) {\n  a {\n    color: red;\n  }\n}\n"
 );
 test!(
-    #[ignore = "our is_invisible_check inside css tree is flawed here"]
     media_does_not_split_when_child_rule_has_invisible_media,
     "@media (min-width: 1px) {
         .first {
@@ -630,3 +628,13 @@ error!(
     empty_query_after_resolving_interpolation,
     "@media #{null} {}", "Error: Expected identifier."
 );
+#[test]
+fn error_in_interpolated_query_points_to_interpolated_expression() {
+    let input = "@media #{\"screen and (\"} {\n  a {\n    color: red;\n  }\n}\n";
+    assert_eq!(macros::error_location(input), ((0, 9), (0, 23)));
+}
+#[test]
+fn error_after_interpolated_query_points_to_source() {
+    let input = "@media screen #{\"and\"} bar {\n  a {\n    color: red;\n  }\n}\n";
+    assert_eq!(macros::error_location(input), ((0, 23), (0, 24)));
+}
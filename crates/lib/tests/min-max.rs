@@ -255,3 +255,27 @@ error!(
     "a {\n  color: min(calc());\n}\n",
     "Error: Expected number, variable, function, or calculation."
 );
+test!(
+    min_mixed_units_with_variable_is_not_folded,
+    "$x: 3px;\na {\n  color: min(1px, $x, 10%);\n}\n",
+    "a {\n  color: min(1px, 3px, 10%);\n}\n"
+);
+test!(
+    min_comparable_units_with_variable_is_folded,
+    "$x: 3px;\na {\n  color: min(1px, $x, 2px);\n}\n",
+    "a {\n  color: 1px;\n}\n"
+);
+test!(
+    max_with_incompatible_sum_is_not_folded,
+    "$x: 3px;\na {\n  color: max(1px, $x + 10%);\n}\n",
+    "a {\n  color: max(1px, 3px + 10%);\n}\n"
+);
+test!(
+    min_with_rest_args_is_sass_function,
+    "$a: 3px, 2px;\na {\n  color: min($a...);\n}\n",
+    "a {\n  color: 2px;\n}\n"
+);
+error!(
+    min_incompatible_known_units,
+    "a {\n  color: min(1px, 1s);\n}\n", "Error: 1px and 1s are incompatible."
+);
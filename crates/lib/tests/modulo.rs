@@ -189,3 +189,40 @@ error!(
     number_mod_color,
     "a {\n  color: 5 % red;\n}\n", r#"Error: Undefined operation "5 % red"."#
 );
+test!(
+    negative_decimal_mod_positive,
+    "a {\n  color: -5.5 % 2;\n}\n",
+    "a {\n  color: 0.5;\n}\n"
+);
+test!(
+    negative_mod_positive_exact_multiple,
+    "a {\n  color: -6 % 3;\n}\n",
+    "a {\n  color: 0;\n}\n"
+);
+test!(
+    positive_mod_negative_exact_multiple,
+    "a {\n  color: 6 % -3;\n}\n",
+    "a {\n  color: 0;\n}\n"
+);
+test!(
+    negative_mod_zero,
+    "a {\n  color: -5 % 0;\n}\n",
+    "a {\n  color: NaN;\n}\n"
+);
+test!(
+    negative_unit_mod_positive_unitless,
+    "a {\n  color: -5px % 3;\n}\n",
+    "a {\n  color: 1px;\n}\n"
+);
+error!(
+    incompatible_units,
+    "a {\n  color: 5px % 3s;\n}\n", "Error: Incompatible units px and s."
+);
+error!(
+    complex_unit_mod_simple_unit,
+    "a {\n  color: 1px*1px % 1px;\n}\n", "Error: Incompatible units px*px and px."
+);
+error!(
+    unquoted_string_mod_number,
+    "a {\n  color: foo % 1;\n}\n", "Error: Undefined operation \"foo % 1\"."
+);
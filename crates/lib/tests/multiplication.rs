@@ -47,3 +47,28 @@ test!(
     "a {\n  color: (0/0) * (0/0);\n}\n",
     "a {\n  color: NaN;\n}\n"
 );
+test!(
+    unit_mul_same_unit,
+    "a {\n  color: inspect(2px * 3px);\n}\n",
+    "a {\n  color: 6px*px;\n}\n"
+);
+test!(
+    unit_mul_different_units,
+    "a {\n  color: inspect(1px * 1px * 1s);\n}\n",
+    "a {\n  color: 1px*px*s;\n}\n"
+);
+test!(
+    inverse_unit_mul_unit_cancels,
+    "@use 'sass:math';\na {\n  color: math.div(1, 2px) * 2px;\n}\n",
+    "a {\n  color: 1;\n}\n"
+);
+test!(
+    complex_unit_mul_cancels_compatible_unit,
+    "@use 'sass:math';\na {\n  color: math.div(1px, 1ms) * 1s;\n}\n",
+    "a {\n  color: 1000px;\n}\n"
+);
+test!(
+    complex_unit_mul_inverse_complex_unit,
+    "@use 'sass:math';\na {\n  color: math.div(1px, 1s) * math.div(1s, 1px);\n}\n",
+    "a {\n  color: 1;\n}\n"
+);
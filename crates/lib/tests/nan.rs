@@ -176,3 +176,38 @@ test!(
     "a {\n  color: /(0/0);\n}\n",
     "a {\n  color: /NaN;\n}\n"
 );
+test!(
+    nan_not_equal_to_itself,
+    "@use \"sass:math\";\n$nan: math.div(0, 0);\na {\n  color: $nan == $nan;\n  color: $nan != $nan;\n}\n",
+    "a {\n  color: false;\n  color: true;\n}\n"
+);
+test!(
+    nan_comparisons_are_false,
+    "@use \"sass:math\";\n$nan: math.div(0, 0);\na {\n  color: $nan < 1;\n  color: $nan > 1;\n  color: $nan >= $nan;\n}\n",
+    "a {\n  color: false;\n  color: false;\n  color: false;\n}\n"
+);
+test!(
+    infinity_from_division_by_zero,
+    "@use \"sass:math\";\na {\n  color: math.div(1, 0);\n  color: math.div(-1, 0);\n  color: math.div(1px, 0);\n}\n",
+    "a {\n  color: Infinity;\n  color: -Infinity;\n  color: Infinitypx;\n}\n"
+);
+test!(
+    infinity_comparisons,
+    "@use \"sass:math\";\n$inf: math.div(1, 0);\na {\n  color: $inf == $inf;\n  color: $inf > 1e300;\n  color: -$inf < -1e300;\n}\n",
+    "a {\n  color: true;\n  color: true;\n  color: true;\n}\n"
+);
+test!(
+    infinity_arithmetic,
+    "@use \"sass:math\";\n$inf: math.div(1, 0);\na {\n  color: $inf + 1;\n  color: $inf - $inf;\n  color: math.max(1, $inf);\n}\n",
+    "a {\n  color: Infinity;\n  color: NaN;\n  color: Infinity;\n}\n"
+);
+test!(
+    infinity_and_nan_in_calculation,
+    "a {\n  color: calc(1px / 0);\n  color: calc(0px / 0);\n}\n",
+    "a {\n  color: Infinitypx;\n  color: NaNpx;\n}\n"
+);
+test!(
+    infinity_in_unsimplified_calculation,
+    "@use \"sass:math\";\na {\n  color: calc(var(--x) + #{math.div(1, 0)} * 1px);\n}\n",
+    "a {\n  color: calc(var(--x) + Infinity * 1px);\n}\n"
+);
@@ -0,0 +1,290 @@
+#![cfg(feature = "pkg-importer")]
+
+use macros::TestFs;
+
+#[macro_use]
+mod macros;
+
+fn compile(input: &str, fs: &TestFs) -> grass::Result<String> {
+    grass::from_string(
+        input.to_owned(),
+        &grass::Options::default().fs(fs).node_package_importer(true),
+    )
+}
+
+#[test]
+fn sass_field() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "node_modules/bootstrap/package.json",
+        r#"{"name": "bootstrap", "sass": "scss/bootstrap.scss", "style": "dist/bootstrap.css"}"#,
+    );
+    fs.add_file(
+        "node_modules/bootstrap/scss/bootstrap.scss",
+        "a { color: red; }",
+    );
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &compile("@use \"pkg:bootstrap\";", &fs).unwrap()
+    );
+}
+
+#[test]
+fn style_field() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "node_modules/normalize/package.json",
+        r#"{"style": "./normalize.css"}"#,
+    );
+    fs.add_file("node_modules/normalize/normalize.css", "a { color: red; }");
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &compile("@use \"pkg:normalize\";", &fs).unwrap()
+    );
+}
+
+#[test]
+fn index_file() {
+    let mut fs = TestFs::new();
+    fs.add_file("node_modules/lib/package.json", "{}");
+    fs.add_file("node_modules/lib/_index.scss", "$a: red;");
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &compile("@use \"pkg:lib\";\na { color: lib.$a; }", &fs).unwrap()
+    );
+}
+
+#[test]
+fn subpath_resolved_against_root() {
+    let mut fs = TestFs::new();
+    fs.add_file("node_modules/bootstrap/package.json", "{}");
+    fs.add_file(
+        "node_modules/bootstrap/scss/_variables.scss",
+        "$primary: blue;",
+    );
+
+    assert_eq!(
+        "a {\n  color: blue;\n}\n",
+        &compile(
+            "@use \"pkg:bootstrap/scss/variables\";\na { color: variables.$primary; }",
+            &fs
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn exports_conditions() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "node_modules/lib/package.json",
+        r#"{"exports": {".": {"types": "./index.d.ts", "sass": "./src/lib.scss", "default": "./index.js"}}}"#,
+    );
+    fs.add_file("node_modules/lib/src/lib.scss", "a { color: red; }");
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &compile("@use \"pkg:lib\";", &fs).unwrap()
+    );
+}
+
+#[test]
+fn exports_conditions_in_order() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "node_modules/lib/package.json",
+        r#"{"exports": {"style": "./lib.css", "sass": "./lib.scss"}}"#,
+    );
+    fs.add_file("node_modules/lib/lib.css", "a { color: red; }");
+    fs.add_file("node_modules/lib/lib.scss", "a { color: blue; }");
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &compile("@use \"pkg:lib\";", &fs).unwrap()
+    );
+}
+
+#[test]
+fn escaped_astral_path() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "node_modules/lib/package.json",
+        r#"{"sass": "\ud83c\udfa8.scss"}"#,
+    );
+    fs.add_file("node_modules/lib/\u{1f3a8}.scss", "a { color: red; }");
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &compile("@use \"pkg:lib\";", &fs).unwrap()
+    );
+}
+
+#[test]
+fn exports_subpath_pattern() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "node_modules/lib/package.json",
+        r#"{"exports": {"./theme/*": {"sass": "./src/themes/*.scss"}}}"#,
+    );
+    fs.add_file(
+        "node_modules/lib/src/themes/dark.scss",
+        "a { color: black; }",
+    );
+
+    assert_eq!(
+        "a {\n  color: black;\n}\n",
+        &compile("@use \"pkg:lib/theme/dark\";", &fs).unwrap()
+    );
+}
+
+#[test]
+fn scoped_package() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "node_modules/@material/button/package.json",
+        r#"{"sass": "button.scss"}"#,
+    );
+    fs.add_file("node_modules/@material/button/button.scss", "$a: red;");
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &compile(
+            "@use \"pkg:@material/button\";\na { color: button.$a; }",
+            &fs
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn nearest_node_modules() {
+    let mut fs = TestFs::new();
+    fs.add_file("src/node_modules/lib/package.json", "{}");
+    fs.add_file("src/node_modules/lib/index.scss", "a { color: red; }");
+    fs.add_file("src/input.scss", "@use \"pkg:lib\";");
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &grass::from_path(
+            "src/input.scss",
+            &grass::Options::default()
+                .fs(&fs)
+                .node_package_importer(true)
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn legacy_tilde_import() {
+    let mut fs = TestFs::new();
+    fs.add_file("node_modules/bootstrap/package.json", "{}");
+    fs.add_file(
+        "node_modules/bootstrap/scss/_mixins.scss",
+        "a { color: red; }",
+    );
+
+    assert_eq!(
+        "a {\n  color: red;\n}\n",
+        &compile("@import \"~bootstrap/scss/mixins\";", &fs).unwrap()
+    );
+}
+
+#[test]
+fn disabled_by_default() {
+    let mut fs = TestFs::new();
+    fs.add_file("node_modules/lib/package.json", "{}");
+    fs.add_file("node_modules/lib/index.scss", "a { color: red; }");
+
+    let err = grass::from_string(
+        "@use \"pkg:lib\";".to_owned(),
+        &grass::Options::default().fs(&fs),
+    )
+    .unwrap_err();
+
+    assert_eq!(err.message(), "Can't find stylesheet to import.");
+}
+
+#[test]
+fn missing_package() {
+    let fs = TestFs::new();
+
+    let err = compile("@use \"pkg:missing\";", &fs).unwrap_err();
+
+    assert_eq!(err.message(), "Can't find stylesheet to import.");
+}
+
+#[test]
+fn exports_target_outside_package() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "node_modules/lib/package.json",
+        r#"{"exports": {".": {"sass": "./../secret.scss"}}}"#,
+    );
+    fs.add_file("node_modules/secret.scss", "a { color: red; }");
+
+    let err = compile("@use \"pkg:lib\";", &fs).unwrap_err();
+
+    assert_eq!(
+        err.message(),
+        "Invalid \"exports\" target \"./../secret.scss\" defined for \".\" in package.json."
+    );
+}
+
+#[test]
+fn exports_target_not_relative() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "node_modules/lib/package.json",
+        r#"{"exports": {"sass": "../secret.scss"}}"#,
+    );
+    fs.add_file("node_modules/secret.scss", "a { color: red; }");
+
+    let err = compile("@use \"pkg:lib\";", &fs).unwrap_err();
+
+    assert_eq!(
+        err.message(),
+        "Invalid \"exports\" target \"../secret.scss\" defined for \".\" in package.json."
+    );
+}
+
+#[test]
+fn exports_pattern_match_outside_package() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "node_modules/lib/package.json",
+        r#"{"exports": {"./*": {"sass": "./src/*"}}}"#,
+    );
+    fs.add_file("node_modules/lib/secret.scss", "a { color: red; }");
+
+    let err = compile("@use \"pkg:lib/../secret.scss\";", &fs).unwrap_err();
+
+    assert_eq!(
+        err.message(),
+        "Invalid subpath \"./../secret.scss\" matched by \"exports\" target \"./src/*\" in package.json."
+    );
+}
+
+#[test]
+fn exports_ambiguous() {
+    let mut fs = TestFs::new();
+    fs.add_file(
+        "node_modules/lib/package.json",
+        r#"{"exports": {"./theme.scss": "./theme.scss", "./_theme.scss": "./_theme.scss"}}"#,
+    );
+    fs.add_file("node_modules/lib/theme.scss", "a { color: red; }");
+    fs.add_file("node_modules/lib/_theme.scss", "a { color: blue; }");
+
+    let err = compile("@use \"pkg:lib/theme\";", &fs).unwrap_err();
+
+    assert!(
+        err.message().starts_with(
+            "Unable to determine which of multiple potential resolutions found for theme in lib should be used."
+        ),
+        "{}",
+        err.message()
+    );
+}
@@ -36,3 +36,48 @@ test!(
     "a {\n  color: NULL;\n}\n",
     "a {\n  color: NULL;\n}\n"
 );
+test!(
+    null_declaration_not_emitted,
+    "a {\n  color: null;\n  width: 1px;\n}\n",
+    "a {\n  width: 1px;\n}\n"
+);
+test!(
+    null_removed_from_space_separated_list,
+    "a {\n  color: 1 null 2;\n}\n",
+    "a {\n  color: 1 2;\n}\n"
+);
+test!(
+    null_removed_from_comma_separated_list,
+    "a {\n  color: null, 1, null;\n  width: 1, (null null), 2;\n}\n",
+    "a {\n  color: 1;\n  width: 1, 2;\n}\n"
+);
+test!(
+    single_null_comma_list_not_emitted,
+    "a {\n  color: (null,);\n}\n",
+    ""
+);
+test!(
+    null_removed_from_bracketed_list,
+    "a {\n  color: [1 null 2];\n}\n",
+    "a {\n  color: [1 2];\n}\n"
+);
+test!(
+    null_in_interpolation_is_empty,
+    "a {\n  color: \"x#{null}y\";\n  width: x#{null}y;\n}\n",
+    "a {\n  color: \"xy\";\n  width: xy;\n}\n"
+);
+test!(
+    only_null_in_interpolation_not_emitted,
+    "a {\n  color: #{null};\n}\n",
+    ""
+);
+test!(
+    null_is_kept_in_inspect,
+    "a {\n  color: inspect(1 null 2);\n}\n",
+    "a {\n  color: 1 null 2;\n}\n"
+);
+test!(
+    null_in_custom_property_is_unparsed,
+    "a {\n  --color: null;\n}\n",
+    "a {\n  --color: null;\n}\n"
+);
@@ -233,3 +233,33 @@ error!(
     scientific_notation_no_number_after_decimal,
     "a {\n  color: 1.e3;\n}\n", "Error: Expected digit."
 );
+test!(
+    division_then_multiplication_is_exactly_equal,
+    "@use \"sass:math\";\na {\n  color: math.div(1, 3) * 3 == 1;\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    decimal_addition_does_not_accumulate_error,
+    "a {\n  color: 0.1 + 0.2 == 0.3;\n  width: 0.1 + 0.2;\n}\n",
+    "a {\n  color: true;\n  width: 0.3;\n}\n"
+);
+test!(
+    repeating_decimal_rounds_to_ten_digits,
+    "@use \"sass:math\";\na {\n  color: math.div(2, 3);\n}\n",
+    "a {\n  color: 0.6666666667;\n}\n"
+);
+test!(
+    multiplication_beyond_u64_does_not_overflow,
+    "a {\n  color: 9999999999999999999 * 9999999999999999999;\n}\n",
+    "a {\n  color: 99999999999999997748809823456034029568;\n}\n"
+);
+test!(
+    addition_beyond_i32_does_not_overflow,
+    "a {\n  color: 2147483647 + 1;\n}\n",
+    "a {\n  color: 2147483648;\n}\n"
+);
+test!(
+    multiplication_beyond_f64_is_infinity,
+    "a {\n  color: 1e308 * 10;\n}\n",
+    "a {\n  color: Infinity;\n}\n"
+);
@@ -79,3 +79,13 @@ error!(
     properly_bubbles_error_when_invalid_char_after_or,
     "a {\n  color: true or? foo;\n}\n", "Error: Expected expression."
 );
+test!(
+    short_circuits_user_defined_function,
+    "@function foo() {\n  @error \"evaluated\";\n}\n\na {\n  color: true or foo();\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    short_circuits_after_and_in_lhs,
+    "@function foo() {\n  @error \"evaluated\";\n}\n\na {\n  color: (false and foo()) or 3;\n}\n",
+    "a {\n  color: 3;\n}\n"
+);
@@ -46,3 +46,53 @@ test!(
     "a {\n  color: 8 + 1 * 2 * 0;\n}\n",
     "a {\n  color: 8;\n}\n"
 );
+test!(
+    relational_then_equality,
+    "a {\n  color: 1 < 2 == true;\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    equality_then_relational,
+    "a {\n  color: 1 == 1 < 2;\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    addition_then_equality,
+    "a {\n  color: 1 + 1 == 2;\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    and_then_or,
+    "a {\n  color: false and true or true;\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    or_then_and,
+    "a {\n  color: true or false and false;\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    not_then_and,
+    "a {\n  color: not false and false;\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    not_then_equality,
+    "a {\n  color: not 1 == 2;\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    addition_then_modulo,
+    "a {\n  color: 2 + 3 % 2;\n}\n",
+    "a {\n  color: 3;\n}\n"
+);
+test!(
+    parens_override_precedence,
+    "a {\n  color: (1 + 2) * 3;\n}\n",
+    "a {\n  color: 9;\n}\n"
+);
+test!(
+    relational_on_both_sides_of_not_equals,
+    "a {\n  color: 1 > 0 != 1 < 0;\n}\n",
+    "a {\n  color: true;\n}\n"
+);
@@ -0,0 +1,234 @@
+#![cfg(feature = "ast-json")]
+
+#[macro_use]
+mod macros;
+
+fn parse(input: &str) -> String {
+    grass::parse_to_json(input.to_owned(), "input.scss", &grass::Options::default()).unwrap()
+}
+
+/// A span in `input.scss`, with 1-indexed lines and columns
+fn span(start: (usize, usize), end: (usize, usize)) -> String {
+    format!(
+        r#"{{"file":"input.scss","start":{{"line":{},"column":{}}},"end":{{"line":{},"column":{}}}}}"#,
+        start.0, start.1, end.0, end.1
+    )
+}
+
+#[test]
+fn empty_stylesheet() {
+    let json = parse("");
+
+    assert!(
+        json.starts_with(r#"{"type":"StyleSheet","body":[],"url":"#),
+        "{}",
+        json
+    );
+    assert!(json.ends_with(r#","isPlainCss":false}"#), "{}", json);
+}
+
+#[test]
+fn variable_declaration() {
+    let json = parse("$a: 1px !default;");
+
+    assert!(
+        json.contains(&format!(
+            concat!(
+                r#"{{"type":"VariableDecl","value":{{"namespace":null,"name":"a","#,
+                r#""value":{{"type":"Number","value":{{"n":1.0,"unit":"px"}}}},"#,
+                r#""isGuarded":true,"isGlobal":false,"span":{}}}}}"#,
+            ),
+            span((1, 1), (1, 17))
+        )),
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn spans_match_error_locations() {
+    for input in ["$a: 1;\r\n$b: 2;", "$a: 1;\r$b: 2;", "$a: 1;\u{c}$b: 2;"] {
+        let json = parse(input);
+
+        assert!(
+            json.contains(&format!(
+                r#""isGlobal":false,"span":{}}}"#,
+                span((2, 1), (2, 6))
+            )),
+            "{:?}: {}",
+            input,
+            json
+        );
+    }
+}
+
+#[test]
+fn style_rule_with_interpolated_selector() {
+    let json = parse("a #{$b} {\n  c: d;\n}");
+
+    assert!(
+        json.contains(&format!(
+            concat!(
+                r#"{{"type":"RuleSet","value":{{"selector":["a"," ",{{"node":{{"type":"Variable","#,
+                r#""value":{{"name":{{"node":"b","span":{}}},"namespace":null}}}},"span":{}}}," "],"#,
+            ),
+            span((1, 5), (1, 7)),
+            span((1, 5), (1, 7))
+        )),
+        "{}",
+        json
+    );
+    assert!(
+        json.contains(&format!(
+            r#"{{"type":"Style","value":{{"name":["c"],"value":{{"node":{{"type":"String","value":[[["d"],"none"],{}]}}"#,
+            span((2, 6), (2, 7))
+        )),
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn binary_operation_and_function_call() {
+    let json = parse("a { b: math.div(1, 2) + 3; }");
+
+    assert!(
+        json.contains(&format!(
+            concat!(
+                r#"{{"type":"BinaryOp","value":{{"lhs":{{"type":"FunctionCall","value":{{"#,
+                r#""namespace":{{"node":"math","span":{}}},"name":"div","arguments":{{"#,
+                r#""positional":[{{"type":"Number","value":{{"n":1.0,"unit":""}}}},"#,
+                r#"{{"type":"Number","value":{{"n":2.0,"unit":""}}}}],"#,
+                r#""named":{{}},"rest":null,"keywordRest":null,"#,
+            ),
+            span((1, 8), (1, 12))
+        )),
+        "{}",
+        json
+    );
+    assert!(
+        json.contains(r#""op":"+","rhs":{"type":"Number","value":{"n":3.0,"unit":""}}"#),
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn map_and_color() {
+    let json = parse("a { b: (c: d) -#fff; }");
+
+    assert!(
+        json.contains(&format!(
+            r#"{{"type":"Map","value":[[{{"node":{{"type":"String","value":[[["c"],"none"],{}]}},"span":{}}},"#,
+            span((1, 9), (1, 10)),
+            span((1, 9), (1, 10))
+        )),
+        "{}",
+        json
+    );
+    assert!(
+        json.contains(
+            r##"{"type":"Color","value":{"text":"#fff","red":255.0,"green":255.0,"blue":255.0,"alpha":1.0}}"##
+        ),
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn forward_visibility_is_sorted() {
+    let json = parse("@forward \"a\" show c, b, $e, $d;");
+
+    assert!(
+        json.contains(concat!(
+            r#""shownMixinsAndFunctions":["b","c"],"shownVariables":["d","e"],"#,
+            r#""hiddenMixinsAndFunctions":null,"hiddenVariables":null"#
+        )),
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn strings_are_escaped() {
+    let json = parse("a { b: \"c\\\"d\"; }");
+
+    assert!(json.contains(r#"[["c\"d"],"quoted"]"#), "{}", json);
+}
+
+#[test]
+fn every_statement_kind() {
+    let json = parse(concat!(
+        "@use \"a\" with ($b: 1);\n",
+        "@forward \"c\";\n",
+        "@import \"d\", \"e.css\";\n",
+        "/* f */\n",
+        "// g\n",
+        "@function h($i) { @if $i { @return 1; } @else { @return 2; } }\n",
+        "@mixin j { @content; }\n",
+        "k {\n",
+        "  @include j { l: m; }\n",
+        "  @for $n from 1 to 2 { @each $o in p { @while q { @debug r; @warn s; @error t; } } }\n",
+        "  @extend u;\n",
+        "  @at-root v { w: x; }\n",
+        "  @media y { z: a; }\n",
+        "  @supports (b: c) { d: e; }\n",
+        "  @f g;\n",
+        "}\n",
+    ));
+
+    for ty in [
+        "Use",
+        "Forward",
+        "ImportRule",
+        "LoudComment",
+        "SilentComment",
+        "FunctionDecl",
+        "If",
+        "Return",
+        "Mixin",
+        "ContentRule",
+        "RuleSet",
+        "Include",
+        "For",
+        "Each",
+        "While",
+        "Debug",
+        "Warn",
+        "ErrorRule",
+        "Extend",
+        "AtRootRule",
+        "Media",
+        "Supports",
+        "UnknownAtRule",
+        "Style",
+    ] {
+        assert!(
+            json.contains(&format!(r#"{{"type":"{}","value":"#, ty)),
+            "{}: {}",
+            ty,
+            json
+        );
+    }
+}
+
+#[test]
+fn indented_syntax() {
+    let json = grass::parse_to_json(
+        "a\n  b: c".to_owned(),
+        "input.sass",
+        &grass::Options::default().input_syntax(grass::InputSyntax::Sass),
+    )
+    .unwrap();
+
+    assert!(json.contains(r#""type":"RuleSet""#), "{}", json);
+}
+
+#[test]
+fn parse_error() {
+    let err = grass::parse_to_json("a {".to_owned(), "input.scss", &grass::Options::default())
+        .unwrap_err()
+        .to_string();
+
+    assert!(err.starts_with("Error: expected \"}\".\n"), "{}", err);
+}
@@ -249,3 +249,55 @@ error!(
     "Error: Nested declarations aren't allowed in plain CSS.",
     grass::Options::default().input_syntax(InputSyntax::Css)
 );
+test!(
+    style_rule_within_media_query,
+    "@media screen {
+        a {
+            color: red;
+        }
+    }
+    b {
+        color: red;
+    }",
+    "@media screen {\n  a {\n    color: red;\n  }\n}\nb {\n  color: red;\n}\n",
+    grass::Options::default().input_syntax(InputSyntax::Css)
+);
+test!(
+    layer_statement,
+    "@layer a, b;",
+    "@layer a, b;\n",
+    grass::Options::default().input_syntax(InputSyntax::Css)
+);
+test!(
+    layer_block,
+    "@layer a {
+        b {
+            c: d;
+        }
+    }",
+    "@layer a {\n  b {\n    c: d;\n  }\n}\n",
+    grass::Options::default().input_syntax(InputSyntax::Css)
+);
+test!(
+    nested_layer_blocks,
+    "@layer a {
+        @layer b {
+            c {
+                d: e;
+            }
+        }
+    }",
+    "@layer a {\n  @layer b {\n    c {\n      d: e;\n    }\n  }\n}\n",
+    grass::Options::default().input_syntax(InputSyntax::Css)
+);
+test!(
+    style_rule_with_defined_variables,
+    "@import \"foo.css\";
+    a {
+        color: red;
+    }",
+    "@import \"foo.css\";\na {\n  color: red;\n}\n",
+    grass::Options::default()
+        .input_syntax(InputSyntax::Css)
+        .define("color", "blue")
+);
@@ -0,0 +1,219 @@
+#![cfg(feature = "plugins")]
+
+use std::{env, path::PathBuf, process::Command};
+
+use grass::plugin::Plugin;
+
+#[macro_use]
+mod macros;
+
+/// A plugin declaring the interface itself, as a plugin written in C would
+const PLUGIN: &str = r##"
+use std::{ffi::{CStr, CString}, os::raw::{c_char, c_int}};
+
+type Call = unsafe extern "C" fn(*const *const c_char, usize, *mut *mut c_char) -> c_int;
+
+#[repr(C)]
+pub struct Function { signature: *const c_char, call: Call }
+
+#[repr(C)]
+pub struct Table {
+    abi_version: u32,
+    functions: *const Function,
+    function_count: usize,
+    free_result: Option<unsafe extern "C" fn(*mut c_char)>,
+}
+
+unsafe impl Sync for Function {}
+unsafe impl Sync for Table {}
+
+unsafe fn arg(args: *const *const c_char, idx: usize) -> Option<String> {
+    let arg = *args.add(idx);
+    if arg.is_null() { None } else { Some(CStr::from_ptr(arg).to_string_lossy().into_owned()) }
+}
+
+unsafe fn ret(result: *mut *mut c_char, value: &str) {
+    *result = CString::new(value).unwrap().into_raw();
+}
+
+unsafe extern "C" fn brand_color(args: *const *const c_char, _: usize, result: *mut *mut c_char) -> c_int {
+    match arg(args, 0).as_deref() {
+        Some("dark") => ret(result, "#111"),
+        _ => ret(result, "#e0245e"),
+    }
+    0
+}
+
+unsafe extern "C" fn echo(args: *const *const c_char, _: usize, result: *mut *mut c_char) -> c_int {
+    ret(result, &arg(args, 0).unwrap());
+    0
+}
+
+unsafe extern "C" fn fail(_: *const *const c_char, _: usize, result: *mut *mut c_char) -> c_int {
+    ret(result, "the design token service is unreachable");
+    1
+}
+
+unsafe extern "C" fn invalid(_: *const *const c_char, _: usize, result: *mut *mut c_char) -> c_int {
+    ret(result, "1; a { b: c }");
+    0
+}
+
+unsafe extern "C" fn free_result(result: *mut c_char) {
+    drop(CString::from_raw(result));
+}
+
+static FUNCTIONS: [Function; 4] = [
+    Function { signature: b"brand-color($variant: null)\0".as_ptr().cast(), call: brand_color },
+    Function { signature: b"echo($values...)\0".as_ptr().cast(), call: echo },
+    Function { signature: b"fail()\0".as_ptr().cast(), call: fail },
+    Function { signature: b"invalid_return()\0".as_ptr().cast(), call: invalid },
+];
+
+static TABLE: Table = Table {
+    abi_version: ABI_VERSION,
+    functions: FUNCTIONS.as_ptr(),
+    function_count: 4,
+    free_result: Some(free_result),
+};
+
+#[no_mangle]
+pub extern "C" fn ENTRY_POINT() -> *const Table {
+    &TABLE
+}
+"##;
+
+/// Compile `source` as a shared library, returning the directory it is in and
+/// its path
+fn build_plugin(source: &str) -> (tempfile::TempDir, PathBuf) {
+    let dir = tempfile::tempdir().unwrap();
+    let source_path = dir.path().join("plugin.rs");
+    let library = dir.path().join(format!(
+        "{}plugin{}",
+        env::consts::DLL_PREFIX,
+        env::consts::DLL_SUFFIX
+    ));
+
+    std::fs::write(&source_path, source).unwrap();
+
+    let status = Command::new(env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned()))
+        .args(["--edition", "2021", "--crate-type", "cdylib", "-o"])
+        .arg(&library)
+        .arg(&source_path)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    (dir, library)
+}
+
+fn load(abi_version: u32, entry_point: &str) -> std::io::Result<(tempfile::TempDir, Plugin)> {
+    let source = PLUGIN
+        .replace("ABI_VERSION", &abi_version.to_string())
+        .replace("ENTRY_POINT", entry_point);
+    let (dir, library) = build_plugin(&source);
+
+    unsafe { Plugin::load(library) }.map(|plugin| (dir, plugin))
+}
+
+fn compile(input: &str, plugin: &Plugin) -> grass::Result<String> {
+    grass::from_string(input.to_owned(), &grass::Options::default().plugin(plugin))
+}
+
+#[test]
+fn calls_plugin_functions() {
+    let (_dir, plugin) = load(grass::plugin::ABI_VERSION, "grass_plugin").unwrap();
+
+    assert_eq!(
+        plugin.function_names().collect::<Vec<_>>(),
+        vec!["brand-color", "echo", "fail", "invalid-return"]
+    );
+
+    assert_eq!(
+        "a {\n  color: #e0245e;\n  background: #111;\n  margin: 1px, \"a\", 3;\n}\n",
+        compile(
+            "a {\n  color: brand-color();\n  background: brand_color($variant: dark);\n  margin: echo(1px, \"a\", 1 + 2);\n}",
+            &plugin
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn plugin_function_arguments_are_checked() {
+    let (_dir, plugin) = load(grass::plugin::ABI_VERSION, "grass_plugin").unwrap();
+
+    assert_eq!(
+        compile("a { b: brand-color(a, b) }", &plugin)
+            .unwrap_err()
+            .message(),
+        "Only 1 argument allowed, but 2 were passed."
+    );
+}
+
+#[test]
+fn plugin_function_unknown_named_argument() {
+    let (_dir, plugin) = load(grass::plugin::ABI_VERSION, "grass_plugin").unwrap();
+
+    assert_eq!(
+        compile("a { b: brand-color($varient: dark) }", &plugin)
+            .unwrap_err()
+            .message(),
+        "No argument named $varient."
+    );
+}
+
+#[test]
+fn plugin_function_error() {
+    let (_dir, plugin) = load(grass::plugin::ABI_VERSION, "grass_plugin").unwrap();
+
+    assert_eq!(
+        compile("a { b: fail() }", &plugin).unwrap_err().message(),
+        "the design token service is unreachable"
+    );
+}
+
+#[test]
+fn plugin_function_invalid_return() {
+    let (_dir, plugin) = load(grass::plugin::ABI_VERSION, "grass_plugin").unwrap();
+
+    assert_eq!(
+        compile("a { b: invalid-return() }", &plugin)
+            .unwrap_err()
+            .message(),
+        "Function invalid-return returned invalid Sass: Expected a single expression."
+    );
+}
+
+#[test]
+fn missing_entry_point() {
+    let err = load(grass::plugin::ABI_VERSION, "not_grass_plugin").unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "plugins must export a function named grass_plugin"
+    );
+}
+
+#[test]
+fn unsupported_abi_version() {
+    let err = load(grass::plugin::ABI_VERSION + 1, "grass_plugin").unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "the plugin was built for version {} of the plugin interface, but only version {} is supported",
+            grass::plugin::ABI_VERSION + 1,
+            grass::plugin::ABI_VERSION
+        )
+    );
+}
+
+#[test]
+fn not_a_library() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("plugin.so");
+    std::fs::write(&path, "not a library").unwrap();
+
+    assert!(unsafe { Plugin::load(path) }.is_err());
+}
@@ -0,0 +1,134 @@
+#![cfg(feature = "json")]
+
+#[macro_use]
+mod macros;
+
+fn compile(input: &str, options: &grass::Options) -> String {
+    grass::to_postcss_json(input.to_owned(), "input.scss", options).unwrap()
+}
+
+#[test]
+fn empty_stylesheet() {
+    assert_eq!(
+        compile("", &grass::Options::default()),
+        r#"{"type":"root","nodes":[],"raws":{"semicolon":true,"after":""}}"#
+    );
+}
+
+#[test]
+fn rule_with_declarations() {
+    assert_eq!(
+        compile("a { b: 1 + 2; c: d }", &grass::Options::default()),
+        concat!(
+            r#"{"type":"root","nodes":[{"type":"rule","selector":"a","nodes":["#,
+            r#"{"type":"decl","prop":"b","value":"3","raws":{"before":"\n  ","between":": "}},"#,
+            r#"{"type":"decl","prop":"c","value":"d","raws":{"before":"\n  ","between":": "}}],"#,
+            r#""raws":{"before":"","between":" ","semicolon":true,"after":"\n"}}],"#,
+            r#""raws":{"semicolon":true,"after":"\n"}}"#
+        )
+    );
+}
+
+#[test]
+fn compressed_raws() {
+    assert_eq!(
+        compile(
+            "a { b: c; d: e }",
+            &grass::Options::default().style(grass::OutputStyle::Compressed)
+        ),
+        concat!(
+            r#"{"type":"root","nodes":[{"type":"rule","selector":"a","nodes":["#,
+            r#"{"type":"decl","prop":"b","value":"c","raws":{"before":"","between":":"}},"#,
+            r#"{"type":"decl","prop":"d","value":"e","raws":{"before":"","between":":"}}],"#,
+            r#""raws":{"before":"","between":"","semicolon":false,"after":""}}],"#,
+            r#""raws":{"semicolon":true,"after":""}}"#
+        )
+    );
+}
+
+#[test]
+fn important_is_separate_from_value() {
+    let json = compile("a { b: c !important }", &grass::Options::default());
+
+    assert!(
+        json.contains(concat!(
+            r#"{"type":"decl","prop":"b","value":"c","important":true,"#,
+            r#""raws":{"before":"\n  ","between":": ","important":" !important"}}"#
+        )),
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn media_rule_with_nested_rule() {
+    assert_eq!(
+        compile("@media print { a { b: c } }", &grass::Options::default()),
+        concat!(
+            r#"{"type":"root","nodes":[{"type":"atrule","name":"media","params":"print","nodes":["#,
+            r#"{"type":"rule","selector":"a","nodes":["#,
+            r#"{"type":"decl","prop":"b","value":"c","raws":{"before":"\n    ","between":": "}}],"#,
+            r#""raws":{"before":"\n  ","between":" ","semicolon":true,"after":"\n  "}}],"#,
+            r#""raws":{"before":"","afterName":" ","between":" ","semicolon":true,"after":"\n"}}],"#,
+            r#""raws":{"semicolon":true,"after":"\n"}}"#
+        )
+    );
+}
+
+#[test]
+fn at_rule_without_body() {
+    let json = compile("@foo bar;", &grass::Options::default());
+
+    assert!(
+        json.contains(
+            r#"{"type":"atrule","name":"foo","params":"bar","raws":{"before":"","afterName":" ","between":""}}"#
+        ),
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn comment_and_group_end() {
+    let json = compile("a { b: c }\n/* d */", &grass::Options::default());
+
+    assert!(
+        json.contains(
+            r#"{"type":"comment","text":"d","raws":{"before":"\n\n","left":" ","right":" "}}"#
+        ),
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn charset_for_non_ascii() {
+    let json = compile("a { b: \"é\" }", &grass::Options::default());
+
+    assert!(
+        json.starts_with(concat!(
+            r#"{"type":"root","nodes":[{"type":"atrule","name":"charset","params":"\"UTF-8\"","#,
+            r#""raws":{"before":"","afterName":" ","between":""}},"#,
+            r#"{"type":"rule","selector":"a","nodes":"#
+        )),
+        "{}",
+        json
+    );
+    assert!(
+        json.contains(r#""raws":{"before":"\n","between":" ","#),
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn error_is_reported() {
+    let err = grass::to_postcss_json(
+        "a { b: 1s + 1px }".to_owned(),
+        "input.scss",
+        &grass::Options::default(),
+    )
+    .unwrap_err();
+
+    assert_eq!(err.message(), "Incompatible units px and s.");
+}
@@ -0,0 +1,148 @@
+/// Compile `input` with error recovery, returning the message and (0-indexed)
+/// starting line and column of each error
+fn recovered_errors(input: &str, options: &grass::Options) -> Vec<(String, usize, usize)> {
+    grass::from_string_with_recovery(input.to_owned(), options)
+        .unwrap_err()
+        .into_iter()
+        .map(|err| match err.kind() {
+            grass::ErrorKind::ParseError { message, loc, .. } => {
+                (message, loc.begin.line, loc.begin.column)
+            }
+            e => panic!("unexpected error {:?}", e),
+        })
+        .collect()
+}
+
+#[test]
+fn recovers_from_errors_in_separate_style_rules() {
+    assert_eq!(
+        recovered_errors(
+            "a {\n  color: ;\n}\nb {\n  color: red;\n}\nc {\n  width: (;\n}\n",
+            &grass::Options::default()
+        ),
+        vec![
+            ("Expected expression.".to_owned(), 1, 8),
+            ("expected \")\".".to_owned(), 7, 10),
+        ]
+    );
+}
+#[test]
+fn recovers_from_errors_in_same_style_rule() {
+    assert_eq!(
+        recovered_errors(
+            "a {\n  b: ;\n  c: d;\n  e: ;\n}\n",
+            &grass::Options::default()
+        ),
+        vec![
+            ("Expected expression.".to_owned(), 1, 4),
+            ("Expected expression.".to_owned(), 3, 4),
+        ]
+    );
+}
+#[test]
+fn recovers_from_errors_in_top_level_statements() {
+    assert_eq!(
+        recovered_errors("@include;\n$a: ;\n", &grass::Options::default()),
+        vec![
+            ("Expected identifier.".to_owned(), 0, 8),
+            ("Expected expression.".to_owned(), 1, 3),
+        ]
+    );
+}
+#[test]
+fn recovery_skips_braces_inside_strings() {
+    assert_eq!(
+        recovered_errors(
+            "a {\n  b: \"}\" ( ;\n}\nc {\n  d: ;\n}\n",
+            &grass::Options::default()
+        ),
+        vec![
+            ("expected \")\".".to_owned(), 1, 11),
+            ("Expected expression.".to_owned(), 4, 4),
+        ]
+    );
+}
+#[test]
+fn recovers_from_unmatched_closing_brace() {
+    assert_eq!(
+        recovered_errors(
+            "a {\n  b: c;\n}\n}\nd {\n  e: ;\n}\n",
+            &grass::Options::default()
+        ),
+        vec![
+            ("unmatched \"}\".".to_owned(), 3, 0),
+            ("Expected expression.".to_owned(), 5, 4),
+        ]
+    );
+}
+#[test]
+fn recovers_from_errors_in_plain_css() {
+    assert_eq!(
+        recovered_errors(
+            "a {\n  b: $c;\n}\nd {\n  e: ;\n}\n",
+            &grass::Options::default().input_syntax(grass::InputSyntax::Css)
+        ),
+        vec![
+            (
+                "Sass variables aren't allowed in plain CSS.".to_owned(),
+                1,
+                5
+            ),
+            ("Expected expression.".to_owned(), 4, 4),
+        ]
+    );
+}
+#[test]
+fn recovery_reports_missing_closing_brace_at_eof() {
+    assert_eq!(
+        recovered_errors(
+            "a {\n  b {\n    c: ;\n  }\n  d: e\n",
+            &grass::Options::default()
+        ),
+        vec![
+            ("Expected expression.".to_owned(), 2, 6),
+            ("expected \"}\".".to_owned(), 4, 6),
+        ]
+    );
+}
+#[test]
+fn recovery_does_not_evaluate_after_syntax_error() {
+    assert_eq!(
+        recovered_errors(
+            "a {\n  b: $undefined;\n  c: ;\n}\n",
+            &grass::Options::default()
+        ),
+        vec![("Expected expression.".to_owned(), 2, 4)]
+    );
+}
+#[test]
+fn recovery_reports_evaluation_error_without_syntax_errors() {
+    assert_eq!(
+        recovered_errors("a {\n  b: $undefined;\n}\n", &grass::Options::default()),
+        vec![("Undefined variable.".to_owned(), 1, 5)]
+    );
+}
+#[test]
+fn recovery_compiles_valid_stylesheet() {
+    assert_eq!(
+        grass::from_string_with_recovery("a {\n  b: c;\n}\n", &grass::Options::default()).unwrap(),
+        "a {\n  b: c;\n}\n"
+    );
+}
+#[test]
+fn recovery_stops_at_first_error_in_indented_syntax() {
+    assert_eq!(
+        recovered_errors(
+            "a\n  b: \n  c: (\n",
+            &grass::Options::default().input_syntax(grass::InputSyntax::Sass)
+        )
+        .len(),
+        1
+    );
+}
+#[test]
+fn no_recovery_without_opting_in() {
+    let err =
+        grass::from_string("a {\n  b: ;\n  c: ;\n}\n", &grass::Options::default()).unwrap_err();
+    assert!(err.to_string().starts_with("Error: Expected expression."));
+}
@@ -41,3 +41,8 @@ test!(
     "a {\n  color: selector-replace(\"c, d\", \"d\", \"e\");\n}\n",
     "a {\n  color: c, e;\n}\n"
 );
+error!(
+    parent_selector_in_selector,
+    "a {\n  color: selector-replace(\"a &\", \"a\", \"b\");\n}\n",
+    "Error: Parent selectors aren't allowed here."
+);
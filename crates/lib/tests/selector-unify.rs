@@ -678,6 +678,51 @@ test!(
     "a {\n  color: selector-unify(\":is(.c)\", \":is(.d)\");\n}\n",
     "a {\n  color: :is(.c):is(.d);\n}\n"
 );
+test!(
+    simple_pseudo_arg_not_complex_selector_arg,
+    "a {\n  color: selector-unify(\":not(c > .d, .e)\", \"f\");\n}\n",
+    "a {\n  color: f:not(c > .d, .e);\n}\n"
+);
+test!(
+    simple_pseudo_arg_not_different_selector_arg,
+    "a {\n  color: selector-unify(\":not(c > .d)\", \":not(.e f)\");\n}\n",
+    "a {\n  color: :not(c > .d):not(.e f);\n}\n"
+);
+test!(
+    simple_pseudo_arg_slotted_same_selector_arg,
+    "a {\n  color: selector-unify(\"::slotted(.c)\", \"::slotted(.c)\");\n}\n",
+    "a {\n  color: ::slotted(.c);\n}\n"
+);
+test!(
+    simple_pseudo_arg_slotted_different_selector_arg,
+    "a {\n  color: selector-unify(\".c::slotted(.d)\", \".e::slotted(.f)\");\n}\n",
+    "a {\n  color: .c.e::slotted(.d.f);\n}\n"
+);
+test!(
+    simple_pseudo_arg_slotted_selector_arg_list,
+    "a {\n  color: selector-unify(\"::slotted(.c)\", \"::slotted(d, e)\");\n}\n",
+    "a {\n  color: ::slotted(d.c, e.c);\n}\n"
+);
+test!(
+    simple_pseudo_arg_slotted_disjoint_selector_arg,
+    "a {\n  color: inspect(selector-unify(\"::slotted(c)\", \"::slotted(d)\"));\n}\n",
+    "a {\n  color: null;\n}\n"
+);
+test!(
+    simple_pseudo_arg_slotted_and_other_element,
+    "a {\n  color: inspect(selector-unify(\"::slotted(.c)\", \"::before\"));\n}\n",
+    "a {\n  color: null;\n}\n"
+);
+test!(
+    simple_pseudo_arg_part_same_arg,
+    "a {\n  color: selector-unify(\"c::part(d)\", \".e::part(d)\");\n}\n",
+    "a {\n  color: c.e::part(d);\n}\n"
+);
+test!(
+    simple_pseudo_arg_part_different_arg,
+    "a {\n  color: inspect(selector-unify(\"::part(c)\", \"::part(d)\"));\n}\n",
+    "a {\n  color: null;\n}\n"
+);
 test!(
     universal_and_class,
     "a {\n  color: selector-unify(\"*\", \".a\");\n}\n",
@@ -688,3 +733,58 @@ test!(
     "a {\n  color: selector-unify(\"*\", \":a\");\n}\n",
     "a {\n  color: :a;\n}\n"
 );
+test!(
+    order_type_before_placeholder,
+    "a {\n  color: selector-unify(\"%c\", \"d.e\");\n}\n",
+    "a {\n  color: d%c.e;\n}\n"
+);
+test!(
+    order_pseudo_class_before_existing_pseudo_element,
+    "a {\n  color: selector-unify(\".c::before\", \".d:hover\");\n}\n",
+    "a {\n  color: .c.d:hover::before;\n}\n"
+);
+test!(
+    duplicate_simple_selectors_in_both,
+    "a {\n  color: selector-unify(\":hover.c\", \".c:hover\");\n}\n",
+    "a {\n  color: :hover.c;\n}\n"
+);
+test!(
+    host_and_class,
+    "a {\n  color: inspect(selector-unify(\":host\", \".c\"));\n}\n",
+    "a {\n  color: null;\n}\n"
+);
+test!(
+    class_and_host,
+    "a {\n  color: inspect(selector-unify(\".c\", \":host\"));\n}\n",
+    "a {\n  color: null;\n}\n"
+);
+test!(
+    host_and_pseudo_class,
+    "a {\n  color: inspect(selector-unify(\":host\", \":hover\"));\n}\n",
+    "a {\n  color: null;\n}\n"
+);
+test!(
+    host_and_selector_pseudo,
+    "a {\n  color: selector-unify(\":host\", \":is(.c)\");\n}\n",
+    "a {\n  color: :is(.c):host;\n}\n"
+);
+test!(
+    host_and_host_with_arg,
+    "a {\n  color: selector-unify(\":host\", \":host(.c)\");\n}\n",
+    "a {\n  color: :host:host(.c);\n}\n"
+);
+test!(
+    host_context_and_host,
+    "a {\n  color: selector-unify(\":host-context(.c)\", \":host\");\n}\n",
+    "a {\n  color: :host-context(.c):host;\n}\n"
+);
+test!(
+    host_context_and_class,
+    "a {\n  color: inspect(selector-unify(\":host-context(.c)\", \".d\"));\n}\n",
+    "a {\n  color: null;\n}\n"
+);
+test!(
+    universal_and_host,
+    "a {\n  color: inspect(selector-unify(\"*\", \":host\"));\n}\n",
+    "a {\n  color: null;\n}\n"
+);
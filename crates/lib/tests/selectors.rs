@@ -102,6 +102,53 @@ test!(
     "[attr   *=   val      ] {\n  color: red;\n}\n",
     "[attr*=val] {\n  color: red;\n}\n"
 );
+test!(
+    selector_attribute_s_modifier,
+    "[attr=\"v a l\" s] {\n  color: red;\n}\n",
+    "[attr=\"v a l\" s] {\n  color: red;\n}\n"
+);
+test!(
+    selector_attribute_modifier_touching_quoted_value,
+    "[attr='val'i] {\n  color: red;\n}\n",
+    "[attr=val i] {\n  color: red;\n}\n"
+);
+test!(
+    selector_attribute_quoted_value_containing_quotes,
+    "[attr=\"a \\\"b\\\" c\"] {\n  color: red;\n}\n",
+    "[attr='a \"b\" c'] {\n  color: red;\n}\n"
+);
+test!(
+    selector_attribute_quoted_value_containing_bracket,
+    "[attr=\"a]\"] {\n  color: red;\n}\n",
+    "[attr=\"a]\"] {\n  color: red;\n}\n"
+);
+test!(
+    selector_attribute_unquoted_value_begins_with_hyphen,
+    "[attr=\"-val\"] {\n  color: red;\n}\n",
+    "[attr=-val] {\n  color: red;\n}\n"
+);
+test!(
+    selector_attribute_unquoted_value_begins_with_escape,
+    "[attr=\\31 val] {\n  color: red;\n}\n",
+    "[attr=\\31 val] {\n  color: red;\n}\n"
+);
+test!(
+    selector_attribute_quotes_value_begins_with_digit,
+    "[attr=\"1val\"] {\n  color: red;\n}\n",
+    "[attr=\"1val\"] {\n  color: red;\n}\n"
+);
+error!(
+    selector_attribute_value_not_identifier_or_string,
+    "[attr=1] {\n  color: red;\n}\n", "Error: Expected string."
+);
+error!(
+    selector_attribute_unterminated_string,
+    "[attr=\"val] {\n  color: red;\n}\n", "Error: Expected \"."
+);
+error!(
+    selector_attribute_two_modifiers,
+    "[attr=val i s] {\n  color: red;\n}\n", "Error: expected \"]\"."
+);
 test!(
     selector_attribute_equals,
     "[attr=val] {\n  color: red;\n}\n",
@@ -142,6 +189,21 @@ test!(
     "[*|foo] {\n  color: red;\n}\n",
     "[*|foo] {\n  color: red;\n}\n"
 );
+test!(
+    selector_attribute_explicit_namespace,
+    "[svg|href=\"#a\"] {\n  color: red;\n}\n",
+    "[svg|href=\"#a\"] {\n  color: red;\n}\n"
+);
+test!(
+    selector_attribute_empty_namespace,
+    "[|foo=bar i] {\n  color: red;\n}\n",
+    "[|foo=bar i] {\n  color: red;\n}\n"
+);
+test!(
+    selector_type_namespaces,
+    "svg|a, *|b, |c, svg|*, *|* {\n  color: red;\n}\n",
+    "svg|a, *|b, |c, svg|*, *|* {\n  color: red;\n}\n"
+);
 error!(
     selector_attribute_missing_equal,
     "[a~b] {\n  color: red;\n}\n", "Error: expected \"=\"."
@@ -325,6 +387,53 @@ test!(
     "a {\n&__b {\n  color: red;\n}\n}\n",
     "a__b {\n  color: red;\n}\n"
 );
+test!(
+    bem_suffix_on_id,
+    "#a {\n&-b {\n  color: red;\n}\n}\n",
+    "#a-b {\n  color: red;\n}\n"
+);
+test!(
+    bem_suffix_on_placeholder,
+    "%a {\n&-b {\n  color: red;\n}\n}\nc {\n  @extend %a-b;\n}\n",
+    "c {\n  color: red;\n}\n"
+);
+test!(
+    bem_suffix_on_pseudo_without_argument,
+    "a:hover {\n&-b {\n  color: red;\n}\n}\n",
+    "a:hover-b {\n  color: red;\n}\n"
+);
+test!(
+    bem_suffix_followed_by_compound,
+    ".a {\n&-b.c:hover {\n  color: red;\n}\n}\n",
+    ".a-b.c:hover {\n  color: red;\n}\n"
+);
+test!(
+    bem_suffix_multiple_complex_parents,
+    ".a .b, .c > .d {\n&-e {\n  color: red;\n}\n}\n",
+    ".a .b-e, .c > .d-e {\n  color: red;\n}\n"
+);
+test!(
+    bem_suffix_interpolated,
+    ".a {\n  $b: c;\n&-#{$b} {\n  color: red;\n}\n}\n",
+    ".a-c {\n  color: red;\n}\n"
+);
+test!(
+    bem_suffix_starts_with_number,
+    ".a {\n&1 {\n  color: red;\n}\n}\n",
+    ".a1 {\n  color: red;\n}\n"
+);
+error!(
+    bem_suffix_on_attribute,
+    "[a] {\n&-b {\n  color: red;\n}\n}\n", "Error: Invalid parent selector \"[a]\""
+);
+error!(
+    bem_suffix_on_pseudo_with_argument,
+    ".a:not(.b) {\n&-c {\n  color: red;\n}\n}\n", "Error: Invalid parent selector \":not(.b)\""
+);
+error!(
+    bem_suffix_on_universal,
+    "* {\n&-b {\n  color: red;\n}\n}\n", "Error: Invalid parent selector \"*\""
+);
 test!(
     selector_interpolation_addition,
     "#{\"foo\" + \" bar\"}baz {color: red;}",
@@ -365,6 +474,35 @@ test!(
     "#{&}a {\nb { color: red; }}",
     "a b {\n  color: red;\n}\n"
 );
+test!(
+    selector_interpolation_before_pseudo,
+    "$name: foo;\n.icon-#{$name}:hover {\n  color: red;\n}\n",
+    ".icon-foo:hover {\n  color: red;\n}\n"
+);
+test!(
+    selector_interpolation_before_combinator,
+    "$sel: \".a\";\n#{$sel} > li {\n  color: red;\n}\n",
+    ".a > li {\n  color: red;\n}\n"
+);
+test!(
+    selector_interpolation_in_attribute_name_and_value,
+    "$attr: foo;\n$val: bar;\n[data-#{$attr}=\"#{$val}\"] {\n  color: red;\n}\n",
+    "[data-foo=bar] {\n  color: red;\n}\n"
+);
+test!(
+    selector_interpolation_in_pseudo_selector_argument,
+    "$sel: \".a, .b\";\n:not(#{$sel}) {\n  color: red;\n}\n",
+    ":not(.a, .b) {\n  color: red;\n}\n"
+);
+test!(
+    selector_interpolation_resolves_to_combinator,
+    "$combinator: \"~\";\na #{$combinator} b {\n  color: red;\n}\n",
+    "a ~ b {\n  color: red;\n}\n"
+);
+error!(
+    selector_interpolation_resolves_to_invalid_selector,
+    "$sel: \"a[\";\n.b #{$sel} {\n  color: red;\n}\n", "Error: Expected identifier."
+);
 test!(
     selector_whitespace,
     "  a  >  b  ,  c  ~  d  e  .f  #g  :h  i.j  [  k  ]  { color: red }",
@@ -440,6 +578,21 @@ test!(
     "a, %b, c {\n  color: red;\n}\n",
     "a, c {\n  color: red;\n}\n"
 );
+test!(
+    placeholder_inside_media_and_supports,
+    "%a {\n  @media screen {\n    @supports (b: c) {\n      d: e;\n    }\n  }\n}\n",
+    ""
+);
+test!(
+    nested_placeholder_does_not_split_parent,
+    "a {\n  b: c;\n  %d {\n    e: f;\n  }\n  g: h;\n}\n",
+    "a {\n  b: c;\n  g: h;\n}\n"
+);
+test!(
+    nested_media_with_only_placeholder_does_not_split_parent,
+    "a {\n  b: c;\n  @media screen {\n    %d {\n      e: f;\n    }\n  }\n  g: h;\n}\n",
+    "a {\n  b: c;\n  g: h;\n}\n"
+);
 test!(
     removes_leading_space,
     "#{&} a {\n  color: red;\n}\n",
@@ -473,6 +626,46 @@ test!(
     "\\! {\n  color: red;\n}\n",
     "\\! {\n  color: red;\n}\n"
 );
+test!(
+    class_escaped_leading_digit,
+    ".\\31 23 {\n  color: red;\n}\n",
+    ".\\31 23 {\n  color: red;\n}\n"
+);
+test!(
+    id_escaped_leading_digit,
+    "#\\31 a {\n  color: red;\n}\n",
+    "#\\31 a {\n  color: red;\n}\n"
+);
+test!(
+    class_escaped_at_sign,
+    ".foo\\@bar {\n  color: red;\n}\n",
+    ".foo\\@bar {\n  color: red;\n}\n"
+);
+test!(
+    class_escaped_colon_is_not_pseudo,
+    ".a\\:b {\n  color: red;\n}\n",
+    ".a\\:b {\n  color: red;\n}\n"
+);
+test!(
+    class_hex_escaped_hyphen_is_reescaped,
+    ".\\2d a {\n  color: red;\n}\n",
+    ".\\-a {\n  color: red;\n}\n"
+);
+test!(
+    class_hex_escaped_digit_after_hyphen_ends_with_space,
+    ".-\\31 {\n  color: red;\n}\n",
+    ".-\\31  {\n  color: red;\n}\n"
+);
+test!(
+    class_hex_escaped_non_ascii_is_unescaped,
+    ".\\E9 t\\E9 {\n  color: red;\n}\n",
+    "@charset \"UTF-8\";\n.été {\n  color: red;\n}\n"
+);
+test!(
+    extend_escaped_class,
+    ".\\31 23 {\n  @extend .foo\\@bar;\n}\n\n.foo\\@bar {\n  color: red;\n}\n",
+    ".foo\\@bar, .\\31 23 {\n  color: red;\n}\n"
+);
 test!(
     multiple_consecutive_immediate_child,
     "> > foo {\n  color: foo;\n}\n",
@@ -543,6 +736,36 @@ test!(
     "a:is(c) {\n  x: y;\n}\n"
 );
 test!(is_placeholder_removes_everything_is, "a:is(%b) {x: y}", "");
+test!(
+    parent_selector_inside_is_and_where,
+    ".a {\n  :is(&, .b) + :where(&-c) {\n    x: y;\n  }\n}\n",
+    ":is(.a, .b) + :where(.a-c) {\n  x: y;\n}\n"
+);
+test!(
+    parent_selector_inside_has,
+    ".a {\n  :has(> &) {\n    x: y;\n  }\n}\n",
+    ":has(> .a) {\n  x: y;\n}\n"
+);
+test!(
+    parent_selector_list_inside_is,
+    ".a, .b {\n  c:is(&) {\n    x: y;\n  }\n}\n",
+    "c:is(.a, .b) {\n  x: y;\n}\n"
+);
+test!(
+    interpolation_inside_is_and_where,
+    "$a: \".b, .c\";\n:is(#{$a}) :where(#{$a} > d) {\n  x: y;\n}\n",
+    ":is(.b, .c) :where(.b, .c > d) {\n  x: y;\n}\n"
+);
+test!(
+    parent_selector_inside_slotted,
+    ".a {\n  ::slotted(&.b) {\n    x: y;\n  }\n}\n",
+    "::slotted(.a.b) {\n  x: y;\n}\n"
+);
+test!(
+    part_with_multiple_idents,
+    "$a: label;\nmy-element::part(#{$a} active):hover {\n  x: y;\n}\n",
+    "my-element::part(label active):hover {\n  x: y;\n}\n"
+);
 test!(
     touching_universal_stays_the_same,
     "a* {\n  color: red;\n}\n",
@@ -789,6 +1012,16 @@ test!(
     ":nth-child(n of a) {\n  color: &;\n}\n",
     ":nth-child(n of a) {\n  color: :nth-child(n of a);\n}\n"
 );
+test!(
+    a_n_plus_b_nth_last_child_of_whitespace,
+    ":nth-last-child( -n + 3  of  b.c,d ) {\n  color: &;\n}\n",
+    ":nth-last-child(-n+3 of b.c, d) {\n  color: :nth-last-child(-n+3 of b.c, d);\n}\n"
+);
+test!(
+    a_n_plus_b_of_parent_selector,
+    "a {\n  :nth-child(odd of &.b) {\n    color: red;\n  }\n}\n",
+    ":nth-child(odd of a.b) {\n  color: red;\n}\n"
+);
 test!(
     escaped_space_at_end_of_selector_immediately_after_pseudo_color,
     "a color:\\  {\n  color: &;\n}\n",
@@ -809,6 +1042,36 @@ test!(
     ":a(a , b) {\n  color: &;\n}\n",
     ":a(a , b) {\n  color: :a(a , b);\n}\n"
 );
+test!(
+    unknown_pseudo_class_custom_state,
+    ":state(--checked):not(:state(--disabled)) {\n  color: red;\n}\n",
+    ":state(--checked):not(:state(--disabled)) {\n  color: red;\n}\n"
+);
+test!(
+    unknown_pseudo_class_arg_with_nested_brackets,
+    ":a((b) [c] d(e, f)) {\n  color: red;\n}\n",
+    ":a((b) [c] d(e, f)) {\n  color: red;\n}\n"
+);
+test!(
+    unknown_pseudo_class_arg_collapses_whitespace,
+    ":a(  b   >  c  ) {\n  color: red;\n}\n",
+    ":a(b > c) {\n  color: red;\n}\n"
+);
+test!(
+    unknown_pseudo_class_arg_interpolation,
+    "$b: 2;\n:a(#{$b}n + #{$b * 2}) {\n  color: red;\n}\n",
+    ":a(2n + 4) {\n  color: red;\n}\n"
+);
+test!(
+    unknown_pseudo_class_arg_parent_selector_not_resolved,
+    "a {\n  :b(&) {\n    color: red;\n  }\n}\n",
+    "a :b(&) {\n  color: red;\n}\n"
+);
+test!(
+    unknown_pseudo_class_arg_not_extended,
+    ":a(.b) {\n  color: red;\n}\n\n.c {\n  @extend .b;\n}\n",
+    ":a(.b) {\n  color: red;\n}\n"
+);
 test!(
     parent_selector_is_null_at_root,
     "#{inspect(&)}  {\n  color: &;\n}\n",
@@ -841,7 +1104,6 @@ test!(
     "[data-key=\"\\\\\"] {\n  color: [data-key=\"\\\\\"];\n}\n"
 );
 test!(
-    #[ignore = "we have to rewrite quoted attribute serialization"]
     attribute_value_escape_ends_with_whitespace,
     r#"[a="a\\66  "] {  color: &;}"#,
     "[a=\"a\\\\66  \"] {\n  color: [a=\"a\\\\66  \"];\n}\n"
@@ -1051,3 +1313,14 @@ error!(
     nth_child_loud_comment_between_n_and_of,
     ":nth-child(n/**/of a) {\n  color: &;\n}\n", "Error: expected \")\"."
 );
+
+#[test]
+fn error_in_interpolated_selector_points_to_interpolated_expression() {
+    let input = ".a #{\"b[\"} {\n  color: red;\n}\n";
+    assert_eq!(macros::error_location(input), ((0, 5), (0, 9)));
+}
+#[test]
+fn error_after_interpolated_selector_points_to_source() {
+    let input = ".a #{\"b\"} c[ {\n  color: red;\n}\n";
+    assert_eq!(macros::error_location(input), ((0, 11), (0, 12)));
+}
@@ -11,3 +11,15 @@ test!(
     "a {\n  color: simple-selectors(\".foo.bar.baz\");\n}\n",
     "a {\n  color: .foo, .bar, .baz;\n}\n"
 );
+error!(
+    combinator,
+    "a {\n  color: simple-selectors(\"> a\");\n}\n", "Error: $selector: Expected identifier."
+);
+error!(
+    complex,
+    "a {\n  color: simple-selectors(\"a b\");\n}\n", "Error: $selector: expected selector."
+);
+error!(
+    list,
+    "a {\n  color: simple-selectors(\"a, b\");\n}\n", "Error: $selector: expected selector."
+);
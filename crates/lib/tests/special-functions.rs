@@ -97,6 +97,50 @@ error!(
     calc_boolean,
     "$a: true; a {\n  color: calc($a);\n}\n", "Error: Value true can't be used in a calculation."
 );
+test!(
+    calc_interpolated_variable_stays_dynamic,
+    "$a: 10px;\na {\n  color: calc(100% - #{$a});\n}\n",
+    "a {\n  color: calc(100% - 10px);\n}\n"
+);
+test!(
+    calc_variable_stays_dynamic,
+    "$a: 3px;\na {\n  color: calc(100% - $a);\n}\n",
+    "a {\n  color: calc(100% - 3px);\n}\n"
+);
+test!(
+    calc_parenthesized_constant_subexpression_is_simplified,
+    "$a: 3px;\na {\n  color: calc(100% - (2 * $a));\n}\n",
+    "a {\n  color: calc(100% - 6px);\n}\n"
+);
+test!(
+    calc_nested_calc_is_simplified,
+    "a {\n  color: calc(1px + calc(2px * 3));\n}\n",
+    "a {\n  color: 7px;\n}\n"
+);
+test!(
+    calc_nested_dynamic_parens_are_preserved,
+    "a {\n  color: calc(2 * (1px + 1em));\n}\n",
+    "a {\n  color: calc(2 * (1px + 1em));\n}\n"
+);
+test!(
+    calc_is_calculation_type,
+    "a {\n  color: type-of(calc(1px + 1em));\n}\n",
+    "a {\n  color: calculation;\n}\n"
+);
+test!(
+    calc_simplifies_to_number_type,
+    "a {\n  color: type-of(calc(1px + 2px));\n}\n",
+    "a {\n  color: number;\n}\n"
+);
+test!(
+    calc_can_be_stored_in_variable,
+    "$a: calc(1px + 1em);\na {\n  color: $a;\n}\n",
+    "a {\n  color: calc(1px + 1em);\n}\n"
+);
+error!(
+    calc_incompatible_units,
+    "a {\n  color: calc(1px + 1s);\n}\n", "Error: 1px and 1s are incompatible."
+);
 test!(
     element_whitespace,
     "a {\n  color: element(       1      );\n}\n",
@@ -72,3 +72,83 @@ error!(
     }",
     "Error: Variable keyword argument map must have string keys."
 );
+test!(
+    splat_map_into_mixin,
+    "@mixin foo($a, $b) {
+        color: $a $b;
+    }
+    a {
+        @include foo((b: 2, a: 1)...);
+    }",
+    "a {\n  color: 1 2;\n}\n"
+);
+test!(
+    splat_list_into_mixin,
+    "@mixin foo($a, $b) {
+        color: $a $b;
+    }
+    a {
+        @include foo(1 2...);
+    }",
+    "a {\n  color: 1 2;\n}\n"
+);
+test!(
+    splat_map_after_positional,
+    "@function foo($a, $b) {
+        @return $a $b;
+    }
+    a {
+        color: foo(1, (b: 2)...);
+    }",
+    "a {\n  color: 1 2;\n}\n"
+);
+test!(
+    splat_list_then_map,
+    "@function foo($a, $b, $c) {
+        @return $a $b $c;
+    }
+    a {
+        color: foo((1, 2)..., (c: 3)...);
+    }",
+    "a {\n  color: 1 2 3;\n}\n"
+);
+test!(
+    splat_list_into_rest_parameter,
+    "@function foo($a, $b...) {
+        @return length($b);
+    }
+    a {
+        color: foo(1 2 3...);
+    }",
+    "a {\n  color: 2;\n}\n"
+);
+test!(
+    splat_map_into_rest_parameter_keywords,
+    "@function foo($args...) {
+        @return inspect(keywords($args));
+    }
+    a {
+        color: foo((b: 2, c: 3)...);
+    }",
+    "a {\n  color: (b: 2, c: 3);\n}\n"
+);
+error!(
+    splat_map_duplicates_positional,
+    "@function foo($a) {
+        @return $a;
+    }
+    a {
+        color: foo(1, (a: 2)...);
+    }",
+    "Error: Argument $a was passed both by position and by name."
+);
+error!(
+    splat_list_too_many_arguments,
+    "@function foo($a) {
+        @return $a;
+    }
+    a {
+        color: foo(1 2...);
+    }",
+    "Error: Only 1 argument allowed, but 2 were passed."
+);
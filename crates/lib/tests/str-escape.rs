@@ -199,3 +199,44 @@ error!(
     newline_after_escape,
     "a {\n  color: \\\n", "Error: Expected escape sequence."
 );
+test!(
+    private_use_character_in_quoted_string_is_escaped,
+    "a {\n  color: \"\\e000\";\n}\n",
+    "a {\n  color: \"\\e000\";\n}\n"
+);
+test!(
+    private_use_character_in_unquoted_string_is_escaped,
+    "a {\n  color: \\e000;\n}\n",
+    "a {\n  color: \\e000;\n}\n"
+);
+test!(
+    private_use_character_followed_by_hex_digit_has_space,
+    "a {\n  color: \"\\f8ff 1\";\n  color: \"\\f8ff x\";\n}\n",
+    "a {\n  color: \"\\f8ff 1\";\n  color: \"\\f8ffx\";\n}\n"
+);
+test!(
+    supplementary_private_use_character_is_escaped,
+    "a {\n  color: \"\\F0000\";\n  color: \"\\10FFFD\";\n}\n",
+    "a {\n  color: \"\\f0000\";\n  color: \"\\10fffd\";\n}\n"
+);
+test!(
+    private_use_character_is_not_escaped_in_compressed_mode,
+    "a {\n  color: \"\\e000\";\n}\n",
+    "\u{feff}a{color:\"\u{e000}\"}",
+    grass::Options::default().style(grass::OutputStyle::Compressed)
+);
+test!(
+    quote_string_containing_double_quote_uses_single_quotes,
+    "a {\n  color: quote('a\"b');\n}\n",
+    "a {\n  color: 'a\"b';\n}\n"
+);
+test!(
+    quote_string_containing_both_quotes_escapes_double_quote,
+    "a {\n  color: quote(unquote(\"a'b\\\"c\"));\n}\n",
+    "a {\n  color: \"a'b\\\"c\";\n}\n"
+);
+test!(
+    unquote_string_containing_quotes,
+    "a {\n  color: unquote(\"a\\\"b\");\n}\n",
+    "a {\n  color: a\"b;\n}\n"
+);
@@ -308,3 +308,16 @@ test!(
     ",
     "a {\n  color: \"aaa\";\n}\n"
 );
+test!(
+    quoted_string_with_interpolation_and_escapes,
+    "a {\n  color: \"foo #{1 + 1} \\62 ar #baz\";\n}\n",
+    "a {\n  color: \"foo 2 bar #baz\";\n}\n"
+);
+error!(
+    quoted_string_ends_at_carriage_return,
+    "a {\n  color: \"foo\rbar\";\n}\n", "Error: Expected \"."
+);
+error!(
+    quoted_string_ends_at_form_feed,
+    "a {\n  color: \"foo\x0Cbar\";\n}\n", "Error: Expected \"."
+);
@@ -101,6 +101,46 @@ test!(
     "$a: foo;\na {\n  co#{$a}lor: red;\n}\n",
     "a {\n  cofoolor: red;\n}\n"
 );
+test!(
+    style_interpolation_before_hyphen,
+    "$prop: margin;\na {\n  #{$prop}-top: 1px;\n}\n",
+    "a {\n  margin-top: 1px;\n}\n"
+);
+test!(
+    style_interpolation_after_hyphen,
+    "$side: left;\na {\n  margin-#{$side}: 0;\n}\n",
+    "a {\n  margin-left: 0;\n}\n"
+);
+test!(
+    style_interpolation_multiple,
+    "$a: b;\na {\n  #{$a}-#{$a}: c;\n}\n",
+    "a {\n  b-b: c;\n}\n"
+);
+test!(
+    style_interpolation_expression,
+    "a {\n  #{1 + 1}x: y;\n}\n",
+    "a {\n  2x: y;\n}\n"
+);
+test!(
+    style_interpolation_value_still_evaluated,
+    "$a: b;\na {\n  #{$a}-c: 1 + 2;\n}\n",
+    "a {\n  b-c: 3;\n}\n"
+);
+test!(
+    style_interpolation_whitespace_before_colon,
+    "$a: b;\na {\n  #{$a} : c;\n}\n",
+    "a {\n  b: c;\n}\n"
+);
+test!(
+    style_interpolation_nested_property_namespace,
+    "$a: margin;\na {\n  #{$a}: {\n    left: 1px;\n  }\n}\n",
+    "a {\n  margin-left: 1px;\n}\n"
+);
+test!(
+    style_interpolation_followed_by_pseudo_is_selector,
+    "$a: b;\na {\n  #{$a}:hover {\n    c: d;\n  }\n}\n",
+    "a b:hover {\n  c: d;\n}\n"
+);
 test!(
     style_val_interpolation_start,
     "a {\n  color: #{r}ed;\n}\n",
@@ -202,11 +202,50 @@ test!(
     "a {\n  color: 2px/em;\n}\n"
 );
 test!(
-    #[ignore = "we need to rewrite how we compare and convert units"]
     complex_units_with_same_denom_and_comparable_numer_are_comparable,
     "a {\n  color: comparable((23in/2fu), (23cm/2fu));\n}\n",
     "a {\n  color: true;\n}\n"
 );
+test!(
+    complex_units_with_comparable_numer_and_denom_are_comparable,
+    "@use \"sass:math\";\na {\n  color: comparable(math.div(1px, 1s), math.div(1in, 1ms));\n}\n",
+    "a {\n  color: true;\n}\n"
+);
+test!(
+    complex_units_with_incomparable_denom_are_not_comparable,
+    "@use \"sass:math\";\na {\n  color: comparable(math.div(1px, 1s), math.div(1px, 1em));\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    complex_units_with_different_number_of_units_are_not_comparable,
+    "a {\n  color: comparable(1px * 1px, 1px);\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+test!(
+    add_complex_units_converts_numer_and_denom,
+    "@use \"sass:math\";\na {\n  color: inspect(math.div(1px, 1s) + math.div(1in, 1ms));\n}\n",
+    "a {\n  color: 96001px/s;\n}\n"
+);
+test!(
+    add_complex_units_converts_numer_in_any_order,
+    "a {\n  color: inspect(1px * 1s + 1ms * 1in);\n}\n",
+    "a {\n  color: 1.096px*s;\n}\n"
+);
+test!(
+    add_complex_units_with_only_denom,
+    "@use \"sass:math\";\na {\n  color: inspect(math.div(1, 1s) + math.div(1, 1ms));\n}\n",
+    "a {\n  color: 1001s^-1;\n}\n"
+);
+test!(
+    compare_complex_units_converts_numer_and_denom,
+    "@use \"sass:math\";\na {\n  color: math.div(96px, 1s) == math.div(1in, 1000ms);\n  color: math.div(1px, 1s) < math.div(1in, 1ms);\n}\n",
+    "a {\n  color: true;\n  color: true;\n}\n"
+);
+error!(
+    add_complex_units_with_incompatible_denom,
+    "@use \"sass:math\";\na {\n  color: math.div(1px, 1s) + math.div(1px, 1em);\n}\n",
+    "Error: Incompatible units px/em and px/s."
+);
 test!(
     complex_unit_many_denom_one_numer,
     "a {\n  color: unit((1rem/1px) / 1vh);\n}\n",
@@ -406,3 +445,32 @@ test_unit_addition!(dpcm, dppx, "38.7952755906");
 test_unit_addition!(dppx, dpi, "1.0104166667");
 test_unit_addition!(dppx, dpcm, "1.0264583333");
 test_unit_addition!(dppx, dppx, "2");
+
+test!(
+    compares_converted_lengths,
+    "a {\n  color: 1in > 95px;\n  color: 1cm < 10.1mm;\n}\n",
+    "a {\n  color: true;\n  color: true;\n}\n"
+);
+test!(
+    compares_converted_angles,
+    "a {\n  color: 1turn == 360deg;\n  color: 1rad > 57deg;\n}\n",
+    "a {\n  color: true;\n  color: true;\n}\n"
+);
+test!(
+    compares_converted_times_frequencies_and_resolutions,
+    "a {\n  color: 1s > 999ms;\n  color: 1kHz <= 1000Hz;\n  color: 1dppx == 96dpi;\n}\n",
+    "a {\n  color: true;\n  color: true;\n  color: true;\n}\n"
+);
+test!(
+    incompatible_units_are_not_equal,
+    "a {\n  color: 1px == 1s;\n}\n",
+    "a {\n  color: false;\n}\n"
+);
+error!(
+    compare_incompatible_units,
+    "a {\n  color: 1px < 1s;\n}\n", "Error: Incompatible units s and px."
+);
+error!(
+    add_incompatible_compound_units,
+    "a {\n  color: 1px * 1s + 1px;\n}\n", "Error: Incompatible units px and px*s."
+);
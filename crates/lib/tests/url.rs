@@ -188,3 +188,38 @@ error!(
     url_nothing_after_hash_in_interpolation_in_quote,
     "a { color: url(#{\"#", "Error: Expected \"."
 );
+test!(
+    query_string_and_fragment,
+    "a {\n  color: url(http://a/b?c=d#e);\n}\n",
+    "a {\n  color: url(http://a/b?c=d#e);\n}\n"
+);
+test!(
+    escaped_parens,
+    "a {\n  color: url(a\\(b\\).png);\n}\n",
+    "a {\n  color: url(a\\(b\\).png);\n}\n"
+);
+test!(
+    escaped_space,
+    "a {\n  color: url(a\\ b);\n}\n",
+    "a {\n  color: url(a\\ b);\n}\n"
+);
+test!(
+    data_uri_with_semicolon_and_comma,
+    "a {\n  color: url(data:image/png;base64,AAA=);\n}\n",
+    "a {\n  color: url(data:image/png;base64,AAA=);\n}\n"
+);
+test!(
+    protocol_relative_url_with_comma,
+    "a {\n  color: url(//x.com/a,b);\n}\n",
+    "a {\n  color: url(//x.com/a,b);\n}\n"
+);
+test!(
+    interpolation_in_middle_of_path,
+    "$a: foo;\na {\n  color: url(a#{$a}b/c);\n}\n",
+    "a {\n  color: url(afoob/c);\n}\n"
+);
+test!(
+    empty_url,
+    "a {\n  color: url();\n}\n",
+    "a {\n  color: url();\n}\n"
+);
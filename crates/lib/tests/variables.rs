@@ -430,3 +430,55 @@ test!(
 );
 
 // todo: test that all scopes can affect global vars
+test!(
+    defined_variable,
+    "a {\n  color: $color;\n}\n",
+    "a {\n  color: red;\n}\n",
+    grass::Options::default().define("color", "red")
+);
+test!(
+    defined_variable_overrides_default,
+    "$env: development !default;\na {\n  env: $env;\n}\n",
+    "a {\n  env: production;\n}\n",
+    grass::Options::default().define("env", "production")
+);
+test!(
+    defined_variable_is_overwritten_by_declaration,
+    "$env: development;\na {\n  env: $env;\n}\n",
+    "a {\n  env: development;\n}\n",
+    grass::Options::default().define("env", "production")
+);
+test!(
+    defined_variable_is_sassscript,
+    "a {\n  width: $width * 2;\n  length: length($list);\n}\n",
+    "a {\n  width: 2px;\n  length: 3;\n}\n",
+    grass::Options::default()
+        .define("width", "1px")
+        .define("list", "(a, b, c)")
+);
+test!(
+    defined_variable_refers_to_previous,
+    "a {\n  color: $b;\n}\n",
+    "a {\n  color: 2;\n}\n",
+    grass::Options::default()
+        .define("a", "1")
+        .define("b", "$a + 1")
+);
+test!(
+    defined_variable_allows_use_rule,
+    "@use 'sass:math';\na {\n  color: math.abs($a);\n}\n",
+    "a {\n  color: 1;\n}\n",
+    grass::Options::default().define("a", "-1")
+);
+error!(
+    defined_variable_with_extra_statements,
+    "a {\n  color: $a;\n}\n",
+    "Error: Expected a single variable declaration.",
+    grass::Options::default().define("a", "red; b { c: d }")
+);
+error!(
+    defined_variable_invalid_value,
+    "a {\n  color: $a;\n}\n",
+    "Error: Expected expression.",
+    grass::Options::default().define("a", ")")
+);
@@ -9,3 +9,470 @@ test!(
     "",
     grass::Options::default().quiet(true)
 );
+
+/// A stack frame, as `(member, line, column)` with (1-indexed) lines and columns
+type Frame = (String, usize, usize);
+
+/// The location and stack trace of each warning
+fn warning_traces(input: &str) -> Vec<(String, Vec<Frame>)> {
+    let logger = macros::TestLogger::new();
+    grass::from_string(input.to_owned(), &grass::Options::default().logger(&logger)).unwrap();
+
+    let warnings = logger.warnings.into_inner();
+    warnings
+        .iter()
+        .map(|warning| {
+            (
+                warning.message().to_owned(),
+                warning
+                    .trace()
+                    .iter()
+                    .map(|frame| {
+                        (
+                            frame.member().to_owned(),
+                            frame.location().begin.line + 1,
+                            frame.location().begin.column + 1,
+                        )
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn warning_at_root_has_location() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "a {\n  @warn \"foo\";\n}\n",
+        &grass::Options::default().logger(&logger),
+    )
+    .unwrap();
+    let warnings = logger.warnings.into_inner();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].message(), "foo");
+    assert_eq!(warnings[0].location().file.name(), "stdin");
+    assert_eq!(
+        (
+            warnings[0].location().begin.line,
+            warnings[0].location().begin.column
+        ),
+        (1, 2)
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "Warning: foo\n    ./stdin:2:3  root stylesheet"
+    );
+}
+#[test]
+fn warning_in_mixin_includes_stack() {
+    assert_eq!(
+        warning_traces(
+            "@mixin inner {\n  @warn \"deep\";\n}\n@mixin outer {\n  @include inner;\n}\na {\n  @include outer;\n}\n"
+        ),
+        vec![(
+            "deep".to_owned(),
+            vec![
+                ("inner()".to_owned(), 2, 3),
+                ("outer()".to_owned(), 5, 12),
+                ("root stylesheet".to_owned(), 8, 12),
+            ]
+        )]
+    );
+}
+#[test]
+fn warning_in_function_includes_stack() {
+    assert_eq!(
+        warning_traces(
+            "@function foo() {\n  @warn \"bar\";\n  @return 1;\n}\na {\n  b: foo();\n}\n"
+        ),
+        vec![(
+            "bar".to_owned(),
+            vec![
+                ("foo()".to_owned(), 2, 3),
+                ("root stylesheet".to_owned(), 6, 6),
+            ]
+        )]
+    );
+}
+#[test]
+fn warning_in_content_block_includes_stack() {
+    assert_eq!(
+        warning_traces(
+            "@mixin foo {\n  @content;\n}\na {\n  @include foo {\n    @warn \"bar\";\n  }\n}\n"
+        ),
+        vec![(
+            "bar".to_owned(),
+            vec![
+                ("@content".to_owned(), 6, 5),
+                ("foo()".to_owned(), 2, 11),
+                ("root stylesheet".to_owned(), 5, 12),
+            ]
+        )]
+    );
+}
+#[test]
+fn stack_is_unwound_after_include() {
+    assert_eq!(
+        warning_traces("@mixin foo {}\na {\n  @include foo;\n  @warn \"bar\";\n}\n"),
+        vec![("bar".to_owned(), vec![("root stylesheet".to_owned(), 4, 3)])]
+    );
+}
+#[test]
+fn debug_is_sent_to_logger() {
+    let logger = macros::TestLogger::new();
+    grass::from_string("@debug 1 + 1;", &grass::Options::default().logger(&logger)).unwrap();
+    let debugs = logger.debugs.into_inner();
+    assert_eq!(debugs.len(), 1);
+    assert_eq!(debugs[0].1, "2");
+}
+#[test]
+fn debug_inspects_values_that_are_not_valid_css() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "@debug (a: 1);\n@debug null;\n@debug ();\n@debug get-function(\"round\");\n@debug 1px * 1px;\n@debug \"a\";",
+        &grass::Options::default().logger(&logger),
+    )
+    .unwrap();
+    let debugs = logger
+        .debugs
+        .into_inner()
+        .into_iter()
+        .map(|(_, message)| message)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        debugs,
+        vec![
+            "(a: 1)",
+            "null",
+            "()",
+            "get-function(\"round\")",
+            "1px*px",
+            "\"a\""
+        ]
+    );
+}
+#[test]
+fn quiet_does_not_call_logger() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "@warn 1; @debug 2;",
+        &grass::Options::default().logger(&logger).quiet(true),
+    )
+    .unwrap();
+    assert!(logger.warnings.into_inner().is_empty());
+    assert!(logger.debugs.into_inner().is_empty());
+}
+#[test]
+fn warning_in_import_includes_stack() {
+    let mut fs = macros::TestFs::new();
+    fs.add_file("a.scss", "@warn \"foo\";");
+    let logger = macros::TestLogger::new();
+
+    grass::from_string(
+        "a {\n  @import \"a\";\n}\n".to_owned(),
+        &grass::Options::default().fs(&fs).logger(&logger),
+    )
+    .unwrap();
+
+    let warnings = logger.warnings.into_inner();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0]
+            .trace()
+            .iter()
+            .map(|frame| (frame.member(), frame.location().file.name()))
+            .collect::<Vec<_>>(),
+        vec![("@import", "a.scss"), ("root stylesheet", "stdin")]
+    );
+}
+
+fn deprecations(input: &str, options: grass::Options) -> Vec<Option<grass::Deprecation>> {
+    let logger = macros::TestLogger::new();
+    grass::from_string(input.to_owned(), &options.logger(&logger)).unwrap();
+    logger
+        .warnings
+        .into_inner()
+        .iter()
+        .map(grass::Warning::deprecation)
+        .collect()
+}
+
+#[test]
+fn slash_div_warning_is_tagged() {
+    assert_eq!(
+        deprecations("a {\n  b: (4px / 2);\n}\n", grass::Options::default()),
+        vec![Some(grass::Deprecation::SlashDiv)]
+    );
+}
+#[test]
+fn slash_div_warning_display_includes_id() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "a {\n  b: (4px / 2);\n}\n",
+        &grass::Options::default().logger(&logger),
+    )
+    .unwrap();
+    assert!(logger.warnings.into_inner()[0]
+        .to_string()
+        .starts_with("Deprecation Warning [slash-div]: Using / for division outside of calc()"));
+}
+#[test]
+fn slash_separated_value_does_not_warn() {
+    assert_eq!(
+        deprecations("a {\n  font: 12px/1.5;\n}\n", grass::Options::default()),
+        vec![]
+    );
+}
+#[test]
+fn silenced_deprecation_does_not_warn() {
+    assert_eq!(
+        deprecations(
+            "a {\n  b: (4px / 2);\n}\n@warn \"c\";\n",
+            grass::Options::default().silence_deprecation(grass::Deprecation::SlashDiv)
+        ),
+        vec![None]
+    );
+}
+#[test]
+fn future_deprecation_does_not_warn_by_default() {
+    assert_eq!(
+        deprecations(
+            "a {\n  b: lighten(red, 10%);\n}\n",
+            grass::Options::default()
+        ),
+        vec![]
+    );
+}
+#[test]
+fn future_deprecation_warns_when_opted_into() {
+    assert_eq!(
+        deprecations(
+            "a {\n  b: lighten(red, 10%);\n  c: fade-out(red, 0.5);\n}\n",
+            grass::Options::default().future_deprecation(grass::Deprecation::ColorFunctions)
+        ),
+        vec![
+            Some(grass::Deprecation::ColorFunctions),
+            Some(grass::Deprecation::ColorFunctions)
+        ]
+    );
+}
+#[test]
+fn silence_takes_precedence_over_future_deprecation() {
+    assert_eq!(
+        deprecations(
+            "a {\n  b: darken(red, 10%);\n}\n",
+            grass::Options::default()
+                .future_deprecation(grass::Deprecation::ColorFunctions)
+                .silence_deprecation(grass::Deprecation::ColorFunctions)
+        ),
+        vec![]
+    );
+}
+#[test]
+fn import_future_deprecation() {
+    let mut fs = macros::TestFs::new();
+    fs.add_file("a.scss", "");
+    assert_eq!(
+        deprecations(
+            "@import \"a\";",
+            grass::Options::default()
+                .fs(&fs)
+                .future_deprecation(grass::Deprecation::Import)
+        ),
+        vec![Some(grass::Deprecation::Import)]
+    );
+}
+#[test]
+fn deprecation_ids_round_trip() {
+    for &deprecation in grass::Deprecation::ALL {
+        assert_eq!(deprecation.id().parse(), Ok(deprecation));
+    }
+    assert_eq!(
+        "not-a-deprecation".parse::<grass::Deprecation>(),
+        Err("Invalid deprecation \"not-a-deprecation\".".to_owned())
+    );
+}
+#[test]
+#[cfg(feature = "json")]
+fn warning_to_json() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "@mixin foo {\n  @warn \"a\\\"b\";\n}\na {\n  @include foo;\n}\n",
+        &grass::Options::default().logger(&logger),
+    )
+    .unwrap();
+    assert_eq!(
+        logger.warnings.into_inner()[0].to_json(),
+        concat!(
+            r#"{"severity":"warning","message":"a\"b","deprecation":null,"lint":null,"#,
+            r#""span":{"file":"stdin","start":{"line":2,"column":3},"end":{"line":2,"column":15}},"#,
+            r#""trace":[{"member":"foo()","span":{"file":"stdin","start":{"line":2,"column":3},"end":{"line":2,"column":15}}},"#,
+            r#"{"member":"root stylesheet","span":{"file":"stdin","start":{"line":5,"column":12},"end":{"line":5,"column":15}}}]}"#
+        )
+    );
+}
+#[test]
+#[cfg(feature = "json")]
+fn deprecation_warning_to_json_includes_id() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "a {\n  b: (4px / 2);\n}\n",
+        &grass::Options::default().logger(&logger),
+    )
+    .unwrap();
+    assert!(logger.warnings.into_inner()[0]
+        .to_json()
+        .contains(r#""deprecation":"slash-div""#));
+}
+#[test]
+#[cfg(feature = "json")]
+fn debug_to_json() {
+    let logger = macros::TestLogger::new();
+    grass::from_string("@debug \"a\";", &grass::Options::default().logger(&logger)).unwrap();
+    let (loc, message) = &logger.debugs.into_inner()[0];
+    assert_eq!(
+        grass::JsonLogger::debug_to_json(loc, message),
+        r#"{"severity":"debug","message":"\"a\"","span":{"file":"stdin","start":{"line":1,"column":8},"end":{"line":1,"column":11}}}"#
+    );
+}
+#[test]
+fn repetitive_deprecations_are_capped() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "@for $i from 1 through 8 {\n  a {\n    b: (4px / $i);\n  }\n}\n",
+        &grass::Options::default().logger(&logger),
+    )
+    .unwrap();
+    assert_eq!(logger.warnings.into_inner().len(), 5);
+    assert_eq!(logger.omitted_deprecations.get(), Some(3));
+}
+#[test]
+fn repetitive_deprecations_are_capped_per_deprecation() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "@for $i from 1 through 6 {\n  a {\n    b: (4px / $i);\n    c: darken(red, $i);\n  }\n}\n",
+        &grass::Options::default()
+            .logger(&logger)
+            .future_deprecation(grass::Deprecation::ColorFunctions),
+    )
+    .unwrap();
+    assert_eq!(logger.warnings.into_inner().len(), 10);
+    assert_eq!(logger.omitted_deprecations.get(), Some(2));
+}
+#[test]
+fn repetitive_warn_rules_are_not_capped() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "@warn \"a\";\n".repeat(8),
+        &grass::Options::default().logger(&logger),
+    )
+    .unwrap();
+    assert_eq!(logger.warnings.into_inner().len(), 8);
+    assert_eq!(logger.omitted_deprecations.get(), None);
+}
+#[test]
+fn verbose_emits_all_deprecations() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "@for $i from 1 through 8 {\n  a {\n    b: (4px / $i);\n  }\n}\n",
+        &grass::Options::default().logger(&logger).verbose(true),
+    )
+    .unwrap();
+    assert_eq!(logger.warnings.into_inner().len(), 8);
+    assert_eq!(logger.omitted_deprecations.get(), None);
+}
+#[test]
+fn few_deprecations_are_not_summarized() {
+    let logger = macros::TestLogger::new();
+    grass::from_string(
+        "@for $i from 1 through 5 {\n  a {\n    b: (4px / $i);\n  }\n}\n",
+        &grass::Options::default().logger(&logger),
+    )
+    .unwrap();
+    assert_eq!(logger.warnings.into_inner().len(), 5);
+    assert_eq!(logger.omitted_deprecations.get(), None);
+}
+#[test]
+fn quiet_deps_silences_deprecations_from_load_paths() {
+    let mut fs = macros::TestFs::new();
+    fs.add_file(
+        "vendor/_lib.scss",
+        "@use \"other\";\na {\n  b: (4px / 2);\n}\n",
+    );
+    fs.add_file("vendor/other.scss", "c {\n  d: (4px / 2);\n}\n");
+    fs.add_file("_local.scss", "e {\n  f: (4px / 2);\n}\n");
+
+    let input = "@use \"lib\";\n@use \"local\";\ng {\n  h: (4px / 2);\n}\n";
+
+    assert_eq!(
+        deprecations(input, grass::Options::default().fs(&fs).load_path("vendor")).len(),
+        4
+    );
+    assert_eq!(
+        deprecations(
+            input,
+            grass::Options::default()
+                .fs(&fs)
+                .load_path("vendor")
+                .quiet_deps(true)
+        ),
+        vec![
+            Some(grass::Deprecation::SlashDiv),
+            Some(grass::Deprecation::SlashDiv)
+        ]
+    );
+}
+#[test]
+fn quiet_deps_does_not_silence_warn_rule() {
+    let mut fs = macros::TestFs::new();
+    fs.add_file("vendor/_lib.scss", "@warn \"a\";");
+    assert_eq!(
+        deprecations(
+            "@use \"lib\";",
+            grass::Options::default()
+                .fs(&fs)
+                .load_path("vendor")
+                .quiet_deps(true)
+        ),
+        vec![None]
+    );
+}
+#[test]
+fn fatal_deprecation_is_error() {
+    assert_err!(
+        "a {\n  b: (4px / 2);\n}\n",
+        "Error: Using / for division outside of calc() is deprecated and will be removed in Dart Sass 2.0.0.",
+        grass::Options::default()
+            .fatal_deprecation(grass::Deprecation::SlashDiv)
+            .silence_deprecation(grass::Deprecation::SlashDiv)
+    );
+}
+#[test]
+fn fatal_deprecation_names_id() {
+    let err = grass::from_string(
+        "a {\n  b: (4px / 2);\n}\n",
+        &grass::Options::default().fatal_deprecation(grass::Deprecation::SlashDiv),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains(
+        "This is only an error because you've set the slash-div deprecation to be fatal."
+    ));
+}
+#[test]
+fn fatal_future_deprecation_requires_opt_in() {
+    assert_eq!(
+        deprecations(
+            "a {\n  b: lighten(red, 10%);\n}\n",
+            grass::Options::default().fatal_deprecation(grass::Deprecation::ColorFunctions)
+        ),
+        vec![]
+    );
+    assert!(grass::from_string(
+        "a {\n  b: lighten(red, 10%);\n}\n",
+        &grass::Options::default()
+            .fatal_deprecation(grass::Deprecation::ColorFunctions)
+            .future_deprecation(grass::Deprecation::ColorFunctions)
+    )
+    .is_err());
+}
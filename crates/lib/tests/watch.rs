@@ -0,0 +1,284 @@
+#![cfg(feature = "watch")]
+
+use std::{
+    cell::{Cell, RefCell},
+    fs,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use grass::Fs;
+use macros::TestFs;
+
+#[macro_use]
+mod macros;
+
+/// A file system whose files can be changed while it is being watched
+#[derive(Debug)]
+struct WatchedFs(RefCell<TestFs>);
+
+impl WatchedFs {
+    fn write(&self, name: &'static str, contents: &'static str) {
+        self.0.borrow_mut().add_file(name, contents);
+    }
+}
+
+impl Fs for WatchedFs {
+    fn is_file(&self, path: &Path) -> bool {
+        self.0.borrow().is_file(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.0.borrow().is_dir(path)
+    }
+
+    fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+        self.0.borrow().read(path)
+    }
+}
+
+fn watch_options() -> grass::WatchOptions {
+    grass::WatchOptions::default()
+        .interval(Duration::from_millis(1))
+        .compare_contents(true)
+}
+
+#[test]
+fn recompiles_only_changed_entrypoints() {
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "@use \"c\";");
+    fs.add_file("b.scss", "b { color: red; }");
+    fs.add_file("_c.scss", "c { color: red; }");
+    let fs = WatchedFs(RefCell::new(fs));
+
+    let mut rounds: Vec<Vec<(PathBuf, String)>> = Vec::new();
+
+    grass::Compiler::new().watch(
+        &["a.scss", "b.scss"],
+        &grass::Options::default().fs(&fs),
+        &watch_options(),
+        |results| {
+            rounds.push(
+                results
+                    .into_iter()
+                    .map(|result| {
+                        (
+                            result.entrypoint().to_path_buf(),
+                            result.into_result().unwrap(),
+                        )
+                    })
+                    .collect(),
+            );
+
+            if rounds.len() == 1 {
+                fs.write("_c.scss", "c { color: blue; }");
+                ControlFlow::Continue(())
+            } else {
+                ControlFlow::Break(())
+            }
+        },
+    );
+
+    assert_eq!(
+        rounds,
+        vec![
+            vec![
+                (
+                    PathBuf::from("a.scss"),
+                    "c {\n  color: red;\n}\n".to_owned()
+                ),
+                (
+                    PathBuf::from("b.scss"),
+                    "b {\n  color: red;\n}\n".to_owned()
+                ),
+            ],
+            vec![(
+                PathBuf::from("a.scss"),
+                "c {\n  color: blue;\n}\n".to_owned()
+            )],
+        ]
+    );
+}
+
+#[test]
+fn passes_errors_to_callback() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "a { color: $b; }");
+    let fs = WatchedFs(RefCell::new(fs));
+
+    let mut errors = Vec::new();
+
+    let css = grass::Compiler::new().watch(
+        &["input.scss"],
+        &grass::Options::default().fs(&fs),
+        &watch_options(),
+        |mut results| {
+            let result = results.pop().unwrap();
+
+            match result.error() {
+                Some(e) => {
+                    errors.push(e.message().into_owned());
+                    fs.write("input.scss", "$b: red;\na { color: $b; }");
+                    ControlFlow::Continue(())
+                }
+                None => ControlFlow::Break(result.css().unwrap().to_owned()),
+            }
+        },
+    );
+
+    assert_eq!(errors, vec!["Undefined variable.".to_owned()]);
+    assert_eq!(css.as_deref(), Some("a {\n  color: red;\n}\n"));
+}
+
+#[test]
+fn stops_when_flag_is_set() {
+    let mut fs = TestFs::new();
+    fs.add_file("input.scss", "a { color: red; }");
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut rounds = 0;
+
+    let result = grass::Compiler::new().watch(
+        &["input.scss"],
+        &grass::Options::default().fs(&fs),
+        &watch_options().stop_flag(Arc::clone(&stop)),
+        |_| {
+            rounds += 1;
+            stop.store(true, Ordering::Relaxed);
+            ControlFlow::<()>::Continue(())
+        },
+    );
+
+    assert_eq!(result, None);
+    assert_eq!(rounds, 1);
+}
+
+#[test]
+fn rebuilds_files_changed_while_compiling() {
+    /// Changes `_c.scss` just after it is first read, as if it were saved while
+    /// the entrypoint was being compiled
+    #[derive(Debug)]
+    struct EditedFs {
+        fs: WatchedFs,
+        edited: Cell<bool>,
+    }
+
+    impl Fs for EditedFs {
+        fn is_file(&self, path: &Path) -> bool {
+            self.fs.is_file(path)
+        }
+
+        fn is_dir(&self, path: &Path) -> bool {
+            self.fs.is_dir(path)
+        }
+
+        fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+            let contents = self.fs.read(path);
+
+            if path.ends_with("_c.scss") && !self.edited.replace(true) {
+                self.fs.write("_c.scss", "c { color: blue; }");
+            }
+
+            contents
+        }
+    }
+
+    let mut fs = TestFs::new();
+    fs.add_file("a.scss", "@use \"c\";");
+    fs.add_file("_c.scss", "c { color: red; }");
+    let fs = EditedFs {
+        fs: WatchedFs(RefCell::new(fs)),
+        edited: Cell::new(false),
+    };
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let timeout = {
+        let stop = Arc::clone(&stop);
+        thread::spawn(move || {
+            thread::sleep(Duration::from_secs(10));
+            stop.store(true, Ordering::Relaxed);
+        })
+    };
+
+    let mut outputs = Vec::new();
+
+    grass::Compiler::new().watch(
+        &["a.scss"],
+        &grass::Options::default().fs(&fs),
+        &watch_options().stop_flag(stop),
+        |mut results| {
+            outputs.push(results.pop().unwrap().into_result().unwrap());
+
+            if outputs.len() == 1 {
+                ControlFlow::Continue(())
+            } else {
+                ControlFlow::Break(())
+            }
+        },
+    );
+
+    drop(timeout);
+
+    assert_eq!(
+        outputs,
+        vec![
+            "c {\n  color: red;\n}\n".to_owned(),
+            "c {\n  color: blue;\n}\n".to_owned(),
+        ]
+    );
+}
+
+#[test]
+fn notified_of_changes_on_disk() {
+    // how long to wait for each compilation before failing
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    let dir = std::env::temp_dir().join(format!("grass-watch-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("input.scss");
+    let partial = dir.join("_partial.scss");
+    fs::write(&input, "@use \"partial\";").unwrap();
+    fs::write(&partial, "a { color: red; }").unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    let stop = Arc::new(AtomicBool::new(false));
+
+    let watcher = {
+        let stop = Arc::clone(&stop);
+
+        thread::spawn(move || {
+            grass::Compiler::new().watch(
+                &[&input],
+                &grass::Options::default(),
+                &grass::WatchOptions::default().stop_flag(stop),
+                |mut results| {
+                    let _ = sender.send(results.pop().unwrap().into_result().unwrap());
+                    ControlFlow::<()>::Continue(())
+                },
+            )
+        })
+    };
+
+    let first = receiver.recv_timeout(TIMEOUT);
+    fs::write(&partial, "a { color: blue; }").unwrap();
+    let second = receiver.recv_timeout(TIMEOUT);
+
+    stop.store(true, Ordering::Relaxed);
+    watcher.join().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        first.expect("the entrypoint was not compiled"),
+        "a {\n  color: red;\n}\n"
+    );
+    assert_eq!(
+        second.expect("the change was not noticed"),
+        "a {\n  color: blue;\n}\n"
+    );
+}
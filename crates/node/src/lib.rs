@@ -0,0 +1,1416 @@
+/*!
+Native Node.js bindings for [grass](https://docs.rs/grass/), built with
+[napi-rs](https://napi.rs/).
+
+This exposes the same `compile(path, options)` and `compileString(source,
+options)` interface as the WebAssembly build, modelled on the modern API of the
+`sass` npm package, but files are read from the real file system rather than
+only through importers. `compileAsync` and `compileStringAsync` compile on the
+libuv thread pool and return promises, so that build tools can compile many
+stylesheets in parallel without blocking the event loop.
+
+Importers, custom functions, and loggers take the same form as they do in the
+WebAssembly build. Importers are tried before the file system. When compiling
+on the thread pool they are called on the main thread, through a threadsafe
+function, while the compilation waits for their result, so they may not block
+on the compilation themselves.
+
+```js
+const grass = require("grass-native");
+
+const { css, loadedUrls } = grass.compile("input.scss", { loadPaths: ["node_modules"] });
+const results = await Promise.all(entrypoints.map((path) => grass.compileAsync(path)));
+```
+*/
+
+#![warn(clippy::all, clippy::dbg_macro)]
+#![allow(
+    // napi requires exported functions to take their arguments by value
+    clippy::needless_pass_by_value,
+    clippy::module_name_repetitions,
+    renamed_and_removed_lints,
+    clippy::unknown_clippy_lints,
+    unknown_lints
+)]
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    io,
+    path::{Path, PathBuf},
+    ptr,
+    rc::Rc,
+    sync::{mpsc, Arc},
+};
+
+use grass_compiler::{
+    __internal::{bind_arguments, current_builtin, parse_signature, Parameter},
+    codemap::{Span, SpanLoc, Spanned},
+    sass_value::{
+        Brackets, Color, ListSeparator, Number, QuoteKind, SassMap, SassNumber, Unit, Value,
+    },
+    Builtin, Deprecation, Error, Fs, InputSyntax, Logger, NullLogger, Options, OutputStyle, StdFs,
+    StdLogger, Visitor, Warning,
+};
+use napi::{
+    bindgen_prelude::AsyncTask,
+    sys,
+    threadsafe_function::{ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode},
+    Env, JsError, JsFunction, JsObject, JsUnknown, Ref, Status, Task, ValueType,
+};
+use napi_derive::napi;
+
+/// The options accepted by every compile function
+///
+/// These are the options of the same names in the `sass` npm package, except
+/// for `quiet`, which silences warnings. Unknown deprecation ids are ignored.
+#[napi(object, object_to_js = false)]
+#[derive(Default)]
+pub struct CompileOptions {
+    /// `"expanded"` (the default) or `"compressed"`
+    pub style: Option<String>,
+    /// The syntax of the source passed to `compileString`: `"scss"` (the
+    /// default), `"indented"`, or `"css"`
+    pub syntax: Option<String>,
+    /// The path of the source passed to `compileString`, which relative loads
+    /// are resolved against
+    pub url: Option<String>,
+    pub load_paths: Option<Vec<String>>,
+    pub charset: Option<bool>,
+    pub quiet: Option<bool>,
+    pub quiet_deps: Option<bool>,
+    pub verbose: Option<bool>,
+    pub alert_unicode: Option<bool>,
+    pub silence_deprecations: Option<Vec<String>>,
+    pub future_deprecations: Option<Vec<String>>,
+    pub fatal_deprecations: Option<Vec<String>>,
+    /// Objects with `canonicalize(url, context)` and `load(canonicalUrl)`
+    /// methods, which are tried in order before the file system
+    pub importers: Option<Vec<JsObject>>,
+    /// Functions callable from Sass, keyed by signatures such as
+    /// `"foo($a, $b: null)"`, which are passed an array of their arguments
+    pub functions: Option<JsObject>,
+    /// An object with `warn(message, options)` and `debug(message, options)`
+    /// methods, through which messages are reported rather than to stderr
+    pub logger: Option<JsObject>,
+}
+
+#[napi(object)]
+#[derive(Debug)]
+pub struct CompileResult {
+    pub css: String,
+    /// The paths of every file loaded, including the entrypoint
+    pub loaded_urls: Vec<String>,
+}
+
+/// Compile the file at `path`
+#[napi]
+pub fn compile(
+    env: Env,
+    path: String,
+    options: Option<CompileOptions>,
+) -> napi::Result<CompileResult> {
+    let (compilation, callbacks) = Compilation::file(env, path, options)?;
+
+    compilation
+        .run(callbacks.map(|callbacks| Caller::Direct(env, callbacks)))
+        .map_err(|failure| failure.into_exception(env))
+}
+
+/// Compile `source`, which is SCSS unless `options.syntax` is passed
+#[napi(js_name = "compileString")]
+pub fn compile_string(
+    env: Env,
+    source: String,
+    options: Option<CompileOptions>,
+) -> napi::Result<CompileResult> {
+    let (compilation, callbacks) = Compilation::string(env, source, options)?;
+
+    compilation
+        .run(callbacks.map(|callbacks| Caller::Direct(env, callbacks)))
+        .map_err(|failure| failure.into_exception(env))
+}
+
+/// Compile the file at `path` on the thread pool
+#[napi(js_name = "compileAsync")]
+pub fn compile_async(
+    env: Env,
+    path: String,
+    options: Option<CompileOptions>,
+) -> napi::Result<AsyncTask<CompileTask>> {
+    let (compilation, callbacks) = Compilation::file(env, path, options)?;
+
+    CompileTask::new(env, compilation, callbacks).map(AsyncTask::new)
+}
+
+/// Compile `source` on the thread pool
+#[napi(js_name = "compileStringAsync")]
+pub fn compile_string_async(
+    env: Env,
+    source: String,
+    options: Option<CompileOptions>,
+) -> napi::Result<AsyncTask<CompileTask>> {
+    let (compilation, callbacks) = Compilation::string(env, source, options)?;
+
+    CompileTask::new(env, compilation, callbacks).map(AsyncTask::new)
+}
+
+pub struct CompileTask {
+    compilation: Option<Compilation>,
+    /// Calls the importers, functions, and logger passed in the options on the
+    /// main thread, if any were passed
+    callbacks: Option<ThreadsafeFunction<Request>>,
+}
+
+impl CompileTask {
+    fn new(env: Env, compilation: Compilation, callbacks: Option<Callbacks>) -> napi::Result<Self> {
+        let callbacks = match callbacks {
+            Some(callbacks) => {
+                let mut callbacks = Some(callbacks);
+
+                // everything is done before the function would be called with
+                // the arguments returned here, so it may as well do nothing
+                let noop = env.create_function("noop", noop)?;
+
+                Some(env.create_threadsafe_function(
+                    &noop,
+                    0,
+                    move |ctx: ThreadSafeCallContext<Request>| {
+                        match ctx.value {
+                            Request::Call(call, reply) => {
+                                if let Some(callbacks) = &callbacks {
+                                    // the compilation may have been abandoned
+                                    let _ = reply.send(callbacks.call(ctx.env, call));
+                                }
+                            }
+                            Request::Release => {
+                                if let Some(callbacks) = callbacks.take() {
+                                    callbacks.release(ctx.env);
+                                }
+                            }
+                        }
+
+                        Ok(Vec::<JsUnknown>::new())
+                    },
+                )?)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            compilation: Some(compilation),
+            callbacks,
+        })
+    }
+}
+
+impl Task for CompileTask {
+    type Output = Result<CompileResult, Failure>;
+    type JsValue = CompileResult;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let compilation = self
+            .compilation
+            .take()
+            .expect("tasks are only computed once");
+
+        Ok(compilation.run(self.callbacks.take().map(Caller::Threadsafe)))
+    }
+
+    fn resolve(&mut self, env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        output.map_err(|failure| failure.into_exception(env))
+    }
+}
+
+unsafe extern "C" fn noop(_env: sys::napi_env, _info: sys::napi_callback_info) -> sys::napi_value {
+    ptr::null_mut()
+}
+
+enum Input {
+    File(PathBuf),
+    String { source: String, url: Option<String> },
+}
+
+/// Everything needed to compile a stylesheet, which can be sent to the thread
+/// pool
+struct Compilation {
+    input: Input,
+    settings: Settings,
+    /// The signatures of the functions passed in the options, in the order
+    /// [`Callbacks`] holds them
+    functions: Vec<(String, Vec<Parameter>)>,
+    importers: usize,
+    has_logger: bool,
+}
+
+/// The options other than importers, functions, and the logger
+#[derive(Debug)]
+struct Settings {
+    style: OutputStyle,
+    syntax: InputSyntax,
+    load_paths: Vec<PathBuf>,
+    charset: bool,
+    quiet: bool,
+    quiet_deps: bool,
+    verbose: bool,
+    alert_unicode: bool,
+    silence_deprecations: Vec<Deprecation>,
+    future_deprecations: Vec<Deprecation>,
+    fatal_deprecations: Vec<Deprecation>,
+}
+
+impl Settings {
+    fn new(options: &CompileOptions) -> Self {
+        let flag = |flag: Option<bool>, default: bool| flag.unwrap_or(default);
+
+        Self {
+            style: match options.style.as_deref() {
+                Some("compressed") => OutputStyle::Compressed,
+                _ => OutputStyle::Expanded,
+            },
+            syntax: match options.syntax.as_deref() {
+                Some("indented") => InputSyntax::Sass,
+                Some("css") => InputSyntax::Css,
+                _ => InputSyntax::Scss,
+            },
+            load_paths: options
+                .load_paths
+                .iter()
+                .flatten()
+                .map(PathBuf::from)
+                .collect(),
+            charset: flag(options.charset, true),
+            quiet: flag(options.quiet, false),
+            quiet_deps: flag(options.quiet_deps, false),
+            verbose: flag(options.verbose, false),
+            alert_unicode: flag(options.alert_unicode, true),
+            silence_deprecations: deprecations(&options.silence_deprecations),
+            future_deprecations: deprecations(&options.future_deprecations),
+            fatal_deprecations: deprecations(&options.fatal_deprecations),
+        }
+    }
+}
+
+impl Compilation {
+    fn file(
+        env: Env,
+        path: String,
+        options: Option<CompileOptions>,
+    ) -> napi::Result<(Self, Option<Callbacks>)> {
+        Self::new(env, |_| Input::File(PathBuf::from(path)), options)
+    }
+
+    fn string(
+        env: Env,
+        source: String,
+        options: Option<CompileOptions>,
+    ) -> napi::Result<(Self, Option<Callbacks>)> {
+        Self::new(
+            env,
+            |options| Input::String {
+                source,
+                url: options.url.clone(),
+            },
+            options,
+        )
+    }
+
+    fn new(
+        env: Env,
+        input: impl FnOnce(&CompileOptions) -> Input,
+        options: Option<CompileOptions>,
+    ) -> napi::Result<(Self, Option<Callbacks>)> {
+        let options = options.unwrap_or_default();
+
+        let mut compilation = Self {
+            input: input(&options),
+            settings: Settings::new(&options),
+            functions: Vec::new(),
+            importers: 0,
+            has_logger: false,
+        };
+
+        let importers = options.importers.unwrap_or_default();
+        if importers.is_empty() && options.functions.is_none() && options.logger.is_none() {
+            return Ok((compilation, None));
+        }
+
+        let mut object = env.create_object()?;
+
+        let mut array = env.create_empty_array()?;
+        for (idx, importer) in importers.into_iter().enumerate() {
+            array.set_element(idx as u32, importer)?;
+            compilation.importers += 1;
+        }
+        object.set_named_property("importers", array)?;
+
+        let mut array = env.create_empty_array()?;
+        if let Some(functions) = &options.functions {
+            let signatures = functions.get_property_names()?;
+            for idx in 0..signatures.get_array_length()? {
+                let signature = string(signatures.get_element(idx)?)?;
+
+                let function: JsUnknown = functions.get_named_property(&signature)?;
+                if function.get_type()? != ValueType::Function {
+                    continue;
+                }
+
+                array.set_element(compilation.functions.len() as u32, function)?;
+                compilation.functions.push(parse_signature(&signature));
+            }
+        }
+        object.set_named_property("functions", array)?;
+
+        if let Some(logger) = options.logger {
+            object.set_named_property("logger", logger)?;
+            compilation.has_logger = true;
+        }
+
+        Ok((compilation, Some(Callbacks(env.create_reference(object)?))))
+    }
+
+    fn run(self, caller: Option<Caller>) -> Result<CompileResult, Failure> {
+        let host = Rc::new(Host {
+            caller,
+            importers: self.importers,
+            functions: self.functions,
+            has_logger: self.has_logger,
+            entrypoint: RefCell::new(None),
+            loaded: RefCell::new(BTreeSet::new()),
+            canonical_urls: RefCell::new(HashMap::new()),
+        });
+
+        let settings = &self.settings;
+
+        let mut options = Options::default()
+            .fs(&*host)
+            .logger(&*host)
+            .load_paths(&settings.load_paths)
+            .style(settings.style)
+            .allows_charset(settings.charset)
+            .quiet_deps(settings.quiet_deps)
+            .verbose(settings.verbose)
+            .unicode_error_messages(settings.alert_unicode);
+
+        if settings.quiet {
+            options = options.logger(&NullLogger);
+        }
+
+        for &deprecation in &settings.silence_deprecations {
+            options = options.silence_deprecation(deprecation);
+        }
+
+        for &deprecation in &settings.future_deprecations {
+            options = options.future_deprecation(deprecation);
+        }
+
+        for &deprecation in &settings.fatal_deprecations {
+            options = options.fatal_deprecation(deprecation);
+        }
+
+        for (name, _) in &host.functions {
+            options = options.add_custom_fn(name, Builtin::new(call_js_function));
+        }
+
+        let mut loaded_urls = Vec::new();
+
+        HOSTS.with(|hosts| hosts.borrow_mut().push(Rc::clone(&host)));
+
+        let result = match self.input {
+            Input::File(path) => grass_compiler::from_path(path, &options),
+            Input::String { source, url } => {
+                options = options.input_syntax(settings.syntax);
+
+                match url {
+                    // served by `host`, so that relative loads are resolved
+                    // against `url`
+                    Some(url) => {
+                        loaded_urls.push(url.clone());
+                        *host.entrypoint.borrow_mut() = Some((PathBuf::from(&url), source));
+                        grass_compiler::from_path(url, &options)
+                    }
+                    None => grass_compiler::from_string(source, &options),
+                }
+            }
+        };
+
+        HOSTS.with(|hosts| hosts.borrow_mut().pop());
+        drop(options);
+
+        let host = Rc::try_unwrap(host).expect("the host is only shared during compilation");
+        if let Some(caller) = host.caller {
+            caller.release();
+        }
+
+        let css = result.map_err(|e| Failure::new(&e))?;
+
+        loaded_urls.extend(
+            host.loaded
+                .into_inner()
+                .into_iter()
+                .map(|path| path.to_string_lossy().into_owned()),
+        );
+        loaded_urls.dedup();
+
+        Ok(CompileResult { css, loaded_urls })
+    }
+}
+
+/// The deprecations with each of `ids`, ignoring those which are not known
+fn deprecations(ids: &Option<Vec<String>>) -> Vec<Deprecation> {
+    ids.iter()
+        .flatten()
+        .filter_map(|id| id.parse().ok())
+        .collect()
+}
+
+/// A compilation error, in a form which can be sent back from the thread pool
+#[derive(Debug)]
+pub struct Failure {
+    /// The error as grass prints it
+    formatted: String,
+    /// The error serialized by [`Error::to_json`]
+    json: String,
+}
+
+impl Failure {
+    fn new(e: &Error) -> Self {
+        Self {
+            formatted: e.to_string(),
+            json: e.to_json(),
+        }
+    }
+
+    /// An `Error` describing this failure, with the `sassMessage`, `span`, and
+    /// `sassStack` properties of a Sass exception
+    fn into_exception(self, env: Env) -> napi::Error {
+        match self.exception(env) {
+            Ok(exception) => napi::Error::from(exception),
+            Err(err) => err,
+        }
+    }
+
+    fn exception(&self, env: Env) -> napi::Result<JsUnknown> {
+        let mut exception = env.create_error(napi::Error::from_reason(self.formatted.clone()))?;
+        let json = parse_json(env, &self.json)?;
+
+        for (from, to) in [
+            ("message", "sassMessage"),
+            ("span", "span"),
+            ("trace", "sassStack"),
+        ] {
+            exception.set_named_property(to, json.get_named_property::<JsUnknown>(from)?)?;
+        }
+
+        Ok(exception.into_unknown())
+    }
+}
+
+fn parse_json(env: Env, text: &str) -> napi::Result<JsObject> {
+    env.get_global()?
+        .get_named_property::<JsObject>("JSON")?
+        .get_named_property::<JsFunction>("parse")?
+        .call(None, &[env.create_string(text)?])?
+        .coerce_to_object()
+}
+
+/// The importers, functions, and logger passed in the options, as a reference
+/// to an object of the form `{ importers, functions, logger }`
+///
+/// This may only be used on the main thread, and must be released once
+/// compilation has finished.
+struct Callbacks(Ref<()>);
+
+/// A call to one of the [`Callbacks`]
+#[derive(Debug)]
+enum Call {
+    /// `importers[importer].canonicalize(url)`, replied to with the canonical
+    /// URL
+    Canonicalize { importer: usize, url: String },
+    /// `importers[importer].load(url)`, replied to with the contents
+    Load { importer: usize, url: String },
+    Function {
+        function: usize,
+        args: Vec<Option<JsValue>>,
+    },
+    Warn {
+        message: String,
+        /// The warning serialized by [`Warning::to_json`]
+        json: String,
+        deprecation: Option<&'static str>,
+    },
+    Debug {
+        message: String,
+        file: String,
+        /// The 1-indexed line and column of the start and end of the span
+        start: (usize, usize),
+        end: (usize, usize),
+    },
+}
+
+#[derive(Debug)]
+enum Reply {
+    Text(Option<String>),
+    Value(JsValue),
+    /// Whether the logger had a method for the message
+    Logged(bool),
+}
+
+enum Request {
+    Call(Call, mpsc::Sender<Result<Reply, String>>),
+    Release,
+}
+
+impl Callbacks {
+    /// Make `call`, returning the message of anything it throws
+    fn call(&self, env: Env, call: Call) -> Result<Reply, String> {
+        self.try_call(env, call)
+            .unwrap_or_else(|err| Err(thrown_message(env, err)))
+    }
+
+    fn try_call(&self, env: Env, call: Call) -> napi::Result<Result<Reply, String>> {
+        let callbacks: JsObject = env.get_reference_value(&self.0)?;
+
+        let element = |name: &str, idx: usize| -> napi::Result<JsObject> {
+            callbacks
+                .get_named_property::<JsObject>(name)?
+                .get_element::<JsObject>(idx as u32)
+        };
+
+        Ok(match call {
+            Call::Canonicalize { importer, url } => {
+                let mut context = env.create_object()?;
+                context.set_named_property("fromImport", false)?;
+
+                let args = [
+                    env.create_string(&url)?.into_unknown(),
+                    context.into_unknown(),
+                ];
+                Ok(Reply::Text(
+                    match call_method(&element("importers", importer)?, "canonicalize", &args)? {
+                        Some(canonical) if !is_nullish(&canonical)? => Some(string(canonical)?),
+                        _ => None,
+                    },
+                ))
+            }
+            Call::Load { importer, url } => {
+                let args = [env.create_string(&url)?.into_unknown()];
+                Ok(Reply::Text(
+                    match call_method(&element("importers", importer)?, "load", &args)? {
+                        Some(result) if result.get_type()? == ValueType::Object => {
+                            let contents: JsUnknown =
+                                result.coerce_to_object()?.get_named_property("contents")?;
+
+                            if contents.get_type()? == ValueType::String {
+                                Some(string(contents)?)
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    },
+                ))
+            }
+            Call::Function { function, args } => {
+                let mut array = env.create_empty_array()?;
+                for (idx, arg) in args.iter().enumerate() {
+                    let arg = match arg {
+                        Some(arg) => arg.to_js(env)?,
+                        None => env.get_null()?.into_unknown(),
+                    };
+                    array.set_element(idx as u32, arg)?;
+                }
+
+                let function = callbacks
+                    .get_named_property::<JsObject>("functions")?
+                    .get_element::<JsFunction>(function as u32)?;
+
+                JsValue::from_js(function.call(None, &[array])?)?.map(Reply::Value)
+            }
+            Call::Warn {
+                message,
+                json,
+                deprecation,
+            } => {
+                let json = parse_json(env, &json)?;
+
+                let mut options = env.create_object()?;
+                options.set_named_property("deprecation", deprecation.is_some())?;
+                if let Some(id) = deprecation {
+                    let mut deprecation_type = env.create_object()?;
+                    deprecation_type.set_named_property("id", id)?;
+                    options.set_named_property("deprecationType", deprecation_type)?;
+                }
+                options
+                    .set_named_property("span", json.get_named_property::<JsUnknown>("span")?)?;
+                options
+                    .set_named_property("stack", json.get_named_property::<JsUnknown>("trace")?)?;
+
+                Ok(Reply::Logged(
+                    self.log(env, &callbacks, "warn", &message, options)?,
+                ))
+            }
+            Call::Debug {
+                message,
+                file,
+                start,
+                end,
+            } => {
+                let location = |(line, column): (usize, usize)| -> napi::Result<JsObject> {
+                    let mut location = env.create_object()?;
+                    location.set_named_property("line", line as u32)?;
+                    location.set_named_property("column", column as u32)?;
+                    Ok(location)
+                };
+
+                let mut span = env.create_object()?;
+                span.set_named_property("file", file)?;
+                span.set_named_property("start", location(start)?)?;
+                span.set_named_property("end", location(end)?)?;
+
+                let mut options = env.create_object()?;
+                options.set_named_property("span", span)?;
+
+                Ok(Reply::Logged(
+                    self.log(env, &callbacks, "debug", &message, options)?,
+                ))
+            }
+        })
+    }
+
+    /// Call `logger[method](message, options)`, returning whether there was
+    /// such a method
+    fn log(
+        &self,
+        env: Env,
+        callbacks: &JsObject,
+        method: &str,
+        message: &str,
+        options: JsObject,
+    ) -> napi::Result<bool> {
+        let logger: JsUnknown = callbacks.get_named_property("logger")?;
+        if logger.get_type()? != ValueType::Object {
+            return Ok(false);
+        }
+
+        let args = [
+            env.create_string(message)?.into_unknown(),
+            options.into_unknown(),
+        ];
+        Ok(call_method(&logger.coerce_to_object()?, method, &args)?.is_some())
+    }
+
+    fn release(mut self, env: Env) {
+        // nothing can be done if this fails
+        let _ = self.0.unref(env);
+    }
+}
+
+/// Call `object[method](args...)`, returning `None` if there is no such method
+fn call_method(
+    object: &JsObject,
+    method: &str,
+    args: &[JsUnknown],
+) -> napi::Result<Option<JsUnknown>> {
+    let function: JsUnknown = object.get_named_property(method)?;
+    if function.get_type()? != ValueType::Function {
+        return Ok(None);
+    }
+
+    JsFunction::try_from(function)?
+        .call(Some(object), args)
+        .map(Some)
+}
+
+fn is_nullish(value: &JsUnknown) -> napi::Result<bool> {
+    Ok(matches!(
+        value.get_type()?,
+        ValueType::Null | ValueType::Undefined
+    ))
+}
+
+/// `String(value)`
+fn string(value: JsUnknown) -> napi::Result<String> {
+    value.coerce_to_string()?.into_utf8()?.into_owned()
+}
+
+/// The message of a value thrown by JavaScript
+fn thrown_message(env: Env, err: napi::Error) -> String {
+    let reason = err.reason.clone();
+    let thrown = JsError::from(err).into_unknown(env);
+
+    let message = || -> napi::Result<String> {
+        if thrown.get_type()? == ValueType::Object {
+            let message: JsUnknown = JsObject::try_from(thrown)?.get_named_property("message")?;
+            if message.get_type()? != ValueType::Undefined {
+                return string(message);
+            }
+
+            return Ok(reason.clone());
+        }
+
+        string(thrown)
+    };
+
+    message().unwrap_or(reason)
+}
+
+/// How the [`Callbacks`] of a compilation are called
+enum Caller {
+    /// Compiling on the main thread, so they are called directly
+    Direct(Env, Callbacks),
+    /// Compiling on the thread pool, so they are called on the main thread
+    /// while compilation waits for their result
+    Threadsafe(ThreadsafeFunction<Request>),
+}
+
+impl Caller {
+    fn call(&self, call: Call) -> Result<Reply, String> {
+        match self {
+            Self::Direct(env, callbacks) => callbacks.call(*env, call),
+            Self::Threadsafe(function) => {
+                let (sender, receiver) = mpsc::channel();
+
+                match function.call(
+                    Ok(Request::Call(call, sender)),
+                    ThreadsafeFunctionCallMode::Blocking,
+                ) {
+                    Status::Ok => receiver.recv().unwrap_or_else(|_| {
+                        Err("The JavaScript callback was not called.".to_owned())
+                    }),
+                    status => Err(format!(
+                        "The JavaScript callback could not be called: {}.",
+                        status
+                    )),
+                }
+            }
+        }
+    }
+
+    fn release(self) {
+        match self {
+            Self::Direct(env, callbacks) => callbacks.release(env),
+            Self::Threadsafe(function) => {
+                function.call(Ok(Request::Release), ThreadsafeFunctionCallMode::Blocking);
+            }
+        }
+    }
+}
+
+thread_local! {
+    /// The host of each compilation in progress on this thread, innermost
+    /// last, through which custom functions are called
+    static HOSTS: RefCell<Vec<Rc<Host>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The file system and logger of a compilation, which call its importers and
+/// logger
+///
+/// Files which no importer canonicalizes are read from disk, and the canonical
+/// path of each file read is recorded. The entrypoint of `compileString`, if it
+/// has a `url`, is served from memory.
+struct Host {
+    caller: Option<Caller>,
+    importers: usize,
+    functions: Vec<(String, Vec<Parameter>)>,
+    has_logger: bool,
+    entrypoint: RefCell<Option<(PathBuf, String)>>,
+    loaded: RefCell<BTreeSet<PathBuf>>,
+    /// The index of the importer which canonicalized each path, and the
+    /// canonical URL it returned, or `None` if no importer recognized it
+    canonical_urls: RefCell<HashMap<PathBuf, Option<(usize, String)>>>,
+}
+
+impl std::fmt::Debug for Host {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Host")
+            .field("importers", &self.importers)
+            .field("functions", &self.functions)
+            .field("has_logger", &self.has_logger)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Host {
+    fn call(&self, call: Call) -> Result<Reply, String> {
+        match &self.caller {
+            Some(caller) => caller.call(call),
+            None => Err("No JavaScript callbacks were passed.".to_owned()),
+        }
+    }
+
+    fn is_entrypoint(&self, path: &Path) -> bool {
+        matches!(&*self.entrypoint.borrow(), Some((url, _)) if url == path)
+    }
+
+    fn resolve(&self, path: &Path) -> io::Result<Option<(usize, String)>> {
+        if self.importers == 0 {
+            return Ok(None);
+        }
+
+        let canonical_urls = self.canonical_urls.borrow();
+        if let Some(resolved) = canonical_urls.get(path) {
+            return Ok(resolved.clone());
+        }
+
+        // grass reads files by their canonical URL, which importers need not
+        // canonicalize again
+        let url = path.to_string_lossy();
+        if let Some(resolved) = canonical_urls
+            .values()
+            .flatten()
+            .find(|(_, canonical)| *canonical == url)
+        {
+            return Ok(Some(resolved.clone()));
+        }
+        drop(canonical_urls);
+
+        let mut resolved = None;
+        for importer in 0..self.importers {
+            let call = Call::Canonicalize {
+                importer,
+                url: url.clone().into_owned(),
+            };
+
+            match self.call(call) {
+                Ok(Reply::Text(Some(canonical))) => {
+                    resolved = Some((importer, canonical));
+                    break;
+                }
+                Ok(..) => {}
+                Err(message) => return Err(io::Error::other(message)),
+            }
+        }
+
+        self.canonical_urls
+            .borrow_mut()
+            .insert(path.to_path_buf(), resolved.clone());
+
+        Ok(resolved)
+    }
+}
+
+impl Fs for Host {
+    fn is_dir(&self, path: &Path) -> bool {
+        StdFs.is_dir(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.is_entrypoint(path)
+            || matches!(self.resolve(path), Ok(Some(..)))
+            || StdFs.is_file(path)
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        if let Some((url, source)) = &*self.entrypoint.borrow() {
+            if url == path {
+                return Ok(source.clone().into_bytes());
+            }
+        }
+
+        if let Some((importer, canonical)) = self.resolve(path)? {
+            let contents = match self.call(Call::Load {
+                importer,
+                url: canonical.clone(),
+            }) {
+                Ok(Reply::Text(Some(contents))) => contents,
+                Ok(..) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("No importer could load \"{}\".", canonical),
+                    ))
+                }
+                Err(message) => return Err(io::Error::other(message)),
+            };
+
+            self.loaded.borrow_mut().insert(PathBuf::from(canonical));
+
+            return Ok(contents.into_bytes());
+        }
+
+        let contents = StdFs.read(path)?;
+
+        self.loaded.borrow_mut().insert(
+            self.canonicalize(path)
+                .unwrap_or_else(|_| path.to_path_buf()),
+        );
+
+        Ok(contents)
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        if self.is_entrypoint(path) {
+            return Ok(path.to_path_buf());
+        }
+
+        match self.resolve(path)? {
+            Some((_, canonical)) => Ok(PathBuf::from(canonical)),
+            None => StdFs.canonicalize(path),
+        }
+    }
+}
+
+/// Passes messages to `options.logger`, or to stderr if it has no method for
+/// them
+impl Logger for Host {
+    fn debug(&self, location: SpanLoc, message: &str) {
+        if self.has_logger {
+            let call = Call::Debug {
+                message: message.to_owned(),
+                file: location.file.name().to_owned(),
+                start: (location.begin.line + 1, location.begin.column + 1),
+                end: (location.end.line + 1, location.end.column + 1),
+            };
+
+            if let Ok(Reply::Logged(true)) = self.call(call) {
+                return;
+            }
+        }
+
+        StdLogger.debug(location, message);
+    }
+
+    fn warn(&self, warning: &Warning) {
+        if self.has_logger {
+            let call = Call::Warn {
+                message: warning.message().to_owned(),
+                json: warning.to_json(),
+                deprecation: warning.deprecation().map(Deprecation::id),
+            };
+
+            if let Ok(Reply::Logged(true)) = self.call(call) {
+                return;
+            }
+        }
+
+        StdLogger.warn(warning);
+    }
+
+    fn omitted_deprecations(&self, count: usize) {
+        StdLogger.omitted_deprecations(count);
+    }
+}
+
+/// The implementation of every function passed in `options.functions`, which
+/// calls the function the builtin was called by
+fn call_js_function(
+    mut args: grass_compiler::sass_value::ArgumentResult,
+    visitor: &mut Visitor,
+) -> grass_compiler::Result<Value> {
+    let span = args.span();
+    let host = HOSTS.with(|hosts| hosts.borrow().last().cloned());
+
+    let found = host.as_ref().and_then(|host| {
+        let name = current_builtin(visitor)?;
+        host.functions.iter().rposition(|(n, _)| n == name)
+    });
+
+    let (host, function) = match (host, found) {
+        (Some(host), Some(function)) => (host, function),
+        _ => return Err(("Undefined function.", span).into()),
+    };
+
+    let args = bind_arguments(&host.functions[function].1, &mut args)?
+        .iter()
+        .map(|arg| arg.as_ref().map(|arg| JsValue::new(arg, span)).transpose())
+        .collect::<grass_compiler::Result<Vec<_>>>()?;
+
+    match host.call(Call::Function { function, args }) {
+        Ok(Reply::Value(value)) => Ok(value.into_value(span)),
+        Ok(..) => Err(("Undefined function.", span).into()),
+        Err(message) => Err((message, span).into()),
+    }
+}
+
+/// A Sass value, in a form which can be sent between threads, and which is
+/// represented in JavaScript as it is in the WebAssembly build
+#[derive(Debug)]
+enum JsValue {
+    Null,
+    Bool(bool),
+    Number {
+        value: f64,
+        unit: String,
+    },
+    String {
+        text: String,
+        quoted: bool,
+    },
+    Color {
+        red: f64,
+        green: f64,
+        blue: f64,
+        alpha: f64,
+    },
+    List {
+        contents: Vec<JsValue>,
+        separator: Option<&'static str>,
+        brackets: bool,
+    },
+    Map(Vec<(JsValue, JsValue)>),
+}
+
+impl JsValue {
+    fn new(value: &Value, span: Span) -> grass_compiler::Result<Self> {
+        let list = |elems: &[Value], separator: ListSeparator, brackets: Brackets| {
+            grass_compiler::Result::Ok(Self::List {
+                contents: elems
+                    .iter()
+                    .map(|elem| Self::new(elem, span))
+                    .collect::<grass_compiler::Result<_>>()?,
+                separator: match separator {
+                    ListSeparator::Comma => Some(","),
+                    ListSeparator::Space => Some(" "),
+                    ListSeparator::Slash => Some("/"),
+                    ListSeparator::Undecided => None,
+                },
+                brackets: brackets == Brackets::Bracketed,
+            })
+        };
+
+        Ok(match value {
+            Value::True => Self::Bool(true),
+            Value::False => Self::Bool(false),
+            Value::Null => Self::Null,
+            Value::Dimension(number) => Self::Number {
+                value: number.num.0,
+                unit: number.unit.to_string(),
+            },
+            Value::String(text, quotes) => Self::String {
+                text: text.clone(),
+                quoted: *quotes == QuoteKind::Quoted,
+            },
+            Value::Color(color) => Self::Color {
+                red: color.red().0,
+                green: color.green().0,
+                blue: color.blue().0,
+                alpha: color.alpha().0,
+            },
+            Value::List(elems, separator, brackets) => list(elems, *separator, *brackets)?,
+            Value::ArgList(args) => list(&args.elems, args.separator, Brackets::None)?,
+            Value::Map(map) => Self::Map(
+                map.iter()
+                    .map(|(key, value)| Ok((Self::new(&key.node, span)?, Self::new(value, span)?)))
+                    .collect::<grass_compiler::Result<_>>()?,
+            ),
+            // calculations are passed as their CSS representation
+            Value::Calculation(..) => Self::String {
+                text: value.inspect(span)?,
+                quoted: false,
+            },
+            Value::FunctionRef(..) => {
+                return Err((
+                    format!(
+                        "{} can't be passed to a JavaScript function.",
+                        value.inspect(span)?
+                    ),
+                    span,
+                )
+                    .into())
+            }
+        })
+    }
+
+    fn into_value(self, span: Span) -> Value {
+        match self {
+            Self::Null => Value::Null,
+            Self::Bool(value) => Value::bool(value),
+            Self::Number { value, unit } => Value::Dimension(SassNumber {
+                num: Number(value),
+                unit: if unit.is_empty() {
+                    Unit::None
+                } else {
+                    Unit::from(unit)
+                },
+                as_slash: None,
+            }),
+            Self::String { text, quoted } => Value::String(
+                text,
+                if quoted {
+                    QuoteKind::Quoted
+                } else {
+                    QuoteKind::None
+                },
+            ),
+            Self::Color {
+                red,
+                green,
+                blue,
+                alpha,
+            } => Value::Color(Arc::new(Color::from_rgba(
+                Number(red),
+                Number(green),
+                Number(blue),
+                Number(alpha),
+            ))),
+            Self::List {
+                contents,
+                separator,
+                brackets,
+            } => Value::List(
+                contents
+                    .into_iter()
+                    .map(|elem| elem.into_value(span))
+                    .collect(),
+                match separator {
+                    Some(",") => ListSeparator::Comma,
+                    Some(" ") => ListSeparator::Space,
+                    Some("/") => ListSeparator::Slash,
+                    _ => ListSeparator::Undecided,
+                },
+                if brackets {
+                    Brackets::Bracketed
+                } else {
+                    Brackets::None
+                },
+            ),
+            Self::Map(pairs) => {
+                let mut map = SassMap::new();
+                for (key, value) in pairs {
+                    map.insert(
+                        Spanned {
+                            node: key.into_value(span),
+                            span,
+                        },
+                        value.into_value(span),
+                    );
+                }
+
+                Value::Map(map)
+            }
+        }
+    }
+
+    fn to_js(&self, env: Env) -> napi::Result<JsUnknown> {
+        let mut object = env.create_object()?;
+
+        match self {
+            Self::Null => return Ok(env.get_null()?.into_unknown()),
+            Self::Bool(value) => return Ok(env.get_boolean(*value)?.into_unknown()),
+            Self::Number { value, unit } => {
+                object.set_named_property("type", "number")?;
+                object.set_named_property("value", *value)?;
+                object.set_named_property("unit", unit.as_str())?;
+            }
+            Self::String { text, quoted } => {
+                object.set_named_property("type", "string")?;
+                object.set_named_property("text", text.as_str())?;
+                object.set_named_property("quoted", *quoted)?;
+            }
+            Self::Color {
+                red,
+                green,
+                blue,
+                alpha,
+            } => {
+                object.set_named_property("type", "color")?;
+                object.set_named_property("red", *red)?;
+                object.set_named_property("green", *green)?;
+                object.set_named_property("blue", *blue)?;
+                object.set_named_property("alpha", *alpha)?;
+            }
+            Self::List {
+                contents,
+                separator,
+                brackets,
+            } => {
+                let mut array = env.create_empty_array()?;
+                for (idx, elem) in contents.iter().enumerate() {
+                    array.set_element(idx as u32, elem.to_js(env)?)?;
+                }
+
+                object.set_named_property("type", "list")?;
+                object.set_named_property("contents", array)?;
+                object.set_named_property("separator", *separator)?;
+                object.set_named_property("brackets", *brackets)?;
+            }
+            Self::Map(pairs) => {
+                let mut array = env.create_empty_array()?;
+                for (idx, (key, value)) in pairs.iter().enumerate() {
+                    let mut pair = env.create_empty_array()?;
+                    pair.set_element(0, key.to_js(env)?)?;
+                    pair.set_element(1, value.to_js(env)?)?;
+                    array.set_element(idx as u32, pair)?;
+                }
+
+                object.set_named_property("type", "map")?;
+                object.set_named_property("contents", array)?;
+            }
+        }
+
+        Ok(object.into_unknown())
+    }
+
+    /// The Sass value represented by `value`, or a message describing why it
+    /// doesn't represent one
+    fn from_js(value: JsUnknown) -> napi::Result<Result<Self, String>> {
+        let object = match value.get_type()? {
+            ValueType::Null | ValueType::Undefined => return Ok(Ok(Self::Null)),
+            ValueType::Boolean => return Ok(Ok(Self::Bool(value.coerce_to_bool()?.get_value()?))),
+            ValueType::Number => {
+                return Ok(Ok(Self::Number {
+                    value: value.coerce_to_number()?.get_double()?,
+                    unit: String::new(),
+                }))
+            }
+            ValueType::String => {
+                return Ok(Ok(Self::String {
+                    text: string(value)?,
+                    quoted: true,
+                }))
+            }
+            ValueType::Object => value.coerce_to_object()?,
+            _ => {
+                return Ok(Err(format!(
+                    "Expected JavaScript function to return a Sass value, but got {}.",
+                    string(value)?
+                )))
+            }
+        };
+
+        let property = |name: &str| -> napi::Result<Option<JsUnknown>> {
+            let value: JsUnknown = object.get_named_property(name)?;
+            Ok(if is_nullish(&value)? {
+                None
+            } else {
+                Some(value)
+            })
+        };
+
+        let number = |name: &str| -> napi::Result<Result<f64, String>> {
+            Ok(match property(name)? {
+                Some(value) if value.get_type()? == ValueType::Number => {
+                    Ok(value.coerce_to_number()?.get_double()?)
+                }
+                _ => Err(format!(
+                    "Expected JavaScript value to have a numeric `{}`.",
+                    name
+                )),
+            })
+        };
+
+        let flag = |name: &str, default: bool| -> napi::Result<bool> {
+            Ok(match property(name)? {
+                Some(value) if value.get_type()? == ValueType::Boolean => {
+                    value.coerce_to_bool()?.get_value()?
+                }
+                _ => default,
+            })
+        };
+
+        let contents = || -> napi::Result<Vec<JsUnknown>> {
+            match property("contents")? {
+                Some(contents) => array(&contents.coerce_to_object()?),
+                None => Ok(Vec::new()),
+            }
+        };
+
+        let kind = match property("type")? {
+            Some(kind) => Some(string(kind)?),
+            None => None,
+        };
+
+        Ok(Ok(match kind.as_deref() {
+            Some("number") => Self::Number {
+                value: match number("value")? {
+                    Ok(value) => value,
+                    Err(message) => return Ok(Err(message)),
+                },
+                unit: match property("unit")? {
+                    Some(unit) => string(unit)?,
+                    None => String::new(),
+                },
+            },
+            Some("string") => Self::String {
+                text: match property("text")? {
+                    Some(text) => string(text)?,
+                    None => String::new(),
+                },
+                quoted: flag("quoted", true)?,
+            },
+            Some("color") => {
+                let mut channels = [0.0; 3];
+                for (channel, name) in channels.iter_mut().zip(["red", "green", "blue"]) {
+                    *channel = match number(name)? {
+                        Ok(value) => value,
+                        Err(message) => return Ok(Err(message)),
+                    };
+                }
+
+                let [red, green, blue] = channels;
+                Self::Color {
+                    red,
+                    green,
+                    blue,
+                    alpha: number("alpha")?.unwrap_or(1.0),
+                }
+            }
+            Some("list") => {
+                let mut elems = Vec::new();
+                for elem in contents()? {
+                    match Self::from_js(elem)? {
+                        Ok(elem) => elems.push(elem),
+                        Err(message) => return Ok(Err(message)),
+                    }
+                }
+
+                Self::List {
+                    contents: elems,
+                    separator: match property("separator")? {
+                        Some(separator) => match string(separator)?.as_str() {
+                            "," => Some(","),
+                            " " => Some(" "),
+                            "/" => Some("/"),
+                            _ => None,
+                        },
+                        None => None,
+                    },
+                    brackets: flag("brackets", false)?,
+                }
+            }
+            Some("map") => {
+                let mut pairs = Vec::new();
+                for pair in contents()? {
+                    let mut pair = match pair.get_type()? {
+                        ValueType::Object => array(&pair.coerce_to_object()?)?,
+                        _ => Vec::new(),
+                    };
+
+                    match (pair.pop(), pair.pop(), pair.is_empty()) {
+                        (Some(value), Some(key), true) => {
+                            match (Self::from_js(key)?, Self::from_js(value)?) {
+                                (Ok(key), Ok(value)) => pairs.push((key, value)),
+                                (Err(message), _) | (_, Err(message)) => return Ok(Err(message)),
+                            }
+                        }
+                        _ => {
+                            return Ok(Err(
+                                "Expected the contents of a JavaScript map to be key-value pairs."
+                                    .to_owned(),
+                            ))
+                        }
+                    }
+                }
+
+                Self::Map(pairs)
+            }
+            _ => {
+                return Ok(Err(format!(
+                    "Expected JavaScript function to return a Sass value, but got {}.",
+                    string(object.into_unknown())?
+                )))
+            }
+        }))
+    }
+}
+
+/// The elements of the array-like `object`
+fn array(object: &JsObject) -> napi::Result<Vec<JsUnknown>> {
+    let len: JsUnknown = object.get_named_property("length")?;
+    let len = match len.get_type()? {
+        ValueType::Number => len.coerce_to_number()?.get_uint32()?,
+        _ => 0,
+    };
+
+    (0..len).map(|idx| object.get_element(idx)).collect()
+}
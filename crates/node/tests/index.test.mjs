@@ -0,0 +1,170 @@
+import assert from "node:assert/strict";
+import { mkdtempSync, mkdirSync, writeFileSync } from "node:fs";
+import { createRequire } from "node:module";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
+import { test } from "node:test";
+
+const grass = createRequire(import.meta.url)("../index.js");
+
+function tempDir() {
+  return mkdtempSync(join(tmpdir(), "grass-"));
+}
+
+test("compileString", () => {
+  assert.equal(grass.compileString("a { b: 1 + 2 }").css, "a {\n  b: 3;\n}\n");
+});
+
+test("compileString with options", () => {
+  const { css } = grass.compileString("a\n  b: c", {
+    style: "compressed",
+    syntax: "indented",
+  });
+  assert.equal(css, "a{b:c}");
+});
+
+test("compileString resolves loads relative to url", () => {
+  const dir = tempDir();
+  writeFileSync(join(dir, "_colors.scss"), "$primary: blue;");
+
+  const { css, loadedUrls } = grass.compileString(
+    "@use 'colors';\na { b: colors.$primary }",
+    { url: join(dir, "input.scss") },
+  );
+  assert.equal(css, "a {\n  b: blue;\n}\n");
+  assert.equal(loadedUrls.length, 2);
+});
+
+test("compile with load paths", () => {
+  const dir = tempDir();
+  mkdirSync(join(dir, "lib"));
+  writeFileSync(join(dir, "lib", "_colors.scss"), "$primary: blue;");
+  writeFileSync(join(dir, "input.scss"), "@use 'colors';\na { b: colors.$primary }");
+
+  const { css } = grass.compile(join(dir, "input.scss"), {
+    loadPaths: [join(dir, "lib")],
+  });
+  assert.equal(css, "a {\n  b: blue;\n}\n");
+});
+
+test("compileAsync", async () => {
+  const dir = tempDir();
+  writeFileSync(join(dir, "a.scss"), "a { b: c }");
+  writeFileSync(join(dir, "b.scss"), "d { e: f }");
+
+  const results = await Promise.all(
+    ["a.scss", "b.scss"].map((name) => grass.compileAsync(join(dir, name))),
+  );
+  assert.deepEqual(
+    results.map((result) => result.css),
+    ["a {\n  b: c;\n}\n", "d {\n  e: f;\n}\n"],
+  );
+});
+
+test("errors have the properties of a sass exception", () => {
+  assert.throws(
+    () => grass.compileString("a { b: 1s + 1px }"),
+    (e) => {
+      assert.equal(e.sassMessage, "Incompatible units px and s.");
+      assert.equal(e.span.start.line, 1);
+      return true;
+    },
+  );
+});
+
+test("compileStringAsync rejects with a sass exception", async () => {
+  await assert.rejects(grass.compileStringAsync("a {"), (e) => {
+    assert.equal(typeof e.sassMessage, "string");
+    return true;
+  });
+});
+
+const importer = {
+  canonicalize(url) {
+    return url.endsWith("virtual/_colors.scss") ? "virtual:colors" : null;
+  },
+  load(canonicalUrl) {
+    assert.equal(canonicalUrl, "virtual:colors");
+    return { contents: "$primary: red;" };
+  },
+};
+
+test("compileString with importers", () => {
+  const { css, loadedUrls } = grass.compileString(
+    "@use 'virtual/colors';\na { b: colors.$primary }",
+    { importers: [importer] },
+  );
+  assert.equal(css, "a {\n  b: red;\n}\n");
+  assert.deepEqual(loadedUrls, ["virtual:colors"]);
+});
+
+test("compileString with functions", () => {
+  const { css } = grass.compileString("a { b: double(2px); c: join-all(1, 2, 3) }", {
+    functions: {
+      "double($n)": ([n]) => ({ ...n, value: n.value * 2 }),
+      "join-all($args...)": ([list]) =>
+        list.contents.map((n) => n.value).join("-"),
+    },
+  });
+  assert.equal(css, 'a {\n  b: 4px;\n  c: "1-2-3";\n}\n');
+});
+
+test("errors thrown by functions are sass exceptions", () => {
+  assert.throws(
+    () =>
+      grass.compileString("a { b: fail() }", {
+        functions: {
+          "fail()": () => {
+            throw new Error("boom");
+          },
+        },
+      }),
+    (e) => {
+      assert.equal(e.sassMessage, "boom");
+      return true;
+    },
+  );
+});
+
+test("compileString with a logger", () => {
+  const warnings = [];
+  const debugs = [];
+  grass.compileString("@warn 'a';\n@debug 1 + 1;", {
+    logger: {
+      warn: (message, { deprecation, span }) => warnings.push([message, deprecation, span.start.line]),
+      debug: (message, { span }) => debugs.push([message, span.start.line]),
+    },
+  });
+  assert.deepEqual(warnings, [["a", false, 1]]);
+  assert.deepEqual(debugs, [["2", 2]]);
+});
+
+test("compileStringAsync calls importers, functions, and loggers", async () => {
+  const warnings = [];
+  const { css } = await grass.compileStringAsync(
+    "@use 'virtual/colors';\n@warn 'w';\na { b: colors.$primary; c: double(3) }",
+    {
+      importers: [importer],
+      functions: { "double($n)": ([n]) => n.value * 2 },
+      logger: { warn: (message) => warnings.push(message) },
+    },
+  );
+  assert.equal(css, "a {\n  b: red;\n  c: 6;\n}\n");
+  assert.deepEqual(warnings, ["w"]);
+});
+
+test("compileStringAsync rejects with errors thrown by functions", async () => {
+  await assert.rejects(
+    grass.compileStringAsync("a { b: fail() }", {
+      functions: {
+        "fail()": () => {
+          throw new Error("boom");
+        },
+      },
+    }),
+    (e) => {
+      assert.equal(e.sassMessage, "boom");
+      return true;
+    },
+  );
+});
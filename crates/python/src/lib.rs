@@ -0,0 +1,347 @@
+/*!
+Python bindings for [grass](https://docs.rs/grass/), built with
+[maturin](https://www.maturin.rs/).
+
+```python
+import grass
+
+css = grass.compile_string("a { b: 1 + 2 }", style="compressed")
+
+try:
+    grass.compile("input.scss", load_paths=["node_modules"])
+except grass.CompileError as e:
+    print(f"{e.file}:{e.line}:{e.column}: {e.message}")
+```
+*/
+
+#![warn(clippy::all, clippy::dbg_macro)]
+#![allow(
+    // the keyword arguments of `compile` and `compile_string`
+    clippy::too_many_arguments,
+    clippy::module_name_repetitions,
+    renamed_and_removed_lints,
+    clippy::unknown_clippy_lints,
+    unknown_lints
+)]
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+use grass_compiler::{
+    Deprecation, Error, Fs, InputSyntax, NullLogger, Options, OutputStyle, StdFs,
+};
+use pyo3::{create_exception, exceptions::PyValueError, prelude::*};
+
+create_exception!(
+    grass,
+    CompileError,
+    PyValueError,
+    "Raised when a stylesheet fails to compile.\n\n\
+     `str(e)` is the error formatted as grass prints it. `e.message` is the message \
+     without its location, and `e.file`, `e.line`, and `e.column` are the 1-indexed \
+     location of the error, or `None` if it has no location."
+);
+
+/// Compile the file at `path`, returning the CSS
+///
+/// Raises `CompileError` if the stylesheet fails to compile.
+#[pyfunction]
+#[pyo3(signature = (
+    path,
+    *,
+    style = "expanded",
+    load_paths = Vec::new(),
+    input_syntax = None,
+    quiet = false,
+    charset = true,
+    importers = Vec::new(),
+    define = HashMap::new(),
+    silence_deprecations = Vec::new(),
+))]
+fn compile(
+    py: Python<'_>,
+    path: PathBuf,
+    style: &str,
+    load_paths: Vec<PathBuf>,
+    input_syntax: Option<&str>,
+    quiet: bool,
+    charset: bool,
+    importers: Vec<PyObject>,
+    define: HashMap<String, String>,
+    silence_deprecations: Vec<String>,
+) -> PyResult<String> {
+    let settings = Settings {
+        style,
+        load_paths,
+        input_syntax,
+        quiet,
+        charset,
+        importers,
+        define,
+        silence_deprecations,
+    };
+
+    settings.compile(py, |options| grass_compiler::from_path(&path, options))
+}
+
+/// Compile `source`, returning the CSS
+///
+/// `source` is parsed as SCSS unless `input_syntax` is given. Raises
+/// `CompileError` if the stylesheet fails to compile.
+#[pyfunction]
+#[pyo3(signature = (
+    source,
+    *,
+    style = "expanded",
+    load_paths = Vec::new(),
+    input_syntax = None,
+    quiet = false,
+    charset = true,
+    importers = Vec::new(),
+    define = HashMap::new(),
+    silence_deprecations = Vec::new(),
+))]
+fn compile_string(
+    py: Python<'_>,
+    source: String,
+    style: &str,
+    load_paths: Vec<PathBuf>,
+    input_syntax: Option<&str>,
+    quiet: bool,
+    charset: bool,
+    importers: Vec<PyObject>,
+    define: HashMap<String, String>,
+    silence_deprecations: Vec<String>,
+) -> PyResult<String> {
+    let settings = Settings {
+        style,
+        load_paths,
+        input_syntax,
+        quiet,
+        charset,
+        importers,
+        define,
+        silence_deprecations,
+    };
+
+    settings.compile(py, |options| grass_compiler::from_string(source, options))
+}
+
+#[pymodule]
+fn grass(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(compile, m)?)?;
+    m.add_function(wrap_pyfunction!(compile_string, m)?)?;
+    m.add("CompileError", py.get_type::<CompileError>())?;
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
+
+/// The keyword arguments shared by `compile` and `compile_string`
+struct Settings<'a> {
+    style: &'a str,
+    load_paths: Vec<PathBuf>,
+    input_syntax: Option<&'a str>,
+    quiet: bool,
+    charset: bool,
+    importers: Vec<PyObject>,
+    define: HashMap<String, String>,
+    silence_deprecations: Vec<String>,
+}
+
+impl Settings<'_> {
+    fn compile(
+        &self,
+        py: Python<'_>,
+        f: impl FnOnce(&Options) -> Result<String, Box<Error>>,
+    ) -> PyResult<String> {
+        let style = match self.style {
+            "expanded" => OutputStyle::Expanded,
+            "compressed" => OutputStyle::Compressed,
+            style => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown style {:?}, expected \"expanded\" or \"compressed\".",
+                    style
+                )))
+            }
+        };
+
+        let fs = PyFs::new(py, &self.importers);
+
+        let mut options = Options::default()
+            .fs(&fs)
+            .style(style)
+            .load_paths(&self.load_paths)
+            .allows_charset(self.charset);
+
+        if self.quiet {
+            options = options.logger(&NullLogger);
+        }
+
+        if let Some(syntax) = self.input_syntax {
+            options = options.input_syntax(match syntax {
+                "scss" => InputSyntax::Scss,
+                "sass" => InputSyntax::Sass,
+                "css" => InputSyntax::Css,
+                syntax => {
+                    return Err(PyValueError::new_err(format!(
+                        "Unknown syntax {:?}, expected \"scss\", \"sass\", or \"css\".",
+                        syntax
+                    )))
+                }
+            });
+        }
+
+        for id in &self.silence_deprecations {
+            let deprecation: Deprecation = id.parse().map_err(PyValueError::new_err)?;
+            options = options.silence_deprecation(deprecation);
+        }
+
+        for (name, value) in &self.define {
+            options = options.define(name.as_str(), value.as_str());
+        }
+
+        let result = f(&options);
+
+        // an exception raised by an importer takes precedence over the error it
+        // caused
+        if let Some(err) = fs.error.borrow_mut().take() {
+            return Err(err);
+        }
+
+        result.map_err(|e| compile_error(py, &e))
+    }
+}
+
+fn compile_error(py: Python<'_>, e: &Error) -> PyErr {
+    let err = CompileError::new_err(e.to_string());
+    let value = err.value(py);
+
+    let (file, line, column) = match e.span() {
+        Some(loc) => (
+            Some(loc.file.name().to_owned()),
+            Some(loc.begin.line + 1),
+            Some(loc.begin.column + 1),
+        ),
+        None => (None, None, None),
+    };
+
+    let attributes = [
+        ("message", e.message().into_owned().into_py(py)),
+        ("file", file.into_py(py)),
+        ("line", line.into_py(py)),
+        ("column", column.into_py(py)),
+    ];
+
+    for (name, attribute) in attributes {
+        if let Err(err) = value.setattr(name, attribute) {
+            return err;
+        }
+    }
+
+    err
+}
+
+/// Loads files through the importers passed to `compile`, falling back to the
+/// file system for files no importer loads
+///
+/// Importers are called, in order, with each path grass tries to load, such as
+/// `"colors.scss"` and `"_colors.scss"` for `@use "colors"`. An importer
+/// returns `None` if it does not recognize the path, the contents of the file
+/// as a string, or a tuple of the name of the file and its contents. Relative
+/// loads within that file are then resolved against its name.
+struct PyFs<'a> {
+    py: Python<'a>,
+    importers: &'a [PyObject],
+    /// The name and contents of each path, or `None` if no importer loaded it
+    loaded: RefCell<HashMap<PathBuf, Option<(PathBuf, String)>>>,
+    /// The first exception raised by an importer
+    error: RefCell<Option<PyErr>>,
+}
+
+impl fmt::Debug for PyFs<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PyFs")
+            .field("importers", &self.importers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> PyFs<'a> {
+    fn new(py: Python<'a>, importers: &'a [PyObject]) -> Self {
+        Self {
+            py,
+            importers,
+            loaded: RefCell::new(HashMap::new()),
+            error: RefCell::new(None),
+        }
+    }
+
+    fn load(&self, path: &Path) -> Option<(PathBuf, String)> {
+        if let Some(loaded) = self.loaded.borrow().get(path) {
+            return loaded.clone();
+        }
+
+        let loaded = self.call_importers(path).unwrap_or_else(|err| {
+            self.error.borrow_mut().get_or_insert(err);
+            None
+        });
+
+        let mut cache = self.loaded.borrow_mut();
+        if let Some((name, _)) = &loaded {
+            // grass reads files by their canonical name
+            cache.insert(name.clone(), loaded.clone());
+        }
+        cache.insert(path.to_path_buf(), loaded.clone());
+
+        loaded
+    }
+
+    fn call_importers(&self, path: &Path) -> PyResult<Option<(PathBuf, String)>> {
+        let url = path.to_string_lossy().into_owned();
+
+        for importer in self.importers {
+            let result = importer.call1(self.py, (url.as_str(),))?;
+
+            if result.is_none(self.py) {
+                continue;
+            }
+
+            if let Ok(contents) = result.extract::<String>(self.py) {
+                return Ok(Some((path.to_path_buf(), contents)));
+            }
+
+            let (name, contents) = result.extract::<(PathBuf, String)>(self.py)?;
+            return Ok(Some((name, contents)));
+        }
+
+        Ok(None)
+    }
+}
+
+impl Fs for PyFs<'_> {
+    fn is_dir(&self, path: &Path) -> bool {
+        StdFs.is_dir(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.load(path).is_some() || StdFs.is_file(path)
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        match self.load(path) {
+            Some((_, contents)) => Ok(contents.into_bytes()),
+            None => StdFs.read(path),
+        }
+    }
+
+    fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        match self.load(path) {
+            Some((name, _)) => Ok(name),
+            None => StdFs.canonicalize(path),
+        }
+    }
+}
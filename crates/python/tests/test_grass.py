@@ -0,0 +1,73 @@
+import pytest
+
+import grass
+
+
+def test_compile_string():
+    assert grass.compile_string("a { b: 1 + 2 }") == "a {\n  b: 3;\n}\n"
+
+
+def test_compile_string_with_options():
+    css = grass.compile_string(
+        "a\n  b: $color",
+        style="compressed",
+        input_syntax="sass",
+        define={"color": "red"},
+    )
+    assert css == "a{b:red}"
+
+
+def test_compile_with_load_paths(tmp_path):
+    (tmp_path / "lib").mkdir()
+    (tmp_path / "lib" / "_colors.scss").write_text("$primary: blue;")
+    (tmp_path / "input.scss").write_text("@use 'colors';\na { b: colors.$primary }")
+
+    css = grass.compile(tmp_path / "input.scss", load_paths=[tmp_path / "lib"])
+    assert css == "a {\n  b: blue;\n}\n"
+
+
+def test_importer():
+    def importer(path):
+        if path == "_colors.scss":
+            return "$primary: blue;"
+        return None
+
+    css = grass.compile_string(
+        "@use 'colors';\na { b: colors.$primary }", importers=[importer]
+    )
+    assert css == "a {\n  b: blue;\n}\n"
+
+
+def test_importer_exception_is_raised():
+    def importer(path):
+        raise KeyError(path)
+
+    with pytest.raises(KeyError):
+        grass.compile_string("@use 'colors';", importers=[importer])
+
+
+def test_compile_error_has_location():
+    with pytest.raises(grass.CompileError) as info:
+        grass.compile_string("a {\n  b: 1px + 1s;\n}")
+
+    assert info.value.message == "Incompatible units s and px."
+    assert info.value.file == "stdin"
+    assert info.value.line == 2
+    assert info.value.column == 6
+    assert str(info.value).startswith("Error: Incompatible units s and px.\n")
+
+
+def test_missing_file_has_no_location():
+    with pytest.raises(grass.CompileError) as info:
+        grass.compile("does-not-exist.scss")
+
+    assert info.value.file is None
+    assert info.value.line is None
+
+
+def test_invalid_options():
+    with pytest.raises(ValueError):
+        grass.compile_string("", style="nested")
+
+    with pytest.raises(ValueError):
+        grass.compile_string("", silence_deprecations=["not-a-deprecation"])
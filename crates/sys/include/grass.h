@@ -0,0 +1,303 @@
+/*
+ * C bindings for grass, a Sass compiler.
+ *
+ * A panic inside grass is returned as an error only if the library is built
+ * with `panic = "unwind"`, as by `cargo build --profile ffi -p grass-sys`.
+ * Otherwise it aborts the process.
+ */
+
+#ifndef GRASS_H
+#define GRASS_H
+
+/*
+ * Generated from src/lib.rs by cbindgen. Do not edit; regenerate with
+ *
+ *     cbindgen --config cbindgen.toml --output include/grass.h
+ */
+
+#include <stdbool.h>
+#include <stdint.h>
+
+/**
+ * Returned by functions which succeeded
+ */
+#define GRASS_OK 0
+
+/**
+ * Returned by functions passed a null pointer, a string which is not valid
+ * UTF-8, or an unknown constant or deprecation
+ */
+#define GRASS_INVALID_ARGUMENT -1
+
+/**
+ * Expanded output, with each declaration on its own line. This is the default
+ */
+#define GRASS_STYLE_EXPANDED 0
+
+/**
+ * Compressed output, with as little whitespace as possible
+ */
+#define GRASS_STYLE_COMPRESSED 1
+
+/**
+ * The SCSS syntax
+ */
+#define GRASS_SYNTAX_SCSS 0
+
+/**
+ * The indented syntax
+ */
+#define GRASS_SYNTAX_SASS 1
+
+/**
+ * Plain CSS
+ */
+#define GRASS_SYNTAX_CSS 2
+
+/**
+ * The options to compile with, created with [`grass_options_new`] and freed
+ * with [`grass_options_free`]
+ */
+typedef struct GrassOptions GrassOptions;
+
+/**
+ * The outcome of compiling a stylesheet, freed with [`grass_result_free`]
+ */
+typedef struct GrassResult GrassResult;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * The version of grass, such as `"0.13.1"`
+ */
+const char *grass_version(void);
+
+/**
+ * Create options with the default settings
+ */
+GrassOptions *grass_options_new(void);
+
+/**
+ * Free options created with [`grass_options_new`]
+ *
+ * # Safety
+ *
+ * `options` must be null or have been returned by [`grass_options_new`], and
+ * must not be used afterwards.
+ */
+void grass_options_free(GrassOptions *options);
+
+/**
+ * Set the output style to [`GRASS_STYLE_EXPANDED`] or
+ * [`GRASS_STYLE_COMPRESSED`]
+ *
+ * # Safety
+ *
+ * `options` must be null or a valid pointer returned by [`grass_options_new`].
+ */
+int grass_options_set_style(GrassOptions *options, int style);
+
+/**
+ * Set the syntax of the entrypoint to [`GRASS_SYNTAX_SCSS`],
+ * [`GRASS_SYNTAX_SASS`], or [`GRASS_SYNTAX_CSS`]
+ *
+ * By default, the syntax of a file is determined by its extension, and strings
+ * are parsed as SCSS.
+ *
+ * # Safety
+ *
+ * `options` must be null or a valid pointer returned by [`grass_options_new`].
+ */
+int grass_options_set_input_syntax(GrassOptions *options, int syntax);
+
+/**
+ * Add a path to use when resolving imports
+ *
+ * # Safety
+ *
+ * `options` must be null or a valid pointer returned by [`grass_options_new`],
+ * and `path` must be null or a NUL-terminated string.
+ */
+int grass_options_add_load_path(GrassOptions *options, const char *path);
+
+/**
+ * Don't print warnings to stderr
+ *
+ * # Safety
+ *
+ * `options` must be null or a valid pointer returned by [`grass_options_new`].
+ */
+int grass_options_set_quiet(GrassOptions *options, bool quiet);
+
+/**
+ * Whether to emit a `@charset` or byte-order mark for non-ASCII output. This
+ * is enabled by default
+ *
+ * # Safety
+ *
+ * `options` must be null or a valid pointer returned by [`grass_options_new`].
+ */
+int grass_options_set_charset(GrassOptions *options, bool allows_charset);
+
+/**
+ * Whether to use non-ASCII characters in formatted errors. This is enabled by
+ * default
+ *
+ * # Safety
+ *
+ * `options` must be null or a valid pointer returned by [`grass_options_new`].
+ */
+int grass_options_set_unicode_error_messages(GrassOptions *options, bool unicode);
+
+/**
+ * Don't emit warnings for the deprecation with the id `deprecation`, such as
+ * `"slash-div"`
+ *
+ * # Safety
+ *
+ * `options` must be null or a valid pointer returned by [`grass_options_new`],
+ * and `deprecation` must be null or a NUL-terminated string.
+ */
+int grass_options_silence_deprecation(GrassOptions *options, const char *deprecation);
+
+/**
+ * Define a global variable in the entrypoint named `name`, without the
+ * leading `$`, whose value is the SassScript expression `value`
+ *
+ * # Safety
+ *
+ * `options` must be null or a valid pointer returned by [`grass_options_new`],
+ * and `name` and `value` must be null or NUL-terminated strings.
+ */
+int grass_options_define(GrassOptions *options, const char *name, const char *value);
+
+/**
+ * Compile the file at `path`
+ *
+ * `options` may be null to use the default options. The result must be freed
+ * with [`grass_result_free`].
+ *
+ * # Safety
+ *
+ * `path` must be null or a NUL-terminated string, and `options` must be null
+ * or a valid pointer returned by [`grass_options_new`].
+ */
+GrassResult *grass_compile_file(const char *path, const GrassOptions *options);
+
+/**
+ * Compile `source`
+ *
+ * `options` may be null to use the default options. The result must be freed
+ * with [`grass_result_free`].
+ *
+ * # Safety
+ *
+ * `source` must be null or a NUL-terminated string, and `options` must be null
+ * or a valid pointer returned by [`grass_options_new`].
+ */
+GrassResult *grass_compile_string(const char *source, const GrassOptions *options);
+
+/**
+ * Whether the stylesheet compiled successfully
+ *
+ * # Safety
+ *
+ * `result` must be null or a valid pointer returned by [`grass_compile_file`]
+ * or [`grass_compile_string`].
+ */
+bool grass_result_is_ok(const GrassResult *result);
+
+/**
+ * The compiled CSS, or null if compilation failed
+ *
+ * # Safety
+ *
+ * `result` must be null or a valid pointer returned by [`grass_compile_file`]
+ * or [`grass_compile_string`].
+ */
+const char *grass_result_css(const GrassResult *result);
+
+/**
+ * The message of the error, without any location information, or null if
+ * compilation succeeded
+ *
+ * # Safety
+ *
+ * `result` must be null or a valid pointer returned by [`grass_compile_file`]
+ * or [`grass_compile_string`].
+ */
+const char *grass_result_error_message(const GrassResult *result);
+
+/**
+ * The error formatted as grass prints it, with a snippet of the source and
+ * its location, or null if compilation succeeded
+ *
+ * # Safety
+ *
+ * `result` must be null or a valid pointer returned by [`grass_compile_file`]
+ * or [`grass_compile_string`].
+ */
+const char *grass_result_error_formatted(const GrassResult *result);
+
+/**
+ * The error as a JSON object, in the format of `grass::Error::to_json`, or
+ * null if compilation succeeded
+ *
+ * # Safety
+ *
+ * `result` must be null or a valid pointer returned by [`grass_compile_file`]
+ * or [`grass_compile_string`].
+ */
+const char *grass_result_error_json(const GrassResult *result);
+
+/**
+ * The file the error occurred in, or null if compilation succeeded or the
+ * error has no location, such as when the entrypoint could not be read
+ *
+ * # Safety
+ *
+ * `result` must be null or a valid pointer returned by [`grass_compile_file`]
+ * or [`grass_compile_string`].
+ */
+const char *grass_result_error_file(const GrassResult *result);
+
+/**
+ * The 1-indexed line the error occurred on, or 0 if compilation succeeded or
+ * the error has no location
+ *
+ * # Safety
+ *
+ * `result` must be null or a valid pointer returned by [`grass_compile_file`]
+ * or [`grass_compile_string`].
+ */
+int grass_result_error_line(const GrassResult *result);
+
+/**
+ * The 1-indexed column the error occurred at, or 0 if compilation succeeded
+ * or the error has no location
+ *
+ * # Safety
+ *
+ * `result` must be null or a valid pointer returned by [`grass_compile_file`]
+ * or [`grass_compile_string`].
+ */
+int grass_result_error_column(const GrassResult *result);
+
+/**
+ * Free a result returned by [`grass_compile_file`] or
+ * [`grass_compile_string`], along with every string returned from it
+ *
+ * # Safety
+ *
+ * `result` must be null or have been returned by [`grass_compile_file`] or
+ * [`grass_compile_string`], and must not be used afterwards.
+ */
+void grass_result_free(GrassResult *result);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* GRASS_H */
@@ -0,0 +1,592 @@
+/*!
+C bindings for [grass](https://docs.rs/grass/), for embedding the compiler in
+C, C++, Go, Swift, and other languages with a C FFI.
+
+The declarations are in `include/grass.h`. Stylesheets are compiled with
+`grass_compile_file` or `grass_compile_string`, which take an optional
+`GrassOptions` and always return a `GrassResult`, holding either the CSS or an
+error:
+
+```c
+GrassOptions *options = grass_options_new();
+grass_options_set_style(options, GRASS_STYLE_COMPRESSED);
+grass_options_add_load_path(options, "node_modules");
+
+GrassResult *result = grass_compile_file("input.scss", options);
+if (grass_result_is_ok(result)) {
+    puts(grass_result_css(result));
+} else {
+    fputs(grass_result_error_formatted(result), stderr);
+}
+
+grass_result_free(result);
+grass_options_free(options);
+```
+
+Strings passed to and returned from these functions are NUL-terminated UTF-8.
+Strings returned from a `GrassResult` are owned by it, and are valid until it
+is freed.
+
+A panic inside grass is returned as an error only if the library is built with
+`panic = "unwind"`, as it is by the `ffi` profile (`cargo build --profile ffi -p
+grass-sys`). The `release` profile of this workspace aborts on panic, so a
+library built with it aborts the process it is loaded into instead.
+*/
+
+#![warn(clippy::all, clippy::dbg_macro)]
+#![deny(missing_debug_implementations)]
+#![allow(
+    clippy::missing_panics_doc,
+    clippy::module_name_repetitions,
+    renamed_and_removed_lints,
+    clippy::unknown_clippy_lints,
+    unknown_lints
+)]
+
+use std::{
+    ffi::{CStr, CString},
+    io,
+    os::raw::{c_char, c_int},
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    ptr,
+};
+
+use grass_compiler::{Deprecation, Error, InputSyntax, NullLogger, Options, OutputStyle};
+
+/// Returned by functions which succeeded
+pub const GRASS_OK: c_int = 0;
+
+/// Returned by functions passed a null pointer, a string which is not valid
+/// UTF-8, or an unknown constant or deprecation
+pub const GRASS_INVALID_ARGUMENT: c_int = -1;
+
+/// Expanded output, with each declaration on its own line. This is the default
+pub const GRASS_STYLE_EXPANDED: c_int = 0;
+
+/// Compressed output, with as little whitespace as possible
+pub const GRASS_STYLE_COMPRESSED: c_int = 1;
+
+/// The SCSS syntax
+pub const GRASS_SYNTAX_SCSS: c_int = 0;
+
+/// The indented syntax
+pub const GRASS_SYNTAX_SASS: c_int = 1;
+
+/// Plain CSS
+pub const GRASS_SYNTAX_CSS: c_int = 2;
+
+/// The options to compile with, created with [`grass_options_new`] and freed
+/// with [`grass_options_free`]
+#[derive(Debug)]
+pub struct GrassOptions {
+    style: OutputStyle,
+    input_syntax: Option<InputSyntax>,
+    load_paths: Vec<PathBuf>,
+    quiet: bool,
+    allows_charset: bool,
+    unicode_error_messages: bool,
+    silenced_deprecations: Vec<Deprecation>,
+    defined_variables: Vec<(String, String)>,
+}
+
+impl Default for GrassOptions {
+    fn default() -> Self {
+        Self {
+            style: OutputStyle::Expanded,
+            input_syntax: None,
+            load_paths: Vec::new(),
+            quiet: false,
+            allows_charset: true,
+            unicode_error_messages: true,
+            silenced_deprecations: Vec::new(),
+            defined_variables: Vec::new(),
+        }
+    }
+}
+
+impl GrassOptions {
+    fn to_options(&self) -> Options<'_> {
+        let mut options = Options::default()
+            .style(self.style)
+            .load_paths(&self.load_paths)
+            .allows_charset(self.allows_charset)
+            .unicode_error_messages(self.unicode_error_messages);
+
+        // warnings are printed to stderr unless silenced
+        if self.quiet {
+            options = options.logger(&NullLogger);
+        }
+
+        if let Some(syntax) = self.input_syntax {
+            options = options.input_syntax(syntax);
+        }
+
+        for &deprecation in &self.silenced_deprecations {
+            options = options.silence_deprecation(deprecation);
+        }
+
+        for (name, value) in &self.defined_variables {
+            options = options.define(name.as_str(), value.as_str());
+        }
+
+        options
+    }
+}
+
+/// The outcome of compiling a stylesheet, freed with [`grass_result_free`]
+#[derive(Debug)]
+pub struct GrassResult(Result<CString, GrassError>);
+
+#[derive(Debug)]
+struct GrassError {
+    message: CString,
+    formatted: CString,
+    json: CString,
+    file: Option<CString>,
+    line: c_int,
+    column: c_int,
+}
+
+impl GrassResult {
+    fn new(result: Result<String, Box<Error>>) -> *mut GrassResult {
+        let result = result.map(c_string).map_err(|e| {
+            let (file, line, column) = match e.span() {
+                Some(loc) => (
+                    Some(c_string(loc.file.name().to_owned())),
+                    to_c_int(loc.begin.line + 1),
+                    to_c_int(loc.begin.column + 1),
+                ),
+                None => (None, 0, 0),
+            };
+
+            GrassError {
+                message: c_string(e.message().into_owned()),
+                formatted: c_string(e.to_string()),
+                json: c_string(e.to_json()),
+                file,
+                line,
+                column,
+            }
+        });
+
+        Box::into_raw(Box::new(GrassResult(result)))
+    }
+
+    fn error(&self) -> Option<&GrassError> {
+        self.0.as_ref().err()
+    }
+}
+
+/// Convert `s` to a C string, replacing NUL bytes, which C strings cannot
+/// contain, with U+FFFD REPLACEMENT CHARACTER
+fn c_string(s: String) -> CString {
+    CString::new(s).unwrap_or_else(|e| {
+        let s = String::from_utf8(e.into_vec())
+            .unwrap()
+            .replace('\0', "\u{FFFD}");
+        CString::new(s).unwrap()
+    })
+}
+
+fn to_c_int(n: usize) -> c_int {
+    c_int::try_from(n).unwrap_or(c_int::MAX)
+}
+
+/// Read the NUL-terminated UTF-8 string `s`, or `None` if it is null or not
+/// valid UTF-8
+unsafe fn read_str<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        return None;
+    }
+
+    CStr::from_ptr(s).to_str().ok()
+}
+
+/// Apply `f` to the options behind `options`, returning
+/// [`GRASS_INVALID_ARGUMENT`] if it is null or `f` returns `None`
+unsafe fn update_options(
+    options: *mut GrassOptions,
+    f: impl FnOnce(&mut GrassOptions) -> Option<()>,
+) -> c_int {
+    match options.as_mut().and_then(f) {
+        Some(()) => GRASS_OK,
+        None => GRASS_INVALID_ARGUMENT,
+    }
+}
+
+fn invalid_argument(message: &str) -> Box<Error> {
+    io::Error::new(io::ErrorKind::InvalidInput, message).into()
+}
+
+/// Compile with `options`, or the default options if it is null, turning
+/// panics into errors so that they do not unwind into foreign code
+///
+/// Panics can only be caught when built with `panic = "unwind"`, as by the
+/// `ffi` profile.
+unsafe fn compile(
+    options: *const GrassOptions,
+    f: impl FnOnce(&Options) -> Result<String, Box<Error>>,
+) -> *mut GrassResult {
+    let default_options = GrassOptions::default();
+    let options = options.as_ref().unwrap_or(&default_options).to_options();
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| f(&options)))
+        .unwrap_or_else(|_| Err(invalid_argument("grass panicked while compiling.")));
+
+    GrassResult::new(result)
+}
+
+/// The version of grass, such as `"0.13.1"`
+#[no_mangle]
+pub extern "C" fn grass_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Create options with the default settings
+#[no_mangle]
+pub extern "C" fn grass_options_new() -> *mut GrassOptions {
+    Box::into_raw(Box::default())
+}
+
+/// Free options created with [`grass_options_new`]
+///
+/// # Safety
+///
+/// `options` must be null or have been returned by [`grass_options_new`], and
+/// must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn grass_options_free(options: *mut GrassOptions) {
+    if !options.is_null() {
+        drop(Box::from_raw(options));
+    }
+}
+
+/// Set the output style to [`GRASS_STYLE_EXPANDED`] or
+/// [`GRASS_STYLE_COMPRESSED`]
+///
+/// # Safety
+///
+/// `options` must be null or a valid pointer returned by [`grass_options_new`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_options_set_style(
+    options: *mut GrassOptions,
+    style: c_int,
+) -> c_int {
+    update_options(options, |options| {
+        options.style = match style {
+            GRASS_STYLE_EXPANDED => OutputStyle::Expanded,
+            GRASS_STYLE_COMPRESSED => OutputStyle::Compressed,
+            _ => return None,
+        };
+        Some(())
+    })
+}
+
+/// Set the syntax of the entrypoint to [`GRASS_SYNTAX_SCSS`],
+/// [`GRASS_SYNTAX_SASS`], or [`GRASS_SYNTAX_CSS`]
+///
+/// By default, the syntax of a file is determined by its extension, and strings
+/// are parsed as SCSS.
+///
+/// # Safety
+///
+/// `options` must be null or a valid pointer returned by [`grass_options_new`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_options_set_input_syntax(
+    options: *mut GrassOptions,
+    syntax: c_int,
+) -> c_int {
+    update_options(options, |options| {
+        options.input_syntax = Some(match syntax {
+            GRASS_SYNTAX_SCSS => InputSyntax::Scss,
+            GRASS_SYNTAX_SASS => InputSyntax::Sass,
+            GRASS_SYNTAX_CSS => InputSyntax::Css,
+            _ => return None,
+        });
+        Some(())
+    })
+}
+
+/// Add a path to use when resolving imports
+///
+/// # Safety
+///
+/// `options` must be null or a valid pointer returned by [`grass_options_new`],
+/// and `path` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn grass_options_add_load_path(
+    options: *mut GrassOptions,
+    path: *const c_char,
+) -> c_int {
+    let path = read_str(path);
+
+    update_options(options, |options| {
+        options.load_paths.push(PathBuf::from(path?));
+        Some(())
+    })
+}
+
+/// Don't print warnings to stderr
+///
+/// # Safety
+///
+/// `options` must be null or a valid pointer returned by [`grass_options_new`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_options_set_quiet(options: *mut GrassOptions, quiet: bool) -> c_int {
+    update_options(options, |options| {
+        options.quiet = quiet;
+        Some(())
+    })
+}
+
+/// Whether to emit a `@charset` or byte-order mark for non-ASCII output. This
+/// is enabled by default
+///
+/// # Safety
+///
+/// `options` must be null or a valid pointer returned by [`grass_options_new`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_options_set_charset(
+    options: *mut GrassOptions,
+    allows_charset: bool,
+) -> c_int {
+    update_options(options, |options| {
+        options.allows_charset = allows_charset;
+        Some(())
+    })
+}
+
+/// Whether to use non-ASCII characters in formatted errors. This is enabled by
+/// default
+///
+/// # Safety
+///
+/// `options` must be null or a valid pointer returned by [`grass_options_new`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_options_set_unicode_error_messages(
+    options: *mut GrassOptions,
+    unicode: bool,
+) -> c_int {
+    update_options(options, |options| {
+        options.unicode_error_messages = unicode;
+        Some(())
+    })
+}
+
+/// Don't emit warnings for the deprecation with the id `deprecation`, such as
+/// `"slash-div"`
+///
+/// # Safety
+///
+/// `options` must be null or a valid pointer returned by [`grass_options_new`],
+/// and `deprecation` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn grass_options_silence_deprecation(
+    options: *mut GrassOptions,
+    deprecation: *const c_char,
+) -> c_int {
+    let deprecation = read_str(deprecation).and_then(|id| id.parse().ok());
+
+    update_options(options, |options| {
+        options.silenced_deprecations.push(deprecation?);
+        Some(())
+    })
+}
+
+/// Define a global variable in the entrypoint named `name`, without the
+/// leading `$`, whose value is the SassScript expression `value`
+///
+/// # Safety
+///
+/// `options` must be null or a valid pointer returned by [`grass_options_new`],
+/// and `name` and `value` must be null or NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn grass_options_define(
+    options: *mut GrassOptions,
+    name: *const c_char,
+    value: *const c_char,
+) -> c_int {
+    let (name, value) = (read_str(name), read_str(value));
+
+    update_options(options, |options| {
+        options
+            .defined_variables
+            .push((name?.to_owned(), value?.to_owned()));
+        Some(())
+    })
+}
+
+/// Compile the file at `path`
+///
+/// `options` may be null to use the default options. The result must be freed
+/// with [`grass_result_free`].
+///
+/// # Safety
+///
+/// `path` must be null or a NUL-terminated string, and `options` must be null
+/// or a valid pointer returned by [`grass_options_new`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_compile_file(
+    path: *const c_char,
+    options: *const GrassOptions,
+) -> *mut GrassResult {
+    let path = read_str(path);
+
+    compile(options, |options| match path {
+        Some(path) => grass_compiler::from_path(path, options),
+        None => Err(invalid_argument("The path is null or not valid UTF-8.")),
+    })
+}
+
+/// Compile `source`
+///
+/// `options` may be null to use the default options. The result must be freed
+/// with [`grass_result_free`].
+///
+/// # Safety
+///
+/// `source` must be null or a NUL-terminated string, and `options` must be null
+/// or a valid pointer returned by [`grass_options_new`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_compile_string(
+    source: *const c_char,
+    options: *const GrassOptions,
+) -> *mut GrassResult {
+    let source = read_str(source);
+
+    compile(options, |options| match source {
+        Some(source) => grass_compiler::from_string(source, options),
+        None => Err(invalid_argument("The source is null or not valid UTF-8.")),
+    })
+}
+
+/// Whether the stylesheet compiled successfully
+///
+/// # Safety
+///
+/// `result` must be null or a valid pointer returned by [`grass_compile_file`]
+/// or [`grass_compile_string`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_result_is_ok(result: *const GrassResult) -> bool {
+    matches!(result.as_ref(), Some(GrassResult(Ok(..))))
+}
+
+/// The compiled CSS, or null if compilation failed
+///
+/// # Safety
+///
+/// `result` must be null or a valid pointer returned by [`grass_compile_file`]
+/// or [`grass_compile_string`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_result_css(result: *const GrassResult) -> *const c_char {
+    match result.as_ref() {
+        Some(GrassResult(Ok(css))) => css.as_ptr(),
+        _ => ptr::null(),
+    }
+}
+
+/// The message of the error, without any location information, or null if
+/// compilation succeeded
+///
+/// # Safety
+///
+/// `result` must be null or a valid pointer returned by [`grass_compile_file`]
+/// or [`grass_compile_string`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_result_error_message(result: *const GrassResult) -> *const c_char {
+    result
+        .as_ref()
+        .and_then(GrassResult::error)
+        .map_or(ptr::null(), |e| e.message.as_ptr())
+}
+
+/// The error formatted as grass prints it, with a snippet of the source and
+/// its location, or null if compilation succeeded
+///
+/// # Safety
+///
+/// `result` must be null or a valid pointer returned by [`grass_compile_file`]
+/// or [`grass_compile_string`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_result_error_formatted(result: *const GrassResult) -> *const c_char {
+    result
+        .as_ref()
+        .and_then(GrassResult::error)
+        .map_or(ptr::null(), |e| e.formatted.as_ptr())
+}
+
+/// The error as a JSON object, in the format of `grass::Error::to_json`, or
+/// null if compilation succeeded
+///
+/// # Safety
+///
+/// `result` must be null or a valid pointer returned by [`grass_compile_file`]
+/// or [`grass_compile_string`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_result_error_json(result: *const GrassResult) -> *const c_char {
+    result
+        .as_ref()
+        .and_then(GrassResult::error)
+        .map_or(ptr::null(), |e| e.json.as_ptr())
+}
+
+/// The file the error occurred in, or null if compilation succeeded or the
+/// error has no location, such as when the entrypoint could not be read
+///
+/// # Safety
+///
+/// `result` must be null or a valid pointer returned by [`grass_compile_file`]
+/// or [`grass_compile_string`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_result_error_file(result: *const GrassResult) -> *const c_char {
+    result
+        .as_ref()
+        .and_then(GrassResult::error)
+        .and_then(|e| e.file.as_ref())
+        .map_or(ptr::null(), |file| file.as_ptr())
+}
+
+/// The 1-indexed line the error occurred on, or 0 if compilation succeeded or
+/// the error has no location
+///
+/// # Safety
+///
+/// `result` must be null or a valid pointer returned by [`grass_compile_file`]
+/// or [`grass_compile_string`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_result_error_line(result: *const GrassResult) -> c_int {
+    result
+        .as_ref()
+        .and_then(GrassResult::error)
+        .map_or(0, |e| e.line)
+}
+
+/// The 1-indexed column the error occurred at, or 0 if compilation succeeded
+/// or the error has no location
+///
+/// # Safety
+///
+/// `result` must be null or a valid pointer returned by [`grass_compile_file`]
+/// or [`grass_compile_string`].
+#[no_mangle]
+pub unsafe extern "C" fn grass_result_error_column(result: *const GrassResult) -> c_int {
+    result
+        .as_ref()
+        .and_then(GrassResult::error)
+        .map_or(0, |e| e.column)
+}
+
+/// Free a result returned by [`grass_compile_file`] or
+/// [`grass_compile_string`], along with every string returned from it
+///
+/// # Safety
+///
+/// `result` must be null or have been returned by [`grass_compile_file`] or
+/// [`grass_compile_string`], and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn grass_result_free(result: *mut GrassResult) {
+    if !result.is_null() {
+        drop(Box::from_raw(result));
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    ptr,
+};
+
+use grass_sys::*;
+
+fn c(s: &str) -> CString {
+    CString::new(s).unwrap()
+}
+
+unsafe fn read(s: *const c_char) -> Option<String> {
+    if s.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(s).to_str().unwrap().to_owned())
+    }
+}
+
+#[test]
+fn compile_string_with_default_options() {
+    unsafe {
+        let source = c("a { b: 1 + 2 }");
+        let result = grass_compile_string(source.as_ptr(), ptr::null());
+
+        assert!(grass_result_is_ok(result));
+        assert_eq!(
+            read(grass_result_css(result)).as_deref(),
+            Some("a {\n  b: 3;\n}\n")
+        );
+        assert_eq!(read(grass_result_error_message(result)), None);
+        assert_eq!(grass_result_error_line(result), 0);
+
+        grass_result_free(result);
+    }
+}
+
+#[test]
+fn compile_string_with_options() {
+    unsafe {
+        let options = grass_options_new();
+        assert_eq!(
+            grass_options_set_style(options, GRASS_STYLE_COMPRESSED),
+            GRASS_OK
+        );
+        assert_eq!(
+            grass_options_set_input_syntax(options, GRASS_SYNTAX_SASS),
+            GRASS_OK
+        );
+        assert_eq!(
+            grass_options_define(options, c("color").as_ptr(), c("red").as_ptr()),
+            GRASS_OK
+        );
+
+        let source = c("a\n  b: $color");
+        let result = grass_compile_string(source.as_ptr(), options);
+
+        assert_eq!(read(grass_result_css(result)).as_deref(), Some("a{b:red}"));
+
+        grass_result_free(result);
+        grass_options_free(options);
+    }
+}
+
+#[test]
+fn compile_file_with_load_path() {
+    let dir = std::env::temp_dir().join(format!("grass-sys-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("lib")).unwrap();
+    std::fs::write(dir.join("lib/_colors.scss"), "$primary: blue;").unwrap();
+    std::fs::write(
+        dir.join("input.scss"),
+        "@use 'colors';\na { b: colors.$primary }",
+    )
+    .unwrap();
+
+    unsafe {
+        let options = grass_options_new();
+        let load_path = c(dir.join("lib").to_str().unwrap());
+        assert_eq!(
+            grass_options_add_load_path(options, load_path.as_ptr()),
+            GRASS_OK
+        );
+
+        let path = c(dir.join("input.scss").to_str().unwrap());
+        let result = grass_compile_file(path.as_ptr(), options);
+
+        assert_eq!(
+            read(grass_result_css(result)).as_deref(),
+            Some("a {\n  b: blue;\n}\n")
+        );
+
+        grass_result_free(result);
+        grass_options_free(options);
+    }
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn error_accessors() {
+    unsafe {
+        let options = grass_options_new();
+        grass_options_set_unicode_error_messages(options, false);
+
+        let source = c("a {\n  b: 1px + 1s;\n}");
+        let result = grass_compile_string(source.as_ptr(), options);
+
+        assert!(!grass_result_is_ok(result));
+        assert_eq!(read(grass_result_css(result)), None);
+        assert_eq!(
+            read(grass_result_error_message(result)).as_deref(),
+            Some("Incompatible units s and px.")
+        );
+        assert_eq!(
+            read(grass_result_error_file(result)).as_deref(),
+            Some("stdin")
+        );
+        assert_eq!(grass_result_error_line(result), 2);
+        assert_eq!(grass_result_error_column(result), 6);
+        assert!(read(grass_result_error_formatted(result))
+            .unwrap()
+            .starts_with("Error: Incompatible units s and px.\n"));
+        assert!(read(grass_result_error_json(result))
+            .unwrap()
+            .starts_with("{\"severity\":\"error\",\"message\":\"Incompatible units s and px.\""));
+
+        grass_result_free(result);
+        grass_options_free(options);
+    }
+}
+
+#[test]
+fn missing_file_has_no_location() {
+    unsafe {
+        let path = c("does-not-exist.scss");
+        let result = grass_compile_file(path.as_ptr(), ptr::null());
+
+        assert!(!grass_result_is_ok(result));
+        assert!(read(grass_result_error_message(result)).is_some());
+        assert_eq!(read(grass_result_error_file(result)), None);
+        assert_eq!(grass_result_error_line(result), 0);
+        assert_eq!(grass_result_error_column(result), 0);
+
+        grass_result_free(result);
+    }
+}
+
+#[test]
+fn invalid_arguments() {
+    unsafe {
+        let options = grass_options_new();
+
+        assert_eq!(grass_options_set_style(options, 2), GRASS_INVALID_ARGUMENT);
+        assert_eq!(
+            grass_options_set_input_syntax(options, -1),
+            GRASS_INVALID_ARGUMENT
+        );
+        assert_eq!(
+            grass_options_add_load_path(options, ptr::null()),
+            GRASS_INVALID_ARGUMENT
+        );
+        assert_eq!(
+            grass_options_silence_deprecation(options, c("not-a-deprecation").as_ptr()),
+            GRASS_INVALID_ARGUMENT
+        );
+        assert_eq!(
+            grass_options_silence_deprecation(options, c("slash-div").as_ptr()),
+            GRASS_OK
+        );
+        assert_eq!(
+            grass_options_set_quiet(ptr::null_mut(), true),
+            GRASS_INVALID_ARGUMENT
+        );
+
+        let result = grass_compile_string(ptr::null(), options);
+        assert!(!grass_result_is_ok(result));
+        assert_eq!(
+            read(grass_result_error_message(result)).as_deref(),
+            Some("The source is null or not valid UTF-8.")
+        );
+        grass_result_free(result);
+
+        grass_options_free(options);
+        grass_options_free(ptr::null_mut());
+        grass_result_free(ptr::null_mut());
+        assert!(!grass_result_is_ok(ptr::null()));
+    }
+}
+
+#[test]
+fn version() {
+    unsafe {
+        assert_eq!(
+            read(grass_version()).as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+    }
+}
+
+/// Every exported function must be declared in the header
+#[test]
+fn header_declares_every_function() {
+    let source = include_str!("../src/lib.rs");
+    let header = include_str!("../include/grass.h");
+
+    let mut lines = source.lines();
+    let mut count = 0;
+
+    while let Some(line) = lines.next() {
+        if line.trim() != "#[no_mangle]" {
+            continue;
+        }
+
+        let signature = lines.next().unwrap();
+        let name = signature
+            .split("fn ")
+            .nth(1)
+            .and_then(|rest| rest.split('(').next())
+            .unwrap();
+
+        assert!(
+            header.contains(&format!(" {}(", name)) || header.contains(&format!("*{}(", name)),
+            "{} is not declared in include/grass.h",
+            name
+        );
+        count += 1;
+    }
+
+    assert_eq!(count, 22);
+}